@@ -3,10 +3,18 @@ use std::{io, path::PathBuf};
 use anyhow::Context;
 use clap::Args;
 
-use nexus_core::config::{vm as vm_config, Config};
+use nexus_core::config::{
+    vm::{self as vm_config, ProverImpl},
+    Config,
+};
+use nexus_core::prover::envelope::{ProofEnvelope, ProofHeader};
+use nexus_core::prover::params::serialize_digest;
 use nexus_progress_bar::TerminalHandle;
 
-use super::{public_params::format_params_file, spartan_key::SetupArgs};
+use super::{
+    public_params::format_params_file,
+    spartan_key::{compressible_impl, SetupArgs},
+};
 
 use crate::{
     command::{cache_path, spartan_key::spartan_setup},
@@ -34,6 +42,10 @@ pub struct CompressArgs {
     /// File containing uncompressed proof
     #[arg(short = 'f', long = "proof-file")]
     pub proof_file: PathBuf,
+
+    /// Prover the proof was produced with; defaults to reading value from vm config.
+    #[arg(long("impl"))]
+    pub prover_impl: Option<ProverImpl>,
 }
 
 pub fn handle_command(args: CompressArgs) -> anyhow::Result<()> {
@@ -43,12 +55,11 @@ pub fn handle_command(args: CompressArgs) -> anyhow::Result<()> {
 pub fn compress_proof(args: CompressArgs) -> anyhow::Result<()> {
     let vm_config = vm_config::VmConfig::from_env()?;
     let k = args.k.unwrap_or(vm_config.k);
+    let prover_impl = compressible_impl(args.prover_impl.unwrap_or(vm_config.prover))?;
 
     let pp_file = match args.pp_file {
         None => {
-            let nova_impl = vm_config::NovaImpl::ParallelCompressible;
-
-            let pp_file_name = format_params_file(nova_impl, k);
+            let pp_file_name = format_params_file(prover_impl, k);
             let cache_path = cache_path()?;
 
             cache_path.join(pp_file_name)
@@ -63,14 +74,10 @@ pub fn compress_proof(args: CompressArgs) -> anyhow::Result<()> {
         );
         return Err(io::Error::from(io::ErrorKind::NotFound).into());
     };
-    tracing::info!(
-        target: LOG_TARGET,
-        path =?pp_file,
-        "Reading the Nova public parameters",
-    );
-    let pp_file_str = pp_file.to_str().context("path is not valid utf8")?;
-
-    let pp = nexus_core::prover::nova::pp::load_pp(pp_file_str)?;
+    let pp_file_str = pp_file
+        .to_str()
+        .context("path is not valid utf8")?
+        .to_owned();
 
     let key_file = if let Some(path) = args.key_file {
         // return early if the path was explicitly specified and doesn't exist
@@ -90,10 +97,10 @@ pub fn compress_proof(args: CompressArgs) -> anyhow::Result<()> {
             k: Some(k),
             pp_file: Some(pp_file),
             srs_file: args.srs_file,
+            prover_impl: Some(prover_impl),
         })?
     };
     let key_file_str = key_file.to_str().context("path is not valid utf8")?;
-    let key = nexus_core::prover::nova::key::load_key(key_file_str)?;
 
     let proof_file = args.proof_file;
     if !proof_file.try_exists()? {
@@ -105,18 +112,72 @@ pub fn compress_proof(args: CompressArgs) -> anyhow::Result<()> {
         return Err(io::Error::from(io::ErrorKind::NotFound).into());
     };
 
+    let current_dir = std::env::current_dir()?;
+    let compressed_proof_path = current_dir.join("nexus-proof-compressed");
+
+    if let ProverImpl::HyperNova(_) = prover_impl {
+        tracing::info!(
+            target: LOG_TARGET,
+            path =?pp_file_str,
+            "Reading the HyperNova public parameters",
+        );
+        let pp = nexus_core::prover::hypernova::pp::load_pp_for(&pp_file_str, prover_impl, k)?;
+        let key = nexus_core::prover::hypernova::key::load_key(key_file_str)?;
+
+        let mut term = TerminalHandle::new_enabled();
+
+        let proof = {
+            let mut context = term.context("Loading").on_step(|_step| "proof".into());
+            let _guard = context.display_step();
+
+            let envelope = ProofEnvelope::load(&proof_file)?;
+            envelope.check(prover_impl, k, false)?;
+            envelope
+                .header
+                .check_params(&serialize_digest(&pp.digest)?)?;
+            envelope.proof()?
+        };
+
+        let compressed_proof = {
+            let mut term_ctx = term
+                .context("Compressing")
+                .on_step(|_step| "the proof".into());
+            let _guard = term_ctx.display_step();
+
+            nexus_core::prover::hypernova::compress(&pp, &key, proof)?
+        };
+
+        let mut context = term.context("Saving").on_step(|_step| "proof".into());
+        let _guard = context.display_step();
+
+        let header = ProofHeader::new(prover_impl, k, true, &pp.digest)?;
+        ProofEnvelope::new(header, None, &compressed_proof)?.save(&compressed_proof_path)?;
+
+        return Ok(());
+    }
+
+    tracing::info!(
+        target: LOG_TARGET,
+        path =?pp_file_str,
+        "Reading the Nova public parameters",
+    );
+    let pp = nexus_core::prover::nova::pp::load_pp_for(&pp_file_str, prover_impl, k)?;
+    let key = nexus_core::prover::nova::key::load_key(key_file_str)?;
+
     let mut term = TerminalHandle::new_enabled();
 
     let proof = {
         let mut context = term.context("Loading").on_step(|_step| "proof".into());
         let _guard = context.display_step();
 
-        nexus_core::prover::nova::load_proof(&proof_file)?
+        let envelope = ProofEnvelope::load(&proof_file)?;
+        envelope.check(prover_impl, k, false)?;
+        envelope
+            .header
+            .check_params(&serialize_digest(&pp.digest)?)?;
+        envelope.proof()?
     };
 
-    let current_dir = std::env::current_dir()?;
-    let compressed_proof_path = current_dir.join("nexus-proof-compressed");
-
     let compressed_proof = {
         let mut term_ctx = term
             .context("Compressing")
@@ -129,7 +190,8 @@ pub fn compress_proof(args: CompressArgs) -> anyhow::Result<()> {
     let mut context = term.context("Saving").on_step(|_step| "proof".into());
     let _guard = context.display_step();
 
-    nexus_core::prover::nova::save_proof(compressed_proof, &compressed_proof_path)?;
+    let header = ProofHeader::new(prover_impl, k, true, &pp.digest)?;
+    ProofEnvelope::new(header, None, &compressed_proof)?.save(&compressed_proof_path)?;
 
     Ok(())
 }
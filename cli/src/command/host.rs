@@ -162,7 +162,7 @@ macro_rules! guest_examples_dir {
 
 const HOST_TEMPLATE_SRC_MAIN: &str = include_str!(concat!(host_examples_dir!(), "/nova_build.rs"));
 
-const GUEST_TEMPLATE_CARGO_CONFIG: &str = r#"[target.riscv32i-unknown-none-elf]
+const GUEST_TEMPLATE_CARGO_CONFIG: &str = r#"[target.riscv32im-unknown-none-elf]
 rustflags = [
   "-C", "link-arg=-Tlink.x",
 ]
@@ -173,5 +173,5 @@ const GUEST_TEMPLATE_SRC_MAIN: &str = include_str!(concat!(guest_examples_dir!()
 // freeze toolchain that works with all provers
 const GUEST_RUST_TOOLCHAIN: &str = r#"[toolchain]
 channel = "1.77.0"
-targets = ["riscv32i-unknown-none-elf"]
+targets = ["riscv32im-unknown-none-elf"]
 "#;
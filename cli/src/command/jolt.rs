@@ -1,8 +1,10 @@
 //! Jolt prove/verify routine implementation.
 
-use std::{fs::File, io::BufReader, path::Path};
+use std::path::Path;
 
+use nexus_core::config::vm::ProverImpl;
 use nexus_core::nvm::memory::MerkleTrie;
+use nexus_core::prover::envelope::{ProofEnvelope, ProofHeader};
 use nexus_core::prover::jolt::{
     parse_elf, trace,
     types::{JoltCommitments, JoltProof},
@@ -10,9 +12,6 @@ use nexus_core::prover::jolt::{
 };
 use nexus_progress_bar::TerminalHandle;
 
-use anyhow::Context;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-
 use super::prove::CommonProveArgs;
 use crate::{utils::path_to_artifact, LOG_TARGET};
 
@@ -40,7 +39,7 @@ pub fn prove(path: &Path) -> anyhow::Result<()> {
     println!("Executing program...");
 
     let start = std::time::Instant::now();
-    let trace = trace(vm)?;
+    let (trace, io) = trace(vm)?;
     println!(
         "Executed {} instructions in {:?}",
         trace.len(),
@@ -54,7 +53,7 @@ pub fn prove(path: &Path) -> anyhow::Result<()> {
         .on_step(|_step| "program execution".into());
     let (proof, commitments) = {
         let _guard = ctx.display_step();
-        nexus_core::prover::jolt::prove(trace, &preprocessing)?
+        nexus_core::prover::jolt::prove(trace, io, &preprocessing)?
     };
 
     let proof = (proof, commitments);
@@ -67,10 +66,9 @@ pub fn prove(path: &Path) -> anyhow::Result<()> {
     {
         let _guard = context.display_step();
 
-        let mut buf = Vec::new();
-
-        CanonicalSerialize::serialize_compressed(&proof, &mut buf)?;
-        std::fs::write(proof_path, buf)?;
+        // the proof is specific to the program, there are no public parameters
+        let header = ProofHeader::new(ProverImpl::Jolt, 0, false, &())?;
+        ProofEnvelope::new(header, None, &proof)?.save(&proof_path)?;
     }
 
     Ok(())
@@ -81,11 +79,9 @@ pub fn verify(proof_path: &Path, prove_args: CommonProveArgs) -> anyhow::Result<
     let path = path_to_artifact(bin, &profile)?;
 
     // load proof
-    let file = File::open(proof_path)?;
-    let reader = BufReader::new(file);
-
-    let (proof, commitments): Proof = CanonicalDeserialize::deserialize_compressed(reader)
-        .context("proof is not in Jolt format")?;
+    let envelope = ProofEnvelope::load(proof_path)?;
+    envelope.check(ProverImpl::Jolt, 0, false)?;
+    let (proof, commitments): Proof = envelope.proof()?;
 
     let bytes = std::fs::read(path)?;
     let vm: VM<MerkleTrie> = parse_elf(&bytes)?;
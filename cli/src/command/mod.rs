@@ -27,7 +27,7 @@ pub enum Command {
     Run(run::RunArgs),
     /// Compute proof of program execution.
     Prove(prove::ProveArgs),
-    /// Request proof status from the network; download it if it's finished.
+    /// Download a proof from the network.
     Request(request::RequestArgs),
     /// Verify the proof.
     Verify(verify::VerifyArgs),
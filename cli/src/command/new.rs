@@ -66,7 +66,7 @@ macro_rules! examples_dir {
     };
 }
 
-const TEMPLATE_CARGO_CONFIG: &str = r#"[target.riscv32i-unknown-none-elf]
+const TEMPLATE_CARGO_CONFIG: &str = r#"[target.riscv32im-unknown-none-elf]
 rustflags = [
   "-C", "link-arg=-Tlink.x",
 ]
@@ -77,5 +77,5 @@ const TEMPLATE_SRC_MAIN: &str = include_str!(concat!(examples_dir!(), "/src/main
 // freeze toolchain that works with all provers
 const RUST_TOOLCHAIN: &str = r#"[toolchain]
 channel = "1.77.0"
-targets = ["riscv32i-unknown-none-elf"]
+targets = ["riscv32im-unknown-none-elf"]
 "#;
@@ -4,10 +4,14 @@ use std::{
 };
 
 use anyhow::Context;
+use ark_serialize::CanonicalSerialize;
 use clap::Args;
 
 use nexus_core::config::{vm as vm_config, Config};
-use nexus_progress_bar::TerminalHandle;
+use nexus_core::nvm::memory::OfflineMemory;
+use nexus_core::prover::envelope::{ProofEnvelope, ProofHeader};
+use nexus_network::client::ProverClient;
+use nexus_progress_bar::{terminal::TerminalContext, TerminalHandle};
 
 use crate::{
     command::{
@@ -47,7 +51,7 @@ pub struct LocalProveArgs {
     #[arg(long("impl"))]
     pub prover_impl: Option<vm_config::ProverImpl>,
 
-    /// Path to the SRS file: only needed when pp_file is None and nova_impl is ParallelCompressible.
+    /// Path to the SRS file: only needed when pp_file is None and the prover is HyperNova or nova-par-com.
     #[arg(long("srs-file"))]
     pub srs_file: Option<PathBuf>,
 }
@@ -83,6 +87,18 @@ pub fn handle_command(args: ProveArgs) -> anyhow::Result<()> {
 
     if network {
         let url = url.context("url must be specified")?;
+
+        // build artifact if needed
+        cargo(
+            None,
+            [
+                "build",
+                "--target=riscv32im-unknown-none-elf",
+                "--profile",
+                &profile,
+            ],
+        )?;
+
         request_prove(&path, &url)
     } else {
         let LocalProveArgs { k, pp_file, prover_impl, srs_file } = local_args;
@@ -97,7 +113,7 @@ pub fn handle_command(args: ProveArgs) -> anyhow::Result<()> {
             None,
             [
                 "build",
-                "--target=riscv32i-unknown-none-elf",
+                "--target=riscv32im-unknown-none-elf",
                 "--profile",
                 &profile,
             ],
@@ -109,22 +125,16 @@ pub fn handle_command(args: ProveArgs) -> anyhow::Result<()> {
     }
 }
 
-fn request_prove(_path: &Path, _url: &str) -> anyhow::Result<()> {
-    // TODO: network errors cannot be converted to anyhow.
-
-    // let client = Client::new(url).map_err(|err| anyhow::anyhow!("url is invalid: {err}"))?;
-    // let proof = client
-    //     .submit_proof("account".to_string(), path)
-    //     .map_err(|err| anyhow::anyhow!("failed to send request: {err}"))?;
+fn request_prove(path: &Path, url: &str) -> anyhow::Result<()> {
+    let client = ProverClient::new(url).map_err(|err| anyhow::anyhow!("url is invalid: {err}"))?;
+    let hash = client
+        .submit_program(path)
+        .map_err(|err| anyhow::anyhow!("failed to send request: {err}"))?;
 
-    // tracing::info!(
-    //     target: LOG_TARGET,
-    //     hash = %proof.hash,
-    //     "Prove request submitted",
-    // );
-    tracing::warn!(
+    tracing::info!(
         target: LOG_TARGET,
-        "Networking commands are disabled",
+        %hash,
+        "Program proven, download the proof with `cargo nexus request {hash} --url {url}`",
     );
 
     Ok(())
@@ -138,13 +148,17 @@ fn local_prove(
     srs_file: Option<PathBuf>,
 ) -> anyhow::Result<()> {
     // handle jolt separately
-    let nova_impl = match prover {
-        vm_config::ProverImpl::Jolt => return jolt::prove(path),
-        vm_config::ProverImpl::HyperNova => {
-            anyhow::bail!("HyperNova not yet supported through the CLI")
+    if let vm_config::ProverImpl::Jolt = prover {
+        return jolt::prove(path);
+    }
+    if let vm_config::ProverImpl::SuperNova = prover {
+        if k != nexus_core::prover::supernova::K {
+            anyhow::bail!(
+                "SuperNova proves a single instruction per step, k must be {}",
+                nexus_core::prover::supernova::K
+            )
         }
-        vm_config::ProverImpl::Nova(nova_impl) => nova_impl,
-    };
+    }
 
     // setup if necessary
     let pp_file = if let Some(path) = pp_file {
@@ -161,7 +175,7 @@ fn local_prove(
     } else {
         setup_params(SetupArgs {
             k: Some(k),
-            nova_impl: Some(nova_impl),
+            prover_impl: Some(prover),
             path: None,
             force: false,
             srs_file,
@@ -174,19 +188,34 @@ fn local_prove(
         machine: None,
         file: Some(path.into()),
     };
-    let trace = nexus_core::prover::nova::run(&opts, true)?;
-    let k = trace.k;
 
     let current_dir = std::env::current_dir()?;
     let proof_path = current_dir.join("nexus-proof");
 
+    // offline memory checking needs the machine, rather than its trace
+    if let vm_config::ProverImpl::Nova(vm_config::NovaImpl::SequentialOffline) = prover {
+        return prove_offline(&opts, path_str, &proof_path);
+    }
+
+    let trace = nexus_core::prover::nova::run(&opts, true)?;
+    let k = trace.k;
+
     let mut term = TerminalHandle::new_enabled();
 
     let tr = nexus_core::prover::nova::init_circuit_trace(trace)?;
     let num_steps = tr.steps();
 
-    let on_step = move |iter: usize| match nova_impl {
-        vm_config::NovaImpl::Parallel | vm_config::NovaImpl::ParallelCompressible => {
+    let is_parallel = matches!(
+        prover,
+        vm_config::ProverImpl::Nova(
+            vm_config::NovaImpl::Parallel | vm_config::NovaImpl::ParallelCompressible
+        ) | vm_config::ProverImpl::HyperNova(
+            vm_config::HyperNovaImpl::Parallel | vm_config::HyperNovaImpl::ParallelCompressible
+        )
+    );
+
+    let on_step = move |iter: usize| {
+        if is_parallel {
             let b = (num_steps + 1).ilog2();
             let a = b - 1 - (num_steps - iter).ilog2();
 
@@ -199,17 +228,15 @@ fn local_prove(
                 "node"
             };
             format!("{step_type} {step}")
+        } else {
+            format!("step {iter}")
         }
-        _ => format!("step {iter}"),
     };
 
-    let icount = {
-        match nova_impl {
-            vm_config::NovaImpl::Parallel | vm_config::NovaImpl::ParallelCompressible => {
-                k * num_steps
-            }
-            _ => tr.instructions(),
-        }
+    let icount = if is_parallel {
+        k * num_steps
+    } else {
+        tr.instructions()
     };
 
     let mut term_ctx = term
@@ -225,6 +252,18 @@ fn local_prove(
             )
         });
 
+    let nova_impl = match prover {
+        vm_config::ProverImpl::Nova(nova_impl) => nova_impl,
+        vm_config::ProverImpl::HyperNova(hypernova_impl) => {
+            return prove_hypernova(hypernova_impl, path_str, &tr, term_ctx, &proof_path);
+        }
+        vm_config::ProverImpl::SuperNova => {
+            let tr = nexus_core::prover::supernova::init_circuit_trace(tr.0)?;
+            return prove_supernova(path_str, &tr, term_ctx, &proof_path);
+        }
+        vm_config::ProverImpl::Jolt => unreachable!(),
+    };
+
     match nova_impl {
         vm_config::NovaImpl::Parallel => {
             assert!((num_steps + 1).is_power_of_two());
@@ -236,7 +275,7 @@ fn local_prove(
                     .on_step(|_step| "public parameters".into());
                 let _guard = term_ctx.display_step();
 
-                nexus_core::prover::nova::pp::load_pp(path_str)?
+                nexus_core::prover::nova::pp::load_pp_for(path_str, prover, k)?
             };
 
             let mut vs = (0..num_steps)
@@ -273,7 +312,7 @@ fn local_prove(
             let mut context = term.context("Saving").on_step(|_step| "proof".into());
             let _guard = context.display_step();
 
-            nexus_core::prover::nova::save_proof(root, &proof_path)?;
+            save_proof(&root, prover, k, &state.digest, &proof_path)?;
         }
         vm_config::NovaImpl::ParallelCompressible => {
             assert!((num_steps + 1).is_power_of_two());
@@ -285,7 +324,7 @@ fn local_prove(
                     .on_step(|_step| "public parameters".into());
                 let _guard = term_ctx.display_step();
 
-                nexus_core::prover::nova::pp::load_pp(path_str)?
+                nexus_core::prover::nova::pp::load_pp_for(path_str, prover, k)?
             };
 
             let mut vs = (0..num_steps)
@@ -322,7 +361,7 @@ fn local_prove(
             let mut context = term.context("Saving").on_step(|_step| "proof".into());
             let _guard = context.display_step();
 
-            nexus_core::prover::nova::save_proof(root, &proof_path)?;
+            save_proof(&root, prover, k, &state.digest, &proof_path)?;
         }
         vm_config::NovaImpl::Sequential => {
             let mut iterm = TerminalHandle::new_enabled();
@@ -332,7 +371,7 @@ fn local_prove(
                     .on_step(|_step| "public parameters".into());
                 let _guard = term_ctx.display_step();
 
-                nexus_core::prover::nova::pp::load_pp(path_str)?
+                nexus_core::prover::nova::pp::load_pp_for(path_str, prover, k)?
             };
 
             let mut proof = nexus_core::prover::nova::prove_seq_step(None, &state, &tr)?;
@@ -345,9 +384,184 @@ fn local_prove(
             let mut context = term.context("Saving").on_step(|_step| "proof".into());
             let _guard = context.display_step();
 
-            nexus_core::prover::nova::save_proof(proof, &proof_path)?;
+            save_proof(&proof, prover, k, &state.digest, &proof_path)?;
+        }
+        vm_config::NovaImpl::SequentialOffline => unreachable!(),
+    }
+
+    Ok(())
+}
+
+fn prove_offline(
+    opts: &nexus_core::nvm::VMOpts,
+    pp_path: &str,
+    proof_path: &Path,
+) -> anyhow::Result<()> {
+    let prover = vm_config::ProverImpl::Nova(vm_config::NovaImpl::SequentialOffline);
+    let mut vm = nexus_core::nvm::load_vm::<OfflineMemory>(opts)?;
+    let mut term = TerminalHandle::new_enabled();
+
+    let mut iterm = TerminalHandle::new_enabled();
+    let state = {
+        let mut term_ctx = iterm
+            .context("Loading")
+            .on_step(|_step| "public parameters".into());
+        let _guard = term_ctx.display_step();
+
+        nexus_core::prover::nova::pp::load_pp_for(pp_path, prover, opts.k)?
+    };
+
+    let proof = {
+        let mut term_ctx = term
+            .context("Computing")
+            .on_step(|_step| "proof".into())
+            .completion_header("Proved");
+        let _guard = term_ctx.display_step();
+
+        nexus_core::prover::nova::prove_seq_offline(&state, &mut vm, opts.k)?
+    };
+
+    let mut context = term.context("Saving").on_step(|_step| "proof".into());
+    let _guard = context.display_step();
+
+    save_proof(&proof, prover, opts.k, &state.digest, proof_path)?;
+
+    Ok(())
+}
+
+fn prove_hypernova(
+    hypernova_impl: vm_config::HyperNovaImpl,
+    pp_path: &str,
+    tr: &nexus_core::prover::hypernova::types::SC,
+    mut term_ctx: TerminalContext<'_>,
+    proof_path: &Path,
+) -> anyhow::Result<()> {
+    let num_steps = tr.steps();
+    let prover = vm_config::ProverImpl::HyperNova(hypernova_impl);
+    let mut term = TerminalHandle::new_enabled();
+
+    match hypernova_impl {
+        // compressible PCD proofs are produced by the parallel prover, and only
+        // differ in the size of the SRS the public parameters were generated with
+        vm_config::HyperNovaImpl::Parallel | vm_config::HyperNovaImpl::ParallelCompressible => {
+            assert!((num_steps + 1).is_power_of_two());
+
+            let mut iterm = TerminalHandle::new_enabled();
+            let state = {
+                let mut term_ctx = iterm
+                    .context("Loading")
+                    .on_step(|_step| "public parameters".into());
+                let _guard = term_ctx.display_step();
+
+                nexus_core::prover::hypernova::pp::load_pp_for(pp_path, prover, tr.0.k)?
+            };
+
+            let mut vs = (0..num_steps)
+                .step_by(2)
+                .map(|i| {
+                    let _guard = term_ctx.display_step();
+
+                    let v = nexus_core::prover::hypernova::prove_par_leaf_step(&state, tr, i)?;
+                    Ok(v)
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            while vs.len() > 1 {
+                vs = vs
+                    .chunks(2)
+                    .map(|ab| {
+                        let _guard = term_ctx.display_step();
+                        let c = nexus_core::prover::hypernova::prove_par_parent_step(
+                            &state, tr, &ab[0], &ab[1],
+                        )?;
+                        Ok(c)
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+            }
+            let root = vs.into_iter().next().unwrap();
+
+            let mut context = term.context("Saving").on_step(|_step| "proof".into());
+            let _guard = context.display_step();
+
+            save_proof(&root, prover, tr.0.k, &state.digest, proof_path)?;
+        }
+        vm_config::HyperNovaImpl::Sequential => {
+            let mut iterm = TerminalHandle::new_enabled();
+            let state = {
+                let mut term_ctx = iterm
+                    .context("Loading")
+                    .on_step(|_step| "public parameters".into());
+                let _guard = term_ctx.display_step();
+
+                nexus_core::prover::hypernova::pp::load_pp_for(pp_path, prover, tr.0.k)?
+            };
+
+            let mut proof = nexus_core::prover::hypernova::prove_seq_step(None, &state, tr)?;
+
+            for _ in 1..num_steps {
+                let _guard = term_ctx.display_step();
+                proof = nexus_core::prover::hypernova::prove_seq_step(Some(proof), &state, tr)?;
+            }
+
+            let mut context = term.context("Saving").on_step(|_step| "proof".into());
+            let _guard = context.display_step();
+
+            save_proof(&proof, prover, tr.0.k, &state.digest, proof_path)?;
         }
     }
 
     Ok(())
 }
+
+fn prove_supernova(
+    pp_path: &str,
+    tr: &nexus_core::prover::supernova::types::SC,
+    mut term_ctx: TerminalContext<'_>,
+    proof_path: &Path,
+) -> anyhow::Result<()> {
+    let num_steps = tr.steps();
+    let mut term = TerminalHandle::new_enabled();
+
+    let mut iterm = TerminalHandle::new_enabled();
+    let state = {
+        let mut term_ctx = iterm
+            .context("Loading")
+            .on_step(|_step| "public parameters".into());
+        let _guard = term_ctx.display_step();
+
+        nexus_core::prover::supernova::pp::load_pp(pp_path)?
+    };
+
+    let mut proof = nexus_core::prover::supernova::prove_seq_step(None, &state, tr)?;
+
+    for _ in 1..num_steps {
+        let _guard = term_ctx.display_step();
+        proof = nexus_core::prover::supernova::prove_seq_step(Some(proof), &state, tr)?;
+    }
+
+    let mut context = term.context("Saving").on_step(|_step| "proof".into());
+    let _guard = context.display_step();
+
+    save_proof(
+        &proof,
+        vm_config::ProverImpl::SuperNova,
+        tr.0.k,
+        &state.digest,
+        proof_path,
+    )?;
+
+    Ok(())
+}
+
+/// Save `proof` in the envelope format shared with the SDK and the network.
+fn save_proof(
+    proof: &impl CanonicalSerialize,
+    prover: vm_config::ProverImpl,
+    k: usize,
+    pp_digest: &impl CanonicalSerialize,
+    path: &Path,
+) -> anyhow::Result<()> {
+    let header = ProofHeader::new(prover, k, false, pp_digest)?;
+    ProofEnvelope::new(header, None, proof)?.save(path)?;
+    Ok(())
+}
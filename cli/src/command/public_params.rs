@@ -6,17 +6,20 @@ use std::{
 use anyhow::Context;
 
 use nexus_core::config::{
-    vm::{self as vm_config, ProverImpl},
+    vm::{self as vm_config, HyperNovaImpl, ProverImpl},
     Config,
 };
-use nexus_core::prover::nova::srs::{get_min_srs_size, test_srs::gen_test_srs_to_file};
-use nexus_core::prover::nova::types::{ComPP, ParPP, SeqPP, SRS};
+use nexus_core::prover::hypernova::types as hypernova_types;
+use nexus_core::prover::nova::types::{ComPP, OfflineSeqPP, ParPP, SeqPP, SRS};
+use nexus_core::prover::params::ParamsHeader;
 use nexus_progress_bar::TerminalHandle;
 
 use crate::{command::cache_path, LOG_TARGET};
 
 mod command_args;
-pub use command_args::{PublicParamsAction, PublicParamsArgs, SRSSetupArgs, SetupArgs};
+pub use command_args::{
+    InspectArgs, PublicParamsAction, PublicParamsArgs, SRSSetupArgs, SetupArgs,
+};
 
 pub fn handle_command(args: PublicParamsArgs) -> anyhow::Result<()> {
     let action = args
@@ -29,6 +32,7 @@ pub fn handle_command(args: PublicParamsArgs) -> anyhow::Result<()> {
         PublicParamsAction::SampleTestSRS(srs_setup_args) => {
             let _ = sample_test_srs(srs_setup_args)?;
         }
+        PublicParamsAction::Inspect(inspect_args) => inspect_params(inspect_args)?,
     }
     Ok(())
 }
@@ -38,22 +42,25 @@ pub(crate) fn setup_params(args: SetupArgs) -> anyhow::Result<PathBuf> {
 
     let force = args.force;
     let k = args.k.unwrap_or(vm_config.k);
-    let nova_impl = if let Some(nova_impl) = args.nova_impl {
-        nova_impl
-    } else {
-        match vm_config.prover {
-            ProverImpl::Jolt => anyhow::bail!("Jolt doesn't require Nova-setup"),
-            ProverImpl::HyperNova => anyhow::bail!("HyperNova not yet supported through the CLI"),
-            ProverImpl::Nova(nova_impl) => nova_impl,
+    let prover_impl = args.prover_impl.unwrap_or(vm_config.prover);
+    if let ProverImpl::Jolt = prover_impl {
+        anyhow::bail!("Jolt doesn't require Nova-setup")
+    }
+    if let ProverImpl::SuperNova = prover_impl {
+        if k != nexus_core::prover::supernova::K {
+            anyhow::bail!(
+                "SuperNova proves a single instruction per step, k must be {}",
+                nexus_core::prover::supernova::K
+            )
         }
-    };
+    }
 
     let srs_file = args.srs_file;
 
     let path = match args.path {
         Some(path) => path,
         None => {
-            let pp_file_name = format_params_file(nova_impl, k);
+            let pp_file_name = format_params_file(prover_impl, k);
             let cache_path = cache_path()?;
 
             cache_path.join(pp_file_name)
@@ -69,7 +76,14 @@ pub(crate) fn setup_params(args: SetupArgs) -> anyhow::Result<PathBuf> {
         return Ok(path);
     }
 
-    setup_params_to_file(&path, nova_impl, k, srs_file)?;
+    match prover_impl {
+        ProverImpl::Nova(nova_impl) => setup_params_to_file(&path, nova_impl, k, srs_file)?,
+        ProverImpl::HyperNova(hypernova_impl) => {
+            setup_hypernova_params_to_file(&path, hypernova_impl, k, srs_file)?
+        }
+        ProverImpl::SuperNova => setup_supernova_params_to_file(&path)?,
+        ProverImpl::Jolt => unreachable!(),
+    }
     Ok(path)
 }
 
@@ -99,7 +113,7 @@ fn setup_params_to_file(
                 nexus_core::prover::nova::pp::gen_vm_pp(k, &())?
             };
             nexus_core::prover::nova::pp::show_pp(&pp);
-            nexus_core::prover::nova::pp::save_pp(&pp, path)
+            nexus_core::prover::nova::pp::save_pp(&pp, ProverImpl::Nova(nova_impl), k, path)
         }
         vm_config::NovaImpl::Parallel => {
             tracing::info!(
@@ -109,27 +123,20 @@ fn setup_params_to_file(
             let pp: ParPP = nexus_core::prover::nova::pp::gen_vm_pp(k, &())?;
 
             nexus_core::prover::nova::pp::show_pp(&pp);
-            nexus_core::prover::nova::pp::save_pp(&pp, path)
+            nexus_core::prover::nova::pp::save_pp(&pp, ProverImpl::Nova(nova_impl), k, path)
         }
-        vm_config::NovaImpl::ParallelCompressible => {
-            let srs_file = match srs_file {
-                None => {
-                    let srs_file_name = format_srs_file(get_min_srs_size(k)?);
-                    let cache_path = cache_path()?;
-
-                    cache_path.join(srs_file_name)
-                }
-                Some(file) => file,
-            };
-
-            if !srs_file.try_exists()? {
-                tracing::error!(
+        vm_config::NovaImpl::SequentialOffline => {
+            tracing::info!(
                 target: LOG_TARGET,
-                "path {} was not found",
-                srs_file.display(),
-                );
-                return Err(io::Error::from(io::ErrorKind::NotFound).into());
-            }
+                "Generating IVC public parameters for offline memory checking",
+            );
+            let pp: OfflineSeqPP = nexus_core::prover::nova::pp::gen_vm_pp(k, &())?;
+
+            nexus_core::prover::nova::pp::show_pp(&pp);
+            nexus_core::prover::nova::pp::save_pp(&pp, ProverImpl::Nova(nova_impl), k, path)
+        }
+        vm_config::NovaImpl::ParallelCompressible => {
+            let srs_file = find_srs_file(srs_file, ProverImpl::Nova(nova_impl), k)?;
             let srs_file_str = srs_file.to_str().context("path is not valid utf8")?;
 
             tracing::info!(
@@ -156,18 +163,218 @@ fn setup_params_to_file(
             };
 
             nexus_core::prover::nova::pp::show_pp(&pp);
-            nexus_core::prover::nova::pp::save_pp(&pp, path)
+            nexus_core::prover::nova::pp::save_pp(&pp, ProverImpl::Nova(nova_impl), k, path)
         }
     };
     Ok(())
 }
 
+fn setup_hypernova_params_to_file(
+    path: &Path,
+    hypernova_impl: HyperNovaImpl,
+    k: usize,
+    srs_file: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let path = path.to_str().context("path is not valid utf8")?;
+
+    // HyperNova commits to the witness of the augmented circuit with Zeromorph,
+    // so every variant needs an SRS.
+    let srs_file = find_srs_file(srs_file, ProverImpl::HyperNova(hypernova_impl), k)?;
+    let srs_file_str = srs_file.to_str().context("path is not valid utf8")?;
+
+    tracing::info!(
+        target: LOG_TARGET,
+        path =?srs_file,
+        "Reading the SRS",
+    );
+    let srs: hypernova_types::SRS = nexus_core::prover::hypernova::srs::load_srs(srs_file_str)?;
+
+    tracing::info!(
+        target: LOG_TARGET,
+        path =?srs_file,
+        "SRS found for a maximum of {} variables",
+        srs.max_num_vars
+    );
+
+    let mut term = TerminalHandle::new_enabled();
+
+    let _ = match hypernova_impl {
+        HyperNovaImpl::Sequential => {
+            tracing::info!(
+                target: LOG_TARGET,
+                "Generating HyperNova IVC public parameters",
+            );
+
+            let pp: hypernova_types::PP = {
+                let mut term_ctx = term
+                    .context("Setting up")
+                    .on_step(|_step| "public parameters for IVC".into());
+                let _guard = term_ctx.display_step();
+
+                nexus_core::prover::hypernova::pp::gen_vm_pp(k, &srs, &())?
+            };
+            nexus_core::prover::hypernova::pp::show_pp(&pp);
+            nexus_core::prover::hypernova::pp::save_pp(
+                &pp,
+                ProverImpl::HyperNova(hypernova_impl),
+                k,
+                path,
+            )
+        }
+        // compressible PCD proofs share the public parameters of the parallel prover
+        HyperNovaImpl::Parallel | HyperNovaImpl::ParallelCompressible => {
+            tracing::info!(
+                target: LOG_TARGET,
+                "Generating HyperNova PCD public parameters",
+            );
+
+            let pp: hypernova_types::ParPP = {
+                let mut term_ctx = term
+                    .context("Setting up")
+                    .on_step(|_step| "public parameters for PCD".into());
+                let _guard = term_ctx.display_step();
+
+                nexus_core::prover::hypernova::pp::gen_vm_pp(k, &srs, &())?
+            };
+            nexus_core::prover::hypernova::pp::show_pp(&pp);
+            nexus_core::prover::hypernova::pp::save_pp(
+                &pp,
+                ProverImpl::HyperNova(hypernova_impl),
+                k,
+                path,
+            )
+        }
+    };
+    Ok(())
+}
+
+fn setup_supernova_params_to_file(path: &Path) -> anyhow::Result<()> {
+    let path = path.to_str().context("path is not valid utf8")?;
+
+    tracing::info!(
+        target: LOG_TARGET,
+        "Generating SuperNova public parameters",
+    );
+
+    let mut term = TerminalHandle::new_enabled();
+    let pp = {
+        let mut term_ctx = term
+            .context("Setting up")
+            .on_step(|_step| "public parameters for NIVC".into());
+        let _guard = term_ctx.display_step();
+
+        nexus_core::prover::supernova::pp::gen_vm_pp()?
+    };
+    nexus_core::prover::supernova::pp::show_pp(&pp);
+    nexus_core::prover::supernova::pp::save_pp(&pp, path)?;
+    Ok(())
+}
+
+fn inspect_params(args: InspectArgs) -> anyhow::Result<()> {
+    let path = match args.path {
+        Some(path) => path,
+        None => {
+            let vm_config = vm_config::VmConfig::from_env()?;
+            let k = args.k.unwrap_or(vm_config.k);
+            let prover_impl = args.prover_impl.unwrap_or(vm_config.prover);
+            let pp_file_name = format_params_file(prover_impl, k);
+            let cache_path = cache_path()?;
+
+            cache_path.join(pp_file_name)
+        }
+    };
+
+    if !path.try_exists()? {
+        tracing::error!(
+            target: LOG_TARGET,
+            "path {} was not found",
+            path.display(),
+        );
+        return Err(io::Error::from(io::ErrorKind::NotFound).into());
+    }
+
+    println!("path:   {}", path.display());
+    match ParamsHeader::load(&path)? {
+        Some(header) => {
+            println!("prover: {}", header.prover());
+            println!("k:      {}", header.k);
+            println!("digest: {}", header.digest_hex());
+        }
+        None => {
+            println!("no header: written by an older version, use `setup --force` to regenerate")
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the public parameters at `path` were generated for `prover` with `k`, and
+/// that they are the ones a proof recording the serialized parameter digest `digest` was
+/// produced with.
+///
+/// Files written without a header are not checked.
+pub(crate) fn check_params_file(
+    path: &Path,
+    prover: ProverImpl,
+    k: usize,
+    digest: &[u8],
+) -> anyhow::Result<()> {
+    match ParamsHeader::load(path)? {
+        Some(header) => {
+            header.check(prover, k)?;
+            header.check_digest(digest)?;
+        }
+        None => tracing::warn!(
+            target: LOG_TARGET,
+            "public parameters {} have no header and cannot be checked, use `setup --force` to regenerate them",
+            path.display(),
+        ),
+    }
+    Ok(())
+}
+
+/// Returns `srs_file`, or the default cached SRS for `prover` if it is `None`, checking that it exists.
+pub(crate) fn find_srs_file(
+    srs_file: Option<PathBuf>,
+    prover: ProverImpl,
+    k: usize,
+) -> anyhow::Result<PathBuf> {
+    let srs_file = match srs_file {
+        None => {
+            let srs_file_name = format_srs_file(get_min_srs_size(prover, k)?);
+            let cache_path = cache_path()?;
+
+            cache_path.join(srs_file_name)
+        }
+        Some(file) => file,
+    };
+
+    if !srs_file.try_exists()? {
+        tracing::error!(
+            target: LOG_TARGET,
+            "path {} was not found",
+            srs_file.display(),
+        );
+        return Err(io::Error::from(io::ErrorKind::NotFound).into());
+    }
+    Ok(srs_file)
+}
+
+/// Minimum (log) size of the SRS needed by `prover` for a given `k`.
+pub(crate) fn get_min_srs_size(prover: ProverImpl, k: usize) -> anyhow::Result<usize> {
+    Ok(match prover {
+        ProverImpl::HyperNova(_) => nexus_core::prover::hypernova::srs::get_min_srs_size(k)?,
+        ProverImpl::SuperNova => anyhow::bail!("SuperNova doesn't require an SRS"),
+        _ => nexus_core::prover::nova::srs::get_min_srs_size(k)?,
+    })
+}
+
 pub fn sample_test_srs(args: SRSSetupArgs) -> anyhow::Result<PathBuf> {
     let num_vars = match args.num_vars {
         None => {
             let vm_config = vm_config::VmConfig::from_env()?;
             let k = args.k.unwrap_or(vm_config.k);
-            get_min_srs_size(k)?
+            let prover_impl = args.prover_impl.unwrap_or(vm_config.prover);
+            get_min_srs_size(prover_impl, k)?
         }
         Some(num_vars) => num_vars,
     };
@@ -193,13 +400,14 @@ pub fn sample_test_srs(args: SRSSetupArgs) -> anyhow::Result<PathBuf> {
 
     let path_str = path.to_str().context("path is not valid utf8")?;
 
-    gen_test_srs_to_file(num_vars, path_str)?;
+    // both provers use the same Zeromorph SRS over BN254
+    nexus_core::prover::nova::srs::test_srs::gen_test_srs_to_file(num_vars, path_str)?;
     Ok(path)
 }
 
 // TODO: make it accessible to all crates.
-pub fn format_params_file(nova_impl: vm_config::NovaImpl, k: usize) -> String {
-    format!("nexus-public-{nova_impl}-{k}.zst")
+pub fn format_params_file(prover_impl: ProverImpl, k: usize) -> String {
+    format!("nexus-public-{prover_impl}-{k}.zst")
 }
 
 pub fn format_srs_file(num_vars: usize) -> String {
@@ -15,6 +15,8 @@ pub enum PublicParamsAction {
     Setup(SetupArgs),
     /// Sample SRS for testing to file: NOT SECURE, and memory-heavy operation.
     SampleTestSRS(SRSSetupArgs),
+    /// Print the prover, `k` and digest recorded in a public parameters file.
+    Inspect(InspectArgs),
 }
 
 #[derive(Debug, Default, Args)]
@@ -27,6 +29,10 @@ pub struct SRSSetupArgs {
     #[arg(short = 'n', long = "num-vars")]
     pub num_vars: Option<usize>,
 
+    /// Prover the SRS is sampled for; defaults to reading value from vm config.
+    #[arg(long("impl"))]
+    pub prover_impl: Option<vm_config::ProverImpl>,
+
     /// File to save test SRS
     #[arg(short, long)]
     pub file: Option<PathBuf>,
@@ -43,7 +49,7 @@ pub struct SetupArgs {
     pub k: Option<usize>,
 
     #[arg(long("impl"))]
-    pub nova_impl: Option<vm_config::NovaImpl>,
+    pub prover_impl: Option<vm_config::ProverImpl>,
 
     /// Where to save the file.
     #[arg(short, long)]
@@ -53,7 +59,22 @@ pub struct SetupArgs {
     #[arg(long)]
     pub force: bool,
 
-    /// Path to the SRS file (only required for compressible PCD proofs).
+    /// Path to the SRS file (only required for HyperNova and compressible Nova PCD proofs).
     #[arg(long("srs_file"))]
     pub srs_file: Option<PathBuf>,
 }
+
+#[derive(Debug, Default, Args)]
+pub struct InspectArgs {
+    /// Number of vm instructions per fold; used to locate the default file.
+    #[arg(short, name = "k")]
+    pub k: Option<usize>,
+
+    /// Prover the parameters were generated for; used to locate the default file.
+    #[arg(long("impl"))]
+    pub prover_impl: Option<vm_config::ProverImpl>,
+
+    /// Path to the public parameters file; defaults to the cached file for the vm config.
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+}
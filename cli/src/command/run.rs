@@ -1,6 +1,6 @@
 use clap::Args;
 use std::collections::HashSet;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::utils::{cargo, path_to_artifact};
 
@@ -28,12 +28,25 @@ pub struct RunArgs {
         use_value_delimiter = true
     )]
     pub features: Vec<String>,
+
+    /// Wait for a GDB client to attach on the given local port before running.
+    #[arg(
+        long,
+        value_name = "PORT",
+        num_args = 0..=1,
+        default_missing_value = "1234"
+    )]
+    pub gdb: Option<u16>,
+
+    /// Profile the guest by function, writing <PATH>.folded (folded stacks) and <PATH>.pb (pprof).
+    #[arg(long, value_name = "PATH", conflicts_with = "gdb")]
+    pub profile_output: Option<PathBuf>,
 }
 
 pub fn handle_command(args: RunArgs) -> anyhow::Result<()> {
-    let RunArgs { verbose, profile, bin, features } = args;
+    let RunArgs { verbose, profile, bin, features, gdb } = args;
 
-    run_vm(bin, verbose, &profile, features)
+    run_vm(bin, verbose, &profile, features, gdb)
 }
 
 fn run_vm(
@@ -41,13 +54,15 @@ fn run_vm(
     verbose: bool,
     profile: &str,
     features: Vec<String>,
+    gdb: Option<u16>,
+    profile_output: Option<PathBuf>,
 ) -> anyhow::Result<()> {
     let allowed_features: HashSet<_> = ALLOWED_FEATURES.iter().cloned().collect();
 
     // Build cargo arguments
     let mut cargo_args = vec![
         "build",
-        "--target=riscv32i-unknown-none-elf",
+        "--target=riscv32im-unknown-none-elf",
         "--profile",
         profile,
     ];
@@ -71,6 +86,12 @@ fn run_vm(
 
     let path = path_to_artifact(bin, profile)?;
 
+    if let Some(port) = gdb {
+        return debug_vm_with_elf_file(&path, port);
+    }
+    if let Some(output) = profile_output {
+        return profile_vm_with_elf_file(&path, &output);
+    }
     run_vm_with_elf_file(&path, verbose)
 }
 
@@ -84,3 +105,22 @@ pub fn run_vm_with_elf_file(path: &Path, verbose: bool) -> anyhow::Result<()> {
     nexus_core::nvm::run_vm::<nexus_core::nvm::memory::Paged>(&opts, true, verbose)
         .map_err(Into::into)
 }
+
+pub fn debug_vm_with_elf_file(path: &Path, port: u16) -> anyhow::Result<()> {
+    let opts = nexus_core::nvm::VMOpts {
+        k: 1,
+        machine: None,
+        file: Some(path.into()),
+    };
+
+    let mut vm = nexus_core::nvm::load_vm::<nexus_core::nvm::memory::Paged>(&opts)?;
+    vm.syscalls.enable_stdout();
+
+    println!("Waiting for debugger on 127.0.0.1:{port}");
+    nexus_core::nvm::gdb::listen(&mut vm, port).map_err(Into::into)
+}
+
+pub fn profile_vm_with_elf_file(path: &Path, output: &Path) -> anyhow::Result<()> {
+    nexus_core::nvm::profile_vm::<nexus_core::nvm::memory::Paged>(&path.into(), true, output)
+        .map_err(Into::into)
+}
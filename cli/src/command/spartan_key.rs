@@ -6,11 +6,14 @@ use std::{
 
 use anyhow::Context;
 
-use nexus_core::config::{vm as vm_config, Config};
-use nexus_core::prover::nova::{srs::get_min_srs_size, types::ComPP};
+use nexus_core::config::{
+    vm::{self as vm_config, HyperNovaImpl, NovaImpl, ProverImpl},
+    Config,
+};
+use nexus_core::prover::{hypernova::types::ParPP as HyperNovaParPP, nova::types::ComPP};
 use nexus_progress_bar::TerminalHandle;
 
-use super::public_params::{format_params_file, format_srs_file};
+use super::public_params::{find_srs_file, format_params_file};
 use crate::{command::cache_path, LOG_TARGET};
 
 #[derive(Debug, Args)]
@@ -46,10 +49,27 @@ pub struct SetupArgs {
     /// Path to the Zeromorph structured reference string.
     #[arg(short = 's', long = "srs")]
     pub srs_file: Option<PathBuf>,
+
+    /// Prover the compressed proofs are produced with; defaults to reading value from vm config.
+    #[arg(long("impl"))]
+    pub prover_impl: Option<ProverImpl>,
+}
+
+pub fn format_key_file(prover_impl: ProverImpl, k: usize) -> String {
+    match prover_impl {
+        ProverImpl::HyperNova(_) => format!("nexus-spartan-key-hypernova-{k}.zst"),
+        _ => format!("nexus-spartan-key-{k}.zst"),
+    }
 }
 
-pub fn format_key_file(k: usize) -> String {
-    format!("nexus-spartan-key-{k}.zst")
+/// Returns the compressible variant of `prover_impl`, failing for provers without compression.
+pub(crate) fn compressible_impl(prover_impl: ProverImpl) -> anyhow::Result<ProverImpl> {
+    Ok(match prover_impl {
+        ProverImpl::Jolt => anyhow::bail!("Jolt proofs cannot be compressed"),
+        ProverImpl::SuperNova => anyhow::bail!("SuperNova proofs cannot be compressed"),
+        ProverImpl::Nova(_) => ProverImpl::Nova(NovaImpl::ParallelCompressible),
+        ProverImpl::HyperNova(_) => ProverImpl::HyperNova(HyperNovaImpl::ParallelCompressible),
+    })
 }
 
 pub fn handle_command(args: SpartanSetupArgs) -> anyhow::Result<()> {
@@ -69,10 +89,10 @@ pub(crate) fn spartan_setup(args: SetupArgs) -> anyhow::Result<PathBuf> {
 
     let force = args.force;
     let k = args.k.unwrap_or(vm_config.k);
-    let nova_impl = vm_config::NovaImpl::ParallelCompressible;
+    let prover_impl = compressible_impl(args.prover_impl.unwrap_or(vm_config.prover))?;
     let pp_file = match args.pp_file {
         None => {
-            let pp_file = format_params_file(nova_impl, k);
+            let pp_file = format_params_file(prover_impl, k);
             let cache_path = cache_path()?;
 
             cache_path.join(pp_file)
@@ -88,28 +108,12 @@ pub(crate) fn spartan_setup(args: SetupArgs) -> anyhow::Result<PathBuf> {
         return Err(io::Error::from(io::ErrorKind::NotFound).into());
     }
 
-    let srs_file = match args.srs_file {
-        None => {
-            let srs_file_name = format_srs_file(get_min_srs_size(k)?);
-            let cache_path = cache_path()?;
-
-            cache_path.join(srs_file_name)
-        }
-        Some(path) => path,
-    };
-    if !srs_file.try_exists()? {
-        tracing::error!(
-            target: LOG_TARGET,
-            "path {} was not found",
-            srs_file.display(),
-        );
-        return Err(io::Error::from(io::ErrorKind::NotFound).into());
-    }
+    let srs_file = find_srs_file(args.srs_file, prover_impl, k)?;
 
     let key_path = match args.path {
         Some(path) => path,
         None => {
-            let key_file_name = format_key_file(vm_config.k);
+            let key_file_name = format_key_file(prover_impl, vm_config.k);
             let cache_path = cache_path()?;
             cache_path.join(key_file_name)
         }
@@ -122,11 +126,16 @@ pub(crate) fn spartan_setup(args: SetupArgs) -> anyhow::Result<PathBuf> {
         );
         return Ok(key_path);
     }
-    spartan_setup_to_file(&key_path, &pp_file, &srs_file)?;
+    spartan_setup_to_file(prover_impl, &key_path, &pp_file, &srs_file)?;
     Ok(key_path)
 }
 
-fn spartan_setup_to_file(key_path: &Path, pp_path: &Path, srs_path: &Path) -> anyhow::Result<()> {
+fn spartan_setup_to_file(
+    prover_impl: ProverImpl,
+    key_path: &Path,
+    pp_path: &Path,
+    srs_path: &Path,
+) -> anyhow::Result<()> {
     let key_path = key_path.to_str().context("path is not valid utf8")?;
     let pp_path_str = pp_path.to_str().context("path is not valid utf8")?;
     let srs_path_str = srs_path.to_str().context("path is not valid utf8")?;
@@ -151,6 +160,32 @@ fn spartan_setup_to_file(key_path: &Path, pp_path: &Path, srs_path: &Path) -> an
         srs.max_num_vars
     );
 
+    if let ProverImpl::HyperNova(_) = prover_impl {
+        tracing::info!(
+            target: LOG_TARGET,
+            pp_file =?pp_path_str,
+            "Reading the HyperNova public parameters",
+        );
+
+        let pp: HyperNovaParPP = {
+            let mut term_ctx = term
+                .context("Loading")
+                .on_step(|_step| "HyperNova public parameters".into());
+            let _guard = term_ctx.display_step();
+
+            nexus_core::prover::hypernova::pp::load_pp(pp_path_str)?
+        };
+
+        let mut term_ctx = term
+            .context("Generating")
+            .on_step(|_step| "Spartan key".into());
+        let _guard = term_ctx.display_step();
+
+        nexus_core::prover::hypernova::key::gen_key_to_file(&pp, &srs, key_path)?;
+
+        return Ok(());
+    }
+
     tracing::info!(
         target: LOG_TARGET,
         pp_file =?pp_path_str,
@@ -1,25 +1,28 @@
-use std::{
-    fs::File,
-    io::BufReader,
-    path::{Path, PathBuf},
-};
+use std::path::{Path, PathBuf};
 
 use anyhow::Context;
-use ark_serialize::CanonicalDeserialize;
 use clap::Args;
 
 use super::{
     jolt,
     prove::{CommonProveArgs, LocalProveArgs},
-    public_params::format_params_file,
-    spartan_key::format_key_file,
+    public_params::{check_params_file, format_params_file},
+    spartan_key::{compressible_impl, format_key_file},
 };
-use crate::{command::cache_path, LOG_TARGET};
+use crate::{command::cache_path, utils::path_to_artifact, LOG_TARGET};
 use nexus_core::config::{
-    vm::{NovaImpl, ProverImpl, VmConfig},
+    vm::{HyperNovaImpl, NovaImpl, ProverImpl, VmConfig},
     Config,
 };
-use nexus_core::prover::nova::types::{ComPCDNode, ComProof, IVCProof, PCDNode};
+use nexus_core::nvm::{program_id, ProgramId};
+use nexus_core::prover::envelope::ProofEnvelope;
+use nexus_core::prover::hypernova::types as hypernova_types;
+use nexus_core::prover::nova::error::ProofError;
+use nexus_core::prover::nova::types::{
+    self as nova_types, ComPCDNode, ComProof, IVCProof, PCDNode, F1,
+};
+use nexus_core::prover::nova::AuditedIVCProof;
+use nexus_core::prover::supernova::types as supernova_types;
 use nexus_progress_bar::TerminalHandle;
 
 #[derive(Debug, Args)]
@@ -47,19 +50,26 @@ pub fn handle_command(args: VerifyArgs) -> anyhow::Result<()> {
     let VerifyArgs {
         file,
         compressed,
-        prover_args: LocalProveArgs { k, pp_file, prover_impl: nova_impl, .. },
+        prover_args: LocalProveArgs { k, pp_file, prover_impl, .. },
         key_file,
         common_args,
     } = args;
 
     let vm_config = VmConfig::from_env()?;
     if compressed {
-        verify_proof_compressed(&file, k.unwrap_or(vm_config.k), pp_file, key_file)
+        verify_proof_compressed(
+            &file,
+            k.unwrap_or(vm_config.k),
+            prover_impl.unwrap_or(vm_config.prover),
+            pp_file,
+            key_file,
+            common_args,
+        )
     } else {
         verify_proof(
             &file,
             k.unwrap_or(vm_config.k),
-            nova_impl.unwrap_or(vm_config.prover),
+            prover_impl.unwrap_or(vm_config.prover),
             common_args,
             pp_file,
         )
@@ -69,16 +79,21 @@ pub fn handle_command(args: VerifyArgs) -> anyhow::Result<()> {
 fn verify_proof_compressed(
     path: &Path,
     k: usize,
+    prover: ProverImpl,
     pp_file: Option<PathBuf>,
     key_file: Option<PathBuf>,
+    prove_args: CommonProveArgs,
 ) -> anyhow::Result<()> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
+    let prover = compressible_impl(prover)?;
+    let program = load_program_id(prove_args)?;
+
+    let envelope = ProofEnvelope::load(path)?;
+    envelope.check(prover, k, true)?;
 
     let pp_path = match pp_file {
         Some(path) => path,
         None => {
-            let pp_file_name = format_params_file(NovaImpl::ParallelCompressible, k);
+            let pp_file_name = format_params_file(prover, k);
             let cache_path = cache_path()?;
 
             cache_path.join(pp_file_name)
@@ -87,11 +102,12 @@ fn verify_proof_compressed(
     .to_str()
     .context("path is not utf-8")?
     .to_owned();
+    check_params_file(Path::new(&pp_path), prover, k, &envelope.header.pp_digest)?;
 
     let key_path = match key_file {
         Some(path) => path,
         None => {
-            let key_file_name = format_key_file(k);
+            let key_file_name = format_key_file(prover, k);
             let cache_path = cache_path()?;
 
             cache_path.join(key_file_name)
@@ -102,27 +118,40 @@ fn verify_proof_compressed(
     .to_owned();
 
     let mut term = TerminalHandle::new_enabled();
-    let params = {
-        let mut ctx = term
-            .context("Loading")
-            .on_step(|_step| "public parameters".into());
-        let _guard = ctx.display_step();
-
-        nexus_core::prover::nova::pp::load_pp(&pp_path)?
-    };
+    let mut iterm = TerminalHandle::new_enabled();
+    let mut load_ctx = iterm
+        .context("Loading")
+        .on_step(|_step| "public parameters".into());
 
     let mut ctx = term
         .context("Verifying compressed")
         .on_step(move |_step| "proof".into());
     let mut _guard = Default::default();
 
-    let result = {
-        let proof = ComProof::deserialize_compressed(reader)?;
+    let result = if let ProverImpl::HyperNova(_) = prover {
+        let params = {
+            let _guard = load_ctx.display_step();
+            nexus_core::prover::hypernova::pp::load_pp(&pp_path)?
+        };
+        let proof: hypernova_types::ComProof = envelope.proof()?;
+        let key = nexus_core::prover::hypernova::key::load_key(&key_path)?;
+
+        _guard = ctx.display_step();
+        hypernova_types::com::SNARK::verify(&key, &params, &proof)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| check_program(&program, proof.i, &proof.z_i))
+    } else {
+        let params = {
+            let _guard = load_ctx.display_step();
+            nexus_core::prover::nova::pp::load_pp(&pp_path)?
+        };
+        let proof: ComProof = envelope.proof()?;
         let key = nexus_core::prover::nova::key::load_key(&key_path)?;
 
         _guard = ctx.display_step();
-        nexus_core::prover::nova::verify_compressed(&key, &params, &proof)
+        nova_types::com::SNARK::verify(&key, &params, &proof)
             .map_err(anyhow::Error::from)
+            .and_then(|_| check_program(&program, proof.i, &proof.z_i))
     };
 
     match result {
@@ -158,19 +187,18 @@ fn verify_proof(
     pp_file: Option<PathBuf>,
 ) -> anyhow::Result<()> {
     // handle jolt separately
-    let nova_impl = match prover {
-        ProverImpl::Jolt => return jolt::verify(path, prove_args),
-        ProverImpl::HyperNova => anyhow::bail!("HyperNova not yet supported through the CLI"),
-        ProverImpl::Nova(nova_impl) => nova_impl,
-    };
+    if let ProverImpl::Jolt = prover {
+        return jolt::verify(path, prove_args);
+    }
 
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
+    let program = load_program_id(prove_args)?;
+    let envelope = ProofEnvelope::load(path)?;
+    envelope.check(prover, k, false)?;
 
     let path = match pp_file {
         Some(path) => path,
         None => {
-            let pp_file_name = format_params_file(nova_impl, k);
+            let pp_file_name = format_params_file(prover, k);
             let cache_path = cache_path()?;
 
             cache_path.join(pp_file_name)
@@ -179,20 +207,22 @@ fn verify_proof(
     .to_str()
     .context("path is not utf8")?
     .to_owned();
+    check_params_file(Path::new(&path), prover, k, &envelope.header.pp_digest)?;
 
     let mut term = TerminalHandle::new_enabled();
     let mut ctx = term.context("Verifying").on_step(move |_step| {
-        match nova_impl {
-            NovaImpl::Parallel => "root",
-            NovaImpl::ParallelCompressible => "root",
-            NovaImpl::Sequential => "proof",
+        match prover {
+            ProverImpl::Nova(NovaImpl::Sequential | NovaImpl::SequentialOffline)
+            | ProverImpl::HyperNova(HyperNovaImpl::Sequential)
+            | ProverImpl::SuperNova => "proof",
+            _ => "root",
         }
         .into()
     });
     let mut _guard = Default::default();
 
-    let result = match nova_impl {
-        NovaImpl::Parallel => {
+    let result = match prover {
+        ProverImpl::Nova(NovaImpl::Parallel) => {
             let mut iterm = TerminalHandle::new_enabled();
             let params = {
                 let mut term_ctx = iterm
@@ -202,12 +232,14 @@ fn verify_proof(
 
                 nexus_core::prover::nova::pp::load_pp(&path)?
             };
-            let root = PCDNode::deserialize_compressed(reader)?;
+            let root: PCDNode = envelope.proof()?;
 
             _guard = ctx.display_step();
-            root.verify(&params).map_err(anyhow::Error::from)
+            root.verify(&params)
+                .map_err(anyhow::Error::from)
+                .and_then(|_| check_program(&program, root.i, &root.z_i))
         }
-        NovaImpl::ParallelCompressible => {
+        ProverImpl::Nova(NovaImpl::ParallelCompressible) => {
             let mut iterm = TerminalHandle::new_enabled();
             let params = {
                 let mut term_ctx = iterm
@@ -217,12 +249,14 @@ fn verify_proof(
 
                 nexus_core::prover::nova::pp::load_pp(&path)?
             };
-            let root = ComPCDNode::deserialize_compressed(reader)?;
+            let root: ComPCDNode = envelope.proof()?;
 
             _guard = ctx.display_step();
-            root.verify(&params).map_err(anyhow::Error::from)
+            root.verify(&params)
+                .map_err(anyhow::Error::from)
+                .and_then(|_| check_program(&program, root.i, &root.z_i))
         }
-        NovaImpl::Sequential => {
+        ProverImpl::Nova(NovaImpl::Sequential) => {
             let mut iterm = TerminalHandle::new_enabled();
             let params = {
                 let mut term_ctx = iterm
@@ -232,11 +266,92 @@ fn verify_proof(
 
                 nexus_core::prover::nova::pp::load_pp(&path)?
             };
-            let proof = IVCProof::deserialize_compressed(reader)?;
+            let proof: IVCProof = envelope.proof()?;
+
+            _guard = ctx.display_step();
+            proof
+                .verify(&params)
+                .map_err(anyhow::Error::from)
+                .and_then(|_| check_program(&program, 0, proof.z_0()))
+        }
+        ProverImpl::Nova(NovaImpl::SequentialOffline) => {
+            let mut iterm = TerminalHandle::new_enabled();
+            let params = {
+                let mut term_ctx = iterm
+                    .context("Loading")
+                    .on_step(|_step| "public parameters".into());
+                let _guard = term_ctx.display_step();
+
+                nexus_core::prover::nova::pp::load_pp(&path)?
+            };
+            let proof: AuditedIVCProof = envelope.proof()?;
+
+            _guard = ctx.display_step();
+            proof
+                .verify(&params)
+                .map_err(anyhow::Error::from)
+                .and_then(|_| {
+                    // the initial state commits to the memory accesses, rather than memory
+                    program
+                        .check_with_memory(proof.proof.z_0(), &proof.initial)
+                        .map_err(ProofError::from)?;
+                    Ok(())
+                })
+        }
+        ProverImpl::HyperNova(HyperNovaImpl::Parallel | HyperNovaImpl::ParallelCompressible) => {
+            let mut iterm = TerminalHandle::new_enabled();
+            let params: hypernova_types::ParPP = {
+                let mut term_ctx = iterm
+                    .context("Loading")
+                    .on_step(|_step| "public parameters".into());
+                let _guard = term_ctx.display_step();
+
+                nexus_core::prover::hypernova::pp::load_pp(&path)?
+            };
+            let root: hypernova_types::PCDNode = envelope.proof()?;
+
+            _guard = ctx.display_step();
+            root.verify(&params)
+                .map_err(anyhow::Error::from)
+                .and_then(|_| check_program(&program, root.i, &root.z_i))
+        }
+        ProverImpl::HyperNova(HyperNovaImpl::Sequential) => {
+            let mut iterm = TerminalHandle::new_enabled();
+            let params: hypernova_types::PP = {
+                let mut term_ctx = iterm
+                    .context("Loading")
+                    .on_step(|_step| "public parameters".into());
+                let _guard = term_ctx.display_step();
+
+                nexus_core::prover::hypernova::pp::load_pp(&path)?
+            };
+            let proof: hypernova_types::IVCProof = envelope.proof()?;
+
+            _guard = ctx.display_step();
+            proof
+                .verify(&params)
+                .map_err(anyhow::Error::from)
+                .and_then(|_| check_program(&program, 0, proof.z_0()))
+        }
+        ProverImpl::SuperNova => {
+            let mut iterm = TerminalHandle::new_enabled();
+            let params = {
+                let mut term_ctx = iterm
+                    .context("Loading")
+                    .on_step(|_step| "public parameters".into());
+                let _guard = term_ctx.display_step();
+
+                nexus_core::prover::supernova::pp::load_pp(&path)?
+            };
+            let proof: supernova_types::NIVCProof = envelope.proof()?;
 
             _guard = ctx.display_step();
-            proof.verify(&params).map_err(anyhow::Error::from)
+            proof
+                .verify(&params, proof.step_num() as usize)
+                .map_err(anyhow::Error::from)
+                .and_then(|_| check_program(&program, 0, proof.z_0()))
         }
+        ProverImpl::Jolt => unreachable!(),
     };
 
     match result {
@@ -255,7 +370,7 @@ fn verify_proof(
                 target: LOG_TARGET,
                 err = ?err,
                 ?k,
-                %nova_impl,
+                %prover,
                 "Proof is invalid",
             );
             std::process::exit(1);
@@ -263,3 +378,22 @@ fn verify_proof(
     }
     Ok(())
 }
+
+/// Compute the identifier of the program built for `prove_args`, which the proof must be of.
+fn load_program_id(prove_args: CommonProveArgs) -> anyhow::Result<ProgramId> {
+    let CommonProveArgs { bin, profile } = prove_args;
+    let path = path_to_artifact(bin, &profile)?;
+
+    Ok(program_id(&std::fs::read(path)?)?)
+}
+
+/// Check that a verified proof, of the steps from `i` on, starts from the initial state of `program`.
+///
+/// The public input and output are not known here, only the program is checked.
+fn check_program(program: &ProgramId, i: u64, z_0: &[F1]) -> anyhow::Result<()> {
+    if i != 0 {
+        return Err(ProofError::InvalidIndex(i as usize).into());
+    }
+    program.check(z_0).map_err(ProofError::from)?;
+    Ok(())
+}
@@ -37,7 +37,7 @@ pub fn path_to_artifact(bin: Option<String>, mut profile: &str) -> anyhow::Resul
         .collect();
 
     let mut path = PathBuf::from(&md.target_directory);
-    path.push("riscv32i-unknown-none-elf");
+    path.push("riscv32im-unknown-none-elf");
 
     // "debug" profile is reserved.
     if profile == "dev" {
@@ -15,7 +15,8 @@ pub struct VmConfig {
 pub enum ProverImpl {
     Jolt,
     Nova(NovaImpl),
-    HyperNova,
+    HyperNova(HyperNovaImpl),
+    SuperNova,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, serde_wrapper::Deserialize)]
@@ -32,6 +33,45 @@ pub enum NovaImpl {
     #[serde(rename = "nova-par-com")]
     #[cfg_attr(feature = "clap_derive", clap(name = "nova-par-com"))]
     ParallelCompressible,
+
+    #[serde(rename = "nova-seq-offline")]
+    #[cfg_attr(feature = "clap_derive", clap(name = "nova-seq-offline"))]
+    SequentialOffline,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, serde_wrapper::Deserialize)]
+#[cfg_attr(feature = "clap_derive", derive(clap::ValueEnum))]
+pub enum HyperNovaImpl {
+    #[serde(rename = "hypernova")]
+    #[cfg_attr(feature = "clap_derive", clap(name = "hypernova"))]
+    Sequential,
+
+    #[serde(rename = "hypernova-par")]
+    #[cfg_attr(feature = "clap_derive", clap(name = "hypernova-par"))]
+    Parallel,
+
+    #[serde(rename = "hypernova-par-com")]
+    #[cfg_attr(feature = "clap_derive", clap(name = "hypernova-par-com"))]
+    ParallelCompressible,
+}
+
+impl std::str::FromStr for ProverImpl {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "jolt" => Self::Jolt,
+            "hypernova" => Self::HyperNova(HyperNovaImpl::Sequential),
+            "hypernova-par" => Self::HyperNova(HyperNovaImpl::Parallel),
+            "hypernova-par-com" => Self::HyperNova(HyperNovaImpl::ParallelCompressible),
+            "nova-seq" => Self::Nova(NovaImpl::Sequential),
+            "nova-par" => Self::Nova(NovaImpl::Parallel),
+            "nova-par-com" => Self::Nova(NovaImpl::ParallelCompressible),
+            "nova-seq-offline" => Self::Nova(NovaImpl::SequentialOffline),
+            "supernova" => Self::SuperNova,
+            _ => return Err(()),
+        })
+    }
 }
 
 // serde(untagged) errors with clap
@@ -42,19 +82,12 @@ impl<'de> de::Deserialize<'de> for ProverImpl {
     {
         UntaggedEnumVisitor::new()
             .string(|s| {
-                Ok(match s {
-                    "jolt" => Self::Jolt,
-                    "hypernova" => Self::HyperNova,
-                    "nova-seq" => Self::Nova(NovaImpl::Sequential),
-                    "nova-par" => Self::Nova(NovaImpl::Parallel),
-                    "nova-par-com" => Self::Nova(NovaImpl::ParallelCompressible),
-                    _ => {
-                        // the error message starts with "expected ..."
-                        return Err(de::Error::invalid_value(
-                            de::Unexpected::Str(s),
-                            &r#"one of ["jolt", "nova-seq", "nova-par", "nova-par-com"]"#,
-                        ));
-                    }
+                s.parse().map_err(|_| {
+                    // the error message starts with "expected ..."
+                    de::Error::invalid_value(
+                        de::Unexpected::Str(s),
+                        &r#"one of ["jolt", "nova-seq", "nova-par", "nova-par-com", "nova-seq-offline", "hypernova", "hypernova-par", "hypernova-par-com", "supernova"]"#,
+                    )
                 })
             })
             .deserialize(deserializer)
@@ -65,8 +98,9 @@ impl fmt::Display for ProverImpl {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ProverImpl::Jolt => write!(f, "jolt"),
-            ProverImpl::HyperNova => write!(f, "hypernova"),
+            ProverImpl::HyperNova(hypernova_impl) => write!(f, "{hypernova_impl}"),
             ProverImpl::Nova(nova_impl) => write!(f, "{nova_impl}"),
+            ProverImpl::SuperNova => write!(f, "supernova"),
         }
     }
 }
@@ -77,6 +111,17 @@ impl fmt::Display for NovaImpl {
             NovaImpl::Sequential => write!(f, "nova-seq"),
             NovaImpl::Parallel => write!(f, "nova-par"),
             NovaImpl::ParallelCompressible => write!(f, "nova-par-com"),
+            NovaImpl::SequentialOffline => write!(f, "nova-seq-offline"),
+        }
+    }
+}
+
+impl fmt::Display for HyperNovaImpl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HyperNovaImpl::Sequential => write!(f, "hypernova"),
+            HyperNovaImpl::Parallel => write!(f, "hypernova-par"),
+            HyperNovaImpl::ParallelCompressible => write!(f, "hypernova-par-com"),
         }
     }
 }
@@ -84,27 +129,35 @@ impl fmt::Display for NovaImpl {
 // `derive(ValueEnum)` only works for enums with unit variants -- needs manual implementation.
 #[cfg(feature = "clap_derive")]
 mod clap_derive {
-    use super::{NovaImpl, ProverImpl};
+    use super::{HyperNovaImpl, NovaImpl, ProverImpl};
     use clap::{builder::PossibleValue, ValueEnum};
 
     impl ValueEnum for ProverImpl {
         fn value_variants<'a>() -> &'a [Self] {
             &[
                 Self::Jolt,
-                Self::HyperNova,
+                Self::HyperNova(HyperNovaImpl::Sequential),
+                Self::HyperNova(HyperNovaImpl::Parallel),
+                Self::HyperNova(HyperNovaImpl::ParallelCompressible),
                 Self::Nova(NovaImpl::Sequential),
                 Self::Nova(NovaImpl::Parallel),
                 Self::Nova(NovaImpl::ParallelCompressible),
+                Self::Nova(NovaImpl::SequentialOffline),
+                Self::SuperNova,
             ]
         }
 
         fn to_possible_value(&self) -> Option<PossibleValue> {
             let str = match self {
                 ProverImpl::Jolt => "jolt",
-                ProverImpl::HyperNova => "hypernova",
+                ProverImpl::HyperNova(HyperNovaImpl::Sequential) => "hypernova",
+                ProverImpl::HyperNova(HyperNovaImpl::Parallel) => "hypernova-par",
+                ProverImpl::HyperNova(HyperNovaImpl::ParallelCompressible) => "hypernova-par-com",
                 ProverImpl::Nova(NovaImpl::Sequential) => "nova-seq",
                 ProverImpl::Nova(NovaImpl::Parallel) => "nova-par",
                 ProverImpl::Nova(NovaImpl::ParallelCompressible) => "nova-par-com",
+                ProverImpl::Nova(NovaImpl::SequentialOffline) => "nova-seq-offline",
+                ProverImpl::SuperNova => "supernova",
             };
             Some(PossibleValue::new(str))
         }
@@ -118,6 +171,7 @@ impl Config for VmConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde::de::{value::StrDeserializer, Deserialize, IntoDeserializer};
 
     #[test]
     fn read_config() {
@@ -126,4 +180,23 @@ mod tests {
 
         <VmConfig as Config>::from_env().unwrap();
     }
+
+    #[test]
+    fn prover_impl_roundtrip() {
+        for prover in [
+            ProverImpl::Jolt,
+            ProverImpl::Nova(NovaImpl::Sequential),
+            ProverImpl::Nova(NovaImpl::Parallel),
+            ProverImpl::Nova(NovaImpl::ParallelCompressible),
+            ProverImpl::Nova(NovaImpl::SequentialOffline),
+            ProverImpl::HyperNova(HyperNovaImpl::Sequential),
+            ProverImpl::HyperNova(HyperNovaImpl::Parallel),
+            ProverImpl::HyperNova(HyperNovaImpl::ParallelCompressible),
+            ProverImpl::SuperNova,
+        ] {
+            let s = prover.to_string();
+            let de: StrDeserializer<'_, de::value::Error> = s.as_str().into_deserializer();
+            assert_eq!(ProverImpl::deserialize(de).unwrap(), prover);
+        }
+    }
 }
@@ -14,11 +14,23 @@ pub mod config {
 /// RISC-V processing
 pub mod nvm {
     pub mod interactive {
-        pub use nexus_vm::{eval, load_elf, parse_elf, trace::trace, trace::Trace};
+        pub use nexus_vm::{
+            eval, load_elf, parse_elf,
+            trace::{trace, Trace, TraceStream},
+        };
     }
-    pub use nexus_vm::{error::NexusVMError, eval::NexusVM, run_vm, trace_vm, VMOpts};
+    pub use nexus_vm::{
+        error::NexusVMError,
+        eval::NexusVM,
+        gdb, load_vm, profile_vm, program_id, run_vm,
+        syscalls::ExitCode,
+        trace::{ProgramId, Statement},
+        trace_vm, VMOpts,
+    };
     pub mod memory {
-        pub use nexus_vm::memory::{paged::Paged, path::Path, trie::MerkleTrie, Memory};
+        pub use nexus_vm::memory::{
+            offline::OfflineMemory, paged::Paged, path::Path, trie::MerkleTrie, Memory,
+        };
     }
 }
 
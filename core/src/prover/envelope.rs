@@ -0,0 +1,310 @@
+//! A versioned container for proofs, shared by the SDK, the CLI and the network.
+//!
+//! An encoded envelope consists of the magic bytes [`MAGIC`], the format
+//! [`VERSION`] as a little-endian `u16`, followed by the compressed
+//! serialization of the [`ProofHeader`], an optional [`ProofView`] and the
+//! compressed serialization of the proof itself.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use nexus_config::vm::ProverImpl;
+use nexus_vm::syscalls::ExitCode;
+
+use super::params::{serialize_digest, ParamsMismatch};
+
+/// Magic bytes identifying an encoded proof envelope.
+pub const MAGIC: [u8; 4] = *b"NXPF";
+
+/// Current version of the envelope format.
+pub const VERSION: u16 = 1;
+
+const LOG_TARGET: &str = "nexus-prover";
+
+/// Errors related to encoding and decoding proof envelopes.
+#[derive(Debug)]
+pub enum EnvelopeError {
+    /// An error occurred reading or writing to the file system
+    IOError(std::io::Error),
+
+    /// An error occurred serializing or deserializing the envelope
+    SerError(SerializationError),
+
+    /// The encoded envelope does not start with [`MAGIC`]
+    InvalidMagic,
+
+    /// The encoded envelope uses an unsupported version of the format
+    UnsupportedVersion(u16),
+
+    /// The header names a prover which is not known
+    UnknownProver(String),
+
+    /// The header does not match the one expected by the verifier
+    HeaderMismatch { expected: String, found: String },
+
+    /// The envelope does not contain a view of the execution
+    MissingView,
+}
+use EnvelopeError::*;
+
+impl From<std::io::Error> for EnvelopeError {
+    fn from(x: std::io::Error) -> EnvelopeError {
+        IOError(x)
+    }
+}
+
+impl From<SerializationError> for EnvelopeError {
+    fn from(x: SerializationError) -> EnvelopeError {
+        SerError(x)
+    }
+}
+
+impl Error for EnvelopeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            IOError(e) => Some(e),
+            SerError(e) => Some(e),
+            InvalidMagic => None,
+            UnsupportedVersion(_) => None,
+            UnknownProver(_) => None,
+            HeaderMismatch { .. } => None,
+            MissingView => None,
+        }
+    }
+}
+
+impl Display for EnvelopeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IOError(e) => write!(f, "{e}"),
+            SerError(e) => write!(f, "{e}"),
+            InvalidMagic => write!(f, "not a Nexus proof"),
+            UnsupportedVersion(v) => write!(f, "unsupported proof format version {v}"),
+            UnknownProver(p) => write!(f, "unknown prover {p}"),
+            HeaderMismatch { expected, found } => {
+                write!(f, "expected a {expected}, found a {found}")
+            }
+            MissingView => write!(f, "proof does not contain a view of the execution"),
+        }
+    }
+}
+
+/// Describes how a proof was produced.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProofHeader {
+    /// Name of the prover, as displayed by [`ProverImpl`].
+    prover: String,
+    /// Number of instructions per folding step.
+    pub k: u64,
+    /// Whether the proof has been compressed.
+    pub compressed: bool,
+    /// Serialized digest of the public parameters used to produce the proof.
+    pub pp_digest: Vec<u8>,
+}
+
+impl ProofHeader {
+    pub fn new(
+        prover: ProverImpl,
+        k: usize,
+        compressed: bool,
+        pp_digest: &impl CanonicalSerialize,
+    ) -> Result<Self, EnvelopeError> {
+        Ok(Self {
+            prover: prover.to_string(),
+            k: k as u64,
+            compressed,
+            pp_digest: serialize_digest(pp_digest)?,
+        })
+    }
+
+    pub fn prover(&self) -> Result<ProverImpl, EnvelopeError> {
+        self.prover
+            .parse()
+            .map_err(|_| UnknownProver(self.prover.clone()))
+    }
+
+    /// Check that the proof was produced with the public parameters whose
+    /// serialized digest is `pp_digest`.
+    pub fn check_params(&self, pp_digest: &[u8]) -> Result<(), ParamsMismatch> {
+        if self.pp_digest != pp_digest {
+            return Err(ParamsMismatch::digests(&self.pp_digest, pp_digest));
+        }
+        Ok(())
+    }
+}
+
+impl Display for ProofHeader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let compressed = if self.compressed { "compressed " } else { "" };
+        write!(f, "{compressed}{} proof with k = {}", self.prover, self.k)
+    }
+}
+
+/// The output of a proven execution, as seen by the prover.
+#[derive(Clone, Debug, Default, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProofView {
+    pub output: Vec<u8>,
+    pub logs: Vec<String>,
+    pub exit_code: ExitCode,
+    pub panic_info: Option<String>,
+}
+
+/// A proof together with its header and, optionally, a view of the execution.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProofEnvelope {
+    pub header: ProofHeader,
+    pub view: Option<ProofView>,
+    proof: Vec<u8>,
+}
+
+impl ProofEnvelope {
+    pub fn new(
+        header: ProofHeader,
+        view: Option<ProofView>,
+        proof: &impl CanonicalSerialize,
+    ) -> Result<Self, EnvelopeError> {
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes)?;
+        Ok(Self { header, view, proof: bytes })
+    }
+
+    /// Deserialize the contained proof.
+    pub fn proof<P: CanonicalDeserialize>(&self) -> Result<P, EnvelopeError> {
+        Ok(P::deserialize_compressed(self.proof.as_slice())?)
+    }
+
+    /// Check that the proof was produced by `prover` with `k` instructions per
+    /// step, and whether it has been compressed.
+    pub fn check(
+        &self,
+        prover: ProverImpl,
+        k: usize,
+        compressed: bool,
+    ) -> Result<(), EnvelopeError> {
+        let header = &self.header;
+        if header.prover()? != prover || header.k != k as u64 || header.compressed != compressed {
+            let expected = ProofHeader {
+                prover: prover.to_string(),
+                k: k as u64,
+                compressed,
+                pp_digest: Vec::new(),
+            };
+            return Err(HeaderMismatch {
+                expected: expected.to_string(),
+                found: header.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, EnvelopeError> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        self.serialize_compressed(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        let Some(rest) = bytes.strip_prefix(&MAGIC) else {
+            return Err(InvalidMagic);
+        };
+        let [v0, v1, rest @ ..] = rest else {
+            return Err(InvalidMagic);
+        };
+        let version = u16::from_le_bytes([*v0, *v1]);
+        if version != VERSION {
+            return Err(UnsupportedVersion(version));
+        }
+        Ok(Self::deserialize_compressed(rest)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), EnvelopeError> {
+        tracing::info!(
+            target: LOG_TARGET,
+            path = %path.display(),
+            "Saving the proof",
+        );
+
+        std::fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, EnvelopeError> {
+        tracing::info!(
+            target: LOG_TARGET,
+            path = %path.display(),
+            "Loading the proof",
+        );
+
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+}
+
+impl Serialize for ProofEnvelope {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = self.to_bytes().map_err(serde::ser::Error::custom)?;
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for ProofEnvelope {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = <Vec<u8>>::deserialize(deserializer)?;
+        Self::from_bytes(&bytes).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_config::vm::NovaImpl;
+
+    fn envelope() -> ProofEnvelope {
+        let header =
+            ProofHeader::new(ProverImpl::Nova(NovaImpl::Sequential), 16, false, &7u64).unwrap();
+        let view = ProofView {
+            output: vec![1, 2, 3],
+            logs: vec!["hello".into()],
+            exit_code: ExitCode::SUCCESS,
+            panic_info: None,
+        };
+        ProofEnvelope::new(header, Some(view), &vec![42u32; 3]).unwrap()
+    }
+
+    #[test]
+    fn envelope_roundtrip() {
+        let env = envelope();
+        let bytes = env.to_bytes().unwrap();
+        assert_eq!(&bytes[..4], &MAGIC);
+
+        let decoded = ProofEnvelope::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, env);
+        assert_eq!(decoded.proof::<Vec<u32>>().unwrap(), vec![42u32; 3]);
+        assert!(decoded
+            .check(ProverImpl::Nova(NovaImpl::Sequential), 16, false)
+            .is_ok());
+        assert!(matches!(
+            decoded.check(ProverImpl::Nova(NovaImpl::Parallel), 16, false),
+            Err(HeaderMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn envelope_rejects_other_formats() {
+        let mut bytes = envelope().to_bytes().unwrap();
+        assert!(matches!(
+            ProofEnvelope::from_bytes(&bytes[4..]),
+            Err(InvalidMagic)
+        ));
+
+        bytes[4] = 2;
+        assert!(matches!(
+            ProofEnvelope::from_bytes(&bytes),
+            Err(UnsupportedVersion(2))
+        ));
+    }
+}
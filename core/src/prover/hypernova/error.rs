@@ -4,9 +4,17 @@ use std::fmt::{self, Debug, Display, Formatter};
 pub use ark_relations::r1cs::SynthesisError;
 pub use ark_serialize::SerializationError;
 pub use nexus_nova::ccs::Error as CCSError;
-pub use nexus_nova::hypernova::{Error as HyperNovaError, HNFoldingError};
+pub use nexus_nova::hypernova::{
+    pcd::compression::SpartanError, Error as HyperNovaError, HNFoldingError,
+};
 pub use nexus_nova::r1cs::Error as R1CSError;
 pub use nexus_vm::error::NexusVMError;
+use nexus_vm::{
+    syscalls::ExitCode,
+    trace::{ProgramId, StatementMismatch},
+};
+
+pub use crate::prover::params::ParamsMismatch;
 
 pub use crate::prover::nova::error::ProofError as NovaProofError;
 
@@ -34,9 +42,20 @@ pub enum ProofError {
     /// Invalid folding step index
     InvalidIndex(usize),
 
+    /// The trace to be proven has no blocks
+    EmptyTrace,
+
+    /// The trace has the contained number of blocks, which is not one
+    /// less than a power of two as needed to prove it in parallel
+    IncompleteTrace(usize),
+
     /// Public Parameters do not match circuit
     InvalidPP,
 
+    /// Public Parameters were generated for another prover, or do not
+    /// match the ones used to produce the proof
+    ParamsMismatch(ParamsMismatch),
+
     /// An error occured while computing the HyperNova folding
     FoldingError(HNFoldingError),
 
@@ -46,8 +65,24 @@ pub enum ProofError {
     /// The HyperNova prover produced an invalid proof
     HyperNovaProofError,
 
+    /// An error occured while sampling the test SRS
+    SRSSamplingError,
+
+    /// An error occured while running the Spartan compression prover
+    CompressionError(SpartanError),
+
     /// A proof has been read from a file that does not match the expected format
     InvalidProofFormat,
+
+    /// The claimed public input or output does not match the proof
+    IOMismatch,
+
+    /// The claimed exit code does not match the proof, which
+    /// proves an execution exiting with the contained code
+    ExitCodeMismatch(ExitCode),
+
+    /// The proof is of an execution of another program, with the contained identifier
+    ProgramMismatch(ProgramId),
 }
 use ProofError::*;
 
@@ -82,6 +117,28 @@ impl From<HyperNovaError> for ProofError {
     }
 }
 
+impl From<SpartanError> for ProofError {
+    fn from(x: SpartanError) -> ProofError {
+        CompressionError(x)
+    }
+}
+
+impl From<StatementMismatch> for ProofError {
+    fn from(x: StatementMismatch) -> ProofError {
+        match x {
+            StatementMismatch::Program(id) => ProgramMismatch(id),
+            StatementMismatch::IO => IOMismatch,
+            StatementMismatch::ExitCode(code) => ExitCodeMismatch(code),
+        }
+    }
+}
+
+impl From<ParamsMismatch> for ProofError {
+    fn from(x: ParamsMismatch) -> ProofError {
+        ParamsMismatch(x)
+    }
+}
+
 impl From<SerializationError> for ProofError {
     fn from(x: SerializationError) -> ProofError {
         SerError(x)
@@ -95,6 +152,7 @@ impl From<NovaProofError> for ProofError {
             NovaProofError::IOError(e) => IOError(e),
             NovaProofError::CircuitError(e) => CircuitError(e),
             NovaProofError::SerError(e) => SerError(e),
+            NovaProofError::ParamsMismatch(e) => ParamsMismatch(e),
             // The above error conversions allow reusing convienence functions
             // from the nova implemementation in this crate.
             //
@@ -116,11 +174,19 @@ impl Error for ProofError {
             R1CSWitnessError(e) => Some(e),
             CCSWitnessError(e) => Some(e),
             InvalidIndex(_) => None,
+            EmptyTrace => None,
+            IncompleteTrace(_) => None,
             InvalidPP => None,
+            ParamsMismatch(e) => Some(e),
             FoldingError(e) => Some(e),
             PolyCommitmentError => None,
             HyperNovaProofError => None,
+            SRSSamplingError => None,
+            CompressionError(e) => Some(e),
             InvalidProofFormat => None,
+            IOMismatch => None,
+            ExitCodeMismatch(_) => None,
+            ProgramMismatch(_) => None,
         }
     }
 }
@@ -135,11 +201,19 @@ impl Display for ProofError {
             R1CSWitnessError(e) => write!(f, "{e}"),
             CCSWitnessError(e) => write!(f, "{e}"),
             InvalidIndex(i) => write!(f, "invalid step index {i}"),
+            EmptyTrace => write!(f, "trace has no blocks to prove"),
+            IncompleteTrace(n) => write!(f, "trace of {n} blocks cannot be proven in parallel"),
             InvalidPP => write!(f, "invalid public parameters"),
+            ParamsMismatch(e) => write!(f, "{e}"),
             FoldingError(e) => write!(f, "{e}"),
             PolyCommitmentError => write!(f, "invalid polynomial commitment setup"),
             HyperNovaProofError => write!(f, "invalid HyperNova proof"),
+            SRSSamplingError => write!(f, "error sampling test SRS"),
+            CompressionError(e) => write!(f, "{e}"),
             InvalidProofFormat => write!(f, "invalid proof format"),
+            IOMismatch => write!(f, "public input or output does not match the proof"),
+            ExitCodeMismatch(code) => write!(f, "program exited with code {code}"),
+            ProgramMismatch(id) => write!(f, "proof is of another program, with id {id}"),
         }
     }
 }
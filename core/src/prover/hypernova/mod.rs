@@ -1,20 +1,22 @@
 pub use super::nova::circuit;
 pub mod error;
+pub mod key;
 pub mod pp;
+pub mod srs;
 pub mod types;
 
 use std::path::Path;
 
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
-use nexus_vm::VMOpts;
+use nexus_vm::{trace::Statement, VMOpts};
 
 use crate::prover::hypernova::{
     error::ProofError,
-    types::{IVCProof, PP, SC},
+    types::{com, ComProof, IVCProof, PCDNode, ParPP, SpartanKey, F1, PP, SC},
 };
 
-use super::nova::{Trace, LOG_TARGET};
+use super::nova::{Trace, TraceStream, LOG_TARGET};
 
 pub fn save_proof<P: CanonicalSerialize>(proof: P, path: &Path) -> anyhow::Result<()> {
     super::nova::save_proof::<P>(proof, path)
@@ -43,6 +45,16 @@ pub fn prove_seq(pp: &PP, trace: Trace) -> Result<IVCProof, ProofError> {
     Ok(proof)
 }
 
+/// Prove a program trace sequentially, consuming it one block at a time.
+pub fn prove_seq_stream(pp: &PP, trace: TraceStream) -> Result<IVCProof, ProofError> {
+    let mut proof = None;
+    for block in trace {
+        let tr = init_circuit_trace(block?)?;
+        proof = Some(prove_seq_step(proof, pp, &tr)?);
+    }
+    proof.ok_or(ProofError::EmptyTrace)
+}
+
 pub fn prove_seq_step(proof: Option<IVCProof>, pp: &PP, tr: &SC) -> Result<IVCProof, ProofError> {
     let mut pr;
 
@@ -57,12 +69,158 @@ pub fn prove_seq_step(proof: Option<IVCProof>, pp: &PP, tr: &SC) -> Result<IVCPr
     Ok(pr)
 }
 
+/// Verify a sequential proof, and check that it proves `statement`, see
+/// [`nova::verify_seq`](super::nova::verify_seq).
+pub fn verify_seq(pp: &PP, proof: &IVCProof, statement: &Statement) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Verifying the proof",
+    );
+
+    proof.verify(pp)?;
+    Ok(statement.check(proof.z_0(), proof.z_i())?)
+}
+
+// Check that the execution proven by a PCD node, or a compression of one,
+// covers the steps from `i` on, and proves `statement`.
+fn check_root(i: u64, z_i: &[F1], z_j: &[F1], statement: &Statement) -> Result<(), ProofError> {
+    if i != 0 {
+        return Err(ProofError::InvalidIndex(i as usize));
+    }
+    Ok(statement.check(z_i, z_j)?)
+}
+
+/// Verify the root of a parallel proof, and check that it proves `statement`,
+/// see [`verify_seq`].
+pub fn verify_par(pp: &ParPP, node: &PCDNode, statement: &Statement) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Verifying the proof",
+    );
+
+    node.verify(pp)?;
+    check_root(node.i, &node.z_i, &node.z_j, statement)
+}
+
+pub fn prove_par(pp: &ParPP, trace: Trace) -> Result<PCDNode, ProofError> {
+    let tr = init_circuit_trace(trace)?;
+    let num_steps = tr.steps();
+
+    assert!((tr.steps() + 1).is_power_of_two());
+
+    let mut vs = (0..num_steps)
+        .step_by(2)
+        .map(|i| prove_par_leaf_step(pp, &tr, i))
+        .collect::<Result<Vec<_>, ProofError>>()?;
+
+    while vs.len() > 1 {
+        vs = vs
+            .chunks(2)
+            .map(|ab| prove_par_parent_step(pp, &tr, &ab[0], &ab[1]))
+            .collect::<Result<Vec<_>, ProofError>>()?;
+    }
+
+    Ok(vs.into_iter().next().unwrap())
+}
+
+/// Streaming version of [`prove_par`], see `nova::prove_par_stream` for the
+/// order in which blocks are consumed.
+pub fn prove_par_stream(pp: &ParPP, trace: TraceStream) -> Result<PCDNode, ProofError> {
+    // completed subtrees, with their height and the block following them
+    let mut stack: Vec<(PCDNode, usize, Option<SC>)> = Vec::new();
+    let mut num_steps = 0;
+
+    for block in trace {
+        let tr = init_circuit_trace(block?)?;
+        let i = tr.0.start;
+        num_steps += 1;
+
+        if i % 2 == 1 {
+            match stack.last_mut() {
+                Some((_, _, next)) => *next = Some(tr),
+                None => return Err(ProofError::InvalidIndex(i)),
+            }
+            continue;
+        }
+
+        let mut node = prove_par_leaf_step(pp, &tr, i)?;
+        let mut height = 0;
+        while let Some((_, h, _)) = stack.last() {
+            if *h != height {
+                break;
+            }
+            let (left, _, next) = stack.pop().unwrap();
+            let next = next.ok_or(ProofError::InvalidIndex(i))?;
+            node = prove_par_parent_step(pp, &next, &left, &node)?;
+            height += 1;
+        }
+        stack.push((node, height, None));
+    }
+
+    if num_steps == 0 {
+        return Err(ProofError::EmptyTrace);
+    }
+    if !(num_steps + 1).is_power_of_two() || stack.len() != 1 {
+        return Err(ProofError::IncompleteTrace(num_steps));
+    }
+
+    Ok(stack.pop().unwrap().0)
+}
+
+pub fn prove_par_leaf_step(pp: &ParPP, tr: &SC, i: usize) -> Result<PCDNode, ProofError> {
+    assert!((tr.steps() + 1).is_power_of_two());
+
+    let v = PCDNode::prove_leaf(pp, tr, i, &tr.input(i)?)?;
+    Ok(v)
+}
+
+pub fn prove_par_parent_step(
+    pp: &ParPP,
+    tr: &SC,
+    ab0: &PCDNode,
+    ab1: &PCDNode,
+) -> Result<PCDNode, ProofError> {
+    assert!((tr.steps() + 1).is_power_of_two());
+
+    let c = PCDNode::prove_parent(pp, tr, ab0, ab1)?;
+    Ok(c)
+}
+
+pub fn compress(pp: &ParPP, key: &SpartanKey, node: PCDNode) -> Result<ComProof, ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Compressing the proof",
+    );
+
+    let compressed_pcd_proof = com::SNARK::compress(pp, key, node)?;
+
+    Ok(compressed_pcd_proof)
+}
+
+/// Verify a compressed proof, and check that it proves `statement`, see
+/// [`verify_seq`].
+pub fn verify_compressed(
+    key: &SpartanKey,
+    params: &ParPP,
+    proof: &ComProof,
+    statement: &Statement,
+) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Verifying the compressed proof",
+    );
+
+    com::SNARK::verify(key, params, proof)?;
+    check_root(proof.i, &proof.z_i, &proof.z_j, statement)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use crate::nvm::memory::MerkleTrie;
     use crate::prover::nova::circuit::nop_circuit;
+    use nexus_vm::{machines::nop_vm, syscalls::ExitCode, trace::ProgramId};
 
     #[test]
     fn test_prove_seq() -> Result<(), ProofError> {
@@ -72,14 +230,69 @@ mod tests {
         let params = pp::test_pp::gen_test_pp(&circuit)?;
 
         let proof = prove_seq(&params, trace)?;
+        let program = ProgramId::initial(&nop_vm::<MerkleTrie>(1));
+        let statement = |output: &[u8], exit_code| Statement::new(program, &[], output, exit_code);
         assert!(proof.verify(&params).is_ok());
+        assert!(verify_seq(&params, &proof, &statement(&[], ExitCode::SUCCESS)?).is_ok());
+        assert!(matches!(
+            verify_seq(&params, &proof, &statement(&[0], ExitCode::SUCCESS)?),
+            Err(ProofError::IOMismatch)
+        ));
+        assert!(matches!(
+            verify_seq(&params, &proof, &statement(&[], ExitCode::PANIC)?),
+            Err(ProofError::ExitCodeMismatch(ExitCode::SUCCESS))
+        ));
+
+        // the initial state is bound to the program, so that a proof cannot
+        // start from another state, e.g. with non-empty tapes
+        let other = ProgramId::initial(&nop_vm::<MerkleTrie>(2));
+        assert!(matches!(
+            verify_seq(&params, &proof, &Statement::new(other, &[], &[], ExitCode::SUCCESS)?),
+            Err(ProofError::ProgramMismatch(id)) if id == program
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prove_par() -> Result<(), ProofError> {
+        let circuit = nop_circuit::<MerkleTrie>(1)?;
+        let params: ParPP = pp::test_pp::gen_test_pp(&circuit)?;
+
+        let trace = nexus_vm::trace::trace(&mut nop_vm::<MerkleTrie>(2), 1, false)?;
+        let node = prove_par(&params, trace)?;
+        let program = ProgramId::initial(&nop_vm::<MerkleTrie>(2));
+        let statement = Statement::new(program, &[], &[], ExitCode::SUCCESS)?;
+        assert!(node.verify(&params).is_ok());
+        assert!(verify_par(&params, &node, &statement).is_ok());
+        let other = Statement::new(program, &[], &[0], ExitCode::SUCCESS)?;
+        assert!(matches!(
+            verify_par(&params, &node, &other),
+            Err(ProofError::IOMismatch)
+        ));
+
+        let stream = prove_par_stream(
+            &params,
+            TraceStream::new(&mut nop_vm::<MerkleTrie>(2), 1, false)?,
+        )?;
+        assert!(stream.verify(&params).is_ok());
+        assert_eq!(stream.z_j, node.z_j);
+
+        // two blocks do not form a complete tree
+        assert!(matches!(
+            prove_par_stream(
+                &params,
+                TraceStream::new(&mut nop_vm::<MerkleTrie>(1), 1, false)?,
+            ),
+            Err(ProofError::IncompleteTrace(2))
+        ));
 
         Ok(())
     }
 
     #[test]
     fn prove_verify_test_machine() -> Result<(), ProofError> {
-        use nexus_vm::{machines::MACHINES, trace_vm};
+        use nexus_vm::{error::NexusVMError, machines::MACHINES, trace_vm};
         let public_params =
             pp::test_pp::gen_vm_test_pp(16).expect("error generating public parameters");
         for (name, _f_code, _f_result, _f_input) in MACHINES {
@@ -89,6 +302,16 @@ mod tests {
                 file: None,
             };
             let trace = trace_vm::<MerkleTrie>(&vm_opts, false, false, false).unwrap();
+            // precompile calls are only proven by SuperNova
+            if *name == "keccak" {
+                assert!(matches!(
+                    prove_seq(&public_params, trace),
+                    Err(ProofError::NexusVMError(
+                        NexusVMError::UnprovablePrecompile(..)
+                    ))
+                ));
+                continue;
+            }
             let proof = prove_seq(&public_params, trace)
                 .unwrap_or_else(|_| panic!("error proving {}", name));
             proof
@@ -1,30 +1,47 @@
 use std::fs::File;
+use std::path::Path;
 use zstd::stream::{Decoder, Encoder};
 
 pub use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
+use nexus_config::vm::ProverImpl;
+
 use super::error::*;
 use super::types::*;
 use super::LOG_TARGET;
 use crate::prover::nova::circuit::nop_circuit;
+use crate::prover::params::{serialize_digest, ParamsHeader};
 
-pub fn gen_pp(circuit: &SC, srs: &SRS, aux: &SetupAux) -> Result<PP, ProofError> {
+pub fn gen_pp<SP>(circuit: &SC, srs: &SRS, aux: &SetupAux) -> Result<GenericPP<SP>, ProofError>
+where
+    SP: SetupParams<G1, G2, C1, C2, RO, SC>,
+{
     tracing::info!(
         target: LOG_TARGET,
         "Generating public parameters",
     );
 
-    Ok(PP::setup(ro_config(), circuit, srs, aux)?)
+    Ok(SP::setup(ro_config(), circuit, srs, aux)?)
 }
 
-pub fn save_pp(pp: &PP, file: &str) -> Result<(), ProofError> {
+pub fn save_pp<SP>(
+    pp: &GenericPP<SP>,
+    prover: ProverImpl,
+    k: usize,
+    file: &str,
+) -> Result<(), ProofError>
+where
+    SP: SetupParams<G1, G2, C1, C2, RO, SC>,
+{
     tracing::info!(
         target: LOG_TARGET,
         path = ?file,
         "Saving public parameters",
     );
 
-    let f = File::create(file)?;
+    let header = ParamsHeader::new(prover, k, &pp.digest)?;
+    let mut f = File::create(file)?;
+    header.write(&mut f)?;
     let mut enc = Encoder::new(&f, 0)?;
     pp.serialize_compressed(&mut enc)?;
     enc.finish()?;
@@ -32,25 +49,63 @@ pub fn save_pp(pp: &PP, file: &str) -> Result<(), ProofError> {
     Ok(())
 }
 
-pub fn load_pp(file: &str) -> Result<PP, ProofError> {
+pub fn load_pp<SP>(file: &str) -> Result<GenericPP<SP>, ProofError>
+where
+    SP: SetupParams<G1, G2, C1, C2, RO, SC> + Sync,
+{
     tracing::info!(
         target: LOG_TARGET,
         path = ?file,
         "Loading public parameters",
     );
 
-    let f = File::open(file)?;
+    let mut f = File::open(file)?;
+    let header = ParamsHeader::read(&mut f)?;
     let mut dec = Decoder::new(&f)?;
-    let pp = PP::deserialize_compressed(&mut dec)?;
+    let mut pp = GenericPP::<SP>::deserialize_compressed(&mut dec)?;
+    // the parameters have been corrupted, or their digest was not computed from them
+    if !pp.check_digest() {
+        return Err(ProofError::InvalidPP);
+    }
+    if let Some(header) = header {
+        // the header was not written for these parameters
+        if header.digest != serialize_digest(&pp.digest)? {
+            return Err(ProofError::InvalidPP);
+        }
+    }
     Ok(pp)
 }
 
-pub fn gen_vm_pp(k: usize, srs: &SRS, aux: &SetupAux) -> Result<PP, ProofError> {
+/// Load public parameters from `file`, checking that they were generated
+/// for `prover` with `k` instructions per step.
+///
+/// Files written without a [`ParamsHeader`] cannot be checked, and are loaded as is.
+pub fn load_pp_for<SP>(
+    file: &str,
+    prover: ProverImpl,
+    k: usize,
+) -> Result<GenericPP<SP>, ProofError>
+where
+    SP: SetupParams<G1, G2, C1, C2, RO, SC> + Sync,
+{
+    if let Some(header) = ParamsHeader::load(Path::new(file))? {
+        header.check(prover, k)?;
+    }
+    load_pp(file)
+}
+
+pub fn gen_vm_pp<SP>(k: usize, srs: &SRS, aux: &SetupAux) -> Result<GenericPP<SP>, ProofError>
+where
+    SP: SetupParams<G1, G2, C1, C2, RO, SC>,
+{
     let tr = nop_circuit(k)?;
     gen_pp(&tr, srs, aux)
 }
 
-pub fn show_pp(pp: &PP) {
+pub fn show_pp<SP>(pp: &GenericPP<SP>)
+where
+    SP: SetupParams<G1, G2, C1, C2, RO, SC>,
+{
     tracing::debug!(
         target: LOG_TARGET,
         "Primary circuit {}",
@@ -66,17 +121,19 @@ pub fn show_pp(pp: &PP) {
 pub mod test_pp {
     use super::*;
 
-    pub fn gen_test_pp(circuit: &SC) -> Result<PP, ProofError> {
-        let params =
-            nexus_nova::hypernova::sequential::PublicParams::<G1, G2, C1, C2, RO, SC>::test_setup(
-                nexus_nova::poseidon_config(),
-                circuit,
-            )?;
+    pub fn gen_test_pp<SP>(circuit: &SC) -> Result<GenericPP<SP>, ProofError>
+    where
+        SP: SetupParams<G1, G2, C1, C2, RO, SC>,
+    {
+        let params = GenericPP::<SP>::test_setup(nexus_nova::poseidon_config(), circuit)?;
 
         Ok(params)
     }
 
-    pub fn gen_vm_test_pp(k: usize) -> Result<PP, ProofError> {
+    pub fn gen_vm_test_pp<SP>(k: usize) -> Result<GenericPP<SP>, ProofError>
+    where
+        SP: SetupParams<G1, G2, C1, C2, RO, SC>,
+    {
         let tr = nop_circuit(k)?;
         gen_test_pp(&tr)
     }
@@ -0,0 +1,60 @@
+use std::fs::File;
+use zstd::stream::Decoder;
+
+use crate::prover::hypernova::{
+    error::ProofError,
+    pp::test_pp::gen_vm_test_pp,
+    types::{ParPP, SpartanKey, SRS},
+};
+
+pub use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+pub fn load_srs(file: &str) -> Result<SRS, ProofError> {
+    let f = File::open(file)?;
+    let mut dec = Decoder::new(&f)?;
+    // see the note in `nova::srs::load_srs` on why the checked deserialization is cheap here.
+    let srs = SRS::deserialize_compressed(&mut dec)?;
+    Ok(srs)
+}
+
+/// Derive the minimum (log) size of the SRS to support both the parallel
+/// prover and compression for a given `k`.
+///
+/// Unlike Nova, the HyperNova public parameters commit to the witness of the
+/// augmented circuit with the same SRS, so the result also covers that commitment.
+pub fn get_min_srs_size(k: usize) -> Result<usize, ProofError> {
+    // these are only used to get the size of the ccs matrices for a given k.
+    let dummy_pp: ParPP = gen_vm_test_pp(k)?;
+    let ParPP { shape, .. } = dummy_pp;
+
+    let pp_size = nexus_nova::safe_loglike!(shape.num_vars.max(shape.num_constraints)) as usize;
+    Ok(SpartanKey::get_min_srs_size(&shape).max(pp_size))
+}
+
+pub mod test_srs {
+    use super::*;
+    use ark_std::test_rng;
+    use zstd::stream::Encoder;
+
+    use crate::prover::hypernova::types::{PolyCommitmentScheme, C1};
+    /// This function should only be used for testing, as it is insescure:
+    /// the SRS should be generated by a trusted setup ceremony.
+    pub fn gen_test_srs(num_vars: usize) -> Result<SRS, ProofError> {
+        let mut rng = test_rng();
+        C1::setup(num_vars, b"test_srs", &mut rng).map_err(|_| ProofError::SRSSamplingError)
+    }
+
+    pub fn save_srs(srs: SRS, file: &str) -> Result<(), ProofError> {
+        let f = File::create(file)?;
+        let mut enc = Encoder::new(&f, 0)?;
+        srs.serialize_compressed(&mut enc)?;
+        enc.finish()?;
+        f.sync_all()?;
+        Ok(())
+    }
+
+    pub fn gen_test_srs_to_file(poly_length: usize, file: &str) -> Result<(), ProofError> {
+        let srs = gen_test_srs(poly_length)?;
+        save_srs(srs, file)
+    }
+}
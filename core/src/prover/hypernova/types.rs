@@ -11,8 +11,13 @@ pub use spartan::polycommitments::{zeromorph::Zeromorph, PolyCommitmentScheme};
 
 // types and traits from nexus prover
 pub use nexus_nova::{
-    commitment::CommitmentScheme, hypernova::public_params::PublicParams,
-    hypernova::sequential as seq, pedersen::PedersenCommitment, StepCircuit,
+    commitment::CommitmentScheme,
+    hypernova::pcd,
+    hypernova::pcd::compression as com,
+    hypernova::public_params::{PublicParams, SetupParams},
+    hypernova::sequential as seq,
+    pedersen::PedersenCommitment,
+    StepCircuit,
 };
 use nexus_vm::memory::trie::MerkleTrie;
 
@@ -40,5 +45,13 @@ pub type SC = crate::prover::nova::circuit::Tr<MerkleTrie>;
 
 // concrete public parameters
 pub type PP = seq::PublicParams<G1, G2, C1, C2, RO, SC>;
+pub type ParPP = pcd::PublicParams<G1, G2, C1, C2, RO, SC>;
+
+// public parameters for a given setup, either sequential or parallel
+pub type GenericPP<SP> = PublicParams<G1, G2, C1, C2, RO, SC, SP>;
+
+pub type SpartanKey = com::SNARKKey<P1, C1>;
 
 pub type IVCProof = seq::IVCProof<G1, G2, C1, C2, RO, SC>;
+pub type PCDNode = pcd::PCDNode<G1, G2, C1, C2, RO, SC>;
+pub type ComProof = com::CompressedPCDProof<G1, G2, C1, C2, RO, SC>;
@@ -1,3 +1,7 @@
-pub use nexus_jolt::{parse::parse_elf, preprocess, prove, trace::trace, verify, Error, VM};
+pub use nexus_jolt::{
+    check_io, parse::parse_elf, preprocess, prove, trace::trace, verify, Error, JoltDevice,
+    MAX_INPUT_SIZE, MAX_OUTPUT_SIZE, VM,
+};
 
+pub mod pp;
 pub mod types;
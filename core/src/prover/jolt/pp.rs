@@ -0,0 +1,111 @@
+//! Saving and loading Jolt preprocessing.
+//!
+//! Jolt has no universal public parameters: the preprocessing of a program
+//! plays their role, and is stored in the same file format (see
+//! [`ParamsHeader`]), with the identifier of the program in place of the digest.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use zstd::stream::{Decoder, Encoder};
+
+pub use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+
+use nexus_config::vm::ProverImpl;
+use nexus_vm::trace::ProgramId;
+
+use super::types::JoltPreprocessing;
+use crate::prover::params::{ParamsHeader, ParamsMismatch};
+
+const LOG_TARGET: &str = "nexus-prover::jolt";
+
+/// Errors related to saving and loading preprocessing.
+#[derive(Debug)]
+pub enum PPError {
+    /// An error occurred reading, writing or (de)serializing the preprocessing
+    SerError(SerializationError),
+
+    /// The file does not contain Jolt preprocessing
+    ParamsMismatch(ParamsMismatch),
+}
+
+impl From<SerializationError> for PPError {
+    fn from(x: SerializationError) -> PPError {
+        PPError::SerError(x)
+    }
+}
+
+impl From<std::io::Error> for PPError {
+    fn from(x: std::io::Error) -> PPError {
+        PPError::SerError(x.into())
+    }
+}
+
+impl From<ParamsMismatch> for PPError {
+    fn from(x: ParamsMismatch) -> PPError {
+        PPError::ParamsMismatch(x)
+    }
+}
+
+impl Error for PPError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PPError::SerError(e) => Some(e),
+            PPError::ParamsMismatch(e) => Some(e),
+        }
+    }
+}
+
+impl Display for PPError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PPError::SerError(e) => write!(f, "{e}"),
+            PPError::ParamsMismatch(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// Save the preprocessing of the program with identifier `program`.
+pub fn save_pp(pp: &JoltPreprocessing, program: &ProgramId, file: &str) -> Result<(), PPError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        path = ?file,
+        "Saving preprocessing",
+    );
+
+    let header = ParamsHeader::new(ProverImpl::Jolt, 0, program)?;
+    let mut f = File::create(file)?;
+    header.write(&mut f)?;
+    let mut enc = Encoder::new(&f, 0)?;
+    pp.serialize_compressed(&mut enc)?;
+    enc.finish()?;
+    f.sync_all()?;
+    Ok(())
+}
+
+/// Load preprocessing saved by [`save_pp`], together with the identifier of
+/// its program.
+pub fn load_pp(file: &str) -> Result<(JoltPreprocessing, ProgramId), PPError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        path = ?file,
+        "Loading preprocessing",
+    );
+
+    let mut f = File::open(file)?;
+    let Some(header) = ParamsHeader::read(&mut f)? else {
+        return Err(ParamsMismatch {
+            expected: "Jolt preprocessing of a program".into(),
+            found: "preprocessing without a header".into(),
+        }
+        .into());
+    };
+    header.check(ProverImpl::Jolt, 0)?;
+    let program = ProgramId::deserialize_compressed(header.digest.as_slice())?;
+
+    let mut dec = Decoder::new(&f)?;
+    Ok((
+        JoltPreprocessing::deserialize_compressed(&mut dec)?,
+        program,
+    ))
+}
@@ -1,6 +1,10 @@
+pub mod envelope;
 #[cfg(feature = "prover_hypernova")]
 pub mod hypernova;
 #[cfg(feature = "prover_jolt")]
 pub mod jolt;
 #[cfg(feature = "prover_nova")]
 pub mod nova;
+pub mod params;
+#[cfg(feature = "prover_supernova")]
+pub mod supernova;
@@ -6,6 +6,12 @@ pub use ark_serialize::SerializationError;
 pub use nexus_nova::nova::{pcd::compression::SpartanError, Error as NovaError};
 pub use nexus_nova::r1cs::Error as R1CSError;
 pub use nexus_vm::error::NexusVMError;
+use nexus_vm::{
+    syscalls::ExitCode,
+    trace::{ProgramId, StatementMismatch},
+};
+
+pub use crate::prover::params::ParamsMismatch;
 
 /// Errors related to proof generation
 #[derive(Debug)]
@@ -28,9 +34,20 @@ pub enum ProofError {
     /// Invalid folding step index
     InvalidIndex(usize),
 
+    /// The trace to be proven has no blocks
+    EmptyTrace,
+
+    /// The trace has the contained number of blocks, which is not one
+    /// less than a power of two as needed to prove it in parallel
+    IncompleteTrace(usize),
+
     /// Public Parameters do not match circuit
     InvalidPP,
 
+    /// Public Parameters were generated for another prover, or do not
+    /// match the ones used to produce the proof
+    ParamsMismatch(ParamsMismatch),
+
     /// The Nova prover produced an invalid proof
     NovaProofError,
 
@@ -45,6 +62,23 @@ pub enum ProofError {
 
     /// A proof has been read from a file that does not match the expected format
     InvalidProofFormat,
+
+    /// The claimed public input or output does not match the proof
+    IOMismatch,
+
+    /// The claimed exit code does not match the proof, which
+    /// proves an execution exiting with the contained code
+    ExitCodeMismatch(ExitCode),
+
+    /// The proof is of an execution of another program, with the contained identifier
+    ProgramMismatch(ProgramId),
+
+    /// The number of claimed statements does not match the number of proofs, which is contained
+    NumProofsMismatch(usize),
+
+    /// The memory accesses of an execution proven using offline memory
+    /// checking do not match its initial memory and the audit of its final memory
+    MemoryMismatch,
 }
 use ProofError::*;
 
@@ -76,6 +110,22 @@ impl From<NovaError> for ProofError {
     }
 }
 
+impl From<StatementMismatch> for ProofError {
+    fn from(x: StatementMismatch) -> ProofError {
+        match x {
+            StatementMismatch::Program(id) => ProgramMismatch(id),
+            StatementMismatch::IO => IOMismatch,
+            StatementMismatch::ExitCode(code) => ExitCodeMismatch(code),
+        }
+    }
+}
+
+impl From<ParamsMismatch> for ProofError {
+    fn from(x: ParamsMismatch) -> ProofError {
+        ParamsMismatch(x)
+    }
+}
+
 impl From<SerializationError> for ProofError {
     fn from(x: SerializationError) -> ProofError {
         SerError(x)
@@ -97,12 +147,20 @@ impl Error for ProofError {
             SerError(e) => Some(e),
             WitnessError(e) => Some(e),
             InvalidPP => None,
+            ParamsMismatch(e) => Some(e),
             InvalidIndex(_) => None,
+            EmptyTrace => None,
+            IncompleteTrace(_) => None,
             NovaProofError => None,
             MissingSRS => None,
             SRSSamplingError => None,
             CompressionError(e) => Some(e),
             InvalidProofFormat => None,
+            IOMismatch => None,
+            ExitCodeMismatch(_) => None,
+            ProgramMismatch(_) => None,
+            NumProofsMismatch(_) => None,
+            MemoryMismatch => None,
         }
     }
 }
@@ -116,12 +174,20 @@ impl Display for ProofError {
             SerError(e) => write!(f, "{e}"),
             WitnessError(e) => write!(f, "{e}"),
             InvalidPP => write!(f, "invalid public parameters"),
+            ParamsMismatch(e) => write!(f, "{e}"),
             InvalidIndex(i) => write!(f, "invalid step index {i}"),
+            EmptyTrace => write!(f, "trace has no blocks to prove"),
+            IncompleteTrace(n) => write!(f, "trace of {n} blocks cannot be proven in parallel"),
             NovaProofError => write!(f, "invalid Nova proof"),
             MissingSRS => write!(f, "missing SRS"),
             SRSSamplingError => write!(f, "error sampling test SRS"),
             CompressionError(e) => write!(f, "{e}"),
             InvalidProofFormat => write!(f, "invalid proof format"),
+            IOMismatch => write!(f, "public input or output does not match the proof"),
+            ExitCodeMismatch(code) => write!(f, "program exited with code {code}"),
+            ProgramMismatch(id) => write!(f, "proof is of another program, with id {id}"),
+            NumProofsMismatch(n) => write!(f, "number of statements does not match {n} proofs"),
+            MemoryMismatch => write!(f, "memory accesses do not match the audited memory"),
         }
     }
 }
@@ -11,7 +11,15 @@ use std::path::Path;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
 use nexus_vm::{
-    memory::{trie::MerkleTrie, Memory},
+    circuit::ARITY,
+    eval::NexusVM,
+    memory::{
+        cacheline::CacheLine,
+        offline::{self, Audit, OfflineMemory},
+        trie::MerkleTrie,
+        Memory,
+    },
+    trace::Statement,
     VMOpts,
 };
 
@@ -20,7 +28,10 @@ use nexus_nova::nova::pcd::compression::SNARK;
 use crate::prover::nova::{
     circuit::Tr,
     error::ProofError,
-    types::{ComPCDNode, ComPP, ComProof, IVCProof, PCDNode, ParPP, SeqPP, SpartanKey, SC},
+    types::{
+        AggregateProof, ComPCDNode, ComPP, ComProof, IVCProof, OfflineIVCProof, OfflineSC,
+        OfflineSeqPP, PCDNode, ParPP, SeqPP, SpartanKey, F1, SC,
+    },
 };
 
 pub const LOG_TARGET: &str = "nexus-prover";
@@ -56,12 +67,16 @@ pub fn load_proof<P: CanonicalDeserialize>(path: &Path) -> Result<P, ProofError>
 }
 
 pub(crate) type Trace = nexus_vm::trace::Trace<<MerkleTrie as Memory>::Proof>;
+pub type TraceStream<'a> = nexus_vm::trace::TraceStream<'a, MerkleTrie>;
 
 pub fn run(opts: &VMOpts, pow: bool) -> Result<Trace, ProofError> {
     Ok(nexus_vm::trace_vm::<MerkleTrie>(opts, pow, true, false)?)
 }
 
+/// Prepare `trace` for proving. Traces calling precompiles are rejected,
+/// as they are only proven by the SuperNova prover.
 pub fn init_circuit_trace(trace: Trace) -> Result<SC, ProofError> {
+    trace.check_no_precompiles()?;
     let tr = Tr::<MerkleTrie>(trace);
     Ok(tr)
 }
@@ -77,6 +92,16 @@ pub fn prove_seq(pp: &SeqPP, trace: Trace) -> Result<IVCProof, ProofError> {
     Ok(proof)
 }
 
+/// Prove a program trace sequentially, consuming it one block at a time.
+pub fn prove_seq_stream(pp: &SeqPP, trace: TraceStream) -> Result<IVCProof, ProofError> {
+    let mut proof = None;
+    for block in trace {
+        let tr = init_circuit_trace(block?)?;
+        proof = Some(prove_seq_step(proof, pp, &tr)?);
+    }
+    proof.ok_or(ProofError::EmptyTrace)
+}
+
 pub fn prove_seq_step(
     proof: Option<IVCProof>,
     pp: &SeqPP,
@@ -95,6 +120,180 @@ pub fn prove_seq_step(
     Ok(pr)
 }
 
+/// Verify a sequential proof, and check that it proves `statement`: that the
+/// proven execution is of the program with identifier `statement.program`
+/// (see [`nexus_vm::program_id`]), read the public input and wrote the output
+/// hashed in `statement.io`, and exited with its exit code.
+pub fn verify_seq(pp: &SeqPP, proof: &IVCProof, statement: &Statement) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Verifying the proof",
+    );
+
+    proof.verify(pp)?;
+    Ok(statement.check(proof.z_0(), proof.z_i())?)
+}
+
+/// A sequential proof of an execution using offline memory checking, together
+/// with the initial memory of the execution and the audit of its final memory
+/// needed to check the memory accesses, see [`offline::verify`].
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct AuditedIVCProof {
+    /// Proof of the execution.
+    pub proof: OfflineIVCProof,
+    /// Contents of memory before the execution.
+    pub initial: Vec<(u32, CacheLine)>,
+    /// Audit of the memory after the execution.
+    pub audit: Audit,
+}
+
+impl AuditedIVCProof {
+    /// Verify the proof, and check that its memory accesses match its initial
+    /// memory and audit. The program is not checked, see [`verify_seq_offline`].
+    pub fn verify(&self, pp: &OfflineSeqPP) -> Result<(), ProofError> {
+        self.proof.verify(pp)?;
+        let start = self.proof.z_0()[ARITY - 1];
+        let end = self.proof.z_i()[ARITY - 1];
+        if !offline::verify(&self.initial, start, end, &self.audit) {
+            return Err(ProofError::MemoryMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// Prove the execution of `vm` sequentially, using offline memory checking
+/// in place of Merkle proofs of memory accesses.
+pub fn prove_seq_offline(
+    pp: &OfflineSeqPP,
+    vm: &mut NexusVM<OfflineMemory>,
+    k: usize,
+) -> Result<AuditedIVCProof, ProofError> {
+    let initial = vm.mem.lines();
+    let (trace, audit) = offline::trace(vm, k, false)?;
+    trace.check_no_precompiles()?;
+    let tr: OfflineSC = Tr(trace);
+
+    let mut proof = OfflineIVCProof::new(&tr.input(0)?);
+    for _ in 0..tr.steps() {
+        proof = OfflineIVCProof::prove_step(proof, pp, &tr)?;
+    }
+
+    Ok(AuditedIVCProof { proof, initial, audit })
+}
+
+/// Verify a sequential proof made using offline memory checking, check that
+/// its memory accesses match its initial memory and audit, and that it proves
+/// `statement`, see [`verify_seq`].
+pub fn verify_seq_offline(
+    pp: &OfflineSeqPP,
+    proof: &AuditedIVCProof,
+    statement: &Statement,
+) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Verifying the proof",
+    );
+
+    proof.verify(pp)?;
+    Ok(statement.check_with_memory(proof.proof.z_0(), proof.proof.z_i(), &proof.initial)?)
+}
+
+/// Aggregate sequential proofs, possibly of different programs, into a single proof.
+///
+/// The proofs are not verified, an invalid proof results in an invalid aggregate proof.
+pub fn aggregate_seq(pp: &SeqPP, proofs: &[IVCProof]) -> Result<AggregateProof, ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        num_proofs = proofs.len(),
+        "Aggregating the proofs",
+    );
+
+    Ok(AggregateProof::aggregate(pp, proofs)?)
+}
+
+/// Verify an aggregate proof, and check that the aggregated proofs prove `statements`,
+/// in order, see [`verify_seq`].
+pub fn verify_aggregate(
+    pp: &SeqPP,
+    proof: &AggregateProof,
+    statements: &[Statement],
+) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        num_proofs = proof.num_proofs(),
+        "Verifying the aggregate proof",
+    );
+
+    if statements.len() != proof.num_proofs() {
+        return Err(ProofError::NumProofsMismatch(proof.num_proofs()));
+    }
+    proof.verify(pp)?;
+    for ((z_0, z_i, _), statement) in proof.statements().zip(statements) {
+        statement.check(z_0, z_i)?;
+    }
+    Ok(())
+}
+
+/// Verify sequential proofs as a batch, which is cheaper than verifying them one by one,
+/// and check that they prove `statements`, in order, see [`verify_seq`].
+pub fn verify_many(
+    pp: &SeqPP,
+    proofs: &[IVCProof],
+    statements: &[Statement],
+) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        num_proofs = proofs.len(),
+        "Verifying the proofs",
+    );
+
+    if statements.len() != proofs.len() {
+        return Err(ProofError::NumProofsMismatch(proofs.len()));
+    }
+    IVCProof::verify_many(pp, proofs)?;
+    for (proof, statement) in proofs.iter().zip(statements) {
+        statement.check(proof.z_0(), proof.z_i())?;
+    }
+    Ok(())
+}
+
+// Check that the execution proven by a PCD node, or a compression of one,
+// covers the steps from `i` on, and proves `statement`.
+fn check_root(i: u64, z_i: &[F1], z_j: &[F1], statement: &Statement) -> Result<(), ProofError> {
+    if i != 0 {
+        return Err(ProofError::InvalidIndex(i as usize));
+    }
+    Ok(statement.check(z_i, z_j)?)
+}
+
+/// Verify the root of a parallel proof, and check that it proves `statement`,
+/// see [`verify_seq`].
+pub fn verify_par(pp: &ParPP, node: &PCDNode, statement: &Statement) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Verifying the proof",
+    );
+
+    node.verify(pp)?;
+    check_root(node.i, &node.z_i, &node.z_j, statement)
+}
+
+/// Verify the root of a compressible parallel proof, and check that it proves
+/// `statement`, see [`verify_seq`].
+pub fn verify_par_com(
+    pp: &ComPP,
+    node: &ComPCDNode,
+    statement: &Statement,
+) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Verifying the proof",
+    );
+
+    node.verify(pp)?;
+    check_root(node.i, &node.z_i, &node.z_j, statement)
+}
+
 macro_rules! prove_par_impl {
     ( $pp_type:ty, $node_type:ty, $name:ident, $leaf_step_name:ident, $parent_step_name:ident) => {
         pub fn $name(pp: &$pp_type, trace: Trace) -> Result<$node_type, ProofError> {
@@ -132,6 +331,56 @@ macro_rules! prove_par_impl {
     };
 }
 
+// Streaming version of `prove_par_impl`. Leaves are proven for even blocks,
+// and each odd block joins the two subtrees on either side of it; nodes are
+// merged as soon as both subtrees are complete, so that only a logarithmic
+// number of nodes and blocks are held at any time.
+macro_rules! prove_par_stream_impl {
+    ( $pp_type:ty, $node_type:ty, $name:ident, $leaf_step_name:ident, $parent_step_name:ident) => {
+        pub fn $name(pp: &$pp_type, trace: TraceStream) -> Result<$node_type, ProofError> {
+            // completed subtrees, with their height and the block following them
+            let mut stack: Vec<($node_type, usize, Option<SC>)> = Vec::new();
+            let mut num_steps = 0;
+
+            for block in trace {
+                let tr = init_circuit_trace(block?)?;
+                let i = tr.0.start;
+                num_steps += 1;
+
+                if i % 2 == 1 {
+                    match stack.last_mut() {
+                        Some((_, _, next)) => *next = Some(tr),
+                        None => return Err(ProofError::InvalidIndex(i)),
+                    }
+                    continue;
+                }
+
+                let mut node = $leaf_step_name(pp, &tr, i)?;
+                let mut height = 0;
+                while let Some((_, h, _)) = stack.last() {
+                    if *h != height {
+                        break;
+                    }
+                    let (left, _, next) = stack.pop().unwrap();
+                    let next = next.ok_or(ProofError::InvalidIndex(i))?;
+                    node = $parent_step_name(pp, &next, &left, &node)?;
+                    height += 1;
+                }
+                stack.push((node, height, None));
+            }
+
+            if num_steps == 0 {
+                return Err(ProofError::EmptyTrace);
+            }
+            if !(num_steps + 1).is_power_of_two() || stack.len() != 1 {
+                return Err(ProofError::IncompleteTrace(num_steps));
+            }
+
+            Ok(stack.pop().unwrap().0)
+        }
+    };
+}
+
 macro_rules! prove_par_leaf_step_impl {
     ( $pp_type:ty, $node_type:ty, $name:ident ) => {
         pub fn $name(pp: &$pp_type, tr: &SC, i: usize) -> Result<$node_type, ProofError> {
@@ -177,6 +426,20 @@ prove_par_impl!(
     prove_par_com_leaf_step,
     prove_par_com_parent_step
 );
+prove_par_stream_impl!(
+    ParPP,
+    PCDNode,
+    prove_par_stream,
+    prove_par_leaf_step,
+    prove_par_parent_step
+);
+prove_par_stream_impl!(
+    ComPP,
+    ComPCDNode,
+    prove_par_com_stream,
+    prove_par_com_leaf_step,
+    prove_par_com_parent_step
+);
 
 pub fn compress(
     compression_pp: &ComPP,
@@ -193,10 +456,13 @@ pub fn compress(
     Ok(compressed_pcd_proof)
 }
 
+/// Verify a compressed proof, and check that it proves `statement`, see
+/// [`verify_seq`].
 pub fn verify_compressed(
     key: &SpartanKey,
     params: &ComPP,
     proof: &ComProof,
+    statement: &Statement,
 ) -> Result<(), ProofError> {
     tracing::info!(
         target: LOG_TARGET,
@@ -204,7 +470,7 @@ pub fn verify_compressed(
     );
 
     SNARK::verify(key, params, proof)?;
-    Ok(())
+    check_root(proof.i, &proof.z_i, &proof.z_j, statement)
 }
 
 #[cfg(test)]
@@ -214,6 +480,7 @@ mod tests {
     use crate::nvm::memory::MerkleTrie;
     use crate::prover::nova::circuit::nop_circuit;
     use nexus_nova::poseidon_config;
+    use nexus_vm::{machines::nop_vm, syscalls::ExitCode, trace::ProgramId};
 
     #[test]
     fn test_prove_seq() -> Result<(), ProofError> {
@@ -225,14 +492,187 @@ mod tests {
         let params = SeqPP::setup(ro_config, &circuit, &(), &())?;
 
         let proof = prove_seq(&params, trace)?;
+        let program = ProgramId::initial(&nop_vm::<MerkleTrie>(1));
+        let statement = |output: &[u8], exit_code| Statement::new(program, &[], output, exit_code);
         assert!(proof.verify(&params).is_ok());
+        assert!(verify_seq(&params, &proof, &statement(&[], ExitCode::SUCCESS)?).is_ok());
+        assert!(matches!(
+            verify_seq(&params, &proof, &statement(&[0], ExitCode::SUCCESS)?),
+            Err(ProofError::IOMismatch)
+        ));
+        assert!(matches!(
+            verify_seq(&params, &proof, &statement(&[], ExitCode::PANIC)?),
+            Err(ProofError::ExitCodeMismatch(ExitCode::SUCCESS))
+        ));
+        let other = Statement::new(
+            ProgramId::initial(&nop_vm::<MerkleTrie>(2)),
+            &[],
+            &[],
+            ExitCode::SUCCESS,
+        )?;
+        assert!(matches!(
+            verify_seq(&params, &proof, &other),
+            Err(ProofError::ProgramMismatch(id)) if id == program
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prove_seq_offline() -> Result<(), ProofError> {
+        let ro_config = poseidon_config();
+        let circuit = nop_circuit::<OfflineMemory>(1)?;
+        let params = OfflineSeqPP::setup(ro_config, &circuit, &(), &())?;
+
+        let mut proof = prove_seq_offline(&params, &mut nop_vm::<OfflineMemory>(3), 1)?;
+        assert_eq!(proof.proof.step_num(), 4);
+        let program = ProgramId::initial(&nop_vm::<OfflineMemory>(3));
+        let statement = Statement::new(program, &[], &[], ExitCode::SUCCESS)?;
+        assert!(verify_seq_offline(&params, &proof, &statement).is_ok());
+
+        let other = ProgramId::initial(&nop_vm::<OfflineMemory>(4));
+        assert!(matches!(
+            verify_seq_offline(&params, &proof, &Statement { program: other, ..statement }),
+            Err(ProofError::ProgramMismatch(_))
+        ));
+
+        // the audit must match the final memory
+        proof.audit.clock += 1;
+        assert!(matches!(
+            verify_seq_offline(&params, &proof, &statement),
+            Err(ProofError::MemoryMismatch)
+        ));
+        proof.audit.clock -= 1;
+
+        // and the execution must start from the initial memory
+        proof.initial.pop();
+        assert!(matches!(
+            verify_seq_offline(&params, &proof, &statement),
+            Err(ProofError::MemoryMismatch)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prove_seq_stream() -> Result<(), ProofError> {
+        let ro_config = poseidon_config();
+        let circuit = nop_circuit::<MerkleTrie>(1)?;
+        let params = SeqPP::setup(ro_config, &circuit, &(), &())?;
+
+        let mut vm = nop_vm::<MerkleTrie>(3);
+        let proof = prove_seq_stream(&params, TraceStream::new(&mut vm, 1, false)?)?;
+        assert_eq!(proof.step_num(), 4);
+        let program = ProgramId::initial(&nop_vm::<MerkleTrie>(3));
+        let statement = Statement::new(program, &[], &[], ExitCode::SUCCESS)?;
+        assert!(verify_seq(&params, &proof, &statement).is_ok());
+
+        let expected = prove_seq(
+            &params,
+            nexus_vm::trace::trace(&mut nop_vm::<MerkleTrie>(3), 1, false)?,
+        )?;
+        assert_eq!(proof.z_i(), expected.z_i());
+
+        // an exhausted stream has no blocks left to prove
+        let mut trace = TraceStream::new(&mut vm, 1, false)?;
+        trace.by_ref().for_each(drop);
+        assert!(matches!(
+            prove_seq_stream(&params, trace),
+            Err(ProofError::EmptyTrace)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prove_par_stream() -> Result<(), ProofError> {
+        let circuit = nop_circuit::<MerkleTrie>(1)?;
+        let params: ParPP = pp::gen_pp(&circuit, &())?;
+
+        let program = ProgramId::initial(&nop_vm::<MerkleTrie>(2));
+        let statement = Statement::new(program, &[], &[], ExitCode::SUCCESS)?;
+
+        let trace = nexus_vm::trace::trace(&mut nop_vm::<MerkleTrie>(2), 1, false)?;
+        let node = prove_par(&params, trace)?;
+        assert!(verify_par(&params, &node, &statement).is_ok());
+
+        let mut vm = nop_vm::<MerkleTrie>(2);
+        let stream = prove_par_stream(&params, TraceStream::new(&mut vm, 1, false)?)?;
+        assert!(verify_par(&params, &stream, &statement).is_ok());
+        assert_eq!((stream.i, stream.j), (node.i, node.j));
+        assert_eq!((&stream.z_i, &stream.z_j), (&node.z_i, &node.z_j));
+
+        // an exhausted stream has no blocks left to prove
+        let mut vm = nop_vm::<MerkleTrie>(2);
+        let mut trace = TraceStream::new(&mut vm, 1, false)?;
+        trace.by_ref().for_each(drop);
+        assert!(matches!(
+            prove_par_stream(&params, trace),
+            Err(ProofError::EmptyTrace)
+        ));
+
+        // two blocks do not form a complete tree
+        let mut vm = nop_vm::<MerkleTrie>(1);
+        assert!(matches!(
+            prove_par_stream(&params, TraceStream::new(&mut vm, 1, false)?),
+            Err(ProofError::IncompleteTrace(2))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregate_seq() -> Result<(), ProofError> {
+        let ro_config = poseidon_config();
+        let circuit = nop_circuit::<MerkleTrie>(1)?;
+        let params = SeqPP::setup(ro_config, &circuit, &(), &())?;
+
+        let proofs = [1, 3]
+            .into_iter()
+            .map(|n| -> Result<IVCProof, ProofError> {
+                let trace = nexus_vm::trace::trace(&mut nop_vm::<MerkleTrie>(n), 1, false)?;
+                prove_seq(&params, trace)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let statements = [1, 3]
+            .into_iter()
+            .map(|n| {
+                let program = ProgramId::initial(&nop_vm::<MerkleTrie>(n));
+                Statement::new(program, &[], &[], ExitCode::SUCCESS)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert!(verify_many(&params, &proofs, &statements).is_ok());
+        let aggregate = aggregate_seq(&params, &proofs)?;
+        assert!(verify_aggregate(&params, &aggregate, &statements).is_ok());
+
+        // the statements are checked in order
+        let swapped = [statements[1], statements[0]];
+        assert!(matches!(
+            verify_many(&params, &proofs, &swapped),
+            Err(ProofError::ProgramMismatch(_))
+        ));
+        assert!(matches!(
+            verify_aggregate(&params, &aggregate, &swapped),
+            Err(ProofError::ProgramMismatch(_))
+        ));
+        assert!(matches!(
+            verify_aggregate(&params, &aggregate, &statements[..1]),
+            Err(ProofError::NumProofsMismatch(2))
+        ));
+
+        let output = Statement::new(statements[1].program, &[], &[0], ExitCode::SUCCESS)?;
+        assert!(matches!(
+            verify_aggregate(&params, &aggregate, &[statements[0], output]),
+            Err(ProofError::IOMismatch)
+        ));
 
         Ok(())
     }
 
     #[test]
     fn prove_verify_test_machine() -> Result<(), ProofError> {
-        use nexus_vm::{machines::MACHINES, trace_vm};
+        use nexus_vm::{error::NexusVMError, machines::MACHINES, trace_vm};
         let public_params = pp::gen_vm_pp(16, &()).expect("error generating public parameters");
         for (name, _f_code, _f_result, _f_input) in MACHINES {
             let vm_opts = VMOpts {
@@ -241,6 +681,16 @@ mod tests {
                 file: None,
             };
             let trace = trace_vm::<MerkleTrie>(&vm_opts, false, false, false).unwrap();
+            // precompile calls are only proven by SuperNova
+            if *name == "keccak" {
+                assert!(matches!(
+                    prove_seq(&public_params, trace),
+                    Err(ProofError::NexusVMError(
+                        NexusVMError::UnprovablePrecompile(..)
+                    ))
+                ));
+                continue;
+            }
             let proof = prove_seq(&public_params, trace)
                 .unwrap_or_else(|_| panic!("error proving {}", name));
             proof
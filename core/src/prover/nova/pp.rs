@@ -1,17 +1,24 @@
 use std::fs::File;
+use std::path::Path;
 use zstd::stream::{Decoder, Encoder};
 
 pub use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
-use super::circuit::nop_circuit;
+use nexus_config::vm::ProverImpl;
+
+use nexus_vm::memory::Memory;
+
+use super::circuit::{nop_circuit, Tr};
 use super::error::*;
 use super::types::*;
 use super::LOG_TARGET;
+use crate::prover::params::{serialize_digest, ParamsHeader};
 
-pub fn gen_pp<C, SP>(circuit: &SC, aux: &C::SetupAux) -> Result<PP<C, SP>, ProofError>
+pub fn gen_pp<C, SP, S>(circuit: &S, aux: &C::SetupAux) -> Result<PP<C, SP, S>, ProofError>
 where
     C: CommitmentScheme<P1>,
-    SP: SetupParams<G1, G2, C, C2, RO, SC>,
+    S: StepCircuit<F1>,
+    SP: SetupParams<G1, G2, C, C2, RO, S>,
 {
     tracing::info!(
         target: LOG_TARGET,
@@ -21,11 +28,16 @@ where
     Ok(SP::setup(ro_config(), circuit, aux, &())?)
 }
 
-pub fn save_pp<C, SP>(pp: &PP<C, SP>, file: &str) -> Result<(), ProofError>
+pub fn save_pp<C, SP, S>(
+    pp: &PP<C, SP, S>,
+    prover: ProverImpl,
+    k: usize,
+    file: &str,
+) -> Result<(), ProofError>
 where
     C: CommitmentScheme<P1>,
-    SC: StepCircuit<F1>,
-    SP: SetupParams<G1, G2, C, C2, RO, SC>,
+    S: StepCircuit<F1>,
+    SP: SetupParams<G1, G2, C, C2, RO, S>,
 {
     tracing::info!(
         target: LOG_TARGET,
@@ -33,7 +45,9 @@ where
         "Saving public parameters",
     );
 
-    let f = File::create(file)?;
+    let header = ParamsHeader::new(prover, k, &pp.digest)?;
+    let mut f = File::create(file)?;
+    header.write(&mut f)?;
     let mut enc = Encoder::new(&f, 0)?;
     pp.serialize_compressed(&mut enc)?;
     enc.finish()?;
@@ -41,11 +55,11 @@ where
     Ok(())
 }
 
-pub fn load_pp<C, SP>(file: &str) -> Result<PP<C, SP>, ProofError>
+pub fn load_pp<C, SP, S>(file: &str) -> Result<PP<C, SP, S>, ProofError>
 where
     C: CommitmentScheme<P1>,
-    SC: StepCircuit<F1> + Sync,
-    SP: SetupParams<G1, G2, C, C2, RO, SC> + Sync,
+    S: StepCircuit<F1> + Sync,
+    SP: SetupParams<G1, G2, C, C2, RO, S> + Sync,
 {
     tracing::info!(
         target: LOG_TARGET,
@@ -53,24 +67,58 @@ where
         "Loading public parameters",
     );
 
-    let f = File::open(file)?;
+    let mut f = File::open(file)?;
+    let header = ParamsHeader::read(&mut f)?;
     let mut dec = Decoder::new(&f)?;
-    let pp = PP::<C, SP>::deserialize_compressed(&mut dec)?;
+    let mut pp = PP::<C, SP, S>::deserialize_compressed(&mut dec)?;
+    // the parameters have been corrupted, or their digest was not computed from them
+    if !pp.check_digest() {
+        return Err(ProofError::InvalidPP);
+    }
+    if let Some(header) = header {
+        // the header was not written for these parameters
+        if header.digest != serialize_digest(&pp.digest)? {
+            return Err(ProofError::InvalidPP);
+        }
+    }
     Ok(pp)
 }
 
-pub fn gen_vm_pp<C, SP>(k: usize, aux: &C::SetupAux) -> Result<PP<C, SP>, ProofError>
+/// Load public parameters from `file`, checking that they were generated
+/// for `prover` with `k` instructions per step.
+///
+/// Files written without a [`ParamsHeader`] cannot be checked, and are loaded as is.
+pub fn load_pp_for<C, SP, S>(
+    file: &str,
+    prover: ProverImpl,
+    k: usize,
+) -> Result<PP<C, SP, S>, ProofError>
+where
+    C: CommitmentScheme<P1>,
+    S: StepCircuit<F1> + Sync,
+    SP: SetupParams<G1, G2, C, C2, RO, S> + Sync,
+{
+    if let Some(header) = ParamsHeader::load(Path::new(file))? {
+        header.check(prover, k)?;
+    }
+    load_pp(file)
+}
+
+pub fn gen_vm_pp<C, SP, M>(k: usize, aux: &C::SetupAux) -> Result<PP<C, SP, Tr<M>>, ProofError>
 where
-    SP: SetupParams<G1, G2, C, C2, RO, SC>,
+    SP: SetupParams<G1, G2, C, C2, RO, Tr<M>>,
     C: CommitmentScheme<P1>,
+    M: Memory,
+    M::Proof: Send + Sync,
 {
-    let tr = nop_circuit(k)?;
+    let tr = nop_circuit::<M>(k)?;
     gen_pp(&tr, aux)
 }
 
-pub fn show_pp<C, SP>(pp: &PP<C, SP>)
+pub fn show_pp<C, SP, S>(pp: &PP<C, SP, S>)
 where
-    SP: SetupParams<G1, G2, C, C2, RO, SC>,
+    S: StepCircuit<F1>,
+    SP: SetupParams<G1, G2, C, C2, RO, S>,
     C: CommitmentScheme<P1>,
 {
     tracing::debug!(
@@ -26,7 +26,7 @@ pub use nexus_nova::{
     r1cs::{R1CSShape, R1CSWitness},
     StepCircuit,
 };
-use nexus_vm::memory::trie::MerkleTrie;
+use nexus_vm::memory::{offline::OfflineMemory, trie::MerkleTrie};
 
 // concrete constraint system
 pub type CS = ConstraintSystemRef<F1>;
@@ -49,16 +49,22 @@ pub type SRS = <PC as PolyCommitmentScheme<P1>>::SRS;
 
 pub type SC = crate::prover::nova::circuit::Tr<MerkleTrie>;
 
+// step circuit using offline memory checking
+pub type OfflineSC = crate::prover::nova::circuit::Tr<OfflineMemory>;
+
 // concrete public parameters
-pub type PP<C, SP> = PublicParams<G1, G2, C, C2, RO, SC, SP>;
+pub type PP<C, SP, S = SC> = PublicParams<G1, G2, C, C2, RO, S, SP>;
 
 pub type SeqPP = seq::PublicParams<G1, G2, C1, C2, RO, SC>;
 pub type ParPP = pcd::PublicParams<G1, G2, C1, C2, RO, SC>;
 pub type ComPP = pcd::PublicParams<G1, G2, PVC1, C2, RO, SC>;
+pub type OfflineSeqPP = seq::PublicParams<G1, G2, C1, C2, RO, OfflineSC>;
 
 pub type SpartanKey = com::SNARKKey<P1, PC>;
 
 pub type IVCProof = seq::IVCProof<G1, G2, C1, C2, RO, SC>;
+pub type OfflineIVCProof = seq::IVCProof<G1, G2, C1, C2, RO, OfflineSC>;
+pub type AggregateProof = seq::AggregateProof<G1, G2, C1, C2, RO, SC>;
 pub type PCDNode = pcd::PCDNode<G1, G2, C1, C2, RO, SC>;
 pub type ComPCDNode = pcd::PCDNode<G1, G2, PVC1, C2, RO, SC>;
 pub type ComProof = com::CompressedPCDProof<G1, G2, PC, C2, RO, SC>;
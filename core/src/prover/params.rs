@@ -0,0 +1,198 @@
+//! Metadata identifying a set of public parameters.
+//!
+//! Public parameter files start with the magic bytes [`MAGIC`], the format
+//! [`VERSION`] as a little-endian `u16` and the compressed serialization of a
+//! [`ParamsHeader`], followed by the zstd-compressed parameters. The header
+//! records the digest of the parameters, which covers the circuit shapes and
+//! commitment keys, so that it can be compared against the digest recorded in
+//! a proof without loading the parameters themselves.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+
+use nexus_config::vm::ProverImpl;
+
+/// Magic bytes identifying a public parameters file.
+pub const MAGIC: [u8; 4] = *b"NXPP";
+
+/// Current version of the public parameters file format.
+pub const VERSION: u16 = 1;
+
+/// The public parameters do not match the ones expected by the prover or verifier.
+#[derive(Debug)]
+pub struct ParamsMismatch {
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParamsMismatch {
+    /// Mismatch between the serialized digests of two sets of public parameters.
+    pub fn digests(expected: &[u8], found: &[u8]) -> Self {
+        Self {
+            expected: format!("parameters with digest {}", to_hex(expected)),
+            found: format!("parameters with digest {}", to_hex(found)),
+        }
+    }
+}
+
+impl Error for ParamsMismatch {}
+
+impl Display for ParamsMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "public parameters mismatch: expected {}, found {}",
+            self.expected, self.found
+        )
+    }
+}
+
+/// Describes the public parameters stored in a file.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ParamsHeader {
+    /// Name of the prover, as displayed by [`ProverImpl`].
+    prover: String,
+    /// Number of instructions per folding step.
+    pub k: u64,
+    /// Serialized digest of the public parameters.
+    pub digest: Vec<u8>,
+}
+
+impl ParamsHeader {
+    pub fn new(
+        prover: ProverImpl,
+        k: usize,
+        digest: &impl CanonicalSerialize,
+    ) -> Result<Self, SerializationError> {
+        Ok(Self {
+            prover: prover.to_string(),
+            k: k as u64,
+            digest: serialize_digest(digest)?,
+        })
+    }
+
+    /// Name of the prover the parameters were generated for.
+    pub fn prover(&self) -> &str {
+        &self.prover
+    }
+
+    /// Hex encoding of the serialized digest.
+    pub fn digest_hex(&self) -> String {
+        to_hex(&self.digest)
+    }
+
+    /// Check that the parameters were generated for `prover` with `k`
+    /// instructions per step.
+    pub fn check(&self, prover: ProverImpl, k: usize) -> Result<(), ParamsMismatch> {
+        if self.prover != prover.to_string() || self.k != k as u64 {
+            return Err(ParamsMismatch {
+                expected: format!("{prover} parameters with k = {k}"),
+                found: self.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that the parameters have the serialized digest `digest`, as
+    /// recorded in a proof.
+    pub fn check_digest(&self, digest: &[u8]) -> Result<(), ParamsMismatch> {
+        if self.digest != digest {
+            return Err(ParamsMismatch::digests(digest, &self.digest));
+        }
+        Ok(())
+    }
+
+    /// Write the header at the start of a public parameters file.
+    pub fn write<W: Write>(&self, mut w: W) -> Result<(), SerializationError> {
+        w.write_all(&MAGIC)?;
+        w.write_all(&VERSION.to_le_bytes())?;
+        self.serialize_compressed(w)
+    }
+
+    /// Read the header at the start of a public parameters file, leaving `r`
+    /// positioned at the start of the compressed parameters.
+    ///
+    /// Files written before headers were introduced start directly with the
+    /// compressed parameters; in that case `r` is rewound and `None` is returned.
+    pub fn read<R: Read + Seek>(mut r: R) -> Result<Option<Self>, SerializationError> {
+        let mut magic = [0u8; 4];
+        if r.read_exact(&mut magic).is_err() || magic != MAGIC {
+            r.seek(SeekFrom::Start(0))?;
+            return Ok(None);
+        }
+        let mut version = [0u8; 2];
+        r.read_exact(&mut version)?;
+        if u16::from_le_bytes(version) != VERSION {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(Some(Self::deserialize_compressed(r)?))
+    }
+
+    /// Read the header of the public parameters file at `path`.
+    pub fn load(path: &Path) -> Result<Option<Self>, SerializationError> {
+        Self::read(File::open(path)?)
+    }
+}
+
+impl Display for ParamsHeader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} parameters with k = {}", self.prover, self.k)
+    }
+}
+
+/// Serialize the digest of a set of public parameters, as recorded in
+/// parameter files and proofs.
+pub fn serialize_digest(digest: &impl CanonicalSerialize) -> Result<Vec<u8>, SerializationError> {
+    let mut bytes = Vec::new();
+    digest.serialize_compressed(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_config::vm::NovaImpl;
+    use std::io::Cursor;
+
+    const PROVER: ProverImpl = ProverImpl::Nova(NovaImpl::Sequential);
+
+    #[test]
+    fn header_roundtrip() {
+        let header = ParamsHeader::new(PROVER, 16, &7u64).unwrap();
+
+        let mut bytes = Vec::new();
+        header.write(&mut bytes).unwrap();
+        bytes.extend_from_slice(b"params");
+
+        let mut r = Cursor::new(bytes);
+        assert_eq!(ParamsHeader::read(&mut r).unwrap(), Some(header.clone()));
+
+        let mut rest = Vec::new();
+        r.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"params");
+
+        assert!(header.check(PROVER, 16).is_ok());
+        assert!(header.check(PROVER, 32).is_err());
+        assert!(header
+            .check(ProverImpl::Nova(NovaImpl::Parallel), 16)
+            .is_err());
+        assert!(header.check_digest(&header.digest).is_ok());
+        assert!(header.check_digest(&[0; 8]).is_err());
+    }
+
+    #[test]
+    fn header_missing() {
+        let mut r = Cursor::new(b"\x28\xb5\x2f\xfdparams".to_vec());
+        assert_eq!(ParamsHeader::read(&mut r).unwrap(), None);
+        assert_eq!(r.position(), 0);
+    }
+}
@@ -0,0 +1,95 @@
+use ark_ff::BigInt;
+pub use ark_r1cs_std::{
+    alloc::AllocVar,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    R1CSVar,
+};
+pub use ark_relations::r1cs::SynthesisError;
+
+use nexus_vm::{
+    circuit::{build_class_constraints, OpcodeClass, ARITY},
+    machines::nop_vm,
+    memory::Memory,
+    trace::{trace, Trace},
+};
+
+use super::error::*;
+use super::types::*;
+
+/// A program trace with `k = 1`, proven using a separate step circuit for
+/// each [`OpcodeClass`].
+///
+/// The circuit state extends the VM state with the class of the next
+/// instruction, which is used as the selector of the next circuit.
+pub struct ClassTr<M: Memory>(pub Trace<M::Proof>);
+
+impl<M: Memory> ClassTr<M> {
+    pub fn steps(&self) -> usize {
+        self.0.blocks.len()
+    }
+
+    pub fn instructions(&self) -> usize {
+        self.0.k * self.0.blocks.len()
+    }
+
+    /// Returns the class of the instruction in block `index`, if there is one.
+    pub fn class(&self, index: usize) -> Option<OpcodeClass> {
+        let w = self.0.block(index)?.iter().next()?;
+        Some(OpcodeClass::of_witness(&w))
+    }
+
+    pub fn input(&self, index: usize) -> Result<Vec<F1>, ProofError> {
+        let mut z = self.0.input(index).ok_or(ProofError::InvalidIndex(index))?;
+        let class = self.class(index).ok_or(ProofError::InvalidIndex(index))?;
+        z.push(F1::from(class.index() as u64));
+        Ok(z)
+    }
+}
+
+pub fn nop_circuit<M: Memory>() -> Result<ClassTr<M>, ProofError> {
+    let mut vm = nop_vm::<M>(1);
+    let trace = trace(&mut vm, 1, false)?;
+    Ok(ClassTr(trace))
+}
+
+impl<M: Memory> NonUniformCircuit<F1> for ClassTr<M>
+where
+    M::Proof: Send + Sync,
+{
+    const ARITY: usize = ARITY + 1;
+
+    const NUM_CIRCUITS: usize = OpcodeClass::ALL.len();
+
+    fn compute_selector(
+        &self,
+        _: CS,
+        _: &FpVar<F1>,
+        z: &[FpVar<F1>],
+    ) -> Result<FpVar<F1>, SynthesisError> {
+        // the class of the current instruction is carried in the state
+        Ok(z[ARITY].clone())
+    }
+
+    fn generate_constraints(
+        &self,
+        cs: CS,
+        pc: u64,
+        i: &FpVar<F1>,
+        z: &[FpVar<F1>],
+    ) -> Result<Vec<FpVar<F1>>, SynthesisError> {
+        let index = i.value().map_or(0, |s| match s.into_bigint() {
+            BigInt(l) => l[0] as usize,
+        });
+        let class = OpcodeClass::from_index(pc as usize).ok_or(SynthesisError::Unsatisfiable)?;
+
+        z[ARITY].enforce_equal(&FpVar::constant(F1::from(pc)))?;
+        let mut v = build_class_constraints(cs.clone(), class, index, &z[..ARITY], &self.0)?;
+
+        // The class of the next instruction is not constrained here: the next
+        // circuit enforces that its instruction belongs to the claimed class.
+        let next = self.class(index + 1).map_or(0, OpcodeClass::index);
+        v.push(FpVar::new_witness(cs, || Ok(F1::from(next as u64)))?);
+        Ok(v)
+    }
+}
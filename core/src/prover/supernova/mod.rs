@@ -0,0 +1,164 @@
+//! Proving NexusVM executions with SuperNova, using a separate step circuit
+//! for each class of instructions.
+//!
+//! Each folding step proves a single instruction, using the circuit of its
+//! [`OpcodeClass`](nexus_vm::circuit::OpcodeClass), so that cheap instructions
+//! do not pay for the constraints of expensive ones.
+
+pub mod circuit;
+pub use super::nova::error;
+pub mod pp;
+pub mod types;
+
+use nexus_vm::{
+    circuit::ARITY,
+    memory::trie::MerkleTrie,
+    trace::{ProgramId, Statement},
+    VMOpts,
+};
+
+use crate::prover::supernova::{
+    circuit::ClassTr,
+    error::ProofError,
+    types::{NIVCProof, PP, SC},
+};
+
+use super::nova::{Trace, TraceStream, LOG_TARGET};
+
+/// Number of instructions proven per folding step.
+pub const K: usize = 1;
+
+pub fn run(opts: &VMOpts, pow: bool) -> Result<Trace, ProofError> {
+    if opts.k != K {
+        return Err(ProofError::InvalidPP);
+    }
+    super::nova::run(opts, pow)
+}
+
+pub fn init_circuit_trace(trace: Trace) -> Result<SC, ProofError> {
+    if trace.k != K {
+        return Err(ProofError::InvalidPP);
+    }
+    Ok(ClassTr::<MerkleTrie>(trace))
+}
+
+pub fn prove_seq(pp: &PP, trace: Trace) -> Result<NIVCProof, ProofError> {
+    let tr = init_circuit_trace(trace)?;
+
+    let mut proof = prove_seq_step(None, pp, &tr)?;
+    for _ in 1..tr.steps() {
+        proof = prove_seq_step(Some(proof), pp, &tr)?;
+    }
+
+    Ok(proof)
+}
+
+/// Prove a program trace sequentially, consuming it one block at a time.
+///
+/// Each step needs the class of the following instruction, so every block
+/// is proven once the next one has been produced.
+pub fn prove_seq_stream(pp: &PP, trace: TraceStream) -> Result<NIVCProof, ProofError> {
+    let mut proof = None;
+    let mut prev: Option<Trace> = None;
+    for block in trace {
+        let block = block?;
+        if let Some(mut tr) = prev.take() {
+            tr.blocks.extend(block.blocks.iter().cloned());
+            proof = Some(prove_seq_step(proof, pp, &init_circuit_trace(tr)?)?);
+        }
+        prev = Some(block);
+    }
+    let tr = init_circuit_trace(prev.ok_or(ProofError::EmptyTrace)?)?;
+    prove_seq_step(proof, pp, &tr)
+}
+
+pub fn prove_seq_step(proof: Option<NIVCProof>, pp: &PP, tr: &SC) -> Result<NIVCProof, ProofError> {
+    let mut pr;
+
+    if proof.is_none() {
+        let z_0 = tr.input(tr.0.start)?;
+        pr = NIVCProof::new(&z_0);
+    } else {
+        pr = proof.unwrap();
+    }
+
+    pr = NIVCProof::prove_step(pr, pp, tr)?;
+    Ok(pr)
+}
+
+/// The identifier of the program proven by `proof`, computed from the VM
+/// state in its initial step circuit state.
+pub fn program_id(proof: &NIVCProof) -> ProgramId {
+    ProgramId::from_state(&proof.z_0()[..ARITY])
+}
+
+/// Verify a sequential proof, and check that it proves `statement`, see
+/// [`nova::verify_seq`](super::nova::verify_seq).
+pub fn verify_seq(pp: &PP, proof: &NIVCProof, statement: &Statement) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Verifying the proof",
+    );
+
+    proof.verify(pp, proof.step_num() as usize)?;
+    Ok(statement.check(proof.z_0(), proof.z_i())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::prover::supernova::{circuit::nop_circuit, types::F1};
+    use nexus_vm::{
+        machines::{lookup_test_machine, loop_vm},
+        syscalls::ExitCode,
+    };
+
+    #[test]
+    fn test_prove_seq() -> Result<(), ProofError> {
+        let params = pp::gen_pp(&nop_circuit::<MerkleTrie>()?)?;
+
+        let trace = nexus_vm::trace::trace(&mut loop_vm::<MerkleTrie>(2), K, false)?;
+        let proof = prove_seq(&params, trace)?;
+        let program = ProgramId::initial(&loop_vm::<MerkleTrie>(2));
+        assert_eq!(program_id(&proof), program);
+        let statement = |output: &[u8]| Statement::new(program, &[], output, ExitCode::SUCCESS);
+        assert!(proof.verify(&params, proof.step_num() as usize).is_ok());
+        assert!(verify_seq(&params, &proof, &statement(&[])?).is_ok());
+        assert!(matches!(
+            verify_seq(&params, &proof, &statement(&[0])?),
+            Err(ProofError::IOMismatch)
+        ));
+        let other = ProgramId::initial(&loop_vm::<MerkleTrie>(3));
+        assert!(matches!(
+            verify_seq(&params, &proof, &Statement::new(other, &[], &[], ExitCode::SUCCESS)?),
+            Err(ProofError::ProgramMismatch(id)) if id == program
+        ));
+
+        let stream = prove_seq_stream(
+            &params,
+            TraceStream::new(&mut loop_vm::<MerkleTrie>(2), K, false)?,
+        )?;
+        assert!(verify_seq(&params, &stream, &statement(&[])?).is_ok());
+        assert_eq!(stream.z_i(), proof.z_i());
+
+        Ok(())
+    }
+    #[test]
+    fn test_prove_keccak() -> Result<(), ProofError> {
+        let params = pp::gen_pp(&nop_circuit::<MerkleTrie>()?)?;
+
+        // the keccak machine hashes a cache line in place, and loads the
+        // first word of the hash into x6
+        let mut vm = lookup_test_machine::<MerkleTrie>("keccak").unwrap();
+        let trace = nexus_vm::trace::trace(&mut vm, K, false)?;
+        let proof = prove_seq(&params, trace)?;
+        assert_eq!(proof.z_i()[1 + 6], F1::from(vm.regs.x[6]));
+
+        let program = ProgramId::initial(&lookup_test_machine::<MerkleTrie>("keccak").unwrap());
+        let statement = Statement::new(program, &[], &[], ExitCode::SUCCESS)?;
+        assert!(verify_seq(&params, &proof, &statement).is_ok());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use std::fs::File;
+use zstd::stream::{Decoder, Encoder};
+
+pub use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use nexus_config::vm::ProverImpl;
+
+use nexus_vm::circuit::OpcodeClass;
+
+use super::circuit::nop_circuit;
+use super::error::*;
+use super::types::*;
+use super::{K, LOG_TARGET};
+use crate::prover::params::{serialize_digest, ParamsHeader};
+
+pub fn gen_pp(circuit: &SC) -> Result<PP, ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        "Generating public parameters",
+    );
+
+    Ok(PP::setup(ro_config(), circuit, &(), &())?)
+}
+
+pub fn save_pp(pp: &PP, file: &str) -> Result<(), ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        path = ?file,
+        "Saving public parameters",
+    );
+
+    let header = ParamsHeader::new(ProverImpl::SuperNova, K, &pp.digest)?;
+    let mut f = File::create(file)?;
+    header.write(&mut f)?;
+    let mut enc = Encoder::new(&f, 0)?;
+    pp.serialize_compressed(&mut enc)?;
+    enc.finish()?;
+    f.sync_all()?;
+    Ok(())
+}
+
+pub fn load_pp(file: &str) -> Result<PP, ProofError> {
+    tracing::info!(
+        target: LOG_TARGET,
+        path = ?file,
+        "Loading public parameters",
+    );
+
+    let mut f = File::open(file)?;
+    let header = ParamsHeader::read(&mut f)?;
+    if let Some(header) = &header {
+        header.check(ProverImpl::SuperNova, K)?;
+    }
+    let mut dec = Decoder::new(&f)?;
+    let mut pp = PP::deserialize_compressed(&mut dec)?;
+    // the parameters have been corrupted, or their digest was not computed from them
+    if !pp.check_digest() {
+        return Err(ProofError::InvalidPP);
+    }
+    if let Some(header) = header {
+        // the header was not written for these parameters
+        if header.digest != serialize_digest(&pp.digest)? {
+            return Err(ProofError::InvalidPP);
+        }
+    }
+    Ok(pp)
+}
+
+/// Generate public parameters for the per-class VM circuits, which always
+/// prove a single instruction per step.
+pub fn gen_vm_pp() -> Result<PP, ProofError> {
+    let tr = nop_circuit()?;
+    gen_pp(&tr)
+}
+
+pub fn show_pp(pp: &PP) {
+    for (class, shape) in OpcodeClass::ALL.iter().zip(&pp.shapes) {
+        tracing::debug!(
+            target: LOG_TARGET,
+            "Primary circuit ({:?}) {}",
+            class,
+            shape,
+        );
+    }
+    tracing::debug!(
+        target: LOG_TARGET,
+        "Secondary circuit {}",
+        pp.shape_secondary,
+    );
+}
@@ -0,0 +1,39 @@
+//! Default types and traits for use by the SuperNova zkVM prover
+
+pub use std::marker::PhantomData;
+
+pub use ark_ff::{Field, PrimeField};
+
+// concrete fields used
+pub use ark_bn254::{g1::Config as G1, Fr as F1, G1Projective as P1};
+pub use ark_grumpkin::{Fr as F2, GrumpkinConfig as G2, Projective as P2};
+
+// concrete sponge used
+pub use ark_crypto_primitives::sponge::poseidon::{PoseidonConfig, PoseidonSponge};
+
+pub use ark_relations::r1cs::ConstraintSystemRef;
+
+// types and traits from nexus prover
+pub use nexus_nova::{
+    commitment::CommitmentScheme, pedersen::PedersenCommitment, supernova, NonUniformCircuit,
+};
+use nexus_vm::memory::trie::MerkleTrie;
+
+// concrete constraint system
+pub type CS = ConstraintSystemRef<F1>;
+
+// random oracle
+pub type ROConfig = PoseidonConfig<F1>;
+pub type RO = PoseidonSponge<F1>;
+pub use nexus_nova::poseidon_config as ro_config;
+
+// commitment scheme
+pub type C1 = PedersenCommitment<P1>;
+pub type C2 = PedersenCommitment<P2>;
+
+pub type SC = crate::prover::supernova::circuit::ClassTr<MerkleTrie>;
+
+// concrete public parameters
+pub type PP = supernova::PublicParams<G1, G2, C1, C2, RO, SC>;
+
+pub type NIVCProof = supernova::NIVCProof<G1, G2, C1, C2, RO, SC>;
@@ -0,0 +1,21 @@
+// Repeatedly builds and drops a 64kb vector, allocating 4mb in total.
+// With the `free-list` feature, the memory of each vector is reused and
+// the program fits in 1mb, while the default bump allocator runs out of
+// memory. Run with `cargo nexus run --bin free_list --features free-list`.
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+extern crate alloc;
+use alloc::vec;
+use core::hint::black_box;
+
+use nexus_rt::println;
+
+#[nexus_rt::main(memlimit = 1)]
+fn main() {
+    for i in 0..64 {
+        let vec = vec![i as u8; 0x10000];
+        black_box(vec);
+    }
+
+    println!("Success!!!");
+}
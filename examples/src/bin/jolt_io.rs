@@ -0,0 +1,18 @@
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+use nexus_rt::{read_public_input, write_output};
+
+fn fib(n: u32) -> u32 {
+    match n {
+        0 => 0,
+        1 => 1,
+        _ => fib(n - 1) + fib(n - 2),
+    }
+}
+
+// Reads `n` from the public input and outputs the `n`-th Fibonacci number.
+#[nexus_rt::main]
+fn main() {
+    let n = read_public_input::<u32>().expect("failed to read public input");
+    write_output::<u32>(&fib(n))
+}
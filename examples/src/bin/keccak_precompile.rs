@@ -0,0 +1,16 @@
+// This example computes the same keccak hash as `keccak.rs`, using
+// the VM precompile rather than a software implementation. The input
+// fits in a cache line, so the execution can be proven with SuperNova.
+
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+use nexus_rt::{keccak256, print, println};
+
+#[nexus_rt::main]
+fn main() {
+    let hash = keccak256(b"Hello, World!");
+    for b in hash {
+        print!("{:02x}", b);
+    }
+    println!();
+}
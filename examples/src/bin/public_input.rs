@@ -0,0 +1,21 @@
+#![cfg_attr(target_arch = "riscv32", no_std, no_main)]
+
+use nexus_rt::{println, read_private_input, read_public_input, write_output};
+
+// Proves the public claim that `n` is composite, without revealing its factors.
+#[nexus_rt::main]
+fn main() {
+    let n = read_public_input::<u32>();
+    let factors = read_private_input::<(u32, u32)>();
+
+    let mut valid = false;
+    if let (Ok(n), Ok((x, y))) = (n, factors) {
+        println!("Read public input: {}", n);
+
+        valid = x > 1 && y > 1 && x.checked_mul(y) == Some(n);
+    } else {
+        println!("Missing public or private input...");
+    }
+
+    write_output::<bool>(&valid)
+}
@@ -3,6 +3,10 @@
 use nexus_vm::rv32::{Inst, RV32};
 
 use jolt_common::rv_trace as jolt_rv;
+use jolt_core::jolt::instruction::{
+    div::DIVInstruction, divu::DIVUInstruction, mulh::MULHInstruction, mulhsu::MULHSUInstruction,
+    rem::REMInstruction, remu::REMUInstruction, VirtualInstructionSequence,
+};
 
 pub fn inst(inst: Inst) -> jolt_rv::ELFInstruction {
     jolt_rv::ELFInstruction {
@@ -54,6 +58,11 @@ pub fn rv32_opcode(inst: RV32) -> jolt_rv::RV32IM {
         ALUI { aop: SRA, .. } => JoltRV32IM::SRAI,
         ALUI { aop: OR, .. } => JoltRV32IM::ORI,
         ALUI { aop: AND, .. } => JoltRV32IM::ANDI,
+        // note: RV32M has no immediate forms, these do not exist
+        ALUI {
+            aop: MUL | MULH | MULHSU | MULHU | DIV | DIVU | REM | REMU,
+            ..
+        } => JoltRV32IM::UNIMPL,
 
         ALU { aop: ADD, .. } => JoltRV32IM::ADD,
         ALU { aop: SUB, .. } => JoltRV32IM::SUB,
@@ -66,9 +75,50 @@ pub fn rv32_opcode(inst: RV32) -> jolt_rv::RV32IM {
         ALU { aop: OR, .. } => JoltRV32IM::OR,
         ALU { aop: AND, .. } => JoltRV32IM::AND,
 
+        ALU { aop: MUL, .. } => JoltRV32IM::MUL,
+        ALU { aop: MULH, .. } => JoltRV32IM::MULH,
+        ALU { aop: MULHSU, .. } => JoltRV32IM::MULHSU,
+        ALU { aop: MULHU, .. } => JoltRV32IM::MULHU,
+        ALU { aop: DIV, .. } => JoltRV32IM::DIV,
+        ALU { aop: DIVU, .. } => JoltRV32IM::DIVU,
+        ALU { aop: REM, .. } => JoltRV32IM::REM,
+        ALU { aop: REMU, .. } => JoltRV32IM::REMU,
+
         FENCE => JoltRV32IM::FENCE,
         ECALL { .. } => JoltRV32IM::ECALL,
         EBREAK { .. } => JoltRV32IM::EBREAK,
         UNIMP => JoltRV32IM::UNIMPL,
     }
 }
+
+/// Expand instructions that Jolt proves as a sequence of virtual instructions.
+///
+/// Must be applied to the bytecode consistently with [`virtual_trace`].
+pub fn virtual_sequence(inst: jolt_rv::ELFInstruction) -> Vec<jolt_rv::ELFInstruction> {
+    use jolt_rv::RV32IM as JoltRV32IM;
+
+    match inst.opcode {
+        JoltRV32IM::MULH => MULHInstruction::<32>::virtual_sequence(inst),
+        JoltRV32IM::MULHSU => MULHSUInstruction::<32>::virtual_sequence(inst),
+        JoltRV32IM::DIV => DIVInstruction::<32>::virtual_sequence(inst),
+        JoltRV32IM::DIVU => DIVUInstruction::<32>::virtual_sequence(inst),
+        JoltRV32IM::REM => REMInstruction::<32>::virtual_sequence(inst),
+        JoltRV32IM::REMU => REMUInstruction::<32>::virtual_sequence(inst),
+        _ => vec![inst],
+    }
+}
+
+/// Expand trace rows of instructions that Jolt proves as a sequence of virtual instructions.
+pub fn virtual_trace(row: jolt_rv::RVTraceRow) -> Vec<jolt_rv::RVTraceRow> {
+    use jolt_rv::RV32IM as JoltRV32IM;
+
+    match row.instruction.opcode {
+        JoltRV32IM::MULH => MULHInstruction::<32>::virtual_trace(row),
+        JoltRV32IM::MULHSU => MULHSUInstruction::<32>::virtual_trace(row),
+        JoltRV32IM::DIV => DIVInstruction::<32>::virtual_trace(row),
+        JoltRV32IM::DIVU => DIVUInstruction::<32>::virtual_trace(row),
+        JoltRV32IM::REM => REMInstruction::<32>::virtual_trace(row),
+        JoltRV32IM::REMU => REMUInstruction::<32>::virtual_trace(row),
+        _ => vec![row],
+    }
+}
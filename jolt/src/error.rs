@@ -14,4 +14,10 @@ pub enum Error {
 
     #[error(transparent)]
     ProofVerify(#[from] ProofVerifyError),
+
+    #[error("Input of {0} bytes exceeds the maximum input size")]
+    InputTooLarge(usize),
+
+    #[error("Proof does not match the expected input, output or exit code")]
+    IOMismatch,
 }
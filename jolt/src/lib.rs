@@ -3,6 +3,10 @@
 //! JoltVM works with a superset of NexusVM instruction set, hence the mapping is almost identical.
 //! Mainly, it's required to modify the link script for the memory shift, fetch additional sections
 //! from the ELF file, and build a trace of memory accesses.
+//!
+//! Jolt does not support ecalls, so the public input, the output and the panic flag are mapped
+//! to the memory regions of a [`JoltDevice`] instead, whose addresses are patched into the
+//! program when it is parsed (see [`parse::parse_elf`]).
 
 #![allow(clippy::type_complexity)]
 
@@ -40,6 +44,13 @@ pub mod parse;
 pub mod trace;
 
 pub use error::Error;
+pub use jolt_rv::JoltDevice;
+
+/// Maximum size of the public input in bytes, including its length prefix.
+pub const MAX_INPUT_SIZE: u64 = 0x1000;
+
+/// Maximum size of the output in bytes.
+pub const MAX_OUTPUT_SIZE: u64 = 0x1000;
 
 /// Wrapper for initialized VM.
 pub struct VM<M: Memory> {
@@ -51,12 +62,47 @@ pub struct VM<M: Memory> {
 
     /// Flattened initial state of memory.
     mem_init: Vec<(u64, u8)>,
+
+    /// Memory-mapped input and output.
+    io: JoltDevice,
 }
 
 impl<M: Memory> VM<M> {
     pub fn bytecode_size(&self) -> usize {
         self.insts.len()
     }
+
+    /// Set the public input of the program.
+    pub fn set_input(&mut self, input: &[u8]) -> Result<(), Error> {
+        let bytes = encode_input(input);
+        if bytes.len() as u64 > MAX_INPUT_SIZE {
+            return Err(Error::InputTooLarge(input.len()));
+        }
+
+        self.vm
+            .init_memory(self.io.memory_layout.input_start as u32, &bytes)?;
+        self.io.inputs = bytes;
+        Ok(())
+    }
+}
+
+/// Encode the public input as read by the program, prefixed with its length.
+fn encode_input(input: &[u8]) -> Vec<u8> {
+    let mut bytes = (input.len() as u32).to_le_bytes().to_vec();
+    bytes.extend_from_slice(input);
+    bytes
+}
+
+/// Addresses of the public input, the start and end of the output, and the panic flag,
+/// as patched into the program.
+fn io_addresses(io: &JoltDevice) -> [u32; 4] {
+    let layout = &io.memory_layout;
+    [
+        layout.input_start as u32,
+        layout.output_start as u32,
+        layout.output_end as u32,
+        layout.panic as u32,
+    ]
 }
 
 pub fn preprocess<M: Memory>(vm: &VM<M>) -> JoltPreprocessing {
@@ -75,9 +121,10 @@ pub fn preprocess<M: Memory>(vm: &VM<M>) -> JoltPreprocessing {
 
 pub fn prove(
     raw_trace: Vec<jolt_rv::RVTraceRow>,
+    io_device: JoltDevice,
     preprocessing: &JoltPreprocessing,
 ) -> Result<(JoltProof, JoltCommitments), Error> {
-    let (io_device, trace, circuit_flags) = build_jolt_trace::<F>(&raw_trace);
+    let (trace, circuit_flags) = build_jolt_trace::<F>(&raw_trace);
 
     Ok(rv32i_vm::RV32IJoltVM::prove(
         io_device,
@@ -92,32 +139,37 @@ pub fn verify(
     proof: JoltProof,
     commitments: JoltCommitments,
 ) -> Result<(), Error> {
+    // the program reads the addresses patched by `parse_elf`, so the proof must use the same layout
+    let expected = JoltDevice::new(MAX_INPUT_SIZE, MAX_OUTPUT_SIZE);
+    if io_addresses(&proof.program_io) != io_addresses(&expected) {
+        return Err(Error::IOMismatch);
+    }
+
     rv32i_vm::RV32IJoltVM::verify(preprocessing, proof, commitments).map_err(Into::into)
 }
 
+/// Check that `proof` attests to an execution with public input `input` and output `output`,
+/// which panicked or exited with a non-zero exit code if and only if `panic` is set.
+///
+/// The proof only commits to its input and output through [`JoltProof::program_io`], so this
+/// check is required in addition to [`verify`].
+pub fn check_io(proof: &JoltProof, input: &[u8], output: &[u8], panic: bool) -> Result<(), Error> {
+    let io = &proof.program_io;
+    if io.inputs != encode_input(input) || io.outputs != output || io.panic != panic {
+        return Err(Error::IOMismatch);
+    }
+    Ok(())
+}
+
 fn build_jolt_trace<F: JoltField>(
     raw_trace: &[jolt_rv::RVTraceRow],
-) -> (jolt_rv::JoltDevice, Vec<JoltTraceStep<RV32I>>, Vec<F>) {
-    // Nexus VM doesn't use JoltVM provided IO.
-    const MAX_INPUT_SIZE: u64 = 0;
-    const MAX_OUTPUT_SIZE: u64 = 0;
-
-    let io_device = jolt_rv::JoltDevice::new(MAX_INPUT_SIZE, MAX_OUTPUT_SIZE);
-
+) -> (Vec<JoltTraceStep<RV32I>>, Vec<F>) {
     // copy of [`jolt_core::host::Program::trace`]
     let trace: Vec<_> = raw_trace
         .into_par_iter()
-        .flat_map(|row| match row.instruction.opcode {
-            jolt_rv::RV32IM::MULH
-            | jolt_rv::RV32IM::MULHSU
-            | jolt_rv::RV32IM::DIV
-            | jolt_rv::RV32IM::DIVU
-            | jolt_rv::RV32IM::REM
-            | jolt_rv::RV32IM::REMU => unimplemented!(),
-            _ => vec![row],
-        })
+        .flat_map(|row| convert::virtual_trace(row.clone()))
         .map(|row| {
-            let instruction_lookup = if let Ok(jolt_instruction) = RV32I::try_from(row) {
+            let instruction_lookup = if let Ok(jolt_instruction) = RV32I::try_from(&row) {
                 Some(jolt_instruction)
             } else {
                 // Instruction does not use lookups
@@ -127,7 +179,7 @@ fn build_jolt_trace<F: JoltField>(
             JoltTraceStep {
                 instruction_lookup,
                 bytecode_row: JoltBytecodeRow::from_instruction::<RV32I>(&row.instruction),
-                memory_ops: (row).into(),
+                memory_ops: (&row).into(),
             }
         })
         .collect();
@@ -149,5 +201,5 @@ fn build_jolt_trace<F: JoltField>(
             });
         });
 
-    (io_device, trace, circuit_flag_trace)
+    (trace, circuit_flag_trace)
 }
@@ -7,14 +7,29 @@ use nexus_vm::elf::{
 };
 use nexus_vm::{
     init_vm,
+    loader::Program,
     memory::Memory,
     parse_elf_bytes,
     rv32::{parse::parse_inst, Inst, RV32},
+    NexusVMError,
 };
 
-use crate::{convert, Error, LOG_TARGET, VM};
+use crate::{
+    convert, io_addresses, Error, JoltDevice, LOG_TARGET, MAX_INPUT_SIZE, MAX_OUTPUT_SIZE, VM,
+};
+
+/// Symbol of the input/output address table of programs built with `nexus-rt` for Jolt.
+const IO_SYMBOL: &str = "__NEXUS_JOLT_IO";
 
+/// Parse an ELF file, and patch the addresses of the memory-mapped input and output
+/// into its input/output address table (if any).
 pub fn parse_elf<M: Memory>(bytes: &[u8]) -> Result<VM<M>, Error> {
+    let io = JoltDevice::new(MAX_INPUT_SIZE, MAX_OUTPUT_SIZE);
+
+    let mut bytes = bytes.to_vec();
+    patch_io_addresses(&mut bytes, &io)?;
+    let bytes = bytes.as_slice();
+
     let elf = parse_elf_bytes(bytes)?;
 
     let vm = init_vm(&elf, bytes)?;
@@ -22,37 +37,76 @@ pub fn parse_elf<M: Memory>(bytes: &[u8]) -> Result<VM<M>, Error> {
     let insts = parse_instructions(&elf, bytes)?
         .into_iter()
         .map(convert::inst)
+        .flat_map(convert::virtual_sequence)
         .collect();
-    let mem_init = parse_raw_memory(&elf, bytes)?;
+    let mem_init = parse_raw_memory(&Program::from_elf(&elf, bytes)?);
 
-    Ok(VM { vm, insts, mem_init })
+    Ok(VM { vm, insts, mem_init, io })
 }
 
-fn parse_raw_memory(elf: &ElfBytes<LittleEndian>, data: &[u8]) -> Result<Vec<(u64, u8)>, Error> {
-    let mut mem_init = Vec::new();
+fn patch_io_addresses(data: &mut [u8], io: &JoltDevice) -> Result<(), Error> {
+    let elf = parse_elf_bytes(data)?;
+    let program = Program::from_elf(&elf, data)?;
+
+    let Some(&addr) = program.symbols.get(IO_SYMBOL) else {
+        tracing::debug!(
+            target: LOG_TARGET,
+            "Program does not use memory-mapped input and output",
+        );
+        return Ok(());
+    };
 
-    let load_phdrs = elf
+    let table: Vec<u8> = io_addresses(io)
+        .iter()
+        .flat_map(|a| a.to_le_bytes())
+        .collect();
+
+    // the table must be initialized by a loadable segment, whose bounds are checked here as the
+    // file is patched before it is loaded
+    let addr = u64::from(addr);
+    let segment = elf
         .segments()
-        .unwrap()
+        .ok_or(NexusVMError::ELFFormat("missing program headers"))?
         .iter()
-        .filter(|phdr| phdr.p_type == PT_LOAD);
+        .filter(|phdr| phdr.p_type == PT_LOAD && phdr.p_memsz > 0)
+        .find(|p| {
+            p.p_vaddr <= addr
+                && p.p_vaddr
+                    .checked_add(p.p_filesz)
+                    .is_some_and(|end| addr + table.len() as u64 <= end)
+        })
+        .ok_or(NexusVMError::ELFFormat(
+            "input/output address table is not initialized",
+        ))?;
+
+    let offset = segment
+        .p_offset
+        .checked_add(addr - segment.p_vaddr)
+        .and_then(|offset| usize::try_from(offset).ok());
+    let dest = offset
+        .and_then(|s| data.get_mut(s..s.checked_add(table.len())?))
+        .ok_or(NexusVMError::ELFFormat("segment exceeds the file"))?;
 
-    for p in load_phdrs {
-        let s = p.p_offset as usize;
-        let e = (p.p_offset + p.p_filesz) as usize;
-        let bytes = &data[s..e];
+    dest.copy_from_slice(&table);
+    Ok(())
+}
 
-        let addr = p.p_vaddr as u32;
-        for (i, byte) in bytes.iter().enumerate() {
-            let addr = addr + (i as u32);
+fn parse_raw_memory(program: &Program) -> Vec<(u64, u8)> {
+    let mut mem_init = Vec::new();
+    for s in &program.segments {
+        for (i, byte) in s.data.iter().enumerate() {
+            let addr = s.vaddr + (i as u32);
             mem_init.push((addr as u64, *byte));
         }
     }
-    Ok(mem_init)
+    mem_init
 }
 
 fn parse_instructions(elf: &ElfBytes<LittleEndian>, data: &[u8]) -> Result<Vec<Inst>, Error> {
-    let sections = elf.section_headers().unwrap().iter().filter(|s| {
+    let Some(shdrs) = elf.section_headers() else {
+        return Err(NexusVMError::ELFFormat("missing section headers").into());
+    };
+    let sections = shdrs.iter().filter(|s| {
         s.sh_type == SHT_PROGBITS
             && s.sh_flags & u64::from(SHF_ALLOC) != 0
             && s.sh_flags & u64::from(SHF_EXECINSTR) != 0
@@ -60,11 +114,15 @@ fn parse_instructions(elf: &ElfBytes<LittleEndian>, data: &[u8]) -> Result<Vec<I
 
     let mut insts = Vec::new();
     for section in sections {
-        let s = section.sh_offset as usize;
-        let e = (section.sh_offset + section.sh_size) as usize;
-        let bytes = &data[s..e];
+        let bytes = usize::try_from(section.sh_offset)
+            .ok()
+            .zip(usize::try_from(section.sh_size).ok())
+            .and_then(|(s, n)| data.get(s..s.checked_add(n)?))
+            .ok_or(NexusVMError::ELFFormat("section exceeds the file"))?;
 
-        assert_eq!(bytes.len() % 4, 0);
+        if bytes.len() % 4 != 0 {
+            return Err(NexusVMError::ELFFormat("misaligned executable section").into());
+        }
 
         for (i, word) in bytes.chunks(4).enumerate() {
             let addr = section.sh_addr + (i as u64 * 4);
@@ -6,11 +6,17 @@ use nexus_vm::{
 
 use jolt_common::rv_trace as jolt_rv;
 
-use crate::{convert, Error, LOG_TARGET, VM};
+use crate::{convert, Error, JoltDevice, LOG_TARGET, VM};
 
 /// Trace VM execution in Jolt format.
-pub fn trace<M: Memory>(vm: VM<M>) -> Result<Vec<jolt_rv::RVTraceRow>, Error> {
-    let VM { mut vm, insts, .. } = vm;
+///
+/// Returns the trace together with the input, output and panic flag of the execution.
+pub fn trace<M: Memory>(vm: VM<M>) -> Result<(Vec<jolt_rv::RVTraceRow>, JoltDevice), Error> {
+    let VM { mut vm, insts, mut io, .. } = vm;
+
+    let output_start = io.memory_layout.output_start as u32;
+    let output_end = io.memory_layout.output_end as u32;
+    let mut output_len = 0;
 
     let mut trace = Vec::new();
     loop {
@@ -53,17 +59,32 @@ pub fn trace<M: Memory>(vm: VM<M>) -> Result<Vec<jolt_rv::RVTraceRow>, Error> {
 
         update_row_post_eval(&vm, &mut rv_row, store_addr);
 
+        if let Some((lop, addr)) = store_addr {
+            if (output_start..output_end).contains(&addr) {
+                let width = match lop {
+                    LOP::LB => 1,
+                    LOP::LH => 2,
+                    _ => 4,
+                };
+                output_len = output_len.max(addr - output_start + width);
+            }
+        }
+
         trace.push(rv_row);
     }
 
+    io.outputs = vm.mem.load_n(output_start, output_len)?;
+    io.panic = vm.mem.load(LOP::LBU, io.memory_layout.panic as u32)?.0 != 0;
+
     tracing::debug!(
         target: LOG_TARGET,
-        "Finished VM execution, trace len = {}, bytecode len = {}",
+        "Finished VM execution, trace len = {}, bytecode len = {}, output len = {}",
         trace.len(),
         insts.len(),
+        output_len,
     );
 
-    Ok(trace)
+    Ok((trace, io))
 }
 
 fn init_trace_row<M: Memory>(
@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 use sha3::{
@@ -28,6 +28,14 @@ impl fmt::Display for Hash {
     }
 }
 
+impl FromStr for Hash {
+    type Err = hex::FromHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Hash(hex::FromHex::from_hex(s)?))
+    }
+}
+
 impl ark_std::rand::distributions::Distribution<Hash> for ark_std::rand::distributions::Standard {
     #[inline]
     fn sample<R: ark_std::rand::Rng + ?Sized>(&self, rng: &mut R) -> Hash {
@@ -71,5 +79,8 @@ mod tests {
 
         let de: Hash = serde_json::de::from_str(&ser).unwrap();
         assert_eq!(hash, de);
+
+        assert_eq!(hash.to_string().parse::<Hash>().unwrap(), hash);
+        assert!("41c0".parse::<Hash>().is_err());
     }
 }
@@ -1,5 +1,5 @@
 use std::future::Future;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
 use http::uri;
 use hyper::{
@@ -13,7 +13,7 @@ use super::*;
 
 const MAX_SIZE: u32 = 40 * 1024 * 1024;
 
-pub async fn read_msg(upgraded: &mut Upgraded) -> Result<NexusMsg> {
+pub async fn read_msg<S: AsyncRead + Unpin>(upgraded: &mut S) -> Result<NexusMsg> {
     let size = upgraded.read_u32().await?;
     if size > MAX_SIZE {
         tracing::warn!(
@@ -29,7 +29,7 @@ pub async fn read_msg(upgraded: &mut Upgraded) -> Result<NexusMsg> {
     Ok(t)
 }
 
-pub async fn write_msg(upgraded: &mut Upgraded, msg: &NexusMsg) -> Result<()> {
+pub async fn write_msg<S: AsyncWrite + Unpin>(upgraded: &mut S, msg: &NexusMsg) -> Result<()> {
     let v = encode_lz4(msg)?;
     let size = v.len() as u32;
     if size > MAX_SIZE {
@@ -1,7 +1,77 @@
+//! Durable store for proof jobs.
+//!
+//! Jobs are recorded in an append-only log: the program, its trace and the
+//! view of its execution when the job is created, every PCD node as soon as
+//! it is proven, and finally the proof, or the error if the proof failed.
+//! When the coordinator starts, the log is replayed so that unfinished jobs
+//! can be resumed from the nodes proven before the restart, and compacted,
+//! dropping the records of finished jobs other than their proofs or errors.
+//!
+//! A failed job is no longer known to the store, so the program can be
+//! submitted again.
+//!
+//! The log starts with the magic bytes [`MAGIC`] and the format [`VERSION`]
+//! as a little-endian `u16`, followed by postcard-encoded records, each
+//! prefixed with its length as a little-endian `u32`.
+
 use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
-use crate::api::Proof;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::{Deserialize, Serialize};
+
+use nexus_core::prover::envelope::ProofView;
+use nexus_core::prover::nova::types::PCDNode;
+use nexus_network::{api::Proof, pcd::Trace, Result, LOG_TARGET};
+
+/// Magic bytes identifying a job log.
+pub const MAGIC: [u8; 4] = *b"NXDB";
+
+/// Current version of the job log format.
+pub const VERSION: u16 = 1;
+
+#[derive(Serialize, Deserialize)]
+enum Record {
+    /// A new job: the program, and the serialized trace and view of its execution.
+    Job {
+        hash: String,
+        elf: Vec<u8>,
+        trace: Vec<u8>,
+        view: Vec<u8>,
+    },
+    /// A serialized PCD node proven for a job.
+    Node { hash: String, node: Vec<u8> },
+    /// The encoded proof envelope of a finished job, proven with `nodes` nodes.
+    Proof {
+        hash: String,
+        nodes: u32,
+        proof: Vec<u8>,
+    },
+    /// The error of a job whose proof failed.
+    Failed { hash: String, error: String },
+}
+
+impl Record {
+    fn hash(&self) -> &str {
+        match self {
+            Record::Job { hash, .. }
+            | Record::Node { hash, .. }
+            | Record::Proof { hash, .. }
+            | Record::Failed { hash, .. } => hash,
+        }
+    }
+}
+
+struct Job {
+    trace: Arc<Trace>,
+    view: ProofView,
+    // nodes proven before a restart, not covered by another such node
+    nodes: HashMap<(u64, u64), PCDNode>,
+}
 
 #[derive(Clone, Default)]
 pub struct DB(Arc<Mutex<DBase>>);
@@ -9,18 +79,83 @@ pub struct DB(Arc<Mutex<DBase>>);
 #[derive(Default)]
 pub struct DBase {
     proofs: HashMap<String, Proof>,
+    jobs: HashMap<String, Job>,
+    failed: HashMap<String, String>,
+    log: Option<File>,
 }
 
 impl DB {
+    /// Create a store which is only kept in memory.
     pub fn new() -> Self {
-        DB(Arc::new(Mutex::new(DBase { proofs: HashMap::new() })))
+        DB::default()
+    }
+
+    /// Open the job log at `path`, creating it if it does not exist.
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut db = DBase::default();
+        if path.exists() {
+            db.replay(path)?;
+            compact(path, &db)?;
+        } else {
+            let mut f = File::create(path)?;
+            f.write_all(&MAGIC)?;
+            f.write_all(&VERSION.to_le_bytes())?;
+            f.sync_all()?;
+        }
+
+        tracing::info!(
+            target: LOG_TARGET,
+            path = %path.display(),
+            proofs = db.proofs.len(),
+            unfinished = db.jobs.len(),
+            "Opened job database",
+        );
+
+        db.log = Some(OpenOptions::new().append(true).open(path)?);
+        Ok(DB(Arc::new(Mutex::new(db))))
+    }
+
+    /// Whether a job exists for the program with hash `hash`.
+    pub fn contains(&self, hash: &str) -> bool {
+        self.0.lock().unwrap().proofs.contains_key(hash)
+    }
+
+    /// Hashes of the jobs which have not been finished.
+    pub fn unfinished(&self) -> Vec<String> {
+        self.0.lock().unwrap().jobs.keys().cloned().collect()
+    }
+
+    pub fn new_job(
+        &mut self,
+        hash: String,
+        elf: &[u8],
+        trace: Trace,
+        view: ProofView,
+    ) -> Result<()> {
+        let record = Record::Job {
+            hash: hash.clone(),
+            elf: elf.to_vec(),
+            trace: to_bytes(&trace)?,
+            view: to_bytes(&view)?,
+        };
+        let mut db = self.0.lock().unwrap();
+        db.append(&record)?;
+        db.new_job(hash, trace, view);
+        Ok(())
+    }
+
+    pub fn query_job(&self, hash: &str) -> Option<(Arc<Trace>, ProofView)> {
+        let db = self.0.lock().unwrap();
+        db.jobs
+            .get(hash)
+            .map(|job| (job.trace.clone(), job.view.clone()))
     }
 
-    pub fn new_proof(&mut self, hash: String, total: u32) {
+    /// Take the node covering steps `i` to `j` of the job, if it was proven
+    /// before the coordinator restarted.
+    pub fn take_node(&self, hash: &str, i: u64, j: u64) -> Option<PCDNode> {
         let mut db = self.0.lock().unwrap();
-        let proof = db.proofs.entry(hash.clone()).or_default();
-        proof.hash = hash;
-        proof.total_nodes = total;
+        db.jobs.get_mut(hash)?.nodes.remove(&(i, j))
     }
 
     pub fn query_proof(&mut self, hash: &str) -> Option<Proof> {
@@ -28,15 +163,343 @@ impl DB {
         db.proofs.get(hash).cloned() // TODO eliminate clone
     }
 
-    pub fn update_complete(&mut self, hash: String, complete: u32) {
+    /// The error of the last job for the program with hash `hash`, if its proof failed.
+    pub fn query_failure(&self, hash: &str) -> Option<String> {
+        let db = self.0.lock().unwrap();
+        db.failed.get(hash).cloned()
+    }
+
+    /// Record a node proven for the job, counting it as complete.
+    pub fn update_node(&mut self, hash: &str, node: &PCDNode) -> Result<()> {
+        let record = Record::Node {
+            hash: hash.to_string(),
+            node: to_bytes(node)?,
+        };
+        let mut db = self.0.lock().unwrap();
+        db.append(&record)?;
+        db.complete_node(hash);
+        Ok(())
+    }
+
+    pub fn update_proof(&mut self, hash: String, proof: Vec<u8>) -> Result<()> {
         let mut db = self.0.lock().unwrap();
-        db.proofs
-            .entry(hash)
-            .and_modify(|p| p.complete_nodes += complete);
+        let nodes = db.proofs.get(&hash).map_or(0, |p| p.total_nodes);
+        let record = Record::Proof { hash, nodes, proof };
+        db.append(&record)?;
+        if let Record::Proof { hash, nodes, proof } = record {
+            db.update_proof(hash, nodes, proof);
+        }
+        Ok(())
     }
 
-    pub fn update_proof(&mut self, hash: String, proof: Vec<u8>) {
+    /// Record that the proof of the job failed with `error`, dropping the job.
+    pub fn fail_job(&mut self, hash: String, error: String) -> Result<()> {
+        let record = Record::Failed { hash, error };
         let mut db = self.0.lock().unwrap();
-        db.proofs.entry(hash).and_modify(|p| p.proof = Some(proof));
+        db.append(&record)?;
+        if let Record::Failed { hash, error } = record {
+            db.fail_job(hash, error);
+        }
+        Ok(())
+    }
+}
+
+impl DBase {
+    fn append(&mut self, record: &Record) -> Result<()> {
+        if let Some(log) = self.log.as_mut() {
+            write_record(log, record)?;
+            log.sync_data()?;
+        }
+        Ok(())
+    }
+
+    fn new_job(&mut self, hash: String, trace: Trace, view: ProofView) {
+        let proof = Proof {
+            hash: hash.clone(),
+            total_nodes: (trace.blocks.len() as u32).saturating_sub(1),
+            ..Proof::default()
+        };
+        self.proofs.insert(hash.clone(), proof);
+        self.failed.remove(&hash);
+
+        let job = Job {
+            trace: Arc::new(trace),
+            view,
+            nodes: HashMap::new(),
+        };
+        self.jobs.insert(hash, job);
+    }
+
+    fn complete_node(&mut self, hash: &str) {
+        if let Some(p) = self.proofs.get_mut(hash) {
+            p.complete_nodes += 1;
+        }
+    }
+
+    fn update_proof(&mut self, hash: String, nodes: u32, proof: Vec<u8>) {
+        self.jobs.remove(&hash);
+        // the job itself is not recorded anymore once the log is compacted
+        let p = self
+            .proofs
+            .entry(hash.clone())
+            .or_insert_with(|| Proof { hash, ..Proof::default() });
+        p.total_nodes = nodes;
+        p.complete_nodes = nodes;
+        p.proof = Some(proof);
+    }
+
+    fn fail_job(&mut self, hash: String, error: String) {
+        self.jobs.remove(&hash);
+        self.proofs.remove(&hash);
+        self.failed.insert(hash, error);
+    }
+
+    fn replay(&mut self, path: &Path) -> Result<()> {
+        let mut r = BufReader::new(File::open(path)?);
+        read_header(&mut r)?;
+
+        while let Some(record) = read_record(&mut r)? {
+            match record {
+                Record::Job { hash, trace, view, .. } => {
+                    self.new_job(hash, from_bytes(&trace)?, from_bytes(&view)?);
+                }
+                Record::Node { hash, node } => {
+                    self.complete_node(&hash);
+                    let Some(job) = self.jobs.get_mut(&hash) else {
+                        continue;
+                    };
+                    let node: PCDNode = from_bytes(&node)?;
+                    let (i, j) = (node.i, node.j);
+                    job.nodes.retain(|&(a, b), _| a < i || b > j);
+                    job.nodes.insert((i, j), node);
+                }
+                Record::Proof { hash, nodes, proof } => self.update_proof(hash, nodes, proof),
+                Record::Failed { hash, error } => self.fail_job(hash, error),
+            }
+        }
+        Ok(())
+    }
+}
+
+// Rewrite the log at `path`, keeping the records of unfinished jobs, and
+// the proofs or errors of finished ones.
+fn compact(path: &Path, db: &DBase) -> Result<()> {
+    let mut tmp = OsString::from(path);
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let mut r = BufReader::new(File::open(path)?);
+    read_header(&mut r)?;
+
+    let mut w = BufWriter::new(File::create(&tmp)?);
+    w.write_all(&MAGIC)?;
+    w.write_all(&VERSION.to_le_bytes())?;
+
+    while let Some(record) = read_record(&mut r)? {
+        let keep = match &record {
+            Record::Proof { .. } => true,
+            Record::Failed { hash, .. } => db.failed.contains_key(hash),
+            _ => db.jobs.contains_key(record.hash()),
+        };
+        if keep {
+            write_record(&mut w, &record)?;
+        }
+    }
+
+    w.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn read_header(r: &mut impl Read) -> Result<()> {
+    let mut header = [0u8; 6];
+    r.read_exact(&mut header)?;
+    if header[..4] != MAGIC {
+        return Err("not a job database".into());
+    }
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if version != VERSION {
+        return Err(format!("unsupported job database version {version}").into());
+    }
+    Ok(())
+}
+
+// Returns `None` at the end of the log. A record which was only partially
+// written, because the coordinator stopped while appending it, is ignored.
+fn read_record(r: &mut impl Read) -> Result<Option<Record>> {
+    let mut len = [0u8; 4];
+    let mut bytes = Vec::new();
+    let res = r.read_exact(&mut len).and_then(|()| {
+        bytes.resize(u32::from_le_bytes(len) as usize, 0);
+        r.read_exact(&mut bytes)
+    });
+    match res {
+        Ok(()) => Ok(Some(postcard::from_bytes(&bytes)?)),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn write_record(w: &mut impl Write, record: &Record) -> Result<()> {
+    let bytes = postcard::to_stdvec(record)?;
+    w.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    w.write_all(&bytes)?;
+    Ok(())
+}
+
+fn to_bytes(t: &impl CanonicalSerialize) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    t.serialize_uncompressed(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn from_bytes<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T> {
+    Ok(T::deserialize_uncompressed(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_core::nvm::{interactive::trace, load_vm, memory::MerkleTrie, VMOpts};
+    use nexus_core::prover::nova::{
+        circuit::{nop_circuit, Tr},
+        pp::gen_vm_pp,
+        types::ParPP,
+    };
+    use nexus_network::pcd::NexusMsg::{LeafReq, NodeReq, PCDRes};
+
+    use crate::post::prove_range;
+    use crate::workers::WorkerState;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("nexus-{name}-{}.db", std::process::id()))
+    }
+
+    #[test]
+    fn resume_from_log() {
+        let path = temp_path("jobs");
+        let trace = nop_circuit::<MerkleTrie>(3).unwrap().0;
+
+        let mut db = DB::open(&path).unwrap();
+        db.new_job("a".into(), b"elf", trace.clone(), ProofView::default())
+            .unwrap();
+        db.new_job("b".into(), b"elf", trace.clone(), ProofView::default())
+            .unwrap();
+        db.update_proof("b".into(), vec![1, 2, 3]).unwrap();
+        drop(db);
+
+        // a record interrupted while being appended is ignored
+        let mut f = OpenOptions::new().append(true).open(&path).unwrap();
+        f.write_all(&[0xff, 0xff, 0, 0, 1]).unwrap();
+        drop(f);
+
+        let mut db = DB::open(&path).unwrap();
+        assert_eq!(db.unfinished(), vec!["a".to_string()]);
+        let (tr, _) = db.query_job("a").unwrap();
+        assert_eq!(tr.blocks.len(), trace.blocks.len());
+
+        let a = db.query_proof("a").unwrap();
+        assert_eq!(a.complete_nodes, 0);
+        assert!(a.proof.is_none());
+        let b = db.query_proof("b").unwrap();
+        assert_eq!(b.total_nodes, trace.blocks.len() as u32 - 1);
+        assert_eq!(b.complete_nodes, b.total_nodes);
+        assert_eq!(b.proof, Some(vec![1, 2, 3]));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn failed_job() {
+        let path = temp_path("failed-jobs");
+        let trace = nop_circuit::<MerkleTrie>(3).unwrap().0;
+
+        let mut db = DB::open(&path).unwrap();
+        db.new_job("a".into(), b"elf", trace.clone(), ProofView::default())
+            .unwrap();
+        db.fail_job("a".into(), "error".into()).unwrap();
+        drop(db);
+
+        // the failure is kept, and the program can be submitted again
+        let mut db = DB::open(&path).unwrap();
+        assert!(!db.contains("a"));
+        assert!(db.unfinished().is_empty());
+        assert!(db.query_proof("a").is_none());
+        assert_eq!(db.query_failure("a").as_deref(), Some("error"));
+
+        db.new_job("a".into(), b"elf", trace, ProofView::default())
+            .unwrap();
+        drop(db);
+
+        let db = DB::open(&path).unwrap();
+        assert!(db.contains("a"));
+        assert_eq!(db.unfinished(), vec!["a".to_string()]);
+        assert!(db.query_failure("a").is_none());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn resume_partial_tree() {
+        let path = temp_path("partial-jobs");
+        let pp: ParPP = gen_vm_pp(1, &()).unwrap();
+
+        let opts = VMOpts {
+            k: 1,
+            machine: Some("nop10".into()),
+            file: None,
+        };
+        let mut vm = load_vm::<MerkleTrie>(&opts).unwrap();
+        let mut trace = trace(&mut vm, 1, true).unwrap();
+        trace.blocks.truncate(4);
+
+        // the leaf covering the first two blocks was proven before the restart
+        let leaf = Tr::<MerkleTrie>(Trace {
+            k: 1,
+            start: 0,
+            blocks: trace.blocks[..2].to_vec(),
+        });
+        let node = PCDNode::prove_leaf(&pp, &leaf, 0, &leaf.input(0).unwrap()).unwrap();
+
+        let mut db = DB::open(&path).unwrap();
+        db.new_job("a".into(), b"elf", trace, ProofView::default())
+            .unwrap();
+        db.update_node("a", &node).unwrap();
+        drop(db);
+
+        let state = WorkerState::new(pp, DB::open(&path).unwrap());
+        let mut db = state.db.clone();
+        assert_eq!(db.query_proof("a").unwrap().complete_nodes, 1);
+
+        // answer every request with the recorded node, keeping track of them
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let (pcd, seen) = (state.pcd.1.clone(), requests.clone());
+        tokio::spawn(async move {
+            while let Ok(work) = pcd.recv().await {
+                let req = match &*work.msg {
+                    LeafReq(t) => ("leaf", t.start),
+                    NodeReq(ns) => ("node", ns.len()),
+                    _ => ("other", 0),
+                };
+                seen.lock().unwrap().push(req);
+                let _ = work.response.send(PCDRes(node.clone()));
+            }
+        });
+
+        // only the second leaf and the root are proven
+        let (trace, _) = db.query_job("a").unwrap();
+        let hash = Arc::new("a".to_string());
+        prove_range(&state, &hash, &trace, 0, 3)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(*requests.lock().unwrap(), vec![("leaf", 2), ("node", 2)]);
+        assert!(db.take_node("a", 0, 1).is_none());
+
+        let p = db.query_proof("a").unwrap();
+        assert_eq!(p.complete_nodes, 3);
+        assert_eq!(p.complete_nodes, p.total_nodes);
+
+        std::fs::remove_file(&path).unwrap();
     }
 }
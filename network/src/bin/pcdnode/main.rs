@@ -7,6 +7,7 @@ mod post;
 mod workers;
 
 use std::net::SocketAddr;
+use std::path::PathBuf;
 
 use clap::Parser;
 
@@ -18,6 +19,9 @@ use hyper::{
 };
 use tracing_subscriber::EnvFilter;
 
+use nexus_core::config::vm::{NovaImpl, ProverImpl};
+
+use db::DB;
 use nexus_network::*;
 use post::*;
 use workers::*;
@@ -72,6 +76,10 @@ pub struct Opts {
     /// public parameters file
     #[arg(long = "public-params", default_value = "nexus-public.zst")]
     pp_file: String,
+
+    /// job database file, used by the coordinator to resume proofs after a restart
+    #[arg(long = "db", default_value = "nexus-jobs.db")]
+    db_file: PathBuf,
 }
 
 #[tokio::main]
@@ -90,10 +98,23 @@ async fn main() -> Result<()> {
         "Loading public parameters",
     );
 
-    let pp = nexus_core::prover::nova::pp::load_pp(&opts.pp_file)?;
-    let state = WorkerState::new(pp);
+    let pp = nexus_core::prover::nova::pp::load_pp_for(
+        &opts.pp_file,
+        ProverImpl::Nova(NovaImpl::Parallel),
+        1,
+    )?;
+    let coordinator = !(opts.pcd || opts.msm);
+    let db = if coordinator {
+        DB::open(&opts.db_file)?
+    } else {
+        DB::new()
+    };
+    let state = WorkerState::new(pp, db);
 
     start_local_workers(state.clone())?;
+    if coordinator {
+        resume_proofs(state.clone())?;
+    }
 
     if opts.msm {
         bin::client(state.clone(), &opts.connect, "msm", msm_client_proxy).await?;
@@ -1,11 +1,10 @@
-use std::collections::VecDeque;
 use std::sync::Arc;
 
 use nexus_network::{
     api::{NexusAPI, Proof},
     pcd::{
-        encode,
-        NexusMsg::{self, LeafReq, NodeReq, PCDRes},
+        NexusMsg::{LeafReq, NodeReq, PCDRes},
+        Trace,
     },
     Result,
 };
@@ -16,23 +15,56 @@ use tokio::task::JoinHandle;
 
 use crate::{
     api::NexusAPI::{Error, NexusProof, Program, Query},
-    request_work, WorkerState, LOG_TARGET,
+    request_work_retry, WorkerState, LOG_TARGET,
 };
+use nexus_core::config::vm::{NovaImpl, ProverImpl};
 use nexus_core::nvm::{
     interactive::{parse_elf, trace},
     memory::MerkleTrie,
     NexusVM,
 };
+use nexus_core::prover::envelope::{ProofEnvelope, ProofHeader, ProofView};
+use nexus_core::prover::nova::{error::ProofError, types::PCDNode};
 
-pub fn manage_proof(
+/// Trace the program and start proving it.
+pub fn new_proof(
     mut state: WorkerState,
     hash: String,
+    elf: &[u8],
     mut vm: NexusVM<MerkleTrie>,
 ) -> Result<()> {
-    let trace = Arc::new(trace(&mut vm, 1, true)?);
+    let trace = trace(&mut vm, 1, true)?;
+    let view = ProofView {
+        output: vm.syscalls.get_output(),
+        logs: vm
+            .syscalls
+            .get_log_buffer()
+            .iter()
+            .map(|l| String::from_utf8_lossy(l).into_owned())
+            .collect(),
+        exit_code: vm.syscalls.get_exit_code(),
+        panic_info: vm.syscalls.get_panic_info(),
+    };
+
+    state.db.new_job(hash.clone(), elf, trace, view)?;
+    manage_proof(state, hash)
+}
+
+/// Resume the proofs which were not finished before the coordinator restarted.
+pub fn resume_proofs(state: WorkerState) -> Result<()> {
+    for hash in state.db.unfinished() {
+        tracing::info!(
+            target: LOG_TARGET,
+            %hash,
+            "resuming proof",
+        );
+        manage_proof(state.clone(), hash)?;
+    }
+    Ok(())
+}
 
-    let steps = trace.blocks.len() as u32;
-    state.db.new_proof(hash.clone(), steps - 1);
+pub fn manage_proof(state: WorkerState, hash: String) -> Result<()> {
+    let (trace, view) = state.db.query_job(&hash).ok_or("proof not found")?;
     let hash = Arc::new(hash);
 
     let t = std::time::Instant::now();
@@ -42,63 +74,110 @@ pub fn manage_proof(
         "starting computing the proof",
     );
 
-    let mut v: VecDeque<JoinHandle<NexusMsg>> = VecDeque::with_capacity(trace.blocks.len() / 2);
-
-    for t in trace.split_by(2) {
-        let ch = state.pcd.0.clone();
-        let jh = tokio::spawn(async move { request_work(&ch, LeafReq(t)).await.unwrap() });
-        v.push_back(jh);
-    }
+    let end = trace.start + trace.blocks.len() - 1;
+    let root = prove_range(&state, &hash, &trace, trace.start, end);
 
-    loop {
-        if v.len() == 1 {
-            let mut state = state.clone();
-            let hash = hash.clone();
-            tokio::spawn(async move {
-                let proof = v.pop_front().unwrap().await.unwrap();
-                state.db.update_complete(hash.to_string(), 1);
-                tracing::info!(
+    let mut db = state.db.clone();
+    tokio::spawn(async move {
+        if let Err(e) = finish_proof(state, &hash, view, root, t).await {
+            tracing::error!(
+                target: LOG_TARGET,
+                %hash,
+                "proof failed: {e}",
+            );
+            // the program can be submitted again
+            if let Err(e) = db.fail_job(hash.to_string(), e.to_string()) {
+                tracing::error!(
                     target: LOG_TARGET,
-                    elapsed = ?t.elapsed(),
-                    "proof complete, verifying",
+                    %hash,
+                    "failed to record the failure of the proof: {e}",
                 );
+            }
+        }
+    });
+    Ok(())
+}
+
+async fn finish_proof(
+    mut state: WorkerState,
+    hash: &str,
+    view: ProofView,
+    root: JoinHandle<Result<PCDNode>>,
+    t: std::time::Instant,
+) -> Result<()> {
+    let node = root.await??;
+    tracing::info!(
+        target: LOG_TARGET,
+        elapsed = ?t.elapsed(),
+        "proof complete, verifying",
+    );
 
-                let PCDRes(ref node) = proof else { panic!() };
-                node.verify(&state.pp).unwrap();
+    node.verify(&state.pp).map_err(ProofError::from)?;
 
-                tracing::info!(
-                    target: LOG_TARGET,
-                    "proof OK",
-                );
-                // at this point we store the proof so user
-                // can get it later
-                let proof: Vec<u8> = encode(&proof).unwrap();
-                state.db.update_proof(hash.to_string(), proof);
-            });
-            break;
-        }
+    tracing::info!(
+        target: LOG_TARGET,
+        "proof OK",
+    );
+    // at this point we store the proof so user
+    // can get it later
+    let header = ProofHeader::new(
+        ProverImpl::Nova(NovaImpl::Parallel),
+        1,
+        false,
+        &state.pp.digest,
+    )?;
+    let proof = ProofEnvelope::new(header, Some(view), &node)?.to_bytes()?;
+    state.db.update_proof(hash.to_string(), proof)
+}
 
-        let mut v2: VecDeque<JoinHandle<NexusMsg>> = VecDeque::with_capacity(v.len() / 2);
-        for _ in 0..v.len() / 2 {
-            let mut state = state.clone();
-            let hash = hash.clone();
-            let ch = state.pcd.0.clone();
-            let l = v.pop_front().unwrap();
-            let r = v.pop_front().unwrap();
-            let trace = trace.clone();
-            v2.push_back(tokio::spawn(async move {
-                let PCDRes(l) = l.await.unwrap() else { panic!() };
-                let PCDRes(r) = r.await.unwrap() else { panic!() };
-                state.db.update_complete(hash.to_string(), 2);
-                let ltr = trace.get(l.j as usize).unwrap();
-                let rtr = trace.get(r.j as usize).unwrap();
-                let req = NodeReq(vec![(l, ltr), (r, rtr)]);
-                request_work(&ch, req).await.unwrap()
-            }));
-        }
-        v = v2;
+// Schedule the proof of the node covering blocks `i` to `j` of the trace,
+// unless it was proven before the coordinator restarted. Each node is
+// recorded in the database as soon as it is proven.
+pub(crate) fn prove_range(
+    state: &WorkerState,
+    hash: &Arc<String>,
+    trace: &Arc<Trace>,
+    i: usize,
+    j: usize,
+) -> JoinHandle<Result<PCDNode>> {
+    if let Some(node) = state.db.take_node(hash, i as u64, j as u64) {
+        return tokio::spawn(async move { Ok(node) });
     }
-    Ok(())
+
+    let mut state = state.clone();
+    let hash = hash.clone();
+    let ch = state.pcd.0.clone();
+
+    if j <= i + 1 {
+        let t = Trace {
+            k: trace.k,
+            start: i,
+            blocks: (i..=j).filter_map(|n| trace.block(n).cloned()).collect(),
+        };
+        return tokio::spawn(async move {
+            let PCDRes(node) = request_work_retry(&ch, LeafReq(t)).await? else {
+                return Err("unexpected response to leaf request".into());
+            };
+            state.db.update_node(&hash, &node)?;
+            Ok(node)
+        });
+    }
+
+    let mid = (i + j + 1) / 2;
+    let l = prove_range(&state, &hash, trace, i, mid - 1);
+    let r = prove_range(&state, &hash, trace, mid, j);
+    let trace = trace.clone();
+    tokio::spawn(async move {
+        let l = l.await??;
+        let r = r.await??;
+        let ltr = trace.get(l.j as usize).ok_or("missing block")?;
+        let rtr = trace.get(r.j as usize).ok_or("missing block")?;
+        let PCDRes(node) = request_work_retry(&ch, NodeReq(vec![(l, ltr), (r, rtr)])).await? else {
+            return Err("unexpected response to node request".into());
+        };
+        state.db.update_node(&hash, &node)?;
+        Ok(node)
+    })
 }
 
 fn api(mut state: WorkerState, msg: NexusAPI) -> Result<NexusAPI> {
@@ -108,9 +187,11 @@ fn api(mut state: WorkerState, msg: NexusAPI) -> Result<NexusAPI> {
                 target: LOG_TARGET,
                 "received prove-request",
             );
-            let vm = parse_elf::<MerkleTrie>(&elf)?;
             let hash = hex::encode(Sha256::digest(&elf));
-            manage_proof(state, hash.clone(), vm)?;
+            if !state.db.contains(&hash) {
+                let vm = parse_elf::<MerkleTrie>(&elf)?;
+                new_proof(state, hash.clone(), &elf, vm)?;
+            }
             Ok(NexusProof(Proof { hash, ..Proof::default() }))
         }
         Query { hash } => {
@@ -120,7 +201,10 @@ fn api(mut state: WorkerState, msg: NexusAPI) -> Result<NexusAPI> {
             );
             let proof = state.db.query_proof(&hash);
             match proof {
-                None => Err("proof not found".into()),
+                None => match state.db.query_failure(&hash) {
+                    Some(e) => Err(format!("proof failed: {e}").into()),
+                    None => Err("proof not found".into()),
+                },
                 Some(p) => Ok(NexusProof(p)),
             }
         }
@@ -1,23 +1,37 @@
+use std::future::pending;
 use std::sync::Arc;
 
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::runtime::Handle;
 use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
     oneshot::{self, channel as oneshot},
-    //   watch, OwnedSemaphorePermit as Permit, Semaphore, TryAcquireError,
 };
-//use tokio::time::{self, Duration};
+use tokio::time::{self, Duration, Instant};
 
 use hyper::upgrade::Upgraded;
 
 use async_channel::{unbounded, Receiver, Sender};
 
-use nexus_core::prover::nova::{circuit::Tr, error::ProofError, types::*};
+use nexus_core::prover::nova::{circuit::Tr, types::*};
 
 use nexus_network::pcd::*;
 use nexus_network::*;
 
 use crate::db::DB;
 
+/// Time after which a worker which has not sent a heartbeat loses its lease on a work item.
+pub const LEASE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Interval at which workers send heartbeats while working.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Number of times a work item is assigned to a worker before giving up on it.
+pub const MAX_ATTEMPTS: u32 = 5;
+
+/// Number of failed work items after which a remote worker is disconnected.
+pub const MAX_WORKER_FAILURES: u32 = 3;
+
 #[derive(Clone)]
 pub struct WorkerState {
     pub pp: Arc<ParPP>,
@@ -27,83 +41,183 @@ pub struct WorkerState {
 }
 
 impl WorkerState {
-    pub fn new(pp: ParPP) -> Self {
+    pub fn new(pp: ParPP, db: DB) -> Self {
         Self {
             pp: Arc::new(pp),
             pcd: unbounded(),
             msm: unbounded(),
-            db: DB::new(),
+            db,
         }
     }
 }
 
 pub struct Work {
-    pub msg: NexusMsg,
+    pub msg: Arc<NexusMsg>,
     pub response: oneshot::Sender<NexusMsg>,
+    /// Renews the lease of the worker handling this item.
+    pub heartbeat: UnboundedSender<()>,
 }
 
-fn send_response(ch: oneshot::Sender<NexusMsg>, msg: NexusMsg) -> Result<()> {
-    ch.send(msg).map_err(|_| "oneshot send error")?;
-    Ok(())
+impl Work {
+    fn new(msg: Arc<NexusMsg>) -> (Self, oneshot::Receiver<NexusMsg>, Lease) {
+        let (response, r) = oneshot();
+        let (heartbeat, lease) = unbounded_channel();
+        (Self { msg, response, heartbeat }, r, lease)
+    }
+
+    fn heartbeat(&self) {
+        // the requester may have stopped waiting for this item
+        let _ = self.heartbeat.send(());
+    }
+
+    // Renew the lease on this item until its requester stops waiting for it.
+    fn keep_alive(&self, rt: &Handle) {
+        let heartbeat = self.heartbeat.clone();
+        rt.spawn(async move {
+            while heartbeat.send(()).is_ok() {
+                time::sleep(HEARTBEAT_INTERVAL).await;
+            }
+        });
+    }
 }
 
+type Lease = UnboundedReceiver<()>;
+
+fn send_response(ch: oneshot::Sender<NexusMsg>, msg: NexusMsg) {
+    // the requester may have given up on the item after its lease expired
+    let _ = ch.send(msg);
+}
+
+/// Send `msg` to a worker and wait for its response.
 pub async fn request_work(ch: &Sender<Work>, msg: NexusMsg) -> Result<NexusMsg> {
-    let (s, r) = oneshot();
-    ch.send(Work { msg, response: s }).await?;
+    let (work, r, _) = Work::new(Arc::new(msg));
+    ch.send(work).await?;
     Ok(r.await?)
 }
 
-/*
-pub async fn request_work_timeout(ch: &Sender<Work>, msg: NexusMsg) -> Result<NexusMsg> {
-    let sleep = time::sleep(Duration::from_secs(1));
-    tokio::select! {
-        res = request_work(ch, msg) => {
-            res
-        }
-        _ = sleep => {
-            Err("timeout".into())
+// Send `msg` to a worker and wait for its response, as long as the worker
+// holds a lease on it. The lease starts with the first heartbeat of the
+// worker, and expires if it does not send another one within `timeout`.
+async fn request_work_lease(
+    ch: &Sender<Work>,
+    msg: Arc<NexusMsg>,
+    timeout: Duration,
+) -> Result<NexusMsg> {
+    let (work, mut r, mut lease) = Work::new(msg);
+    ch.send(work).await?;
+
+    let mut deadline = None;
+    loop {
+        let expired = async move {
+            match deadline {
+                Some(d) => time::sleep_until(d).await,
+                None => pending().await,
+            }
+        };
+        tokio::select! {
+            res = &mut r => {
+                return match res? {
+                    Failed(e) => Err(e.into()),
+                    res => Ok(res),
+                };
+            }
+            Some(()) = lease.recv() => deadline = Some(Instant::now() + timeout),
+            () = expired => return Err("lease expired".into()),
         }
     }
 }
 
+/// Send `msg` to a worker and wait for its response. If the worker fails,
+/// disconnects or lets its lease expire, the item is reassigned, up to
+/// [`MAX_ATTEMPTS`] times.
 pub async fn request_work_retry(ch: &Sender<Work>, msg: NexusMsg) -> Result<NexusMsg> {
-    let mut count = 5;
-    loop {
-        match request_work_timeout(ch, msg.clone()).await {
-            Ok(x) => return Ok(x),
+    retry(ch, msg, LEASE_TIMEOUT).await
+}
+
+async fn retry(ch: &Sender<Work>, msg: NexusMsg, timeout: Duration) -> Result<NexusMsg> {
+    let msg = Arc::new(msg);
+    for attempt in 1..=MAX_ATTEMPTS {
+        match request_work_lease(ch, msg.clone(), timeout).await {
+            Ok(res) => return Ok(res),
             Err(e) => {
-                eprintln!("{e}");
+                tracing::warn!(
+                    target: LOG_TARGET,
+                    attempt,
+                    "work item failed: {e}",
+                );
             }
         }
-        count -= 1;
-        if count <= 0 {
-            break;
-        }
     }
-    Err("request failed".into())
+    Err(format!("work item failed {MAX_ATTEMPTS} times").into())
 }
-*/
 
-async fn chan_to_net(ch: Receiver<Work>, mut upg: Upgraded) -> Result<()> {
+// Forward work items to a remote worker. The connection is dropped, and the
+// item reassigned, if the worker does not send a heartbeat or a response
+// within `timeout`, or once it has failed `MAX_WORKER_FAILURES` items.
+async fn chan_to_net<S>(ch: Receiver<Work>, mut upg: S, timeout: Duration) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut completed = 0;
+    let mut failures = 0;
     loop {
-        let Work { msg, response: ch } = ch.recv().await?;
-        bin::write_msg(&mut upg, &msg).await?;
-        let msg = bin::read_msg(&mut upg).await?;
-        send_response(ch, msg)?;
+        let work = ch.recv().await?;
+        bin::write_msg(&mut upg, &work.msg).await?;
+
+        let res = loop {
+            match time::timeout(timeout, bin::read_msg(&mut upg)).await {
+                Ok(Ok(Heartbeat)) => work.heartbeat(),
+                Ok(res) => break res?,
+                Err(_) => return Err("worker lease expired, disconnecting".into()),
+            }
+        };
+
+        if let Failed(ref e) = res {
+            failures += 1;
+            tracing::warn!(
+                target: LOG_TARGET,
+                completed,
+                failures,
+                "worker failed: {e}",
+            );
+        } else {
+            completed += 1;
+        }
+        send_response(work.response, res);
+
+        if failures >= MAX_WORKER_FAILURES {
+            return Err("worker failed too many times, disconnecting".into());
+        }
     }
 }
 
-async fn net_to_chan(ch: Sender<Work>, mut upg: Upgraded) -> Result<()> {
+// Handle work items received from the coordinator, sending heartbeats
+// while they are in progress.
+async fn net_to_chan<S>(ch: Sender<Work>, mut upg: S) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
     loop {
         let req: NexusMsg = bin::read_msg(&mut upg).await?;
-        let res = request_work(&ch, req).await?;
+
+        let res = request_work(&ch, req);
+        tokio::pin!(res);
+        let mut heartbeat = time::interval(HEARTBEAT_INTERVAL);
+        let res = loop {
+            tokio::select! {
+                res = &mut res => break res,
+                _ = heartbeat.tick() => bin::write_msg(&mut upg, &Heartbeat).await?,
+            }
+        };
+
+        let res = res.unwrap_or_else(|e| Failed(e.to_string()));
         bin::write_msg(&mut upg, &res).await?;
     }
 }
 
 pub async fn msm_server_proxy(state: WorkerState, upg: Upgraded) -> Result<()> {
     let ch = state.msm.1.clone();
-    chan_to_net(ch, upg).await
+    chan_to_net(ch, upg, LEASE_TIMEOUT).await
 }
 
 pub async fn msm_client_proxy(state: WorkerState, upg: Upgraded) -> Result<()> {
@@ -113,7 +227,7 @@ pub async fn msm_client_proxy(state: WorkerState, upg: Upgraded) -> Result<()> {
 
 pub async fn pcd_server_proxy(state: WorkerState, upg: Upgraded) -> Result<()> {
     let ch = state.pcd.1.clone();
-    chan_to_net(ch, upg).await
+    chan_to_net(ch, upg, LEASE_TIMEOUT).await
 }
 
 pub async fn pcd_client_proxy(state: WorkerState, upg: Upgraded) -> Result<()> {
@@ -124,29 +238,26 @@ pub async fn pcd_client_proxy(state: WorkerState, upg: Upgraded) -> Result<()> {
 pub fn start_local_workers(state: WorkerState) -> Result<()> {
     let state2 = state.clone();
     let handle = Handle::current();
+    let handle2 = handle.clone();
     std::thread::spawn(move || local_pcd(handle, state));
-    std::thread::spawn(move || local_msm(state2));
+    std::thread::spawn(move || local_msm(handle2, state2));
     Ok(())
 }
 
-fn request_msm(rt: &Handle, state: &WorkerState, w: &R1CSWitness<P1>) -> P1 {
+fn request_msm(rt: &Handle, state: &WorkerState, w: &R1CSWitness<P1>) -> Result<P1> {
     tracing::trace!(
         target: LOG_TARGET,
         "sending MSM request",
     );
     // TODO eliminate clone
     let msg = MSMReq(w.W.clone());
-    match rt.block_on(request_work(&state.msm.0, msg)) {
-        Ok(MSMRes(p)) => p,
-        _ => panic!("bad result"), // TODO this function cannot fail
+    match rt.block_on(request_work_retry(&state.msm.0, msg))? {
+        MSMRes(p) => Ok(p),
+        _ => Err("unexpected response to MSM request".into()),
     }
 }
 
-fn prove_leaf(
-    rt: &Handle,
-    st: &WorkerState,
-    trace: Trace,
-) -> std::result::Result<PCDNode, ProofError> {
+fn prove_leaf(rt: &Handle, st: &WorkerState, trace: Trace) -> Result<PCDNode> {
     let i = trace.start;
     let tr = Tr(trace);
     tracing::trace!(
@@ -164,19 +275,20 @@ fn prove_node(
     rt: &Handle,
     st: &WorkerState,
     trace: Trace,
-    l: PCDNode,
-    r: PCDNode,
-) -> std::result::Result<PCDNode, ProofError> {
+    l: &PCDNode,
+    r: &PCDNode,
+) -> Result<PCDNode> {
     let tr = Tr(trace);
     let node =
-        PCDNode::prove_parent_with_commit_fn(&st.pp, &tr, &l, &r, |_pp, w| request_msm(rt, st, w))?;
+        PCDNode::prove_parent_with_commit_fn(&st.pp, &tr, l, r, |_pp, w| request_msm(rt, st, w))?;
     Ok(node)
 }
 
 fn local_pcd(rt: Handle, state: WorkerState) -> Result<()> {
     loop {
-        let Work { msg, response: ch } = state.pcd.1.recv_blocking()?;
-        match msg {
+        let work = state.pcd.1.recv_blocking()?;
+        work.keep_alive(&rt);
+        let res = match &*work.msg {
             LeafReq(t) => {
                 tracing::trace!(
                     target: LOG_TARGET,
@@ -184,13 +296,14 @@ fn local_pcd(rt: Handle, state: WorkerState) -> Result<()> {
                     t.start,
                     t.blocks.len(),
                 );
-                let node = prove_leaf(&rt, &state, t)?;
-                send_response(ch, PCDRes(node))?;
+                prove_leaf(&rt, &state, t.clone())
             }
-            NodeReq(mut ns) => {
+            NodeReq(ns) => {
                 // TODO extend to > 2 nodes
-                let (r, _) = ns.pop().unwrap();
-                let (l, lt) = ns.pop().unwrap();
+                let [(l, lt), (r, _)] = ns.as_slice() else {
+                    send_response(work.response, Failed("expected two nodes".into()));
+                    continue;
+                };
                 tracing::trace!(
                     target: LOG_TARGET,
                     "PCDNode {}-{}, {}-{} lts:{}",
@@ -201,23 +314,36 @@ fn local_pcd(rt: Handle, state: WorkerState) -> Result<()> {
                     lt.start,
                 );
 
-                let node = prove_node(&rt, &state, lt, l, r)?;
-                send_response(ch, PCDRes(node))?;
+                prove_node(&rt, &state, lt.clone(), l, r)
             }
             _ => {
                 tracing::error!(
                     target: LOG_TARGET,
                     "unexpected message in pcd-channel",
                 );
+                continue;
             }
-        }
+        };
+
+        let res = res.map_or_else(
+            |e| {
+                tracing::warn!(
+                    target: LOG_TARGET,
+                    "PCD proof failed: {e}",
+                );
+                Failed(e.to_string())
+            },
+            PCDRes,
+        );
+        send_response(work.response, res);
     }
 }
 
-fn local_msm(state: WorkerState) -> Result<()> {
+fn local_msm(rt: Handle, state: WorkerState) -> Result<()> {
     loop {
-        let Work { msg, response: ch } = state.msm.1.recv_blocking()?;
-        match msg {
+        let work = state.msm.1.recv_blocking()?;
+        work.keep_alive(&rt);
+        match &*work.msg {
             MSMReq(fs) => {
                 tracing::trace!(
                     target: LOG_TARGET,
@@ -225,8 +351,8 @@ fn local_msm(state: WorkerState) -> Result<()> {
                     fs.len(),
                 );
 
-                let res: P1 = C1::commit(&state.pp.pp, &fs);
-                send_response(ch, MSMRes(res))?;
+                let res: P1 = C1::commit(&state.pp.pp, fs);
+                send_response(work.response, MSMRes(res));
             }
             _ => {
                 tracing::error!(
@@ -237,3 +363,148 @@ fn local_msm(state: WorkerState) -> Result<()> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::fields::AdditiveGroup;
+    use tokio::io::duplex;
+    use tokio::task::JoinHandle;
+
+    use nexus_core::nvm::memory::MerkleTrie;
+    use nexus_core::prover::envelope::ProofEnvelope;
+    use nexus_core::prover::nova::pp::gen_vm_pp;
+    use nexus_vm::machines::nop_vm;
+
+    const TIMEOUT: Duration = Duration::from_millis(200);
+
+    fn msm_req() -> NexusMsg {
+        MSMReq(vec![F1::from(1)])
+    }
+
+    // Connect a remote worker to the coordinator channel `ch`. The worker
+    // handles items with `f`, and hangs on items for which it returns `None`.
+    // Returns the coordinator proxy and the worker.
+    fn remote_worker(
+        ch: &Receiver<Work>,
+        timeout: Duration,
+        f: fn(&NexusMsg) -> Option<NexusMsg>,
+    ) -> (JoinHandle<Result<()>>, JoinHandle<Result<()>>) {
+        let (coord, upg) = duplex(1 << 20);
+        let proxy = tokio::spawn(chan_to_net(ch.clone(), coord, timeout));
+
+        let (s, r) = unbounded::<Work>();
+        tokio::spawn(async move {
+            while let Ok(work) = r.recv().await {
+                match f(&work.msg) {
+                    Some(res) => send_response(work.response, res),
+                    None => pending().await,
+                }
+            }
+        });
+        (proxy, tokio::spawn(net_to_chan(s, upg)))
+    }
+
+    #[tokio::test]
+    async fn reassign_after_worker_killed() {
+        let (s, r) = unbounded();
+        let (proxy, worker) = remote_worker(&r, LEASE_TIMEOUT, |_| None);
+
+        let req = tokio::spawn(async move { retry(&s, msm_req(), LEASE_TIMEOUT).await });
+        time::sleep(TIMEOUT).await;
+        assert!(r.is_empty());
+
+        // the item is reassigned as soon as the connection to the worker is lost
+        worker.abort();
+        assert!(proxy.await.unwrap().is_err());
+        remote_worker(&r, LEASE_TIMEOUT, |_| Some(MSMRes(P1::ZERO)));
+
+        assert!(matches!(req.await.unwrap(), Ok(MSMRes(_))));
+    }
+
+    #[tokio::test]
+    async fn reassign_after_lease_expired() {
+        let (s, r) = unbounded();
+
+        // takes the item and stops sending heartbeats
+        let r2 = r.clone();
+        tokio::spawn(async move {
+            let work = r2.recv().await.unwrap();
+            work.heartbeat();
+            pending::<()>().await;
+        });
+
+        let req = tokio::spawn(async move { retry(&s, msm_req(), TIMEOUT).await });
+        time::sleep(TIMEOUT / 2).await;
+        remote_worker(&r, LEASE_TIMEOUT, |_| Some(MSMRes(P1::ZERO)));
+
+        assert!(matches!(req.await.unwrap(), Ok(MSMRes(_))));
+    }
+
+    #[tokio::test]
+    async fn failing_worker_disconnected() {
+        let (s, r) = unbounded();
+        let (mut a, _) = remote_worker(&r, TIMEOUT, |_| Some(Failed("error".into())));
+        let (mut b, _) = remote_worker(&r, TIMEOUT, |_| Some(Failed("error".into())));
+
+        // an item is attempted a bounded number of times, and a worker is
+        // disconnected after failing too many of them
+        let err = retry(&s, msm_req(), TIMEOUT).await.unwrap_err();
+        assert!(err.to_string().contains("failed"));
+        let res = tokio::select! {
+            res = &mut a => res,
+            res = &mut b => res,
+        };
+        assert!(res.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn unresponsive_worker_disconnected() {
+        let (s, r) = unbounded();
+        let (proxy, _) = remote_worker(&r, TIMEOUT, |_| None);
+        assert!(request_work(&s, msm_req()).await.is_err());
+        assert!(proxy.await.unwrap().is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn prove_with_killed_worker() {
+        let pp: ParPP = gen_vm_pp(1, &()).unwrap();
+        let state = WorkerState::new(pp, DB::new());
+
+        // all nodes are proven by remote workers, one of which is killed
+        // while the proof is in progress
+        let mut workers = Vec::new();
+        for _ in 0..2 {
+            let worker = WorkerState {
+                pp: state.pp.clone(),
+                pcd: unbounded(),
+                msm: unbounded(),
+                db: DB::new(),
+            };
+            start_local_workers(worker.clone()).unwrap();
+
+            let (coord, upg) = duplex(1 << 20);
+            tokio::spawn(chan_to_net(state.pcd.1.clone(), coord, LEASE_TIMEOUT));
+            workers.push(tokio::spawn(net_to_chan(worker.pcd.0.clone(), upg)));
+        }
+
+        // 2 nops and unimp, proven as 2 leaves and their parent
+        let vm = nop_vm::<MerkleTrie>(2);
+        crate::post::new_proof(state.clone(), "nop2".into(), &[], vm).unwrap();
+
+        let mut db = state.db.clone();
+        while db.query_proof("nop2").unwrap().complete_nodes < 1 {
+            time::sleep(Duration::from_millis(100)).await;
+        }
+        workers[0].abort();
+
+        let proof = loop {
+            if let Some(proof) = db.query_proof("nop2").unwrap().proof {
+                break proof;
+            }
+            time::sleep(Duration::from_millis(100)).await;
+        };
+        let node: PCDNode = ProofEnvelope::from_bytes(&proof).unwrap().proof().unwrap();
+        node.verify(&state.pp).unwrap();
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(feature = "snmalloc")]
+#[global_allocator]
+static ALLOC: snmalloc_rs::SnMalloc = snmalloc_rs::SnMalloc;
+
+use std::net::SocketAddr;
+
+use clap::Parser;
+use tracing_subscriber::EnvFilter;
+
+use nexus_network::{
+    rpc::{start_server, ProverService, DEFAULT_MAX_PROOFS, DEFAULT_MAX_TRACE_LEN, PROVER},
+    Result, LOG_TARGET,
+};
+
+#[derive(Debug, Parser)]
+#[command(author, version, about, long_about = None)]
+pub struct Opts {
+    #[arg(short, default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+
+    /// Instructions per step
+    #[arg(short, default_value = "16")]
+    k: usize,
+
+    /// public parameters file
+    #[arg(long = "public-params", default_value = "nexus-public-nova-seq-16.zst")]
+    pp_file: String,
+
+    /// Maximum number of instructions executed by a proven program
+    #[arg(long, default_value_t = DEFAULT_MAX_TRACE_LEN)]
+    max_trace_len: usize,
+
+    /// Maximum number of proofs kept in memory
+    #[arg(long, default_value_t = DEFAULT_MAX_PROOFS)]
+    max_proofs: usize,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let filter = EnvFilter::from_default_env();
+    tracing_subscriber::fmt()
+        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+        .with_env_filter(filter)
+        .init();
+
+    let opts = Opts::parse();
+
+    tracing::info!(
+        target: LOG_TARGET,
+        path = ?opts.pp_file,
+        "Loading public parameters",
+    );
+
+    let pp = nexus_core::prover::nova::pp::load_pp_for(&opts.pp_file, PROVER, opts.k)?;
+    let service = ProverService::with_limits(pp, opts.k, opts.max_trace_len, opts.max_proofs);
+
+    let (_addr, handle) = start_server(service, opts.listen).await?;
+    handle.stopped().await;
+    Ok(())
+}
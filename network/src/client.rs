@@ -1,13 +1,19 @@
+use std::future::Future;
 use std::path::Path;
+use std::time::Duration;
 
 use http::uri;
 use hyper::body::{Buf, HttpBody};
 use hyper::client::HttpConnector;
+use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
 use tokio::runtime;
 
+use nexus_rpc_common::{hash::Hash, ElfBytes};
+
 use crate::client::NexusAPI::{Error, NexusProof, Program, Query};
 use crate::{
     api::{NexusAPI, Proof},
+    rpc::{self, RpcClient},
     Result,
 };
 
@@ -76,3 +82,93 @@ impl Client {
         self.request(msg)
     }
 }
+
+/// How long to wait for a response: proving requests block until the proof is computed.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Client for the JSON-RPC prover service (see [`rpc`]).
+#[derive(Clone)]
+pub struct ProverClient {
+    url: String,
+}
+
+impl ProverClient {
+    /// Create a client for the server at `url`, either a `ws://` url or a `host:port` address.
+    pub fn new(url: &str) -> Result<Self> {
+        let url = if url.contains("://") {
+            url.to_owned()
+        } else {
+            format!("ws://{url}")
+        };
+        url.parse::<uri::Uri>()
+            .map_err(|_err| "invalid url".to_owned())?;
+
+        Ok(Self { url })
+    }
+
+    async fn connect(&self) -> Result<WsClient> {
+        let client = WsClientBuilder::default()
+            .max_request_size(rpc::MAX_MESSAGE_SIZE)
+            .max_response_size(rpc::MAX_MESSAGE_SIZE)
+            .request_timeout(REQUEST_TIMEOUT)
+            .build(&self.url)
+            .await?;
+        Ok(client)
+    }
+
+    /// Request the server to prove the execution of `elf`, returning the hash identifying the proof.
+    ///
+    /// Blocks until the proof is computed.
+    pub async fn prove(&self, elf: ElfBytes) -> Result<Hash> {
+        let client = self.connect().await?;
+        Ok(RpcClient::<rpc::Proof>::prove(&client, elf).await?)
+    }
+
+    /// Download the proof identified by `hash`.
+    pub async fn get_proof(&self, hash: Hash) -> Result<rpc::Proof> {
+        let client = self.connect().await?;
+        Ok(RpcClient::<rpc::Proof>::get_proof(&client, hash).await?)
+    }
+
+    /// Request the server to prove the execution of the ELF file at `path`, see [`ProverClient::prove`].
+    pub fn submit_program(&self, path: &Path) -> Result<Hash> {
+        tracing::info!(
+            target: LOG_TARGET,
+            "sending request to {}",
+            self.url,
+        );
+
+        let elf = std::fs::read(path)?;
+        let client = self.clone();
+        block_on(async move { client.prove(elf).await })
+    }
+
+    /// Download the proof identified by `hash`, see [`ProverClient::get_proof`].
+    pub fn fetch_proof(&self, hash: Hash) -> Result<rpc::Proof> {
+        tracing::info!(
+            target: LOG_TARGET,
+            "sending request to {}",
+            self.url,
+        );
+
+        let client = self.clone();
+        block_on(async move { client.get_proof(hash).await })
+    }
+}
+
+// Run `f` to completion on a separate thread, which may be called from within another runtime.
+fn block_on<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: Future<Output = Result<T>> + Send + 'static,
+{
+    std::thread::spawn(move || -> Result<T> {
+        let rt = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        rt.block_on(f)
+    })
+    .join()
+    .map_err(|_err| "request failed".to_owned())?
+}
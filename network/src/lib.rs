@@ -2,6 +2,7 @@ pub mod api;
 pub mod bin;
 pub mod client;
 pub mod pcd;
+pub mod rpc;
 pub mod ws;
 
 pub type DynError = Box<dyn std::error::Error + Send + Sync>;
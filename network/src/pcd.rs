@@ -30,6 +30,12 @@ pub enum NexusMsg {
 
     #[serde(with = "ark")]
     PCDRes(PCDNode),
+
+    /// Sent by workers while they are working on a request, to renew their lease on it.
+    Heartbeat,
+
+    /// Sent by workers instead of a response when they failed to handle a request.
+    Failed(String),
 }
 pub use NexusMsg::*;
 
@@ -200,6 +206,8 @@ mod test {
     #[test]
     fn round_trip_other() {
         round_trip(&Connect("ID".to_string()));
+        round_trip(&Heartbeat);
+        round_trip(&Failed("error".to_string()));
     }
 
     #[test]
@@ -0,0 +1,240 @@
+//! JSON-RPC prover service, implementing [`RpcServer`] on top of [`nexus_core::prover`].
+//!
+//! Programs are proven with sequential Nova, and their proofs are kept in memory,
+//! indexed by the [`hash`] of the program. Executions are bounded by a maximum
+//! trace length, and at most a fixed number of proofs are kept, the oldest being
+//! evicted first.
+
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    server::{Server, ServerHandle},
+    types::{error::INTERNAL_ERROR_CODE, ErrorObjectOwned},
+};
+
+use nexus_core::config::vm::{NovaImpl, ProverImpl};
+use nexus_core::nvm::{
+    interactive::{parse_elf, TraceStream},
+    memory::MerkleTrie,
+};
+use nexus_core::prover::envelope::{ProofEnvelope, ProofHeader, ProofView};
+use nexus_core::prover::nova::{prove_seq_stream, types::SeqPP};
+pub use nexus_rpc_common::hash::Hash;
+use nexus_rpc_common::{hash::hash, ElfBytes};
+pub use nexus_rpc_traits::{RpcClient, RpcServer};
+
+use crate::{Result, LOG_TARGET};
+
+/// Proofs returned by the service.
+pub type Proof = ProofEnvelope;
+
+/// Prover used by the service.
+pub const PROVER: ProverImpl = ProverImpl::Nova(NovaImpl::Sequential);
+
+/// Maximum size of requests and responses, which contain programs and proofs.
+pub const MAX_MESSAGE_SIZE: u32 = 256 * 1024 * 1024;
+
+/// Error code returned when the requested proof is not known to the server.
+pub const PROOF_NOT_FOUND_CODE: i32 = -32001;
+
+/// Default maximum number of instructions executed by a proven program.
+pub const DEFAULT_MAX_TRACE_LEN: usize = 1 << 22;
+
+/// Default maximum number of proofs kept by the service.
+pub const DEFAULT_MAX_PROOFS: usize = 256;
+
+#[derive(Clone)]
+pub struct ProverService {
+    pp: Arc<SeqPP>,
+    k: usize,
+    max_trace_len: usize,
+    proofs: Arc<Mutex<Proofs>>,
+}
+
+// Completed proofs, evicted in the order they were inserted.
+struct Proofs {
+    capacity: usize,
+    map: HashMap<Hash, Proof>,
+    order: VecDeque<Hash>,
+}
+
+impl Proofs {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            map: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn insert(&mut self, hash: Hash, proof: Proof) {
+        if self.map.insert(hash, proof).is_some() {
+            return;
+        }
+        self.order.push_back(hash);
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.map.remove(&oldest);
+            }
+        }
+    }
+}
+
+impl ProverService {
+    /// Create a service proving programs with `k` instructions per step, with the
+    /// default limits.
+    pub fn new(pp: SeqPP, k: usize) -> Self {
+        Self::with_limits(pp, k, DEFAULT_MAX_TRACE_LEN, DEFAULT_MAX_PROOFS)
+    }
+
+    /// Create a service proving programs with `k` instructions per step, which execute
+    /// at most `max_trace_len` instructions, and keeping at most `max_proofs` proofs.
+    pub fn with_limits(pp: SeqPP, k: usize, max_trace_len: usize, max_proofs: usize) -> Self {
+        Self {
+            pp: Arc::new(pp),
+            k,
+            max_trace_len,
+            proofs: Arc::new(Mutex::new(Proofs::new(max_proofs))),
+        }
+    }
+
+    fn prove_elf(&self, elf: &[u8]) -> Result<Proof> {
+        let mut vm = parse_elf::<MerkleTrie>(elf)?;
+        vm.set_max_trace_len(self.max_trace_len);
+        let tr = TraceStream::new(&mut vm, self.k, false)?;
+        let proof = prove_seq_stream(&self.pp, tr)?;
+
+        let view = ProofView {
+            output: vm.syscalls.get_output(),
+            logs: vm
+                .syscalls
+                .get_log_buffer()
+                .iter()
+                .map(|l| String::from_utf8_lossy(l).into_owned())
+                .collect(),
+            exit_code: vm.syscalls.get_exit_code(),
+            panic_info: vm.syscalls.get_panic_info(),
+        };
+        let header = ProofHeader::new(PROVER, self.k, false, &self.pp.digest)?;
+        Ok(ProofEnvelope::new(header, Some(view), &proof)?)
+    }
+}
+
+fn internal_error(err: impl ToString) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(INTERNAL_ERROR_CODE, err.to_string(), None::<()>)
+}
+
+#[async_trait]
+impl RpcServer<Proof> for ProverService {
+    async fn prove(&self, elf: ElfBytes) -> RpcResult<Hash> {
+        let hash = hash(&elf);
+        if self.proofs.lock().unwrap().map.contains_key(&hash) {
+            return Ok(hash);
+        }
+
+        tracing::info!(
+            target: LOG_TARGET,
+            %hash,
+            "received prove-request",
+        );
+
+        let service = self.clone();
+        let proof = tokio::task::spawn_blocking(move || service.prove_elf(&elf))
+            .await
+            .map_err(internal_error)?
+            .map_err(internal_error)?;
+
+        tracing::info!(
+            target: LOG_TARGET,
+            %hash,
+            "proof complete",
+        );
+
+        self.proofs.lock().unwrap().insert(hash, proof);
+        Ok(hash)
+    }
+
+    async fn get_proof(&self, hash: Hash) -> RpcResult<Proof> {
+        tracing::info!(
+            target: LOG_TARGET,
+            %hash,
+            "received proof-query",
+        );
+
+        self.proofs
+            .lock()
+            .unwrap()
+            .map
+            .get(&hash)
+            .cloned()
+            .ok_or_else(|| {
+                ErrorObjectOwned::owned(PROOF_NOT_FOUND_CODE, "proof not found", None::<()>)
+            })
+    }
+}
+
+/// Start serving `service` on `addr`, returning the bound address and a handle to the server.
+pub async fn start_server(
+    service: ProverService,
+    addr: SocketAddr,
+) -> Result<(SocketAddr, ServerHandle)> {
+    let server = Server::builder()
+        .max_request_body_size(MAX_MESSAGE_SIZE)
+        .max_response_body_size(MAX_MESSAGE_SIZE)
+        .build(addr)
+        .await?;
+    let addr = server.local_addr()?;
+
+    tracing::info!(
+        target: LOG_TARGET,
+        "Listening on ws://{addr}",
+    );
+
+    Ok((addr, server.start(service.into_rpc())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ProverClient;
+    use nexus_core::prover::nova::{pp::gen_vm_pp, types::IVCProof};
+    use nexus_vm::loader::test_elf::TestElf;
+
+    #[tokio::test]
+    async fn prove_and_get_proof() {
+        let pp: SeqPP = gen_vm_pp(1, &()).unwrap();
+        let service = ProverService::with_limits(pp, 1, 16, 1);
+        let (addr, handle) = start_server(service.clone(), "127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+
+        let client = ProverClient::new(&addr.to_string()).unwrap();
+        let program = TestElf::new(&[0x13, 0x13, 0x13, 0xc0001073]).build(); // 3 nops, unimp
+
+        let hash = client.prove(program.clone()).await.unwrap();
+        assert_eq!(hash, nexus_rpc_common::hash::hash(&program));
+
+        let envelope = client.get_proof(hash).await.unwrap();
+        assert!(envelope.check(PROVER, 1, false).is_ok());
+        let proof: IVCProof = envelope.proof().unwrap();
+        proof.verify(&service.pp).unwrap();
+
+        let unknown = nexus_rpc_common::hash::hash(b"unknown");
+        assert!(client.get_proof(unknown).await.is_err());
+
+        // programs exceeding the maximum trace length are not proven
+        let looping = TestElf::new(&[0x6f]).build(); // j 0
+        assert!(client.prove(looping).await.is_err());
+
+        // the oldest proof is evicted
+        let other = TestElf::new(&[0x13, 0xc0001073]).build();
+        let other_hash = client.prove(other).await.unwrap();
+        assert!(client.get_proof(other_hash).await.is_ok());
+        assert!(client.get_proof(hash).await.is_err());
+
+        handle.stop().unwrap();
+    }
+}
@@ -394,6 +394,48 @@ impl<G: CurveGroup, C: PolyCommitmentScheme<G>> LCCSInstance<G, C> {
             vs,
         })
     }
+
+    /// Folds an incoming **linearized** [`LCCSInstance`] into the current one. As with [`LCCSInstance::fold`], the
+    /// auxillary inputs are the new evaluation point `rs`, together with the evaluations of the current (`sigmas`) and
+    /// incoming (`thetas`) instances at that point.
+    pub fn fold_with_linearized(
+        &self,
+        U2: &LCCSInstance<G, C>,
+        rho: &G::ScalarField,
+        rs: &[G::ScalarField],
+        sigmas: &[G::ScalarField],
+        thetas: &[G::ScalarField],
+    ) -> Result<Self, Error> {
+        // both instances are relaxed, so unlike in `fold` the leading `u` entries are folded like any other input
+        let (uX1, comm_W1) = (&self.X, self.commitment_W.clone());
+        let (uX2, comm_W2) = (&U2.X, U2.commitment_W.clone());
+
+        if self.rs.len() != rs.len() || U2.rs.len() != rs.len() {
+            return Err(Error::InvalidEvaluationPoint);
+        }
+
+        if uX1.len() != uX2.len() {
+            return Err(Error::InvalidInputLength);
+        }
+
+        if sigmas.len() != thetas.len() {
+            return Err(Error::InvalidTargets);
+        }
+
+        let commitment_W = comm_W1 + comm_W2 * *rho;
+
+        let X: Vec<G::ScalarField> = ark_std::cfg_iter!(uX1)
+            .zip(uX2)
+            .map(|(a, b)| *a + *b * *rho)
+            .collect();
+
+        let vs: Vec<G::ScalarField> = ark_std::cfg_iter!(sigmas)
+            .zip(thetas)
+            .map(|(sigma, theta)| *sigma + *theta * *rho)
+            .collect();
+
+        Ok(Self { commitment_W, X, rs: rs.to_owned(), vs })
+    }
 }
 
 /// A type that holds an LCCS instance.
@@ -795,4 +837,64 @@ mod tests {
         ccs_shape.is_satisfied_linearized(&folded_instance, &witness, &ck)?;
         Ok(())
     }
+
+    #[test]
+    fn folded_linearized_instance_is_satisfied() -> Result<(), Error> {
+        // Fold two linearized instances together and verify that resulting linearized instance is satisfied.
+        let (a, b, c) = {
+            (
+                to_field_sparse::<G>(A),
+                to_field_sparse::<G>(B),
+                to_field_sparse::<G>(C),
+            )
+        };
+
+        const NUM_CONSTRAINTS: usize = 4;
+        const NUM_WITNESS: usize = 4;
+        const NUM_PUBLIC: usize = 2;
+
+        let rho: Fr = Fr::from(11);
+
+        let r1cs_shape: R1CSShape<G> =
+            R1CSShape::<G>::new(NUM_CONSTRAINTS, NUM_WITNESS, NUM_PUBLIC, &a, &b, &c).unwrap();
+
+        let ccs_shape = CCSShape::from(r1cs_shape);
+
+        let mut rng = test_rng();
+        let SRS = Z::setup(3, b"test", &mut rng).unwrap();
+        let PCSKeys { ck, .. } = Z::trim(&SRS, 3);
+
+        let X = to_field_elements::<G>(&[1, 35]);
+        let W = to_field_elements::<G>(&[3, 9, 27, 30]);
+        let W1 = CCSWitness::<G>::new(&ccs_shape, &W)?;
+        let W2 = W1.clone();
+
+        let commitment_W = W1.commit::<Z>(&ck);
+
+        let s = safe_loglike!(NUM_CONSTRAINTS);
+        let z = [X.as_slice(), W.as_slice()].concat();
+        let evaluate = |rs: &[Fr]| -> Vec<Fr> {
+            ark_std::cfg_iter!(&ccs_shape.Ms)
+                .map(|M| vec_to_mle(M.multiply_vec(&z).as_slice()).evaluate::<G>(rs))
+                .collect()
+        };
+
+        let rs1: Vec<Fr> = (0..s).map(|_| Fr::rand(&mut rng)).collect();
+        let U1 = LCCSInstance::<G, Z>::new(&ccs_shape, &commitment_W, &X, &rs1, &evaluate(&rs1))?;
+
+        let rs2: Vec<Fr> = (0..s).map(|_| Fr::rand(&mut rng)).collect();
+        let U2 = LCCSInstance::<G, Z>::new(&ccs_shape, &commitment_W, &X, &rs2, &evaluate(&rs2))?;
+
+        let rs: Vec<Fr> = (0..s).map(|_| Fr::rand(&mut rng)).collect();
+        let sigmas = evaluate(&rs);
+        let thetas = sigmas.clone();
+
+        let folded_instance = U1.fold_with_linearized(&U2, &rho, &rs, &sigmas, &thetas)?;
+        assert_eq!(folded_instance.X[0], Fr::ONE + rho);
+
+        let witness = W1.fold(&W2, &rho)?;
+
+        ccs_shape.is_satisfied_linearized(&folded_instance, &witness, &ck)?;
+        Ok(())
+    }
 }
@@ -8,6 +8,7 @@ use ark_r1cs_std::fields::fp::FpVar;
 use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
 use ark_spartan::polycommitments::PolyCommitmentScheme;
 
+pub mod pcd;
 pub mod sequential;
 
 pub mod public_params;
@@ -58,6 +59,7 @@ where
     // sumcheck rounds. That gives an augmented circuit with size 92 -- which is a fixpoint
     // as 7 sumcheck rounds remains sufficient.
     fn project_augmented_circuit_size_upper_bound(
+        ro_config: &'_ RO::Config,
         step_circuit: &'_ SC,
     ) -> Result<(usize, usize), SynthesisError>;
 
@@ -0,0 +1,211 @@
+use ark_ec::CurveGroup;
+use ark_ff::Field;
+use ark_spartan::{
+    committed_linearized_snark::LinearizedR1CSInstance, errors::R1CSError,
+    polycommitments::PolyCommitmentScheme, Assignment, Instance, VarsAssignment,
+};
+use ark_std::{error::Error, fmt::Display};
+
+use crate::{
+    ccs::{CCSShape, CCSWitness, LCCSInstance},
+    r1cs::SparseMatrix,
+};
+
+#[derive(Debug)]
+pub enum ConversionError {
+    ConversionError(R1CSError),
+    /// The CCS shape was not derived from an R1CS shape.
+    UnsupportedShape,
+    /// The linearized instance does not hold exactly one evaluation per R1CS matrix.
+    InvalidInstance,
+}
+
+impl From<R1CSError> for ConversionError {
+    fn from(error: R1CSError) -> Self {
+        Self::ConversionError(error)
+    }
+}
+
+impl Error for ConversionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConversionError::ConversionError(e) => Some(e),
+            ConversionError::UnsupportedShape => None,
+            ConversionError::InvalidInstance => None,
+        }
+    }
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ConversionError(e) => write!(f, "Conversion error: {e}"),
+            Self::UnsupportedShape => write!(f, "Conversion error: CCS shape is not an R1CS shape"),
+            Self::InvalidInstance => write!(f, "Conversion error: invalid linearized instance"),
+        }
+    }
+}
+
+/// Returns whether `shape` is the CCS representation of an R1CS shape, that is,
+/// if it encodes the constraint (A·z) ∘ (B·z) - C·z = 0.
+fn is_r1cs<G: CurveGroup>(shape: &CCSShape<G>) -> bool {
+    shape.num_matrices == 3
+        && shape.Ms.len() == 3
+        && shape.cSs
+            == [
+                (G::ScalarField::ONE, vec![0, 1]),
+                (-G::ScalarField::ONE, vec![2]),
+            ]
+}
+
+impl<G: CurveGroup> TryFrom<CCSShape<G>> for Instance<G::ScalarField> {
+    type Error = ConversionError;
+    fn try_from(shape: CCSShape<G>) -> Result<Self, Self::Error> {
+        if !is_r1cs(&shape) {
+            return Err(ConversionError::UnsupportedShape);
+        }
+        let CCSShape {
+            num_constraints,
+            num_vars,
+            // This includes the leading `u` entry
+            num_io,
+            Ms,
+            ..
+        } = shape;
+        // Spartan arranges the R1CS matrices using Z = [w, u, x], rather than [u, x, w]
+        let rearrange =
+            |matrix: &SparseMatrix<G::ScalarField>| -> Vec<(usize, usize, G::ScalarField)> {
+                matrix.iter().map(|(row, col, val)|
+                // this is a witness entry
+                if col >= num_io {
+                    (row, col - num_io, val)
+                } else {
+                    // this is an IO entry
+                    (row, col + num_vars, val)
+                }).collect()
+            };
+        Ok(Instance::new(
+            num_constraints,
+            num_vars,
+            // Spartan does not include the leading `u` entry in `num_inputs`.
+            num_io - 1,
+            rearrange(&Ms[0]).as_slice(),
+            rearrange(&Ms[1]).as_slice(),
+            rearrange(&Ms[2]).as_slice(),
+        )?)
+    }
+}
+
+impl<G, PC> TryFrom<LCCSInstance<G, PC>> for LinearizedR1CSInstance<G, PC>
+where
+    G: CurveGroup,
+    PC: PolyCommitmentScheme<G>,
+{
+    type Error = ConversionError;
+    fn try_from(instance: LCCSInstance<G, PC>) -> Result<Self, Self::Error> {
+        let LCCSInstance { commitment_W, X, rs, vs } = instance;
+        let [v_A, v_B, v_C] = vs[..] else {
+            return Err(ConversionError::InvalidInstance);
+        };
+        Ok(LinearizedR1CSInstance {
+            input: Assignment::new(&X[1..])?,
+            u: X[0],
+            comm_W: commitment_W,
+            rx: rs,
+            evals: (v_A, v_B, v_C),
+        })
+    }
+}
+
+impl<G: CurveGroup> TryFrom<CCSWitness<G>> for VarsAssignment<G::ScalarField> {
+    type Error = ConversionError;
+    fn try_from(witness: CCSWitness<G>) -> Result<Self, Self::Error> {
+        Ok(Assignment::new(&witness.W)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{g1::Config as Bn254Config, Bn254};
+    use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+    use ark_ff::PrimeField;
+    use ark_spartan::committed_linearized_snark::{LinearizedSNARKKey, SNARK};
+    use ark_std::{test_rng, UniformRand};
+    use merlin::Transcript;
+
+    use super::*;
+    use crate::{
+        ccs::mle::vec_to_mle, circuits::hypernova::pcd::compression::SNARKKey, safe_loglike,
+        test_utils::setup_test_ccs, zeromorph::Zeromorph,
+    };
+
+    fn test_conversion_helper<G, PC>()
+    where
+        G: SWCurveConfig,
+        G::BaseField: PrimeField,
+        PC: PolyCommitmentScheme<Projective<G>>,
+        PC::PolyCommitmentKey: Clone,
+    {
+        let mut rng = test_rng();
+        let (shape, _, _, _) = setup_test_ccs::<G, PC>(3, None, Some(&mut rng));
+
+        let min_num_vars = SNARKKey::<Projective<G>, PC>::get_min_srs_size(&shape);
+        let srs = PC::setup(min_num_vars, b"test_srs_cubic", &mut rng)
+            .expect("SRS sampling should not produce an error");
+
+        let spartan_shape = Instance::<G::ScalarField>::try_from(shape.clone()).unwrap();
+        let (num_cons, num_vars, num_inputs) = (
+            spartan_shape.inst.get_num_cons(),
+            spartan_shape.inst.get_num_vars(),
+            spartan_shape.inst.get_num_inputs(),
+        );
+        let num_nz_entries = shape.Ms.iter().map(|M| M.len()).max().unwrap();
+        let key = LinearizedSNARKKey::<Projective<G>, PC>::new(
+            &srs,
+            num_cons,
+            num_vars,
+            num_inputs,
+            num_nz_entries,
+        );
+        let (_, u, w, _) = setup_test_ccs::<G, PC>(3, Some(&key.keys.ck), Some(&mut rng));
+
+        // linearize the committed instance at a random point
+        let s = safe_loglike!(shape.num_constraints) as usize;
+        let rs: Vec<G::ScalarField> = (0..s).map(|_| G::ScalarField::rand(&mut rng)).collect();
+        let z = [u.X.as_slice(), w.W.as_slice()].concat();
+        let vs: Vec<G::ScalarField> = shape
+            .Ms
+            .iter()
+            .map(|M| vec_to_mle(M.multiply_vec(&z).as_slice()).evaluate::<Projective<G>>(&rs))
+            .collect();
+        let U = LCCSInstance::<Projective<G>, PC>::new(&shape, &u.commitment_W, &u.X, &rs, &vs)
+            .unwrap();
+        shape.is_satisfied_linearized(&U, &w, &key.keys.ck).unwrap();
+
+        // convert to the corresponding Spartan types and check that the proof verifies
+        let instance = LinearizedR1CSInstance::<Projective<G>, PC>::try_from(U).unwrap();
+        let vars = VarsAssignment::<G::ScalarField>::try_from(w).unwrap();
+
+        let (comm, decomm) = SNARK::<Projective<G>, PC>::encode(&spartan_shape, &key);
+        let mut transcript = Transcript::new(b"test");
+        let proof = SNARK::prove(
+            &spartan_shape,
+            &instance,
+            vars,
+            &comm,
+            &decomm,
+            &key,
+            &mut transcript,
+        );
+
+        let mut transcript = Transcript::new(b"test");
+        proof
+            .verify(&comm, &instance, &mut transcript, &key)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_conversion() {
+        test_conversion_helper::<Bn254Config, Zeromorph<Bn254>>()
+    }
+}
@@ -0,0 +1,86 @@
+use ark_spartan::errors::ProofVerifyError;
+use ark_std::{error::Error, fmt::Display};
+
+use super::conversion::ConversionError;
+pub use crate::folding::hypernova::cyclefold::Error as HyperNovaError;
+
+#[derive(Debug)]
+pub enum ProofError {
+    InvalidProof,
+    InvalidPublicInput,
+    InvalidSpartanProof(ProofVerifyError),
+    SecondaryCircuitNotSatisfied,
+}
+
+#[derive(Debug)]
+pub enum SpartanError {
+    ConversionError(ConversionError),
+    FoldingError(HyperNovaError),
+    InvalidProof(ProofError),
+}
+
+impl From<ConversionError> for SpartanError {
+    fn from(error: ConversionError) -> Self {
+        Self::ConversionError(error)
+    }
+}
+
+impl From<HyperNovaError> for SpartanError {
+    fn from(error: HyperNovaError) -> Self {
+        Self::FoldingError(error)
+    }
+}
+
+impl From<ProofError> for SpartanError {
+    fn from(error: ProofError) -> Self {
+        Self::InvalidProof(error)
+    }
+}
+
+impl From<ProofVerifyError> for SpartanError {
+    fn from(error: ProofVerifyError) -> Self {
+        Self::InvalidProof(ProofError::InvalidSpartanProof(error))
+    }
+}
+
+impl Error for SpartanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SpartanError::ConversionError(e) => Some(e),
+            SpartanError::FoldingError(e) => Some(e),
+            SpartanError::InvalidProof(e) => Some(e),
+        }
+    }
+}
+
+impl Display for SpartanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpartanError::ConversionError(e) => write!(f, "{}", e),
+            SpartanError::FoldingError(e) => write!(f, "{}", e),
+            SpartanError::InvalidProof(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for ProofError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidProof => None,
+            Self::InvalidPublicInput => None,
+            Self::InvalidSpartanProof(e) => Some(e),
+            Self::SecondaryCircuitNotSatisfied => None,
+        }
+    }
+}
+
+impl Display for ProofError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidProof => write!(f, "Invalid proof"),
+            Self::InvalidPublicInput => write!(f, "Invalid public input"),
+            Self::InvalidSpartanProof(e) => write!(f, "{}", e),
+            Self::SecondaryCircuitNotSatisfied => write!(f, "Secondary circuit not satisfied"),
+        }
+    }
+}
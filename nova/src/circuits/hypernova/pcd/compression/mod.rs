@@ -0,0 +1,406 @@
+use ark_crypto_primitives::sponge::{
+    constraints::{CryptographicSpongeVar, SpongeWithGadget},
+    Absorb,
+};
+use ark_ec::{
+    short_weierstrass::{Projective, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_spartan::{
+    committed_linearized_snark as spartan_snark,
+    committed_linearized_snark::LinearizedSNARKKey as SNARKGens,
+    polycommitments::PolyCommitmentScheme, ComputationCommitment, ComputationDecommitment,
+    Instance,
+};
+use ark_std::marker::PhantomData;
+use merlin::Transcript;
+
+use super::{augmented::SQUEEZE_NATIVE_ELEMENTS_NUM, PCDNode, PublicParams, LOG_TARGET};
+use crate::{
+    absorb::CryptographicSpongeExt,
+    ccs::CCSShape,
+    commitment::CommitmentScheme,
+    folding::hypernova::cyclefold::nimfs::{
+        CCSInstance, LCCSInstance, NIMFSProof, RelaxedR1CSInstance, RelaxedR1CSWitness,
+    },
+    StepCircuit,
+};
+
+mod conversion;
+
+pub mod error;
+
+pub use conversion::ConversionError;
+pub use error::{ProofError, SpartanError};
+
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct CompressedPCDProof<G1, G2, C1, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: PolyCommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    pub i: u64,
+    pub j: u64,
+
+    pub z_i: Vec<G1::ScalarField>,
+    pub z_j: Vec<G1::ScalarField>,
+
+    pub U: LCCSInstance<G1, C1>,
+    pub u: CCSInstance<G1, C1>,
+    pub U_secondary: RelaxedR1CSInstance<G2, C2>,
+
+    pub W_secondary_prime: RelaxedR1CSWitness<G2>,
+
+    pub spartan_proof: spartan_snark::SNARK<Projective<G1>, C1>,
+    pub folding_proof: NIMFSProof<G1, G2, C1, C2, RO>,
+
+    _random_oracle: PhantomData<RO>,
+    _step_circuit: PhantomData<SC>,
+}
+
+#[derive(CanonicalDeserialize, CanonicalSerialize)]
+pub struct SNARKKey<G: CurveGroup, PC: PolyCommitmentScheme<G>> {
+    shape: Instance<G::ScalarField>,
+    computation_comm: ComputationCommitment<G, PC>,
+    computation_decomm: ComputationDecommitment<G::ScalarField>,
+    snark_gens: SNARKGens<G, PC>,
+}
+
+impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> SNARKKey<G, PC> {
+    /// convenience function to derive the minimum log size of the SRS
+    /// needed to support compession for a given `shape`.
+    ///
+    /// Note that the same SRS is used to commit to the witness of the augmented
+    /// circuit, thus it should be at least as large as required by the public parameters.
+    pub fn get_min_srs_size(shape: &CCSShape<G>) -> usize {
+        let CCSShape {
+            num_constraints, num_vars, num_io, Ms, ..
+        } = shape;
+        // spartan uses the convention that num_inputs does not include the leading `u`.
+        let num_inputs = num_io - 1;
+        let num_nz_entries = Ms.iter().map(|M| M.len()).max().unwrap_or_default();
+        SNARKGens::<G, PC>::get_min_num_vars(
+            *num_constraints,
+            *num_vars,
+            num_inputs,
+            num_nz_entries,
+        )
+    }
+}
+
+pub struct SNARK<G1, G2, C1, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G1::BaseField: PrimeField + Absorb,
+    G2: SWCurveConfig,
+    C1: PolyCommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    _group: PhantomData<G1>,
+    _group_secondary: PhantomData<G2>,
+    _poly_commitment: PhantomData<C1>,
+    _commitment: PhantomData<C2>,
+    _random_oracle: PhantomData<RO>,
+    _step_circuit: PhantomData<SC>,
+}
+
+impl<G1, G2, C1, C2, RO, SC> SNARK<G1, G2, C1, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: PolyCommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    pub fn setup(
+        pp: &PublicParams<G1, G2, C1, C2, RO, SC>,
+        srs: &C1::SRS,
+    ) -> Result<SNARKKey<Projective<G1>, C1>, SpartanError> {
+        let _span = tracing::debug_span!(target: LOG_TARGET, "Spartan_setup").entered();
+        let PublicParams { shape: _shape, .. } = pp;
+        // converts the CCSShape from this crate into an R1CS instance from the Spartan crate
+        let shape: Instance<G1::ScalarField> = _shape.clone().try_into()?;
+        let (num_cons, num_vars, num_inputs) = (
+            shape.inst.get_num_cons(),
+            shape.inst.get_num_vars(),
+            shape.inst.get_num_inputs(),
+        );
+
+        let num_nz_entries = _shape.Ms.iter().map(|M| M.len()).max().unwrap_or_default();
+        let snark_gens = SNARKGens::new(srs, num_cons, num_vars, num_inputs, num_nz_entries);
+        let (computation_comm, computation_decomm) =
+            spartan_snark::SNARK::<Projective<G1>, C1>::encode(&shape, &snark_gens);
+        Ok(SNARKKey {
+            shape,
+            computation_comm,
+            computation_decomm,
+            snark_gens,
+        })
+    }
+
+    pub fn compress(
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+        key: &SNARKKey<Projective<G1>, C1>,
+        pcd_proof: PCDNode<G1, G2, C1, C2, RO, SC>,
+    ) -> Result<CompressedPCDProof<G1, G2, C1, C2, RO, SC>, SpartanError> {
+        let _span = tracing::debug_span!(target: LOG_TARGET, "Spartan_prove").entered();
+        let SNARKKey {
+            shape,
+            computation_comm,
+            computation_decomm,
+            snark_gens,
+        } = key;
+        let PCDNode {
+            i,
+            j,
+            z_i,
+            z_j,
+            U,
+            W,
+            U_secondary,
+            W_secondary,
+            u,
+            w,
+            ..
+        } = pcd_proof;
+        // First, we fold the instance-witness pair `(u,w)` into the running instances.
+        let (folding_proof, (U_prime, W_prime), (_U_secondary_prime, W_secondary_prime)) =
+            NIMFSProof::prove(
+                &params.pp_secondary,
+                &params.ro_config,
+                &params.digest,
+                (&params.shape, &params.shape_secondary),
+                (&U, &W),
+                (&U_secondary, &W_secondary),
+                (&u, &w),
+            )?;
+        let mut transcript = Transcript::new(b"spartan_snark");
+        // Now, we use Spartan to prove knowledge of the witness `W_prime`
+        // for the linearized instance `U_prime`
+        let spartan_proof = spartan_snark::SNARK::<Projective<G1>, C1>::prove(
+            shape,
+            &U_prime.try_into()?,
+            W_prime.try_into()?,
+            computation_comm,
+            computation_decomm,
+            snark_gens,
+            &mut transcript,
+        );
+
+        Ok(CompressedPCDProof {
+            i,
+            j,
+            z_i,
+            z_j,
+            U,
+            u,
+            U_secondary,
+            W_secondary_prime,
+            spartan_proof,
+            folding_proof,
+            _random_oracle: PhantomData,
+            _step_circuit: PhantomData,
+        })
+    }
+
+    pub fn verify(
+        key: &SNARKKey<Projective<G1>, C1>,
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+        proof: &CompressedPCDProof<G1, G2, C1, C2, RO, SC>,
+    ) -> Result<(), SpartanError> {
+        let _span =
+            tracing::debug_span!(target: LOG_TARGET, "Spartan_verify", i = proof.i, j = proof.j,)
+                .entered();
+        let CompressedPCDProof {
+            i,
+            j,
+            z_i,
+            z_j,
+            U,
+            u,
+            U_secondary,
+            W_secondary_prime,
+            spartan_proof,
+            folding_proof,
+            ..
+        } = proof;
+        // First, we hash the running instances U, U_secondary and check that
+        // the public IO of `u` is equal to this hash value.
+        let mut random_oracle = RO::new(&params.ro_config);
+        random_oracle.absorb(&params.digest);
+        random_oracle.absorb(&G1::ScalarField::from(*i));
+        random_oracle.absorb(&G1::ScalarField::from(*j));
+        random_oracle.absorb(z_i);
+        random_oracle.absorb(z_j);
+        random_oracle.absorb(&U);
+        random_oracle.absorb_non_native(&U_secondary);
+
+        let hash: &G1::ScalarField =
+            &random_oracle.squeeze_field_elements(SQUEEZE_NATIVE_ELEMENTS_NUM)[0];
+        if hash != &u.X[1] {
+            return Err(SpartanError::InvalidProof(ProofError::InvalidPublicInput));
+        }
+
+        // Now, using the folding proof provided by the prover, we compute the folded
+        // instances U_prime and U_secondary_prime.
+        let (U_prime, U_secondary_prime) = folding_proof.verify(
+            &params.ro_config,
+            &params.digest,
+            &params.shape,
+            U,
+            U_secondary,
+            u,
+        )?;
+
+        // We check that the provided witness `W_secondary_prime` satisfies the
+        // committed relaxed r1cs instance `U_secondary_prime`.
+        params
+            .shape_secondary
+            .is_relaxed_satisfied(&U_secondary_prime, W_secondary_prime, &params.pp_secondary)
+            .map_err(|_| SpartanError::InvalidProof(ProofError::SecondaryCircuitNotSatisfied))?;
+
+        // Finally, we verify the Spartan proof for the linearized instance `U_prime`.
+        let mut transcript = Transcript::new(b"spartan_snark");
+        spartan_snark::SNARK::<Projective<G1>, C1>::verify(
+            spartan_proof,
+            &key.computation_comm,
+            &U_prime.try_into()?,
+            &mut transcript,
+            &key.snark_gens,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{g1::Config as Bn254Config, Bn254};
+    use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
+    use ark_ec::CurveConfig;
+    use ark_grumpkin::{GrumpkinConfig, Projective as GrumpkinProjective};
+    use ark_std::{cmp::max, test_rng, One};
+
+    use super::*;
+    use crate::{
+        circuits::hypernova::sequential::tests::CubicCircuit, pedersen::PedersenCommitment,
+        poseidon_config, safe_loglike, zeromorph::Zeromorph,
+    };
+
+    type TestParams<G1, G2, C1, C2> = PublicParams<
+        G1,
+        G2,
+        C1,
+        C2,
+        PoseidonSponge<<G1 as CurveConfig>::ScalarField>,
+        CubicCircuit<<G1 as CurveConfig>::ScalarField>,
+    >;
+
+    fn test_setup_helper<G1, G2, C1, C2>() -> (C1::SRS, TestParams<G1, G2, C1, C2>)
+    where
+        G1: SWCurveConfig,
+        G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+        G1::BaseField: PrimeField + Absorb,
+        G2::BaseField: PrimeField + Absorb,
+        C1: PolyCommitmentScheme<Projective<G1>>,
+        C2: CommitmentScheme<Projective<G2>, SetupAux = ()>,
+    {
+        let mut rng = test_rng();
+        let step_circuit = CubicCircuit::<G1::ScalarField>::default();
+
+        // The SRS has to support both the augmented circuit and the Spartan key, and
+        // the size of the latter is only known once the shape is synthesized.
+        let params =
+            TestParams::<G1, G2, C1, C2>::test_setup(poseidon_config(), &step_circuit).unwrap();
+        let shape = &params.shape;
+        let min_num_vars = max(
+            SNARKKey::<Projective<G1>, C1>::get_min_srs_size(shape),
+            safe_loglike!(shape.num_vars.max(shape.num_constraints)) as usize,
+        );
+
+        let srs = C1::setup(min_num_vars, b"test_srs", &mut rng).unwrap();
+        let params =
+            TestParams::<G1, G2, C1, C2>::setup(poseidon_config(), &step_circuit, &srs, &())
+                .expect("setup should not fail");
+        (srs, params)
+    }
+
+    fn compression_test_helper<G1, G2, C1, C2>()
+    where
+        G1: SWCurveConfig,
+        G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+        G1::BaseField: PrimeField + Absorb,
+        G2::BaseField: PrimeField + Absorb,
+        C1: PolyCommitmentScheme<Projective<G1>>,
+        C2: CommitmentScheme<Projective<G2>, SetupAux = ()>,
+    {
+        let circuit = CubicCircuit::<G1::ScalarField>::default();
+        let z_0 = vec![G1::ScalarField::one(); 5];
+        let mut z_1 = z_0.clone();
+        z_1[0] = G1::ScalarField::from(7);
+
+        // We set up the public parameters both for HyperNova and Spartan.
+        let (srs, params) = test_setup_helper::<G1, G2, C1, C2>();
+        let key = SNARK::<
+            G1,
+            G2,
+            C1,
+            C2,
+            PoseidonSponge<G1::ScalarField>,
+            CubicCircuit<G1::ScalarField>,
+        >::setup(&params, &srs)
+        .unwrap();
+
+        // Now, we perform a PCD proof step and check that the resulting proof verifies.
+        let node = PCDNode::prove_leaf(&params, &circuit, 0, &z_0).unwrap();
+        node.verify(&params).unwrap();
+
+        assert_eq!(&node.z_j, &z_1);
+
+        // Now, we compress the proof using Spartan
+        let compressed_pcd_proof = SNARK::<
+            G1,
+            G2,
+            C1,
+            C2,
+            PoseidonSponge<G1::ScalarField>,
+            CubicCircuit<G1::ScalarField>,
+        >::compress(&params, &key, node)
+        .unwrap();
+
+        // And check that the compressed proof verifies.
+        SNARK::<
+            G1,
+            G2,
+            C1,
+            C2,
+            PoseidonSponge<G1::ScalarField>,
+            CubicCircuit<G1::ScalarField>,
+        >::verify(&key, &params, &compressed_pcd_proof)
+        .unwrap();
+    }
+
+    #[test]
+    #[ignore]
+    fn compression_test() {
+        compression_test_helper::<
+            Bn254Config,
+            GrumpkinConfig,
+            Zeromorph<Bn254>,
+            PedersenCommitment<GrumpkinProjective>,
+        >();
+    }
+}
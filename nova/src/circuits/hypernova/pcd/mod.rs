@@ -0,0 +1,622 @@
+//! Cycle-fold HyperNova based proof carrying data construction.
+//!
+//! Similar to [IVC](super::sequential::IVCProof) each [`PCDNode`] proves a range of computing F_i,
+//! with the difference of left index possibly being non-zero.
+//!
+//! A node proving range [i; k) can be folded with another node that proves range [k + 1; j) to build
+//! a new node with range [i; j). These indices correspond to [`PCDNode::min_step`] and [`PCDNode::max_step`].
+//! Therefore, this construction is often associated with binary tree, where the root proves the whole
+//! n steps in range [0; n).
+//!
+//! ### Example
+//!
+//! For simplicity, notating i as F_i(z_i), one can prove sequential computation
+//!
+//! 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 6
+//!
+//! as a full binary tree of pcd nodes
+//!
+//!```text
+//!              ┌───┐
+//!        ┌─────┤ 3 ├─────┐
+//!        │     └───┘     │
+//!      ┌─┴─┐           ┌─┴─┐
+//!    ┌─┤ 1 ├─┐       ┌─┤ 5 ├─┐
+//!    │ └───┘ │       │ └───┘ │
+//!  ┌─┴─┐   ┌─┴─┐   ┌─┴─┐   ┌─┴─┐
+//!  │ 0 │   │ 2 │   │ 4 │   │ 6 │
+//!  └───┘   └───┘   └───┘   └───┘
+//!```
+//!
+//! To get to the root of the tree, a prover would start with proving even steps of F_i -- leaves.
+//! Folding leaf [i; i + 1) with [i + 2; i + 3) will require prover to execute step i + 1, the output
+//! will be a parent node proving [i; i + 3).
+//!
+//! In a parent node, the committed instance of each child is first folded into its linearized
+//! instance, and the two resulting linearized instances are then folded together.
+//!
+//! This algorithm is then applied recursively to each pair of nodes.
+//!
+//! Current implementation requires padding execution of F to `next_power_of_two() - 1`.
+
+use std::marker::PhantomData;
+
+use ark_crypto_primitives::sponge::{
+    constraints::{CryptographicSpongeVar, SpongeWithGadget},
+    Absorb, CryptographicSponge,
+};
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+use ark_ff::{AdditiveGroup, PrimeField};
+use ark_r1cs_std::R1CSVar;
+use ark_relations::r1cs::{ConstraintSystem, SynthesisMode};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_spartan::polycommitments::{PCSKeys, PolyCommitmentScheme};
+
+use super::{public_params, HyperNovaConstraintSynthesizer, StepCircuit};
+use crate::{
+    absorb::CryptographicSpongeExt,
+    commitment::CommitmentScheme,
+    folding::hypernova::cyclefold::{
+        self,
+        nimfs::{
+            CCSInstance, CCSShape, CCSWitness, LCCSInstance, NIMFSProof, R1CSShape,
+            RelaxedR1CSInstance, RelaxedR1CSWitness,
+        },
+    },
+    safe_loglike,
+};
+
+pub(crate) mod augmented;
+
+pub mod compression;
+
+use augmented::{
+    HyperNovaAugmentedCircuit, HyperNovaAugmentedCircuitInput,
+    HyperNovaAugmentedCircuitNonBaseInput, PCDNodeInput,
+};
+
+const LOG_TARGET: &str = "nexus-nova::hypernova::pcd";
+
+#[doc(hidden)]
+pub struct SetupParams<T>(PhantomData<T>);
+
+impl<G1, G2, C1, C2, RO, SC> public_params::SetupParams<G1, G2, C1, C2, RO, SC>
+    for SetupParams<(G1, G2, C1, C2, RO, SC)>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: PolyCommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    fn setup(
+        ro_config: <RO as CryptographicSponge>::Config,
+        step_circuit: &SC,
+        srs: &C1::SRS,
+        aux: &C2::SetupAux,
+    ) -> Result<public_params::PublicParams<G1, G2, C1, C2, RO, SC, Self>, cyclefold::Error> {
+        let _span = tracing::debug_span!(target: LOG_TARGET, "setup").entered();
+
+        let (sumcheck_rounds, projected_augmented_circuit_size_upper_bound) =
+            HyperNovaAugmentedCircuit::<G1, G2, C1, C2, RO, SC>::project_augmented_circuit_size_upper_bound(
+                &ro_config,
+                step_circuit,
+            )?;
+
+        let (U, proof) = HyperNovaAugmentedCircuit::<G1, G2, C1, C2, RO, SC>::base(sumcheck_rounds);
+
+        let cs = ConstraintSystem::new_ref();
+        cs.set_mode(SynthesisMode::Setup);
+
+        let input = HyperNovaAugmentedCircuitInput::<G1, G2, C1, C2, RO>::Base {
+            vk: G1::ScalarField::ZERO,
+            i: G1::ScalarField::ZERO,
+            z_i: vec![G1::ScalarField::ZERO; SC::ARITY],
+            U,
+            proof,
+        };
+        let circuit =
+            HyperNovaAugmentedCircuit::new(&ro_config, step_circuit, sumcheck_rounds, input);
+        let _ = HyperNovaConstraintSynthesizer::generate_constraints(circuit, cs.clone())?;
+
+        cs.finalize();
+
+        let shape = CCSShape::from(R1CSShape::from(cs));
+        let shape_secondary = cyclefold::secondary::setup_shape::<G1, G2>()?;
+
+        tracing::debug!(
+            target: LOG_TARGET,
+            "circuit generation done; augmented circuit size: {}, projected upper bound: {}",
+            shape.num_constraints,
+            projected_augmented_circuit_size_upper_bound,
+        );
+        assert!(shape.num_constraints <= projected_augmented_circuit_size_upper_bound,
+                "shape does not conform to projected upper bound on circuit size, aborting to prevent invalid recursion");
+
+        let max_poly_vars: usize =
+            safe_loglike!(shape.num_vars.max(shape.num_constraints)) as usize;
+        let PCSKeys { ck, .. } = C1::trim(srs, max_poly_vars);
+
+        let pp_secondary = C2::setup(
+            shape_secondary
+                .num_vars
+                .max(shape_secondary.num_constraints),
+            b"hypernova_pcd_secondary_curve",
+            aux,
+        );
+
+        let mut params = public_params::PublicParams {
+            ro_config,
+            shape,
+            shape_secondary,
+            ck,
+            pp_secondary,
+            digest: G1::ScalarField::ZERO,
+
+            _step_circuit: PhantomData,
+            _setup_params: PhantomData,
+        };
+        let digest = params.hash();
+        params.digest = digest;
+
+        tracing::debug!(
+            target: LOG_TARGET,
+            "public params setup done; augmented circuit: {}, secondary circuit: {}",
+            params.shape,
+            params.shape_secondary,
+        );
+        Ok(params)
+    }
+
+    fn project_augmented_circuit_size_upper_bound(
+        ro_config: &RO::Config,
+        step_circuit: &SC,
+    ) -> Result<(usize, usize), cyclefold::Error> {
+        Ok(
+            HyperNovaAugmentedCircuit::<G1, G2, C1, C2, RO, SC>::project_augmented_circuit_size_upper_bound(
+                ro_config,
+                step_circuit,
+            )?,
+        )
+    }
+}
+
+pub type PublicParams<G1, G2, C1, C2, RO, SC> =
+    public_params::PublicParams<G1, G2, C1, C2, RO, SC, SetupParams<(G1, G2, C1, C2, RO, SC)>>;
+
+/// Proof-carrying data tree node.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct PCDNode<G1, G2, C1, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: PolyCommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    pub i: u64,
+    pub j: u64,
+
+    pub z_i: Vec<G1::ScalarField>,
+    pub z_j: Vec<G1::ScalarField>,
+
+    pub U: LCCSInstance<G1, C1>,
+    pub W: CCSWitness<G1>,
+    pub U_secondary: RelaxedR1CSInstance<G2, C2>,
+    pub W_secondary: RelaxedR1CSWitness<G2>,
+
+    pub u: CCSInstance<G1, C1>,
+    pub w: CCSWitness<G1>,
+
+    _random_oracle: PhantomData<RO>,
+    _step_circuit: PhantomData<SC>,
+}
+
+impl<G1, G2, C1, C2, RO, SC> PCDNode<G1, G2, C1, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: PolyCommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    pub fn min_step(&self) -> u64 {
+        self.i
+    }
+
+    pub fn max_step(&self) -> u64 {
+        self.j
+    }
+
+    pub fn prove_leaf(
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+        step_circuit: &SC,
+        i: usize,
+        z_i: &[G1::ScalarField],
+    ) -> Result<Self, cyclefold::Error> {
+        Self::prove_leaf_with_commit_fn(params, step_circuit, i, z_i, |ck, w| w.commit::<C1>(ck))
+    }
+
+    /// Proves step of step circuit execution and calls `commit_fn(ck, w)` to
+    /// compute commitment to the witness of the augmented circuit.
+    pub fn prove_leaf_with_commit_fn(
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+        step_circuit: &SC,
+        i: usize,
+        z_i: &[G1::ScalarField],
+        mut commit_fn: impl FnMut(&C1::PolyCommitmentKey, &CCSWitness<G1>) -> C1::Commitment,
+    ) -> Result<Self, cyclefold::Error> {
+        let _span = tracing::debug_span!(
+            target: LOG_TARGET,
+            "prove_leaf",
+            ?i,
+            j = i + 1,
+        )
+        .entered();
+
+        let i = i as u64;
+        let sumcheck_rounds: usize = safe_loglike!(params.shape.num_constraints) as usize;
+
+        let (U, proof) = HyperNovaAugmentedCircuit::<G1, G2, C1, C2, RO, SC>::base(sumcheck_rounds);
+        let W = CCSWitness::zero(&params.shape);
+
+        let U_secondary = RelaxedR1CSInstance::<G2, C2>::new(&params.shape_secondary);
+        let W_secondary = RelaxedR1CSWitness::zero(&params.shape_secondary);
+
+        let input = HyperNovaAugmentedCircuitInput::<G1, G2, C1, C2, RO>::Base {
+            vk: params.digest,
+            i: G1::ScalarField::from(i),
+            z_i: z_i.to_owned(),
+            U: U.clone(),
+            proof,
+        };
+
+        let cs = ConstraintSystem::new_ref();
+        cs.set_mode(SynthesisMode::Prove { construct_matrices: false });
+
+        let circuit =
+            HyperNovaAugmentedCircuit::new(&params.ro_config, step_circuit, sumcheck_rounds, input);
+        let z_next =
+            tracing::debug_span!(target: LOG_TARGET, "satisfying_assignment").in_scope(|| {
+                HyperNovaConstraintSynthesizer::generate_constraints(circuit, cs.clone())
+            })?;
+
+        let cs_borrow = cs.borrow().unwrap();
+        let witness = cs_borrow.witness_assignment.clone();
+        let pub_io = cs_borrow.instance_assignment.clone();
+
+        let w = CCSWitness::<G1> { W: witness };
+
+        let commitment_W = commit_fn(&params.ck, &w);
+        let u = CCSInstance::<G1, C1> { commitment_W, X: pub_io };
+        let z_j = z_next
+            .iter()
+            .map(R1CSVar::value)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            i,
+            j: i + 1,
+            z_i: z_i.to_owned(),
+            z_j,
+            U,
+            W,
+            U_secondary,
+            W_secondary,
+            u,
+            w,
+            _random_oracle: PhantomData,
+            _step_circuit: PhantomData,
+        })
+    }
+
+    pub fn prove_parent(
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+        step_circuit: &SC,
+        left_node: &Self,
+        right_node: &Self,
+    ) -> Result<Self, cyclefold::Error> {
+        Self::prove_parent_with_commit_fn(params, step_circuit, left_node, right_node, |ck, w| {
+            w.commit::<C1>(ck)
+        })
+    }
+
+    pub fn prove_parent_with_commit_fn(
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+        step_circuit: &SC,
+        left_node: &Self,
+        right_node: &Self,
+        mut commit_fn: impl FnMut(&C1::PolyCommitmentKey, &CCSWitness<G1>) -> C1::Commitment,
+    ) -> Result<Self, cyclefold::Error> {
+        let _span = tracing::debug_span!(
+            target: LOG_TARGET,
+            "prove_parent",
+            i = left_node.i,
+            j = right_node.j,
+        )
+        .entered();
+
+        let sumcheck_rounds: usize = safe_loglike!(params.shape.num_constraints) as usize;
+
+        // proof left node
+        let (proof_left, (U_l, W_l), (U_l_secondary, W_l_secondary)) = NIMFSProof::prove(
+            &params.pp_secondary,
+            &params.ro_config,
+            &params.digest,
+            (&params.shape, &params.shape_secondary),
+            (&left_node.U, &left_node.W),
+            (&left_node.U_secondary, &left_node.W_secondary),
+            (&left_node.u, &left_node.w),
+        )?;
+        // proof right node
+        let (proof_right, (U_r, W_r), (U_r_secondary, W_r_secondary)) = NIMFSProof::prove(
+            &params.pp_secondary,
+            &params.ro_config,
+            &params.digest,
+            (&params.shape, &params.shape_secondary),
+            (&right_node.U, &right_node.W),
+            (&right_node.U_secondary, &right_node.W_secondary),
+            (&right_node.u, &right_node.w),
+        )?;
+
+        // proof resulting node
+        let (proof, (U, W), (U_secondary, W_secondary)) = NIMFSProof::prove_with_linearized(
+            &params.pp_secondary,
+            &params.ro_config,
+            &params.digest,
+            (&params.shape, &params.shape_secondary),
+            (&U_l, &W_l),
+            (&U_l_secondary, &W_l_secondary),
+            (&U_r, &W_r),
+            (&U_r_secondary, &W_r_secondary),
+        )?;
+
+        let (i, j, k) = (left_node.i, left_node.j, right_node.j);
+        let (z_i, z_j, z_k) = (&left_node.z_i, &left_node.z_j, &right_node.z_j);
+        let left_node = PCDNodeInput::<G1, G2, C1, C2, RO> {
+            U: left_node.U.clone(),
+            U_secondary: left_node.U_secondary.clone(),
+            u: left_node.u.clone(),
+            proof: proof_left,
+        };
+        let right_node = PCDNodeInput::<G1, G2, C1, C2, RO> {
+            U: right_node.U.clone(),
+            U_secondary: right_node.U_secondary.clone(),
+            u: right_node.u.clone(),
+            proof: proof_right,
+        };
+
+        let input = HyperNovaAugmentedCircuitNonBaseInput::<G1, G2, C1, C2, RO> {
+            i: i.into(),
+            j: j.into(),
+            k: k.into(),
+            z_i: z_i.to_owned(),
+            z_j: z_j.to_owned(),
+            z_k: z_k.to_owned(),
+
+            vk: params.digest,
+            nodes: [left_node, right_node],
+            proof,
+        };
+
+        let cs = ConstraintSystem::new_ref();
+        cs.set_mode(SynthesisMode::Prove { construct_matrices: false });
+
+        let circuit = HyperNovaAugmentedCircuit::new(
+            &params.ro_config,
+            step_circuit,
+            sumcheck_rounds,
+            HyperNovaAugmentedCircuitInput::NonBase(input),
+        );
+        let _ =
+            tracing::debug_span!(target: LOG_TARGET, "satisfying_assignment").in_scope(|| {
+                HyperNovaConstraintSynthesizer::generate_constraints(circuit, cs.clone())
+            })?;
+
+        let cs_borrow = cs.borrow().unwrap();
+        let witness = cs_borrow.witness_assignment.clone();
+        let pub_io = cs_borrow.instance_assignment.clone();
+
+        let w = CCSWitness::<G1> { W: witness };
+
+        let commitment_W = commit_fn(&params.ck, &w);
+        let u = CCSInstance::<G1, C1> { commitment_W, X: pub_io };
+
+        Ok(Self {
+            i,
+            j: k,
+            z_i: z_i.to_owned(),
+            z_j: z_k.to_owned(),
+            U,
+            W,
+            U_secondary,
+            W_secondary,
+            u,
+            w,
+            _random_oracle: PhantomData,
+            _step_circuit: PhantomData,
+        })
+    }
+
+    pub fn verify(
+        &self,
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+    ) -> Result<(), cyclefold::Error> {
+        let _span = tracing::debug_span!(
+            target: LOG_TARGET,
+            "verify",
+            i = self.i,
+            j = self.j,
+        )
+        .entered();
+
+        const NOT_SATISFIED_ERROR: cyclefold::Error =
+            cyclefold::Error::CCS(crate::ccs::Error::NotSatisfied);
+        let PCDNode {
+            i,
+            j,
+            z_i,
+            z_j,
+            U,
+            W,
+            U_secondary,
+            W_secondary,
+            u,
+            w,
+            ..
+        } = self;
+
+        let mut random_oracle = RO::new(&params.ro_config);
+        random_oracle.absorb(&params.digest);
+        random_oracle.absorb(&G1::ScalarField::from(*i));
+        random_oracle.absorb(&G1::ScalarField::from(*j));
+        random_oracle.absorb(z_i);
+        random_oracle.absorb(z_j);
+        random_oracle.absorb(U);
+        random_oracle.absorb_non_native(U_secondary);
+
+        let hash: &G1::ScalarField =
+            &random_oracle.squeeze_field_elements(augmented::SQUEEZE_NATIVE_ELEMENTS_NUM)[0];
+        if hash != &u.X[1] {
+            return Err(NOT_SATISFIED_ERROR);
+        }
+
+        params.shape.is_satisfied_linearized(U, W, &params.ck)?;
+        params.shape_secondary.is_relaxed_satisfied(
+            U_secondary,
+            W_secondary,
+            &params.pp_secondary,
+        )?;
+        params.shape.is_satisfied(u, w, &params.ck)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        circuits::hypernova::sequential::tests::CubicCircuit, pedersen::PedersenCommitment,
+        poseidon_config, zeromorph::Zeromorph, LOG_TARGET as HYPERNOVA_TARGET,
+    };
+
+    use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
+
+    use tracing_subscriber::{
+        filter, fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt,
+    };
+
+    fn z<F: PrimeField>(x: u64) -> Vec<F> {
+        let mut z = vec![F::ONE; 5];
+        z[0] = F::from(x);
+        z
+    }
+
+    #[test]
+    fn pcd_base_step() {
+        pcd_base_step_with_cycle::<
+            ark_bn254::g1::Config,
+            ark_grumpkin::GrumpkinConfig,
+            Zeromorph<ark_bn254::Bn254>,
+            PedersenCommitment<ark_grumpkin::Projective>,
+        >()
+        .unwrap()
+    }
+
+    fn pcd_base_step_with_cycle<G1, G2, C1, C2>() -> Result<(), cyclefold::Error>
+    where
+        G1: SWCurveConfig,
+        G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+        G1::BaseField: PrimeField + Absorb,
+        G2::BaseField: PrimeField + Absorb,
+        C1: PolyCommitmentScheme<Projective<G1>>,
+        C2: CommitmentScheme<Projective<G2>, SetupAux = ()>,
+    {
+        let ro_config = poseidon_config();
+
+        let circuit = CubicCircuit::<G1::ScalarField>::default();
+
+        let params = PublicParams::<
+            G1,
+            G2,
+            C1,
+            C2,
+            PoseidonSponge<G1::ScalarField>,
+            CubicCircuit<G1::ScalarField>,
+        >::test_setup(ro_config, &circuit)?;
+
+        let recursive_snark = PCDNode::prove_leaf(&params, &circuit, 0, &z(1))?;
+        recursive_snark.verify(&params)?;
+
+        assert_eq!(&recursive_snark.z_j, &z(7));
+
+        Ok(())
+    }
+
+    #[test]
+    fn pcd_multiple_steps() {
+        pcd_multiple_steps_with_cycle::<
+            ark_bn254::g1::Config,
+            ark_grumpkin::GrumpkinConfig,
+            Zeromorph<ark_bn254::Bn254>,
+            PedersenCommitment<ark_grumpkin::Projective>,
+        >()
+        .unwrap()
+    }
+
+    fn pcd_multiple_steps_with_cycle<G1, G2, C1, C2>() -> Result<(), cyclefold::Error>
+    where
+        G1: SWCurveConfig,
+        G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+        G1::BaseField: PrimeField + Absorb,
+        G2::BaseField: PrimeField + Absorb,
+        C1: PolyCommitmentScheme<Projective<G1>>,
+        C2: CommitmentScheme<Projective<G2>, SetupAux = ()>,
+    {
+        let filter = filter::Targets::new().with_target(HYPERNOVA_TARGET, tracing::Level::DEBUG);
+        let _guard = tracing_subscriber::registry()
+            .with(
+                tracing_subscriber::fmt::layer().with_span_events(FmtSpan::ENTER | FmtSpan::CLOSE),
+            )
+            .with(filter)
+            .set_default();
+
+        let ro_config = poseidon_config();
+
+        let circuit = CubicCircuit::<G1::ScalarField>::default();
+
+        let params = PublicParams::<
+            G1,
+            G2,
+            C1,
+            C2,
+            PoseidonSponge<G1::ScalarField>,
+            CubicCircuit<G1::ScalarField>,
+        >::test_setup(ro_config, &circuit)?;
+
+        let node_0 = PCDNode::prove_leaf(&params, &circuit, 0, &z(1))?;
+        let node_1 = PCDNode::prove_leaf(&params, &circuit, 2, &z(355))?;
+
+        let root = PCDNode::prove_parent(&params, &circuit, &node_0, &node_1)?;
+
+        assert_eq!(&root.z_i, &z(1));
+        assert_eq!(&root.z_j, &z(44739235));
+
+        root.verify(&params)?;
+
+        Ok(())
+    }
+}
@@ -56,6 +56,15 @@ where
         SP::setup(ro_config, step_circuit, srs, aux)
     }
 
+    /// Recomputes the digest of the parameters, e.g. once they are deserialized, and checks
+    /// that it matches [`Self::digest`].
+    pub fn check_digest(&mut self) -> bool {
+        let digest = std::mem::replace(&mut self.digest, G1::ScalarField::ZERO);
+        let expected = self.hash();
+        self.digest = digest;
+        digest == expected
+    }
+
     /// Returns first [`SQUEEZE_ELEMENTS_BIT_SIZE`] bits of public parameters sha3 hash reinterpreted
     /// as scalar field element in little-endian order.
     pub(super) fn hash(&self) -> G1::ScalarField {
@@ -88,18 +97,21 @@ where
         srs: &C1::SRS,
         aux: &C2::SetupAux,
     ) -> Result<PublicParams<G1, G2, C1, C2, RO, SC, Self>, Error>;
+
+    /// Returns the number of sumcheck rounds and an upper bound on the number of
+    /// constraints of the augmented circuit, see [`HyperNovaConstraintSynthesizer`].
+    ///
+    /// [`HyperNovaConstraintSynthesizer`]: super::HyperNovaConstraintSynthesizer
+    fn project_augmented_circuit_size_upper_bound(
+        ro_config: &RO::Config,
+        step_circuit: &SC,
+    ) -> Result<(usize, usize), Error>;
 }
 
 pub mod test_pp {
     use super::*;
 
-    use crate::{
-        circuits::hypernova::{
-            sequential::augmented::HyperNovaAugmentedCircuit, HyperNovaConstraintSynthesizer,
-        },
-        folding::hypernova::cyclefold,
-        safe_loglike,
-    };
+    use crate::{folding::hypernova::cyclefold, safe_loglike};
     use ark_crypto_primitives::sponge::constraints::{CryptographicSpongeVar, SpongeWithGadget};
     use ark_crypto_primitives::sponge::Absorb;
     use ark_std::test_rng;
@@ -120,14 +132,7 @@ pub mod test_pp {
     {
         pub fn test_setup(ro_config: RO::Config, step_circuit: &SC) -> Result<Self, Error> {
             let (_, projected_augmented_circuit_size_upper_bound) =
-                HyperNovaAugmentedCircuit::<
-                    G1,
-                    G2,
-                    C1,
-                    C2,
-                    RO,
-                    SC,
-                >::project_augmented_circuit_size_upper_bound(step_circuit)?;
+                SP::project_augmented_circuit_size_upper_bound(&ro_config, step_circuit)?;
 
             let mut rng = test_rng();
             let max_poly_vars: usize =
@@ -31,7 +31,7 @@ use crate::{
     folding::hypernova::cyclefold::{
         self,
         nimfs::{
-            CCSInstance, CCSShape, HNProof, LCCSInstance, NIMFSProof, R1CSShape,
+            CCSInstance, CCSShape, HNProof, LCCSInstance, NIFSProof, NIMFSProof, R1CSShape,
             RelaxedR1CSInstance,
         },
         secondary::Circuit as SecondaryCircuit,
@@ -347,6 +347,7 @@ where
                     thetas: vec![G1::ScalarField::ZERO; NUM_MATRICES],
                     _random_oracle: PhantomData,
                 },
+                proof_secondary: NIFSProof::default(),
                 _poly_commitment: PhantomData,
             },
         )
@@ -492,6 +493,7 @@ where
     }
 
     fn project_augmented_circuit_size_upper_bound(
+        _ro_config: &'_ RO::Config,
         step_circuit: &'_ SC,
     ) -> Result<(usize, usize), SynthesisError> {
         // todo: make more robust
@@ -789,7 +791,9 @@ mod tests {
             C2,
             PoseidonSponge<G1::ScalarField>,
             SC1,
-        >::project_augmented_circuit_size_upper_bound(&TestCircuit1)
+        >::project_augmented_circuit_size_upper_bound(
+            &ro_config, &TestCircuit1
+        )
         .unwrap()
         .0;
 
@@ -61,6 +61,7 @@ where
 
         let (sumcheck_rounds, projected_augmented_circuit_size_upper_bound) =
             HyperNovaAugmentedCircuit::<G1, G2, C1, C2, RO, SC>::project_augmented_circuit_size_upper_bound(
+                &ro_config,
                 step_circuit,
             )?;
 
@@ -129,6 +130,18 @@ where
         );
         Ok(params)
     }
+
+    fn project_augmented_circuit_size_upper_bound(
+        ro_config: &RO::Config,
+        step_circuit: &SC,
+    ) -> Result<(usize, usize), cyclefold::Error> {
+        Ok(
+            HyperNovaAugmentedCircuit::<G1, G2, C1, C2, RO, SC>::project_augmented_circuit_size_upper_bound(
+                ro_config,
+                step_circuit,
+            )?,
+        )
+    }
 }
 
 pub type PublicParams<G1, G2, C1, C2, RO, SC> =
@@ -235,6 +248,10 @@ where
         }
     }
 
+    pub fn z_0(&self) -> &[G1::ScalarField] {
+        &self.z_0
+    }
+
     pub fn z_i(&self) -> &[G1::ScalarField] {
         self.non_base
             .as_ref()
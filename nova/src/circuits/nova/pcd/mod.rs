@@ -212,18 +212,25 @@ where
         i: usize,
         z_i: &[G1::ScalarField],
     ) -> Result<Self, cyclefold::Error> {
-        Self::prove_leaf_with_commit_fn(params, step_circuit, i, z_i, |pp, w| w.commit::<C1>(pp))
+        Self::prove_leaf_with_commit_fn(
+            params,
+            step_circuit,
+            i,
+            z_i,
+            |pp, w| Ok(w.commit::<C1>(pp)),
+        )
     }
 
     /// Proves step of step circuit execution and calls `commit_fn(pp, w)` to
-    /// compute commitment to the witness of the augmented circuit.
-    pub fn prove_leaf_with_commit_fn(
+    /// compute commitment to the witness of the augmented circuit. An error
+    /// returned by `commit_fn` is returned as is.
+    pub fn prove_leaf_with_commit_fn<E: From<cyclefold::Error>>(
         params: &PublicParams<G1, G2, C1, C2, RO, SC>,
         step_circuit: &SC,
         i: usize,
         z_i: &[G1::ScalarField],
-        mut commit_fn: impl FnMut(&C1::PP, &R1CSWitness<G1>) -> C1::Commitment,
-    ) -> Result<Self, cyclefold::Error> {
+        mut commit_fn: impl FnMut(&C1::PP, &R1CSWitness<G1>) -> Result<C1::Commitment, E>,
+    ) -> Result<Self, E> {
         let _span = tracing::debug_span!(
             target: LOG_TARGET,
             "prove_leaf",
@@ -250,7 +257,8 @@ where
 
         let circuit = NovaAugmentedCircuit::new(&params.ro_config, step_circuit, input);
         let z_next = tracing::debug_span!(target: LOG_TARGET, "satisfying_assignment")
-            .in_scope(|| NovaConstraintSynthesizer::generate_constraints(circuit, cs.clone()))?;
+            .in_scope(|| NovaConstraintSynthesizer::generate_constraints(circuit, cs.clone()))
+            .map_err(cyclefold::Error::from)?;
 
         let cs_borrow = cs.borrow().unwrap();
         let witness = cs_borrow.witness_assignment.clone();
@@ -258,12 +266,13 @@ where
 
         let w = R1CSWitness::<G1> { W: witness };
 
-        let commitment_W = commit_fn(&params.pp, &w);
+        let commitment_W = commit_fn(&params.pp, &w)?;
         let u = R1CSInstance::<G1, C1> { commitment_W, X: pub_io };
         let z_j = z_next
             .iter()
             .map(R1CSVar::value)
-            .collect::<Result<_, _>>()?;
+            .collect::<Result<_, _>>()
+            .map_err(cyclefold::Error::from)?;
 
         Ok(Self {
             i,
@@ -288,17 +297,19 @@ where
         right_node: &Self,
     ) -> Result<Self, cyclefold::Error> {
         Self::prove_parent_with_commit_fn(params, step_circuit, left_node, right_node, |pp, w| {
-            w.commit::<C1>(pp)
+            Ok(w.commit::<C1>(pp))
         })
     }
 
-    pub fn prove_parent_with_commit_fn(
+    /// Folds two nodes into their parent, calling `commit_fn(pp, w)` as
+    /// [`PCDNode::prove_leaf_with_commit_fn`] does.
+    pub fn prove_parent_with_commit_fn<E: From<cyclefold::Error>>(
         params: &PublicParams<G1, G2, C1, C2, RO, SC>,
         step_circuit: &SC,
         left_node: &Self,
         right_node: &Self,
-        mut commit_fn: impl FnMut(&C1::PP, &R1CSWitness<G1>) -> C1::Commitment,
-    ) -> Result<Self, cyclefold::Error> {
+        mut commit_fn: impl FnMut(&C1::PP, &R1CSWitness<G1>) -> Result<C1::Commitment, E>,
+    ) -> Result<Self, E> {
         let _span = tracing::debug_span!(
             target: LOG_TARGET,
             "prove_parent",
@@ -380,7 +391,8 @@ where
             NovaAugmentedCircuitInput::NonBase(input),
         );
         let _ = tracing::debug_span!(target: LOG_TARGET, "satisfying_assignment")
-            .in_scope(|| NovaConstraintSynthesizer::generate_constraints(circuit, cs.clone()))?;
+            .in_scope(|| NovaConstraintSynthesizer::generate_constraints(circuit, cs.clone()))
+            .map_err(cyclefold::Error::from)?;
 
         let cs_borrow = cs.borrow().unwrap();
         let witness = cs_borrow.witness_assignment.clone();
@@ -388,7 +400,7 @@ where
 
         let w = R1CSWitness::<G1> { W: witness };
 
-        let commitment_W = commit_fn(&params.pp, &w);
+        let commitment_W = commit_fn(&params.pp, &w)?;
         let u = R1CSInstance::<G1, C1> { commitment_W, X: pub_io };
 
         Ok(Self {
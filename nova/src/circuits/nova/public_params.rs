@@ -55,6 +55,15 @@ where
         SP::setup(ro_config, step_circuit, aux1, aux2)
     }
 
+    /// Recomputes the digest of the parameters, e.g. once they are deserialized, and checks
+    /// that it matches [`Self::digest`].
+    pub fn check_digest(&mut self) -> bool {
+        let digest = std::mem::replace(&mut self.digest, G1::ScalarField::ZERO);
+        let expected = self.hash();
+        self.digest = digest;
+        digest == expected
+    }
+
     /// Returns first [`SQUEEZE_ELEMENTS_BIT_SIZE`] bits of public parameters sha3 hash reinterpreted
     /// as scalar field element in little-endian order.
     pub(super) fn hash(&self) -> G1::ScalarField {
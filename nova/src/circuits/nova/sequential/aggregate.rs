@@ -0,0 +1,371 @@
+//! Aggregation of independent IVC proofs.
+//!
+//! The running instances of the proofs, together with their last step, are folded into a single
+//! relaxed R1CS instance on each curve. The aggregate proof keeps the public instances of every
+//! proof and the folding proofs, but only the folded witnesses: it is verified by checking the
+//! public input of every proof, recomputing the folded instances and checking that they are
+//! satisfied, which costs a single satisfiability check on each curve regardless of the number
+//! of proofs.
+
+use std::marker::PhantomData;
+
+use ark_crypto_primitives::sponge::{
+    constraints::{CryptographicSpongeVar, SpongeWithGadget},
+    Absorb, CryptographicSponge,
+};
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::{
+    commitment::CommitmentScheme,
+    folding::nova::cyclefold::{
+        self,
+        nimfs::{NIFSProof, R1CSInstance, RelaxedR1CSInstance, RelaxedR1CSWitness},
+    },
+};
+
+use super::{IVCProof, IVCProofNonBase, PublicParams, StepCircuit, LOG_TARGET};
+
+const NOT_SATISFIED_ERROR: cyclefold::Error =
+    cyclefold::Error::R1CS(crate::r1cs::Error::NotSatisfied);
+
+/// Public part of an aggregated proof.
+#[derive(CanonicalDeserialize, CanonicalSerialize)]
+struct AggregatedInstance<G1, G2, C1, C2>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+{
+    z_0: Vec<G1::ScalarField>,
+    z_i: Vec<G1::ScalarField>,
+    i: u64,
+
+    U: RelaxedR1CSInstance<G1, C1>,
+    U_secondary: RelaxedR1CSInstance<G2, C2>,
+    u: R1CSInstance<G1, C1>,
+}
+
+/// A proof attesting to a sequence of IVC proofs generated with the same public parameters.
+#[derive(CanonicalDeserialize, CanonicalSerialize)]
+pub struct AggregateProof<G1, G2, C1, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: CryptographicSponge + Send + Sync,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    instances: Vec<AggregatedInstance<G1, G2, C1, C2>>,
+
+    proofs: Vec<NIFSProof<Projective<G1>, C1, RO>>,
+    proofs_secondary: Vec<NIFSProof<Projective<G2>, C2, RO>>,
+
+    W: RelaxedR1CSWitness<G1>,
+    W_secondary: RelaxedR1CSWitness<G2>,
+
+    _step_circuit: PhantomData<SC>,
+}
+
+impl<G1, G2, C1, C2, RO, SC> AggregateProof<G1, G2, C1, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    /// Aggregate `proofs`, which may prove the execution of the step circuit from different
+    /// initial states.
+    ///
+    /// The proofs are not verified: if any of them is invalid, so is the aggregate proof.
+    pub fn aggregate(
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+        proofs: &[IVCProof<G1, G2, C1, C2, RO, SC>],
+    ) -> Result<Self, cyclefold::Error> {
+        let _span = tracing::debug_span!(
+            target: LOG_TARGET,
+            "aggregate",
+            num_proofs = proofs.len(),
+        )
+        .entered();
+
+        let non_base = proofs
+            .iter()
+            .map(|proof| proof.non_base.as_ref().ok_or(NOT_SATISFIED_ERROR))
+            .collect::<Result<Vec<_>, _>>()?;
+        if non_base.is_empty() {
+            return Err(NOT_SATISFIED_ERROR);
+        }
+
+        let mut random_oracle = RO::new(&params.ro_config);
+        random_oracle.absorb(&params.digest);
+        for IVCProofNonBase { u, .. } in &non_base {
+            random_oracle.absorb(u);
+        }
+
+        let mut running = non_base.iter().flat_map(|non_base| {
+            [
+                (non_base.U.clone(), non_base.W.clone()),
+                (
+                    RelaxedR1CSInstance::from(&non_base.u),
+                    RelaxedR1CSWitness::from_r1cs_witness(&params.shape, &non_base.w),
+                ),
+            ]
+        });
+        let (mut U, mut W) = running.next().expect("at least one proof");
+        let mut folding_proofs = Vec::with_capacity(2 * non_base.len() - 1);
+        for (U2, W2) in running {
+            let (proof, (U_next, W_next)) = NIFSProof::prove_with_relaxed(
+                &params.pp,
+                &mut random_oracle,
+                &params.shape,
+                (&U, &W),
+                (&U2, &W2),
+            )?;
+            folding_proofs.push(proof);
+            (U, W) = (U_next, W_next);
+        }
+
+        let (mut U_secondary, mut W_secondary) = (
+            non_base[0].U_secondary.clone(),
+            non_base[0].W_secondary.clone(),
+        );
+        let mut proofs_secondary = Vec::with_capacity(non_base.len() - 1);
+        for non_base in &non_base[1..] {
+            let (proof, (U_next, W_next)) = NIFSProof::prove_with_relaxed(
+                &params.pp_secondary,
+                &mut random_oracle,
+                &params.shape_secondary,
+                (&U_secondary, &W_secondary),
+                (&non_base.U_secondary, &non_base.W_secondary),
+            )?;
+            proofs_secondary.push(proof);
+            (U_secondary, W_secondary) = (U_next, W_next);
+        }
+
+        let instances = proofs
+            .iter()
+            .zip(non_base)
+            .map(|(proof, non_base)| AggregatedInstance {
+                z_0: proof.z_0.clone(),
+                z_i: non_base.z_i.clone(),
+                i: non_base.i,
+                U: non_base.U.clone(),
+                U_secondary: non_base.U_secondary.clone(),
+                u: non_base.u.clone(),
+            })
+            .collect();
+
+        Ok(Self {
+            instances,
+            proofs: folding_proofs,
+            proofs_secondary,
+            W,
+            W_secondary,
+            _step_circuit: PhantomData,
+        })
+    }
+
+    /// Number of aggregated proofs.
+    pub fn num_proofs(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Initial state, final state and number of steps of each aggregated proof, in order.
+    pub fn statements(
+        &self,
+    ) -> impl Iterator<Item = (&[G1::ScalarField], &[G1::ScalarField], u64)> + '_ {
+        self.instances
+            .iter()
+            .map(|instance| (&instance.z_0[..], &instance.z_i[..], instance.i))
+    }
+
+    pub fn verify(
+        &self,
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+    ) -> Result<(), cyclefold::Error> {
+        let num_proofs = self.num_proofs();
+        let _span =
+            tracing::debug_span!(target: LOG_TARGET, "verify_aggregate", %num_proofs).entered();
+
+        if num_proofs == 0
+            || self.proofs.len() != 2 * num_proofs - 1
+            || self.proofs_secondary.len() != num_proofs - 1
+        {
+            return Err(NOT_SATISFIED_ERROR);
+        }
+
+        let mut random_oracle = RO::new(&params.ro_config);
+        random_oracle.absorb(&params.digest);
+
+        for AggregatedInstance { z_0, z_i, i, U, U_secondary, u } in &self.instances {
+            let hash = IVCProof::<G1, G2, C1, C2, RO, SC>::hash_public_io(
+                params,
+                *i,
+                z_0,
+                z_i,
+                U,
+                U_secondary,
+            );
+            if *i == 0 || u.X.len() < 2 || hash != u.X[1] {
+                return Err(NOT_SATISFIED_ERROR);
+            }
+            random_oracle.absorb(u);
+        }
+
+        let mut running = self
+            .instances
+            .iter()
+            .flat_map(|instance| [instance.U.clone(), RelaxedR1CSInstance::from(&instance.u)]);
+        let mut U = running.next().expect("at least one proof");
+        for (proof, U2) in self.proofs.iter().zip(running) {
+            U = proof.verify_with_relaxed(&mut random_oracle, &U, &U2)?;
+        }
+
+        let mut U_secondary = self.instances[0].U_secondary.clone();
+        for (proof, instance) in self.proofs_secondary.iter().zip(&self.instances[1..]) {
+            U_secondary = proof.verify_with_relaxed(
+                &mut random_oracle,
+                &U_secondary,
+                &instance.U_secondary,
+            )?;
+        }
+
+        params.shape.is_relaxed_satisfied(&U, &self.W, &params.pp)?;
+        params.shape_secondary.is_relaxed_satisfied(
+            &U_secondary,
+            &self.W_secondary,
+            &params.pp_secondary,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl<G1, G2, C1, C2, RO, SC> IVCProof<G1, G2, C1, C2, RO, SC>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    C1: CommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: SpongeWithGadget<G1::ScalarField> + Send + Sync,
+    RO::Var: CryptographicSpongeVar<G1::ScalarField, RO, Parameters = RO::Config>,
+    RO::Config: CanonicalSerialize + CanonicalDeserialize + Sync,
+    SC: StepCircuit<G1::ScalarField>,
+{
+    /// Verify `proofs` together, by aggregating them.
+    ///
+    /// This is cheaper than verifying each proof, since the witnesses of the proofs are only
+    /// committed to once they are folded.
+    pub fn verify_many(
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+        proofs: &[Self],
+    ) -> Result<(), cyclefold::Error> {
+        AggregateProof::aggregate(params, proofs)?.verify(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pedersen::PedersenCommitment, poseidon_config};
+
+    use super::super::tests::CubicCircuit;
+    use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
+    use ark_ff::Field;
+
+    #[test]
+    fn aggregate_verify() {
+        aggregate_verify_with_cycle::<
+            ark_pallas::PallasConfig,
+            ark_vesta::VestaConfig,
+            PedersenCommitment<ark_pallas::Projective>,
+            PedersenCommitment<ark_vesta::Projective>,
+        >()
+        .unwrap()
+    }
+
+    fn aggregate_verify_with_cycle<G1, G2, C1, C2>() -> Result<(), cyclefold::Error>
+    where
+        G1: SWCurveConfig,
+        G2: SWCurveConfig<BaseField = G1::ScalarField, ScalarField = G1::BaseField>,
+        G1::BaseField: PrimeField + Absorb,
+        G2::BaseField: PrimeField + Absorb,
+        C1: CommitmentScheme<Projective<G1>, SetupAux = ()>,
+        C2: CommitmentScheme<Projective<G2>, SetupAux = ()>,
+    {
+        let ro_config = poseidon_config();
+
+        let circuit = CubicCircuit::<G1::ScalarField>(PhantomData);
+
+        let params = PublicParams::<
+            G1,
+            G2,
+            C1,
+            C2,
+            PoseidonSponge<G1::ScalarField>,
+            CubicCircuit<G1::ScalarField>,
+        >::setup(ro_config, &circuit, &(), &())?;
+
+        let mut proofs = Vec::new();
+        for (z_0, num_steps) in [(1u64, 1), (2, 3), (3, 2)] {
+            let mut proof = IVCProof::new(&[G1::ScalarField::from(z_0)]);
+            for _ in 0..num_steps {
+                proof = proof.prove_step(&params, &circuit)?;
+            }
+            proofs.push(proof);
+        }
+
+        let aggregate = AggregateProof::aggregate(&params, &proofs)?;
+        aggregate.verify(&params)?;
+        IVCProof::verify_many(&params, &proofs)?;
+
+        assert_eq!(aggregate.num_proofs(), 3);
+        for ((z_0, z_i, i), proof) in aggregate.statements().zip(&proofs) {
+            assert_eq!(z_0, &proof.z_0[..]);
+            assert_eq!(z_i, proof.z_i());
+            assert_eq!(i, proof.step_num());
+        }
+
+        let mut bytes = Vec::new();
+        aggregate.serialize_compressed(&mut bytes).unwrap();
+        let aggregate = AggregateProof::<
+            G1,
+            G2,
+            C1,
+            C2,
+            PoseidonSponge<G1::ScalarField>,
+            CubicCircuit<G1::ScalarField>,
+        >::deserialize_compressed(&bytes[..])
+        .unwrap();
+        aggregate.verify(&params)?;
+
+        // the claimed final state of a proof must match its public input
+        let mut tampered = AggregateProof::aggregate(&params, &proofs)?;
+        tampered.instances[1].z_i[0] += G1::ScalarField::ONE;
+        assert!(tampered.verify(&params).is_err());
+
+        // a proof cannot be dropped from the aggregate
+        let mut truncated = AggregateProof::aggregate(&params, &proofs)?;
+        truncated.instances.pop();
+        assert!(truncated.verify(&params).is_err());
+
+        // an invalid proof makes the aggregate invalid
+        proofs[2].non_base.as_mut().unwrap().w.W[0] += G1::ScalarField::ONE;
+        assert!(IVCProof::verify_many(&params, &proofs).is_err());
+
+        Ok(())
+    }
+}
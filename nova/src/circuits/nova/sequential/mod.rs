@@ -24,7 +24,9 @@ use crate::{
 
 use super::{public_params, NovaConstraintSynthesizer, StepCircuit};
 
+mod aggregate;
 mod augmented;
+pub use aggregate::AggregateProof;
 use augmented::{
     NovaAugmentedCircuit, NovaAugmentedCircuitInput, NovaAugmentedCircuitNonBaseInput,
 };
@@ -214,6 +216,10 @@ where
         }
     }
 
+    pub fn z_0(&self) -> &[G1::ScalarField] {
+        &self.z_0
+    }
+
     pub fn z_i(&self) -> &[G1::ScalarField] {
         self.non_base
             .as_ref()
@@ -370,19 +376,8 @@ where
             return Err(NOT_SATISFIED_ERROR);
         }
 
-        let mut random_oracle = RO::new(&params.ro_config);
-
-        random_oracle.absorb(&params.digest);
-        random_oracle.absorb(&G1::ScalarField::from(*i));
-        random_oracle.absorb(&self.z_0);
-        random_oracle.absorb(&z_i);
-        random_oracle.absorb(U);
-        random_oracle.absorb_non_native(U_secondary);
-
-        let hash: &G1::ScalarField =
-            &random_oracle.squeeze_field_elements(augmented::SQUEEZE_NATIVE_ELEMENTS_NUM)[0];
-
-        if hash != &u.X[1] {
+        let hash = Self::hash_public_io(params, *i, &self.z_0, z_i, U, U_secondary);
+        if hash != u.X[1] {
             return Err(NOT_SATISFIED_ERROR);
         }
 
@@ -396,6 +391,28 @@ where
 
         Ok(())
     }
+
+    // Hash of the running instances after `i` steps, which the augmented circuit
+    // outputs as its public input.
+    fn hash_public_io(
+        params: &PublicParams<G1, G2, C1, C2, RO, SC>,
+        i: u64,
+        z_0: &[G1::ScalarField],
+        z_i: &[G1::ScalarField],
+        U: &RelaxedR1CSInstance<G1, C1>,
+        U_secondary: &RelaxedR1CSInstance<G2, C2>,
+    ) -> G1::ScalarField {
+        let mut random_oracle = RO::new(&params.ro_config);
+
+        random_oracle.absorb(&params.digest);
+        random_oracle.absorb(&G1::ScalarField::from(i));
+        random_oracle.absorb(&z_0);
+        random_oracle.absorb(&z_i);
+        random_oracle.absorb(U);
+        random_oracle.absorb_non_native(U_secondary);
+
+        random_oracle.squeeze_field_elements(augmented::SQUEEZE_NATIVE_ELEMENTS_NUM)[0]
+    }
 }
 
 #[cfg(test)]
@@ -480,6 +497,24 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[test]
+    fn check_digest() {
+        let circuit = CubicCircuit::<ark_pallas::Fr>(PhantomData);
+        let mut params = PublicParams::<
+            ark_pallas::PallasConfig,
+            ark_vesta::VestaConfig,
+            PedersenCommitment<ark_pallas::Projective>,
+            PedersenCommitment<ark_vesta::Projective>,
+            PoseidonSponge<ark_pallas::Fr>,
+            CubicCircuit<ark_pallas::Fr>,
+        >::setup(poseidon_config(), &circuit, &(), &())
+        .unwrap();
+        assert!(params.check_digest());
+
+        params.digest += ark_pallas::Fr::ONE;
+        assert!(!params.check_digest());
+    }
+
     #[test]
     fn ivc_multiple_steps() {
         ivc_multiple_steps_with_cycle::<
@@ -269,6 +269,10 @@ where
         }
     }
 
+    pub fn z_0(&self) -> &[G1::ScalarField] {
+        &self.z_0
+    }
+
     pub fn z_i(&self) -> &[G1::ScalarField] {
         self.non_base
             .as_ref()
@@ -0,0 +1,359 @@
+use ark_crypto_primitives::sponge::{Absorb, CryptographicSponge};
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+use ark_ff::PrimeField;
+use ark_std::marker::PhantomData;
+
+use ark_spartan::polycommitments::{PolyCommitmentScheme, PolyCommitmentTrait};
+
+use super::{
+    secondary, CCSShape, CCSWitness, Error, HNProof, LCCSInstance, NIFSProof, NIMFSProof,
+    R1CSShape, RelaxedR1CSInstance, RelaxedR1CSWitness, SQUEEZE_ELEMENTS_BIT_SIZE,
+};
+use crate::{
+    absorb::CryptographicSpongeExt,
+    commitment::{Commitment, CommitmentScheme},
+    r1cs,
+    utils::cast_field_element_unique,
+};
+
+impl<G1, G2, C1, C2, RO> NIMFSProof<G1, G2, C1, C2, RO>
+where
+    G1: SWCurveConfig<BaseField = G2::ScalarField, ScalarField = G2::BaseField>,
+    G2: SWCurveConfig,
+    C1: PolyCommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    G1::BaseField: PrimeField + Absorb,
+    G2::BaseField: PrimeField + Absorb,
+    RO: CryptographicSponge,
+{
+    /// Folds two linearized instances, together with their secondary running instances.
+    pub fn prove_with_linearized(
+        pp_secondary: &C2::PP,
+        config: &RO::Config,
+        vk: &G1::ScalarField,
+        (shape, shape_secondary): (&CCSShape<G1>, &R1CSShape<G2>),
+        (U1, W1): (&LCCSInstance<G1, C1>, &CCSWitness<G1>),
+        (U1_secondary, W1_secondary): (&RelaxedR1CSInstance<G2, C2>, &RelaxedR1CSWitness<G2>),
+        (U2, W2): (&LCCSInstance<G1, C1>, &CCSWitness<G1>),
+        (U2_secondary, W2_secondary): (&RelaxedR1CSInstance<G2, C2>, &RelaxedR1CSWitness<G2>),
+    ) -> Result<
+        (
+            Self,
+            (LCCSInstance<G1, C1>, CCSWitness<G1>),
+            (RelaxedR1CSInstance<G2, C2>, RelaxedR1CSWitness<G2>),
+        ),
+        Error,
+    > {
+        let mut random_oracle = RO::new(config);
+
+        random_oracle.absorb_non_native(&U1_secondary);
+
+        let (hypernova_proof, (folded_U, folded_W), rho) =
+            HNProof::prove_as_subprotocol_with_linearized(
+                &mut random_oracle,
+                vk,
+                shape,
+                (U1, W1),
+                (U2, W2),
+            )?;
+
+        // See `NIMFSProof::prove` for the restriction on the poly commitment scheme.
+        let W_comm_trace = secondary::synthesize::<G1, G2, C2>(
+            secondary::Circuit {
+                g1: U1
+                    .commitment_W
+                    .clone()
+                    .try_into_affine_point()
+                    .unwrap()
+                    .into(),
+                g2: U2
+                    .commitment_W
+                    .clone()
+                    .try_into_affine_point()
+                    .unwrap()
+                    .into(),
+                g_out: folded_U
+                    .commitment_W
+                    .clone()
+                    .try_into_affine_point()
+                    .unwrap()
+                    .into(),
+                r: rho,
+            },
+            pp_secondary,
+        )?;
+        debug_assert!(shape_secondary
+            .is_satisfied(&W_comm_trace.0, &W_comm_trace.1, pp_secondary)
+            .is_ok());
+
+        let (T, commitment_T) = r1cs::commit_T(
+            shape_secondary,
+            pp_secondary,
+            U1_secondary,
+            W1_secondary,
+            &W_comm_trace.0,
+            &W_comm_trace.1,
+        )?;
+        random_oracle.absorb_non_native(&W_comm_trace.0);
+        random_oracle.absorb(&commitment_T.into_affine());
+        random_oracle.absorb(&cast_field_element_unique::<G1::BaseField, G1::ScalarField>(&rho));
+
+        let rho_p: G1::BaseField =
+            random_oracle.squeeze_field_elements_with_sizes(&[SQUEEZE_ELEMENTS_BIT_SIZE])[0];
+
+        let U_secondary = U1_secondary.fold(&W_comm_trace.0, &commitment_T, &rho_p)?;
+        let W_secondary = W1_secondary.fold(&W_comm_trace.1, &T, &rho_p)?;
+
+        let commitment_W_proof = secondary::Proof { commitment_T, U: W_comm_trace.0 };
+
+        random_oracle.absorb(&cast_field_element_unique::<G1::BaseField, G1::ScalarField>(&rho_p));
+        let (proof_secondary, (U_secondary, W_secondary)) = NIFSProof::prove_with_relaxed(
+            pp_secondary,
+            &mut random_oracle,
+            shape_secondary,
+            (&U_secondary, &W_secondary),
+            (U2_secondary, W2_secondary),
+        )?;
+
+        let proof = Self {
+            commitment_W_proof,
+            hypernova_proof,
+            proof_secondary,
+            _poly_commitment: PhantomData,
+        };
+
+        Ok((proof, (folded_U, folded_W), (U_secondary, W_secondary)))
+    }
+
+    #[cfg(test)]
+    pub fn verify_with_linearized(
+        &self,
+        config: &RO::Config,
+        vk: &G1::ScalarField,
+        shape: &CCSShape<G1>,
+        U1: &LCCSInstance<G1, C1>,
+        U1_secondary: &RelaxedR1CSInstance<G2, C2>,
+        U2: &LCCSInstance<G1, C1>,
+        U2_secondary: &RelaxedR1CSInstance<G2, C2>,
+    ) -> Result<(LCCSInstance<G1, C1>, RelaxedR1CSInstance<G2, C2>), Error> {
+        let mut random_oracle = RO::new(config);
+
+        random_oracle.absorb_non_native(&U1_secondary);
+
+        let (folded_U, rho) = self.hypernova_proof.verify_as_subprotocol_with_linearized(
+            &mut random_oracle,
+            vk,
+            shape,
+            U1,
+            U2,
+        )?;
+
+        let secondary::Proof { U: comm_W_proof, commitment_T } = &self.commitment_W_proof;
+        let pub_io = comm_W_proof
+            .parse_secondary_io::<G1>()
+            .ok_or(Error::InvalidPublicInput)?;
+
+        if pub_io.r != rho
+            || pub_io.g1
+                != Into::<Projective<G1>>::into(
+                    U1.commitment_W.clone().try_into_affine_point().unwrap(),
+                )
+            || pub_io.g2
+                != Into::<Projective<G1>>::into(
+                    U2.commitment_W.clone().try_into_affine_point().unwrap(),
+                )
+        {
+            return Err(Error::InvalidPublicInput);
+        }
+
+        random_oracle.absorb_non_native(&comm_W_proof);
+        random_oracle.absorb(&commitment_T.into_affine());
+        random_oracle.absorb(&cast_field_element_unique::<G1::BaseField, G1::ScalarField>(&rho));
+
+        let rho_p =
+            random_oracle.squeeze_field_elements_with_sizes(&[SQUEEZE_ELEMENTS_BIT_SIZE])[0];
+
+        let U_secondary = U1_secondary.fold(comm_W_proof, commitment_T, &rho_p)?;
+
+        random_oracle.absorb(&cast_field_element_unique::<G1::BaseField, G1::ScalarField>(&rho_p));
+        let U_secondary = self.proof_secondary.verify_with_relaxed(
+            &mut random_oracle,
+            &U_secondary,
+            U2_secondary,
+        )?;
+
+        Ok((folded_U, U_secondary))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::CCSInstance;
+    use super::*;
+    use crate::{
+        ccs::mle::vec_to_mle, pedersen::PedersenCommitment, poseidon_config,
+        r1cs::tests::to_field_elements, safe_loglike, test_utils::setup_test_ccs,
+        zeromorph::Zeromorph,
+    };
+
+    use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
+    use ark_ff::Field;
+    use ark_std::{rand::Rng, test_rng, UniformRand};
+    use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+    #[test]
+    fn prove_verify() {
+        prove_verify_with_cycle::<
+            ark_bn254::g1::Config,
+            ark_grumpkin::GrumpkinConfig,
+            Zeromorph<ark_bn254::Bn254>,
+            PedersenCommitment<ark_grumpkin::Projective>,
+        >()
+        .unwrap();
+    }
+
+    fn prove_verify_with_cycle<G1, G2, C1, C2>() -> Result<(), Error>
+    where
+        G1: SWCurveConfig<BaseField = G2::ScalarField, ScalarField = G2::BaseField>,
+        G2: SWCurveConfig,
+        C1: PolyCommitmentScheme<Projective<G1>>,
+        C2: CommitmentScheme<Projective<G2>, SetupAux = ()>,
+        G1::BaseField: PrimeField + Absorb,
+        G2::BaseField: PrimeField + Absorb,
+        C1::PolyCommitmentKey: Clone,
+    {
+        let config = poseidon_config();
+
+        let vk = G1::ScalarField::ONE;
+
+        let mut rng = test_rng();
+        let (shape, u1, w1, ck) = setup_test_ccs::<G1, C1>(3, None, Some(&mut rng));
+        let (_, u2, w2, _) = setup_test_ccs::<G1, C1>(5, Some(&ck), Some(&mut rng));
+
+        let shape_secondary = secondary::setup_shape::<G1, G2>()?;
+        let pp_secondary = C2::setup(
+            shape_secondary.num_vars + shape_secondary.num_constraints,
+            b"test",
+            &(),
+        );
+
+        let ((U1, W1), (U1_secondary, W1_secondary)) = setup_non_trivial::<G1, G2, C1, C2>(
+            &mut rng,
+            &ck,
+            &pp_secondary,
+            (&shape, &shape_secondary),
+            (&u1, &w1),
+        )?;
+        let ((U2, W2), (U2_secondary, W2_secondary)) = setup_non_trivial::<G1, G2, C1, C2>(
+            &mut rng,
+            &ck,
+            &pp_secondary,
+            (&shape, &shape_secondary),
+            (&u2, &w2),
+        )?;
+
+        let (proof, (U, W), (U_secondary, W_secondary)) =
+            NIMFSProof::<_, _, _, _, PoseidonSponge<G1::ScalarField>>::prove_with_linearized(
+                &pp_secondary,
+                &config,
+                &vk,
+                (&shape, &shape_secondary),
+                (&U1, &W1),
+                (&U1_secondary, &W1_secondary),
+                (&U2, &W2),
+                (&U2_secondary, &W2_secondary),
+            )?;
+
+        shape.is_satisfied_linearized(&U, &W, &ck).unwrap();
+        shape_secondary
+            .is_relaxed_satisfied(&U_secondary, &W_secondary, &pp_secondary)
+            .unwrap();
+
+        let (_U, _U_secondary) = proof.verify_with_linearized(
+            &config,
+            &vk,
+            &shape,
+            &U1,
+            &U1_secondary,
+            &U2,
+            &U2_secondary,
+        )?;
+
+        assert_eq!(_U, U);
+        assert_eq!(_U_secondary, U_secondary);
+
+        // instances folded in the wrong order must not verify
+        assert!(proof
+            .verify_with_linearized(&config, &vk, &shape, &U2, &U2_secondary, &U1, &U1_secondary,)
+            .is_err());
+
+        Ok(())
+    }
+
+    /// Returns linearized instance-witness pair with a non-trivial secondary instance, by folding a fresh
+    /// instance into a random linearized instance of the zero witness.
+    #[allow(clippy::type_complexity)]
+    fn setup_non_trivial<G1, G2, C1, C2>(
+        rng: &mut impl Rng,
+        ck: &C1::PolyCommitmentKey,
+        pp_secondary: &C2::PP,
+        (shape, shape_secondary): (&CCSShape<G1>, &R1CSShape<G2>),
+        (u, w): (&CCSInstance<G1, C1>, &CCSWitness<G1>),
+    ) -> Result<
+        (
+            (LCCSInstance<G1, C1>, CCSWitness<G1>),
+            (RelaxedR1CSInstance<G2, C2>, RelaxedR1CSWitness<G2>),
+        ),
+        Error,
+    >
+    where
+        G1: SWCurveConfig<BaseField = G2::ScalarField, ScalarField = G2::BaseField>,
+        G2: SWCurveConfig,
+        C1: PolyCommitmentScheme<Projective<G1>>,
+        C2: CommitmentScheme<Projective<G2>>,
+        G1::BaseField: PrimeField + Absorb,
+        G2::BaseField: PrimeField + Absorb,
+    {
+        let config = poseidon_config();
+
+        let vk = G1::ScalarField::ONE;
+
+        let X = to_field_elements::<Projective<G1>>((vec![0; shape.num_io]).as_slice());
+        let W = CCSWitness::zero(shape);
+        let commitment_W = W.commit::<C1>(ck);
+
+        let s = safe_loglike!(shape.num_constraints);
+        let rs: Vec<G1::ScalarField> = (0..s).map(|_| G1::ScalarField::rand(rng)).collect();
+
+        let z = [X.as_slice(), W.W.as_slice()].concat();
+        let vs: Vec<G1::ScalarField> = ark_std::cfg_iter!(&shape.Ms)
+            .map(|M| {
+                vec_to_mle(M.multiply_vec(&z).as_slice()).evaluate::<Projective<G1>>(rs.as_slice())
+            })
+            .collect();
+
+        let U = LCCSInstance::<Projective<G1>, C1>::new(
+            shape,
+            &commitment_W,
+            &X,
+            rs.as_slice(),
+            vs.as_slice(),
+        )?;
+
+        let U_secondary = RelaxedR1CSInstance::<G2, C2>::new(shape_secondary);
+        let W_secondary = RelaxedR1CSWitness::<G2>::zero(shape_secondary);
+
+        let (_, (U, W), (U_secondary, W_secondary)) =
+            NIMFSProof::<_, _, _, _, PoseidonSponge<G1::ScalarField>>::prove(
+                pp_secondary,
+                &config,
+                &vk,
+                (shape, shape_secondary),
+                (&U, &W),
+                (&U_secondary, &W_secondary),
+                (u, w),
+            )?;
+
+        Ok(((U, W), (U_secondary, W_secondary)))
+    }
+}
@@ -1,20 +1,25 @@
 use ark_crypto_primitives::sponge::{Absorb, CryptographicSponge};
 use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
 use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
 use ark_std::marker::PhantomData;
 
 use ark_spartan::polycommitments::{PolyCommitmentScheme, PolyCommitmentTrait};
 
 use crate::commitment::{Commitment, CommitmentScheme};
 
+pub(crate) mod linearized;
+
 pub(crate) use crate::folding::hypernova::nimfs::NIMFSProof as HNProof;
 pub use crate::folding::hypernova::nimfs::SQUEEZE_ELEMENTS_BIT_SIZE;
+pub use crate::folding::nova::nifs::NIFSProof;
 
 pub(crate) use super::{secondary, CCSInstance, CCSShape, CCSWitness, Error, LCCSInstance};
 pub(crate) use crate::folding::cyclefold::{R1CSShape, RelaxedR1CSInstance, RelaxedR1CSWitness};
 use crate::{absorb::CryptographicSpongeExt, r1cs, utils::cast_field_element_unique};
 
 /// Non-interactive multi-folding scheme proof.
+#[derive(CanonicalSerialize)]
 pub struct NIMFSProof<
     G1: SWCurveConfig,
     G2: SWCurveConfig,
@@ -24,9 +29,56 @@ pub struct NIMFSProof<
 > {
     pub(crate) commitment_W_proof: secondary::Proof<G2, C2>,
     pub(crate) hypernova_proof: HNProof<Projective<G1>, RO>,
+    pub(crate) proof_secondary: NIFSProof<Projective<G2>, C2, RO>,
     pub(crate) _poly_commitment: PhantomData<C1::Commitment>,
 }
 
+impl<G1, G2, C1, C2, RO> Valid for NIMFSProof<G1, G2, C1, C2, RO>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: PolyCommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: Sync,
+{
+    fn check(&self) -> Result<(), ark_serialize::SerializationError> {
+        self.commitment_W_proof.check()?;
+        self.hypernova_proof.check()?;
+        self.proof_secondary.check()
+    }
+}
+
+impl<G1, G2, C1, C2, RO> CanonicalDeserialize for NIMFSProof<G1, G2, C1, C2, RO>
+where
+    G1: SWCurveConfig,
+    G2: SWCurveConfig,
+    C1: PolyCommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    RO: Sync,
+{
+    fn deserialize_with_mode<R: ark_serialize::Read>(
+        mut reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, ark_serialize::SerializationError> {
+        let commitment_W_proof =
+            secondary::Proof::<G2, C2>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let hypernova_proof =
+            HNProof::<Projective<G1>, RO>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let proof_secondary = NIFSProof::<Projective<G2>, C2, RO>::deserialize_with_mode(
+            &mut reader,
+            compress,
+            validate,
+        )?;
+        Ok(Self {
+            commitment_W_proof,
+            hypernova_proof,
+            proof_secondary,
+            _poly_commitment: PhantomData,
+        })
+    }
+}
+
 impl<G1, G2, C1, C2, RO> Clone for NIMFSProof<G1, G2, C1, C2, RO>
 where
     G1: SWCurveConfig,
@@ -38,6 +90,7 @@ where
         Self {
             commitment_W_proof: self.commitment_W_proof.clone(),
             hypernova_proof: self.hypernova_proof.clone(),
+            proof_secondary: self.proof_secondary.clone(),
             _poly_commitment: self._poly_commitment,
         }
     }
@@ -132,6 +185,7 @@ where
         let proof = Self {
             commitment_W_proof,
             hypernova_proof,
+            proof_secondary: NIFSProof::default(),
             _poly_commitment: PhantomData,
         };
 
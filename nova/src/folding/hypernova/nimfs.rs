@@ -6,6 +6,7 @@ use ark_crypto_primitives::sponge::{Absorb, CryptographicSponge, FieldElementSiz
 use ark_ec::CurveGroup;
 use ark_ff::{Field, PrimeField, ToConstraintField};
 use ark_poly::Polynomial;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError, Valid};
 use ark_spartan::{dense_mlpoly::EqPolynomial, polycommitments::PolyCommitmentScheme};
 
 use ark_std::{fmt::Display, rc::Rc};
@@ -63,6 +64,7 @@ impl ark_std::error::Error for Error {
     }
 }
 
+#[derive(CanonicalSerialize)]
 pub struct NIMFSProof<G: CurveGroup, RO> {
     pub(crate) sumcheck_proof: ml_sumcheck::Proof<G::ScalarField>,
     pub(crate) poly_info: ml_sumcheck::PolynomialInfo,
@@ -71,6 +73,40 @@ pub struct NIMFSProof<G: CurveGroup, RO> {
     pub(crate) _random_oracle: PhantomData<RO>,
 }
 
+impl<G: CurveGroup, RO: Sync> Valid for NIMFSProof<G, RO> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.sumcheck_proof.check()?;
+        self.poly_info.check()?;
+        self.sigmas.check()?;
+        self.thetas.check()
+    }
+}
+
+impl<G: CurveGroup, RO: Sync> CanonicalDeserialize for NIMFSProof<G, RO> {
+    fn deserialize_with_mode<R: ark_serialize::Read>(
+        mut reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, SerializationError> {
+        let sumcheck_proof = ml_sumcheck::Proof::<G::ScalarField>::deserialize_with_mode(
+            &mut reader,
+            compress,
+            validate,
+        )?;
+        let poly_info =
+            ml_sumcheck::PolynomialInfo::deserialize_with_mode(&mut reader, compress, validate)?;
+        let sigmas = Vec::<G::ScalarField>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let thetas = Vec::<G::ScalarField>::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(Self {
+            sumcheck_proof,
+            poly_info,
+            sigmas,
+            thetas,
+            _random_oracle: PhantomData,
+        })
+    }
+}
+
 impl<G: CurveGroup, RO> Clone for NIMFSProof<G, RO> {
     fn clone(&self) -> Self {
         Self {
@@ -250,6 +286,150 @@ where
 
         Ok((U, rho))
     }
+
+    /// Folds two linearized instances together, as required for combining the accumulators of sibling nodes in
+    /// proof-carrying data.
+    ///
+    /// No zero-check is needed, so the sumcheck runs over a degree 2 polynomial claiming the random linear combination
+    /// `sum_j gamma^j * v1_j + sum_j gamma^{t + j} * v2_j` of the targets of both instances.
+    pub fn prove_as_subprotocol_with_linearized<C: PolyCommitmentScheme<G>>(
+        random_oracle: &mut RO,
+        vk: &G::ScalarField,
+        shape: &CCSShape<G>,
+        (U1, W1): (&LCCSInstance<G, C>, &CCSWitness<G>),
+        (U2, W2): (&LCCSInstance<G, C>, &CCSWitness<G>),
+    ) -> Result<(Self, (LCCSInstance<G, C>, CCSWitness<G>), G::BaseField), Error> {
+        random_oracle.absorb(&vk);
+        random_oracle.absorb(&U1);
+        random_oracle.absorb(&U2);
+
+        let rho: G::BaseField =
+            random_oracle.squeeze_field_elements_with_sizes(&[SQUEEZE_ELEMENTS_BIT_SIZE])[0];
+        let rho_scalar: G::ScalarField =
+            unsafe { cast_field_element::<G::BaseField, G::ScalarField>(&rho) };
+
+        let s: usize = safe_loglike!(shape.num_constraints) as usize;
+
+        let gamma: G::ScalarField = random_oracle.squeeze_field_elements(1)[0];
+
+        let z1 = [U1.X.as_slice(), W1.W.as_slice()].concat();
+        let z2 = [U2.X.as_slice(), W2.W.as_slice()].concat();
+
+        let mut g = ListOfProductsOfPolynomials::new(s);
+
+        for (k, (U, z)) in [(U1, &z1), (U2, &z2)].into_iter().enumerate() {
+            let eq = EqPolynomial::new(U.rs.clone());
+            let eqrs = vec_to_ark_mle(eq.evals().as_slice());
+
+            (1..=shape.num_matrices).for_each(|j| {
+                let mut summand_L =
+                    vec![vec_to_ark_mle(shape.Ms[j - 1].multiply_vec(z).as_slice())];
+
+                summand_L.push(eqrs.clone());
+                g.add_product(
+                    summand_L.iter().map(|Lj| Rc::new(Lj.clone())),
+                    gamma.pow([(k * shape.num_matrices + j) as u64]),
+                );
+            });
+        }
+
+        let (sumcheck_proof, sumcheck_state) = MLSumcheck::prove_as_subprotocol(random_oracle, &g);
+
+        let rs_p = sumcheck_state.randomness;
+
+        let sigmas: Vec<G::ScalarField> = ark_std::cfg_iter!(&shape.Ms)
+            .map(|M| vec_to_ark_mle(M.multiply_vec(&z1).as_slice()).evaluate(&rs_p))
+            .collect();
+
+        let thetas: Vec<G::ScalarField> = ark_std::cfg_iter!(&shape.Ms)
+            .map(|M| vec_to_ark_mle(M.multiply_vec(&z2).as_slice()).evaluate(&rs_p))
+            .collect();
+
+        let U = U1.fold_with_linearized(U2, &rho_scalar, &rs_p, &sigmas, &thetas)?;
+        let W = W1.fold(W2, &rho_scalar)?;
+
+        Ok((
+            Self {
+                sumcheck_proof,
+                poly_info: g.info(),
+                sigmas,
+                thetas,
+                _random_oracle: PhantomData,
+            },
+            (U, W),
+            rho,
+        ))
+    }
+
+    pub fn verify_as_subprotocol_with_linearized<C: PolyCommitmentScheme<G>>(
+        &self,
+        random_oracle: &mut RO,
+        vk: &G::ScalarField,
+        shape: &CCSShape<G>,
+        U1: &LCCSInstance<G, C>,
+        U2: &LCCSInstance<G, C>,
+    ) -> Result<(LCCSInstance<G, C>, G::BaseField), Error> {
+        random_oracle.absorb(&vk);
+        random_oracle.absorb(&U1);
+        random_oracle.absorb(&U2);
+
+        let rho: G::BaseField =
+            random_oracle.squeeze_field_elements_with_sizes(&[SQUEEZE_ELEMENTS_BIT_SIZE])[0];
+        let rho_scalar: G::ScalarField =
+            unsafe { cast_field_element::<G::BaseField, G::ScalarField>(&rho) };
+
+        let gamma: G::ScalarField = random_oracle.squeeze_field_elements(1)[0];
+
+        let gamma_powers: Vec<G::ScalarField> = (1..=2 * shape.num_matrices)
+            .map(|j| gamma.pow([j as u64]))
+            .collect();
+        let (gamma_powers_1, gamma_powers_2) = gamma_powers.split_at(shape.num_matrices);
+
+        let claimed_sum = gamma_powers
+            .iter()
+            .zip(U1.vs.iter().chain(U2.vs.iter()))
+            .map(|(a, b)| *a * b)
+            .sum();
+
+        let sumcheck_subclaim = MLSumcheck::verify_as_subprotocol(
+            random_oracle,
+            &self.poly_info,
+            claimed_sum,
+            &self.sumcheck_proof,
+        )?;
+
+        let rs_p = sumcheck_subclaim.point;
+
+        let eq1 = EqPolynomial::new(U1.rs.clone());
+        let eqrs1 = vec_to_ark_mle(eq1.evals().as_slice());
+        let e1 = eqrs1.evaluate(&rs_p);
+
+        let eq2 = EqPolynomial::new(U2.rs.clone());
+        let eqrs2 = vec_to_ark_mle(eq2.evals().as_slice());
+        let e2 = eqrs2.evaluate(&rs_p);
+
+        let cl: G::ScalarField = gamma_powers_1
+            .iter()
+            .zip(self.sigmas.iter())
+            .map(|(a, b)| *a * b)
+            .sum::<G::ScalarField>()
+            * e1;
+
+        let cr: G::ScalarField = gamma_powers_2
+            .iter()
+            .zip(self.thetas.iter())
+            .map(|(a, b)| *a * b)
+            .sum::<G::ScalarField>()
+            * e2;
+
+        if sumcheck_subclaim.expected_evaluation != cl + cr {
+            return Err(Error::InconsistentSubclaim);
+        }
+
+        let U = U1.fold_with_linearized(U2, &rho_scalar, &rs_p, &self.sigmas, &self.thetas)?;
+
+        Ok((U, rho))
+    }
 }
 
 #[cfg(test)]
@@ -279,6 +459,11 @@ pub(crate) mod tests {
         prove_verify_as_subprotocol_with_curve::<G, Z>().unwrap()
     }
 
+    #[test]
+    fn prove_verify_as_subprotocol_with_linearized() {
+        prove_verify_as_subprotocol_with_linearized_with_curve::<G, Z>().unwrap()
+    }
+
     fn prove_verify_as_subprotocol_with_curve<G, C>() -> Result<(), Error>
     where
         G: SWCurveConfig,
@@ -358,4 +543,84 @@ pub(crate) mod tests {
 
         Ok(())
     }
+
+    fn prove_verify_as_subprotocol_with_linearized_with_curve<G, C>() -> Result<(), Error>
+    where
+        G: SWCurveConfig,
+        G::BaseField: PrimeField + Absorb,
+        G::ScalarField: Absorb,
+        C: PolyCommitmentScheme<Projective<G>>,
+        C::PolyCommitmentKey: Clone,
+    {
+        let config = poseidon_config::<G::ScalarField>();
+
+        let mut rng = test_rng();
+
+        let (shape, U, W, ck) = setup_test_ccs::<G, C>(3, None, Some(&mut rng));
+
+        let X = to_field_elements::<Projective<G>>((vec![0; shape.num_io]).as_slice());
+        let W_zero = CCSWitness::zero(&shape);
+        let commitment_W = W_zero.commit::<C>(&ck);
+
+        let s = safe_loglike!(shape.num_constraints);
+        let rs: Vec<G::ScalarField> = (0..s).map(|_| G::ScalarField::rand(&mut rng)).collect();
+        let vs = vec![G::ScalarField::ZERO; shape.num_matrices];
+
+        let U_zero = LCCSInstance::<Projective<G>, C>::new(&shape, &commitment_W, &X, &rs, &vs)?;
+
+        let vk = G::ScalarField::ZERO;
+
+        // produce two non-trivial linearized instances by folding fresh instances into the zero instance
+        let mut random_oracle = PoseidonSponge::new(&config);
+        let (_, (U1, W1), _) = NIMFSProof::prove_as_subprotocol(
+            &mut random_oracle,
+            &vk,
+            &shape,
+            (&U_zero, &W_zero),
+            (&U, &W),
+        )?;
+
+        let (_, U2, W2, _) = setup_test_ccs(5, Some(&ck), Some(&mut rng));
+
+        let mut random_oracle = PoseidonSponge::new(&config);
+        let (_, (U2, W2), _) = NIMFSProof::prove_as_subprotocol(
+            &mut random_oracle,
+            &vk,
+            &shape,
+            (&U_zero, &W_zero),
+            (&U2, &W2),
+        )?;
+
+        let mut random_oracle = PoseidonSponge::new(&config);
+        let (proof, (folded_U, folded_W), _rho) = NIMFSProof::<
+            Projective<G>,
+            PoseidonSponge<G::ScalarField>,
+        >::prove_as_subprotocol_with_linearized(
+            &mut random_oracle,
+            &vk,
+            &shape,
+            (&U1, &W1),
+            (&U2, &W2),
+        )?;
+
+        let mut random_oracle = PoseidonSponge::new(&config);
+        let (v_folded_U, _rho) = proof.verify_as_subprotocol_with_linearized(
+            &mut random_oracle,
+            &vk,
+            &shape,
+            &U1,
+            &U2,
+        )?;
+        assert_eq!(folded_U, v_folded_U);
+
+        shape.is_satisfied_linearized(&folded_U, &folded_W, &ck)?;
+
+        // the proof must not verify against different instances
+        let mut random_oracle = PoseidonSponge::new(&config);
+        assert!(proof
+            .verify_as_subprotocol_with_linearized(&mut random_oracle, &vk, &shape, &U2, &U1)
+            .is_err());
+
+        Ok(())
+    }
 }
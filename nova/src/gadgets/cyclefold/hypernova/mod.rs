@@ -16,6 +16,7 @@ use ark_r1cs_std::{
     boolean::Boolean,
     eq::EqGadget,
     fields::{fp::FpVar, FieldVar},
+    groups::curves::short_weierstrass::ProjectiveVar,
     R1CSVar,
 };
 use ark_relations::r1cs::SynthesisError;
@@ -253,6 +254,221 @@ where
     Ok((folded_U, U_secondary))
 }
 
+pub fn multifold_with_linearized<G1, G2, C1, C2, RO>(
+    config: &<RO::Var as CryptographicSpongeVar<G1::ScalarField, RO>>::Parameters,
+    vk: &FpVar<G1::ScalarField>,
+    sumcheck_rounds: usize,
+    U1: &primary::LCCSInstanceFromR1CSVar<G1, C1>,
+    U1_secondary: &secondary::RelaxedR1CSInstanceVar<G2, C2>,
+    U2: &primary::LCCSInstanceFromR1CSVar<G1, C1>,
+    U2_secondary: &secondary::RelaxedR1CSInstanceVar<G2, C2>,
+    commitment_W_proof: &secondary::ProofVar<G2, C2>,
+    commitment_T_secondary: &ProjectiveVar<G2, FpVar<G2::BaseField>>,
+    hypernova_proof: &primary::ProofFromR1CSVar<G1, RO>,
+    should_enforce: &Boolean<G1::ScalarField>,
+) -> Result<
+    (
+        primary::LCCSInstanceFromR1CSVar<G1, C1>,
+        secondary::RelaxedR1CSInstanceVar<G2, C2>,
+    ),
+    SynthesisError,
+>
+where
+    G1: SWCurveConfig<BaseField = G2::ScalarField, ScalarField = G2::BaseField>,
+    G2: SWCurveConfig,
+    C1: PolyCommitmentScheme<Projective<G1>>,
+    C2: CommitmentScheme<Projective<G2>>,
+    G1::BaseField: PrimeField,
+    G2::BaseField: PrimeField,
+    RO: SpongeWithGadget<G1::ScalarField>,
+{
+    let cs = U1.cs();
+    let mut random_oracle = RO::Var::new(cs.clone(), config);
+
+    random_oracle.absorb(&U1_secondary)?;
+
+    random_oracle.absorb(&vk)?;
+    random_oracle.absorb(&U1.var())?;
+    random_oracle.absorb(&U2.var())?;
+
+    let (rho, rho_bits) = random_oracle
+        .squeeze_nonnative_field_elements_with_sizes::<G1::BaseField>(&[
+            SQUEEZE_ELEMENTS_BIT_SIZE,
+        ])?;
+    let rho = &rho[0];
+    let rho_bits = &rho_bits[0];
+    let rho_scalar = Boolean::le_bits_to_fp_var(rho_bits)?;
+
+    // HyperNova Verification Circuit - implementation is specific to R1CS origin for constraints
+
+    const NUM_MATRICES: usize = 3;
+    // both summands are the product of a matrix-vector mle with an eq polynomial
+    const DEGREE: usize = 2;
+
+    let gamma: FpVar<G1::ScalarField> = random_oracle.squeeze_field_elements(1)?[0].clone();
+
+    let gamma_powers: Vec<FpVar<G1::ScalarField>> = (1..=2 * NUM_MATRICES)
+        .map(|j| gamma.pow_le(&Boolean::constant_vec_from_bytes(&j.to_le_bytes())))
+        .collect::<Result<Vec<FpVar<G1::ScalarField>>, SynthesisError>>()?;
+    let (gamma_powers_1, gamma_powers_2) = gamma_powers.split_at(NUM_MATRICES);
+
+    let mut expected: FpVar<G1::ScalarField> = gamma_powers
+        .iter()
+        .zip(U1.var().vs.iter().chain(U2.var().vs.iter()))
+        .fold(
+            FpVar::<G1::ScalarField>::Constant(G1::ScalarField::ZERO),
+            |acc, (a, b)| acc + (a * b),
+        );
+
+    // (i, \prod_{j != i} (i - j))
+    let interpolation_constants = [
+        (G1::ScalarField::from(0), G1::ScalarField::from(2)), // (0 - 1)(0 - 2) =  2
+        (G1::ScalarField::from(1), G1::ScalarField::from(-1)), // (1 - 0)(1 - 2) = -1
+        (G1::ScalarField::from(2), G1::ScalarField::from(2)), // (2 - 0)(2 - 1) =  2
+    ];
+
+    random_oracle.absorb(&hypernova_proof.var().poly_info.var())?;
+
+    let mut rs_p: Vec<FpVar<G1::ScalarField>> = vec![];
+    for round in 0..sumcheck_rounds {
+        random_oracle.absorb(&hypernova_proof.var().sumcheck_proof[round])?;
+        let r = random_oracle.squeeze_field_elements(SQUEEZE_NATIVE_ELEMENTS_NUM)?[0].clone();
+        random_oracle.absorb(&r)?;
+
+        let evals = &hypernova_proof.var().sumcheck_proof[round];
+        expected.conditional_enforce_equal(&(&evals[0] + &evals[1]), should_enforce)?;
+
+        // lagrange interpolate and evaluate polynomial, as in `multifold`
+        let prod: FpVar<G1::ScalarField> = (0..(DEGREE + 1)).fold(
+            FpVar::<G1::ScalarField>::Constant(G1::ScalarField::ONE),
+            |acc, i| acc * (&r - interpolation_constants[i].0),
+        );
+
+        expected = (0..(DEGREE + 1))
+            .map(|i| {
+                let num = &prod * &evals[i];
+                let denom = (&r - interpolation_constants[i].0) * interpolation_constants[i].1;
+                num.mul_by_inverse(&denom)
+            })
+            .collect::<Result<Vec<FpVar<G1::ScalarField>>, SynthesisError>>()?
+            .iter()
+            .sum();
+
+        rs_p.push(r);
+    }
+
+    let eq = |rs: &[FpVar<G1::ScalarField>]| {
+        (0..rs.len())
+            .map(|i| {
+                &rs[i] * &rs_p[i]
+                    + (FpVar::<G1::ScalarField>::Constant(G1::ScalarField::ONE) - &rs[i])
+                        * (FpVar::<G1::ScalarField>::Constant(G1::ScalarField::ONE) - &rs_p[i])
+            })
+            .fold(
+                FpVar::<G1::ScalarField>::Constant(G1::ScalarField::ONE),
+                |acc, x| acc * x,
+            )
+    };
+
+    let e1 = eq(&U1.var().rs);
+    let e2 = eq(&U2.var().rs);
+
+    let cl = gamma_powers_1
+        .iter()
+        .zip(hypernova_proof.var().sigmas.iter())
+        .fold(
+            FpVar::<G1::ScalarField>::Constant(G1::ScalarField::ZERO),
+            |acc, (a, b)| acc + (a * b),
+        )
+        * e1;
+
+    let cr = gamma_powers_2
+        .iter()
+        .zip(hypernova_proof.var().thetas.iter())
+        .fold(
+            FpVar::<G1::ScalarField>::Constant(G1::ScalarField::ZERO),
+            |acc, (a, b)| acc + (a * b),
+        )
+        * e2;
+
+    expected.conditional_enforce_equal(&(cl + cr), should_enforce)?;
+
+    // End HyperNova Verification Circuit
+
+    let secondary::ProofVar {
+        U: comm_W_secondary_instance,
+        commitment_T,
+    } = &commitment_W_proof;
+
+    // The rest of the secondary public input is reconstructed from primary instances.
+    let comm_W_secondary_instance = secondary::R1CSInstanceVar::from_allocated_input(
+        comm_W_secondary_instance,
+        &U1.var().commitment_W,
+        &U2.var().commitment_W,
+    )?;
+    let (rho_secondary, g_out) = comm_W_secondary_instance.parse_secondary_io::<G1>()?;
+    rho_secondary.conditional_enforce_equal(rho, should_enforce)?;
+
+    let commitment_W = g_out;
+    random_oracle.absorb(&comm_W_secondary_instance)?;
+    random_oracle.absorb(&commitment_T)?;
+    random_oracle.absorb(&cast_field_element_unique::<G1::BaseField, G1::ScalarField>(rho)?)?;
+
+    let (rho_p, rho_p_bits) = random_oracle
+        .squeeze_nonnative_field_elements_with_sizes::<G1::BaseField>(&[
+            SQUEEZE_ELEMENTS_BIT_SIZE,
+        ])?;
+    let rho_p = &rho_p[0];
+    let rho_p_bits = &rho_p_bits[0];
+
+    // unlike in `multifold`, both instances are linearized, so the leading `u` entries are folded as well
+    let folded_U = primary::LCCSInstanceFromR1CSVar::new(
+        commitment_W,
+        U1.var()
+            .X
+            .iter()
+            .zip(U2.var().X.iter())
+            .map(|(a, b)| a + &rho_scalar * b)
+            .collect(),
+        rs_p,
+        hypernova_proof
+            .var()
+            .sigmas
+            .iter()
+            .zip(hypernova_proof.var().thetas.iter())
+            .map(|(a, b)| a + &rho_scalar * b)
+            .collect(),
+    );
+
+    let U1_secondary = U1_secondary.fold(&[(
+        (&comm_W_secondary_instance, None),
+        commitment_T,
+        rho_p,
+        rho_p_bits,
+    )])?;
+
+    random_oracle.absorb(&cast_field_element_unique::<G1::BaseField, G1::ScalarField>(rho_p)?)?;
+    random_oracle.absorb(&U1_secondary)?;
+    random_oracle.absorb(&U2_secondary)?;
+    random_oracle.absorb(&commitment_T_secondary)?;
+
+    let (r, r_bits) =
+        random_oracle.squeeze_nonnative_field_elements_with_sizes::<G1::BaseField>(&[
+            SQUEEZE_ELEMENTS_BIT_SIZE,
+        ])?;
+    let r = &r[0];
+    let r_bits = &r_bits[0];
+
+    let U_secondary = U1_secondary.fold(&[(
+        (&U2_secondary.into(), Some(&U2_secondary.commitment_E)),
+        commitment_T_secondary,
+        r,
+        &r_bits[..],
+    )])?;
+
+    Ok((folded_U, U_secondary))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -464,4 +680,161 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn verify_linearized_in_circuit() {
+        verify_linearized_in_circuit_with_cycle::<
+            ark_bn254::g1::Config,
+            ark_grumpkin::GrumpkinConfig,
+            Zeromorph<ark_bn254::Bn254>,
+            PedersenCommitment<ark_grumpkin::Projective>,
+        >()
+        .unwrap();
+    }
+
+    fn verify_linearized_in_circuit_with_cycle<G1, G2, C1, C2>() -> Result<(), SynthesisError>
+    where
+        G1: SWCurveConfig<BaseField = G2::ScalarField, ScalarField = G2::BaseField>,
+        G2: SWCurveConfig,
+        C1: PolyCommitmentScheme<Projective<G1>>,
+        C2: CommitmentScheme<Projective<G2>, SetupAux = ()>,
+        G1::BaseField: PrimeField + Absorb,
+        G2::BaseField: PrimeField + Absorb,
+    {
+        let config = poseidon_config();
+
+        let vk = G1::ScalarField::ONE;
+
+        let mut rng = test_rng();
+        let (shape, u1, w1, ck) = setup_test_ccs::<G1, C1>(3, None, Some(&mut rng));
+        let (_, u2, w2, _) = setup_test_ccs::<G1, C1>(5, Some(&ck), Some(&mut rng));
+
+        let shape_secondary = multifold_secondary::setup_shape::<G1, G2>()?;
+
+        let pp_secondary = C2::setup(
+            shape_secondary.num_vars + shape_secondary.num_constraints,
+            b"test",
+            &(),
+        );
+
+        let X = to_field_elements::<Projective<G1>>((vec![0; shape.num_io]).as_slice());
+        let W = CCSWitness::zero(&shape);
+
+        let commitment_W = W.commit::<C1>(&ck);
+
+        let s = safe_loglike!(shape.num_constraints);
+        let rs: Vec<G1::ScalarField> = (0..s).map(|_| G1::ScalarField::rand(&mut rng)).collect();
+
+        let z = [X.as_slice(), W.W.as_slice()].concat();
+        let vs: Vec<G1::ScalarField> = ark_std::cfg_iter!(&shape.Ms)
+            .map(|M| {
+                vec_to_mle(M.multiply_vec(&z).as_slice()).evaluate::<Projective<G1>>(rs.as_slice())
+            })
+            .collect();
+
+        let U =
+            LCCSInstance::<G1, C1>::new(&shape, &commitment_W, &X, rs.as_slice(), vs.as_slice())
+                .unwrap();
+
+        let U_secondary = RelaxedR1CSInstance::<G2, C2>::new(&shape_secondary);
+        let W_secondary = RelaxedR1CSWitness::<G2>::zero(&shape_secondary);
+
+        let (_, (U1, W1), (U1_secondary, W1_secondary)) =
+            NIMFSProof::<_, _, _, _, PoseidonSponge<G1::ScalarField>>::prove(
+                &pp_secondary,
+                &config,
+                &vk,
+                (&shape, &shape_secondary),
+                (&U, &W),
+                (&U_secondary, &W_secondary),
+                (&u1, &w1),
+            )
+            .unwrap();
+        let (_, (U2, W2), (U2_secondary, W2_secondary)) =
+            NIMFSProof::<_, _, _, _, PoseidonSponge<G1::ScalarField>>::prove(
+                &pp_secondary,
+                &config,
+                &vk,
+                (&shape, &shape_secondary),
+                (&U, &W),
+                (&U_secondary, &W_secondary),
+                (&u2, &w2),
+            )
+            .unwrap();
+
+        let (proof, (folded_U, folded_W), (folded_U_secondary, folded_W_secondary)) =
+            NIMFSProof::<_, _, _, _, PoseidonSponge<G1::ScalarField>>::prove_with_linearized(
+                &pp_secondary,
+                &config,
+                &vk,
+                (&shape, &shape_secondary),
+                (&U1, &W1),
+                (&U1_secondary, &W1_secondary),
+                (&U2, &W2),
+                (&U2_secondary, &W2_secondary),
+            )
+            .unwrap();
+
+        let cs = ConstraintSystem::<G1::ScalarField>::new_ref();
+        let U1_cs = primary::LCCSInstanceFromR1CSVar::<G1, C1>::new_input(cs.clone(), || Ok(&U1))?;
+        let U1_secondary_cs =
+            secondary::RelaxedR1CSInstanceVar::<G2, C2>::new_input(cs.clone(), || {
+                Ok(&U1_secondary)
+            })?;
+        let U2_cs = primary::LCCSInstanceFromR1CSVar::<G1, C1>::new_input(cs.clone(), || Ok(&U2))?;
+        let U2_secondary_cs =
+            secondary::RelaxedR1CSInstanceVar::<G2, C2>::new_input(cs.clone(), || {
+                Ok(&U2_secondary)
+            })?;
+        let commitment_T_secondary_cs = <ProjectiveVar<G2, FpVar<G2::BaseField>> as AllocVar<
+            Projective<G2>,
+            G2::BaseField,
+        >>::new_input(cs.clone(), || {
+            Ok(proof.proof_secondary.commitment_T.into())
+        })?;
+
+        let hypernova_proof = &proof.hypernova_proof;
+        let comm_W_proof = &proof.commitment_W_proof;
+
+        let vk_cs = FpVar::new_input(cs.clone(), || Ok(vk))?;
+        let hypernova_proof =
+            primary::ProofFromR1CSVar::<G1, PoseidonSponge<G1::ScalarField>>::new_input(
+                cs.clone(),
+                || Ok(hypernova_proof),
+            )?;
+        let comm_W_proof =
+            secondary::ProofVar::<G2, C2>::new_input(cs.clone(), || Ok(comm_W_proof))?;
+
+        let s: usize = safe_loglike!(shape.num_constraints) as usize;
+
+        let (_U_cs, _U_secondary_cs) =
+            multifold_with_linearized::<G1, G2, C1, C2, PoseidonSponge<G1::ScalarField>>(
+                &config,
+                &vk_cs,
+                s,
+                &U1_cs,
+                &U1_secondary_cs,
+                &U2_cs,
+                &U2_secondary_cs,
+                &comm_W_proof,
+                &commitment_T_secondary_cs,
+                &hypernova_proof,
+                &Boolean::TRUE,
+            )?;
+
+        let _U = _U_cs.value()?;
+        let _U_secondary = _U_secondary_cs.value()?;
+
+        assert_eq!(_U, folded_U);
+        shape.is_satisfied_linearized(&_U, &folded_W, &ck).unwrap();
+
+        assert_eq!(_U_secondary, folded_U_secondary);
+        shape_secondary
+            .is_relaxed_satisfied(&_U_secondary, &folded_W_secondary, &pp_secondary)
+            .unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+
+        Ok(())
+    }
 }
@@ -11,7 +11,7 @@ else
 fi
 
 rm -f bin/***.a
-for ext in i imc
+for ext in i im imc
 do
     ${GCC_PREFIX}gcc -c -mabi=ilp32 -march=rv32${ext} -mcmodel=medlow asm.S -o bin/nexus-rt.o
     ${GCC_PREFIX}ar crs bin/riscv32${ext}-unknown-none-elf.a bin/nexus-rt.o
@@ -5,8 +5,13 @@ use std::fs;
 use std::path::PathBuf;
 
 fn main() {
+    println!("cargo:rustc-check-cfg=cfg(nexus_jolt)");
+
     let target = env::var("TARGET").unwrap();
-    if !target.starts_with("riscv32i-") && !target.starts_with("riscv32imc-") {
+    if !target.starts_with("riscv32i-")
+        && !target.starts_with("riscv32im-")
+        && !target.starts_with("riscv32imc-")
+    {
         return;
     }
 
@@ -15,7 +20,11 @@ fn main() {
     println!("cargo:rerun-if-env-changed={PROVER_ENV}");
 
     let script_path = match env::var(PROVER_ENV) {
-        Ok(s) if &s == "jolt" => "linker-scripts/jolt.x",
+        Ok(s) if &s == "jolt" => {
+            // Jolt has no ecalls, input and output are memory-mapped instead
+            println!("cargo:rustc-cfg=nexus_jolt");
+            "linker-scripts/jolt.x"
+        }
         _ => "linker-scripts/default.x",
     };
     let script_bytes = fs::read(script_path).unwrap();
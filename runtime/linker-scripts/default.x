@@ -6,8 +6,9 @@ SECTIONS
   /*                                                                                    */
   /* Because the stack will grow down from this point, and if the heap requests memory  */
   /* being used by the stack then the runtime will panic, this value also functions as  */
-  /* the memory limit for the guest program execution more generally.                   */
-  __memory_top = 0x400000;
+  /* the memory limit for the guest program execution more generally. The VM faults on  */
+  /* accesses above it. It is overridden by `#[nexus_rt::main(memlimit = N)]`.          */
+  PROVIDE(__memory_top = 0x400000);
   . = 0;
 
   .text : ALIGN(4)
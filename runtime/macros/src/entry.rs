@@ -34,6 +34,16 @@ pub fn main(args: TokenStream, input: TokenStream) -> Result<TokenStream, Error>
         ));
     }
 
+    // The memory limit overrides the top of memory provided by the linker
+    // script, where the stack starts, and which the VM enforces.
+    let memory_top = memlimit.map(|limit| {
+        let def = format!(".equ __memory_top, {limit:#x}");
+        quote! {
+            #[cfg(target_arch = "riscv32")]
+            core::arch::global_asm!(".globl __memory_top", #def);
+        }
+    });
+
     Ok(quote! {
         const _: fn() = main;
 
@@ -41,13 +51,6 @@ pub fn main(args: TokenStream, input: TokenStream) -> Result<TokenStream, Error>
         #[allow(unused)]
         #func
 
-        #[cfg(target_arch = "riscv32")]
-        #[doc(hidden)]
-        mod __private {
-            #[no_mangle]
-            pub fn get_stack_size() -> i32 {
-                #memlimit
-            }
-        }
+        #memory_top
     })
 }
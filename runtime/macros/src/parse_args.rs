@@ -4,7 +4,7 @@ use syn::parse::{Parse, ParseStream};
 
 const MEMORY_LIMIT_IDENT: &str = "memlimit";
 
-struct MemLimit(i32);
+struct MemLimit(u32);
 
 impl Parse for MemLimit {
     fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
@@ -20,7 +20,13 @@ impl Parse for MemLimit {
                 ))?;
             if let syn::Lit::Int(i) = meta.lit {
                 let value = i.base10_parse::<u16>()?;
-                Ok(Self(i32::from(value).saturating_mul(0x100000)))
+                u32::from(value)
+                    .checked_mul(0x100000)
+                    .map(Self)
+                    .ok_or(syn::Error::new(
+                        Span::call_site(),
+                        "memory limit should be less than 4096",
+                    ))
             } else {
                 Err(syn::Error::new(
                     Span::call_site(),
@@ -33,11 +39,11 @@ impl Parse for MemLimit {
     }
 }
 
-/// Parse memory limit from macro arguments.
-pub fn parse_memory_limit(args: TokenStream) -> syn::Result<i32> {
+/// Parse memory limit from macro arguments, in bytes.
+pub fn parse_memory_limit(args: TokenStream) -> syn::Result<Option<u32>> {
     if args.is_empty() {
-        Ok(-1)
+        Ok(None)
     } else {
-        syn::parse::<MemLimit>(args.into()).map(|m| m.0)
+        syn::parse::<MemLimit>(args.into()).map(|m| Some(m.0))
     }
 }
@@ -1,6 +1,6 @@
 pub use core::fmt::Write;
 
-#[cfg(target_arch = "riscv32")]
+#[cfg(all(target_arch = "riscv32", not(nexus_jolt)))]
 mod riscv32 {
     extern crate alloc;
     use serde::{de::DeserializeOwned, Serialize};
@@ -23,6 +23,25 @@ mod riscv32 {
         ecall!(1, s.as_ptr(), s.len(), _out);
     }
 
+    /// Write a string to the panic message of the program
+    ///
+    /// the panic message is reported by the host after the program exits
+    pub fn write_panic(s: &str) {
+        let mut _out: u32;
+        ecall!(7, s.as_ptr(), s.len(), _out);
+    }
+
+    /// Exit the program with the given exit code
+    ///
+    /// the exit code is committed to by the proof, and a program which
+    /// returns from `main` exits with code 0
+    pub fn exit(code: u32) -> ! {
+        let inp: u32 = 0;
+        let mut _out: u32;
+        ecall!(8, code, inp, _out);
+        unsafe { core::arch::asm!("unimp", options(noreturn)) }
+    }
+
     /// Read an object off the private input tape
     ///
     /// exhausts the private input tape, so can only be used once
@@ -44,6 +63,27 @@ mod riscv32 {
         } // u32::MAX is used a sentinel value that there is nothing (left) on the input tape
     }
 
+    /// Read an object off the public input tape
+    ///
+    /// exhausts the public input tape, so can only be used once
+    pub fn read_public_input<T: DeserializeOwned>() -> Result<T, postcard::Error> {
+        let bytes: alloc::vec::Vec<u8> = core::iter::from_fn(read_from_public_input).collect();
+        postcard::from_bytes::<T>(bytes.as_slice())
+    }
+
+    /// Read a byte from the public input tape
+    pub fn read_from_public_input() -> Option<u8> {
+        let inp: u32 = 0;
+        let mut out: u32;
+        ecall!(4, inp, inp, out);
+
+        if out == u32::MAX {
+            None
+        } else {
+            Some(out.to_le_bytes()[0])
+        } // u32::MAX is used a sentinel value that there is nothing (left) on the input tape
+    }
+
     /// Write an object to the output tape
     pub fn write_output<T: Serialize + ?Sized>(val: &T) {
         let ser: alloc::vec::Vec<u8> = postcard::to_allocvec(&val).unwrap();
@@ -53,9 +93,14 @@ mod riscv32 {
     }
 
     /// Write a slice to the output tape
+    ///
+    /// bytes are written one at a time, so that each can be committed to by the proof
     pub fn write_to_output(b: &[u8]) {
+        let inp: u32 = 0;
         let mut _out: u32;
-        ecall!(3, b.as_ptr(), b.len(), _out);
+        for x in b {
+            ecall!(3, *x as u32, inp, _out);
+        }
     }
 
     /// Bench cycles with input is function name
@@ -64,6 +109,46 @@ mod riscv32 {
         ecall!(5, s.as_ptr(), s.len(), _out);
     }
 
+    // The output of a precompile call, which is written to a cache line.
+    #[repr(align(32))]
+    struct Line([u8; 32]);
+
+    // Call the precompile with syscall number `code` on `input`.
+    //
+    // inputs which fit in the output line are copied to it, and hashed in
+    // place: calls of the Keccak-256 precompile made this way can be proven
+    fn precompile(code: u32, input: &[u8]) -> [u8; 32] {
+        let mut out = Line([0; 32]);
+        let src = if input.len() <= out.0.len() {
+            out.0[..input.len()].copy_from_slice(input);
+            out.0.as_ptr()
+        } else {
+            input.as_ptr()
+        };
+        let mut _len: u32;
+        unsafe {
+            core::arch::asm!("ecall", in("s2") code, in("a1") src, in("a2") input.len(), in("a3") out.0.as_mut_ptr(), out("a0") _len)
+        }
+        out.0
+    }
+
+    /// Compute the Keccak-256 hash of `input` using the VM precompile
+    pub fn keccak256(input: &[u8]) -> [u8; 32] {
+        precompile(0x100, input)
+    }
+
+    /// Compute the SHA-256 hash of `input` using the VM precompile
+    pub fn sha256(input: &[u8]) -> [u8; 32] {
+        precompile(0x101, input)
+    }
+
+    /// Compute the Poseidon hash of `input` using the VM precompile
+    ///
+    /// the result is a little-endian encoded field element
+    pub fn poseidon(input: &[u8]) -> [u8; 32] {
+        precompile(0x102, input)
+    }
+
     /// An empty type representing the VM terminal
     pub struct NexusLog;
 
@@ -74,9 +159,14 @@ mod riscv32 {
         }
     }
 }
-#[cfg(target_arch = "riscv32")]
+#[cfg(all(target_arch = "riscv32", not(nexus_jolt)))]
 pub use riscv32::*;
 
+#[cfg(all(target_arch = "riscv32", nexus_jolt))]
+mod jolt;
+#[cfg(all(target_arch = "riscv32", nexus_jolt))]
+pub use jolt::*;
+
 /// Prints to the VM terminal
 #[cfg(target_arch = "riscv32")]
 #[macro_export]
@@ -109,26 +199,7 @@ macro_rules! println {
 #[cfg(not(target_arch = "riscv32"))]
 pub use std::{print, println};
 
-/// Read an object off the private input tape
-#[cfg(not(target_arch = "riscv32"))]
-pub fn read_private_input<T: serde::de::DeserializeOwned>() -> Result<T, postcard::Error> {
-    panic!("private input is not available outside of NexusVM")
-}
-
-/// Read a byte from the private input tape
 #[cfg(not(target_arch = "riscv32"))]
-pub fn read_from_private_input() -> Option<u8> {
-    panic!("private input is not available outside of NexusVM")
-}
-
-/// Write an object to the output tape
-#[cfg(not(target_arch = "riscv32"))]
-pub fn write_output<T: serde::Serialize + ?Sized>(_: &T) {
-    panic!("output is not available outside of NexusVM")
-}
-
-/// Write a slice to the output tape
+mod native;
 #[cfg(not(target_arch = "riscv32"))]
-pub fn write_to_output(_: &[u8]) {
-    panic!("output is not available outside of NexusVM")
-}
+pub use native::*;
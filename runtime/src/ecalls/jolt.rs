@@ -0,0 +1,146 @@
+//! Input and output for programs proven with Jolt.
+//!
+//! Jolt does not support ecalls. Instead, the public input, the output and
+//! the panic flag are mapped to memory regions chosen by the prover, which
+//! writes their addresses into `__NEXUS_JOLT_IO` when loading the program.
+//! The public input is prefixed with its length as a little-endian `u32`.
+
+extern crate alloc;
+use core::ptr::{addr_of, read_volatile, write_volatile};
+use serde::{de::DeserializeOwned, Serialize};
+
+// Addresses of the public input, the start and end of the output, and the
+// panic flag. The placeholder is non-zero so that the table is allocated in
+// `.data`, where the prover can patch it.
+#[no_mangle]
+#[used]
+static mut __NEXUS_JOLT_IO: [u32; 4] = [u32::MAX; 4];
+
+const INPUT_START: usize = 0;
+const OUTPUT_START: usize = 1;
+const OUTPUT_END: usize = 2;
+const PANIC: usize = 3;
+
+// Programs are single-threaded, so the tape positions can be kept in statics.
+static mut INPUT_POS: u32 = 0;
+static mut OUTPUT_POS: u32 = 0;
+
+fn io_address(i: usize) -> u32 {
+    unsafe { read_volatile(addr_of!(__NEXUS_JOLT_IO[i])) }
+}
+
+fn load_byte(addr: u32) -> u8 {
+    unsafe { read_volatile(addr as *const u8) }
+}
+
+/// Write a string to the output console (if any).
+///
+/// Jolt does not record logs, so this is a no-op.
+pub fn write_log(_: &str) {}
+
+/// Write a string to the panic message of the program
+///
+/// Jolt does not record panic messages, so this is a no-op.
+pub fn write_panic(_: &str) {}
+
+/// Exit the program with the given exit code
+///
+/// Jolt only commits to whether the program exited successfully, so any
+/// non-zero exit code sets the panic flag.
+pub fn exit(code: u32) -> ! {
+    if code != 0 {
+        unsafe { write_volatile(io_address(PANIC) as *mut u8, 1) };
+    }
+    unsafe { core::arch::asm!("unimp", options(noreturn)) }
+}
+
+/// Read an object off the private input tape
+///
+/// Jolt does not support private input, so the tape is always empty.
+pub fn read_private_input<T: DeserializeOwned>() -> Result<T, postcard::Error> {
+    postcard::from_bytes::<T>(&[])
+}
+
+/// Read a byte from the private input tape
+///
+/// Jolt does not support private input, so the tape is always empty.
+pub fn read_from_private_input() -> Option<u8> {
+    None
+}
+
+/// Read an object off the public input tape
+///
+/// exhausts the public input tape, so can only be used once
+pub fn read_public_input<T: DeserializeOwned>() -> Result<T, postcard::Error> {
+    let bytes: alloc::vec::Vec<u8> = core::iter::from_fn(read_from_public_input).collect();
+    postcard::from_bytes::<T>(bytes.as_slice())
+}
+
+/// Read a byte from the public input tape
+pub fn read_from_public_input() -> Option<u8> {
+    let start = io_address(INPUT_START);
+    let len = u32::from_le_bytes(core::array::from_fn(|i| load_byte(start + i as u32)));
+
+    unsafe {
+        if INPUT_POS >= len {
+            return None;
+        }
+        let b = load_byte(start + 4 + INPUT_POS);
+        INPUT_POS += 1;
+        Some(b)
+    }
+}
+
+/// Write an object to the output tape
+pub fn write_output<T: Serialize + ?Sized>(val: &T) {
+    let ser: alloc::vec::Vec<u8> = postcard::to_allocvec(&val).unwrap();
+    write_to_output(ser.as_slice())
+}
+
+/// Write a slice to the output tape
+///
+/// panics if the output does not fit in the memory region reserved by the prover
+pub fn write_to_output(b: &[u8]) {
+    let start = io_address(OUTPUT_START);
+    let end = io_address(OUTPUT_END);
+    for x in b {
+        unsafe {
+            let addr = start + OUTPUT_POS;
+            if addr >= end {
+                panic!("output exceeds the maximum output size");
+            }
+            write_volatile(addr as *mut u8, *x);
+            OUTPUT_POS += 1;
+        }
+    }
+}
+
+/// Bench cycles with input is function name
+///
+/// Jolt does not report cycle counts, so this is a no-op.
+pub fn cycle_count_ecall(_: &str) {}
+
+/// Compute the Keccak-256 hash of `input` using the VM precompile
+pub fn keccak256(_: &[u8]) -> [u8; 32] {
+    panic!("precompiles are not supported by Jolt")
+}
+
+/// Compute the SHA-256 hash of `input` using the VM precompile
+pub fn sha256(_: &[u8]) -> [u8; 32] {
+    panic!("precompiles are not supported by Jolt")
+}
+
+/// Compute the Poseidon hash of `input` using the VM precompile
+pub fn poseidon(_: &[u8]) -> [u8; 32] {
+    panic!("precompiles are not supported by Jolt")
+}
+
+/// An empty type representing the VM terminal
+pub struct NexusLog;
+
+impl core::fmt::Write for NexusLog {
+    fn write_str(&mut self, s: &str) -> Result<(), core::fmt::Error> {
+        write_log(s);
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+//! Input and output for programs run natively, on the host.
+//!
+//! The tapes are backed by host buffers, with the same semantics as in the
+//! VM: inputs are read a byte at a time until they are exhausted, and the
+//! output is appended to. The buffers are per thread, so that the unit tests
+//! of a guest program, which `cargo test` runs in parallel, do not share
+//! tapes. Tests set the inputs with [`set_public_input`] and
+//! [`set_private_input`], and read the output with [`read_output`].
+//!
+//! A guest program run as a host binary (see `nexus_sdk::native`) instead
+//! reads its tapes from the directory named by [`TAPES_DIR_ENV`]: the inputs
+//! from the files `public_input` and `private_input`, while the output is
+//! appended to the file `output` as it is written. As exit codes of host
+//! processes may be truncated, [`exit`] also writes its code to the file
+//! `exit_code`, as a decimal number.
+//!
+//! Precompiles are evaluated on the host with the same implementations as
+//! in the VM, so that they return the same outputs.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use nexus_vm::precompiles::{Keccak256, Poseidon, Precompile, Sha256};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Environment variable naming the directory holding the tapes of a program
+/// run as a host binary.
+pub const TAPES_DIR_ENV: &str = "NEXUS_TAPES_DIR";
+
+const PUBLIC_INPUT_FILE: &str = "public_input";
+const PRIVATE_INPUT_FILE: &str = "private_input";
+const OUTPUT_FILE: &str = "output";
+const EXIT_CODE_FILE: &str = "exit_code";
+
+struct Tapes {
+    public_input: VecDeque<u8>,
+    private_input: VecDeque<u8>,
+    output: Vec<u8>,
+}
+
+thread_local! {
+    static TAPES: RefCell<Tapes> = RefCell::new(Tapes::from_env());
+}
+
+fn tapes_dir() -> Option<PathBuf> {
+    std::env::var_os(TAPES_DIR_ENV).map(PathBuf::from)
+}
+
+impl Tapes {
+    fn from_env() -> Self {
+        let read = |name| match tapes_dir() {
+            Some(dir) => fs::read(dir.join(name))
+                .unwrap_or_else(|e| panic!("unable to read the {name} tape: {e}"))
+                .into(),
+            None => VecDeque::new(),
+        };
+        Self {
+            public_input: read(PUBLIC_INPUT_FILE),
+            private_input: read(PRIVATE_INPUT_FILE),
+            output: Vec::new(),
+        }
+    }
+}
+
+// Append `bytes` to the file `name` of the tapes directory, if any.
+fn append_to_file(name: &str, bytes: &[u8]) {
+    if let Some(dir) = tapes_dir() {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(name))
+            .and_then(|mut f| f.write_all(bytes))
+            .unwrap_or_else(|e| panic!("unable to write the {name} tape: {e}"));
+    }
+}
+
+/// Write a string to the output console (if any).
+///
+/// logs are written to the standard output
+pub fn write_log(s: &str) {
+    print!("{s}");
+}
+
+/// Write a string to the panic message of the program
+///
+/// panic messages are written to the standard error
+pub fn write_panic(s: &str) {
+    eprint!("{s}");
+}
+
+/// Exit the program with the given exit code
+///
+/// the process exits, so this should not be called from unit tests
+pub fn exit(code: u32) -> ! {
+    if let Some(dir) = tapes_dir() {
+        // ignore errors, the process exit code is still available
+        let _ = fs::write(dir.join(EXIT_CODE_FILE), code.to_string());
+    }
+    std::process::exit(code as i32)
+}
+
+/// Read an object off the private input tape
+///
+/// exhausts the private input tape, so can only be used once
+pub fn read_private_input<T: DeserializeOwned>() -> Result<T, postcard::Error> {
+    let bytes: Vec<u8> = core::iter::from_fn(read_from_private_input).collect();
+    postcard::from_bytes::<T>(bytes.as_slice())
+}
+
+/// Read a byte from the private input tape
+pub fn read_from_private_input() -> Option<u8> {
+    TAPES.with_borrow_mut(|t| t.private_input.pop_front())
+}
+
+/// Read an object off the public input tape
+///
+/// exhausts the public input tape, so can only be used once
+pub fn read_public_input<T: DeserializeOwned>() -> Result<T, postcard::Error> {
+    let bytes: Vec<u8> = core::iter::from_fn(read_from_public_input).collect();
+    postcard::from_bytes::<T>(bytes.as_slice())
+}
+
+/// Read a byte from the public input tape
+pub fn read_from_public_input() -> Option<u8> {
+    TAPES.with_borrow_mut(|t| t.public_input.pop_front())
+}
+
+/// Write an object to the output tape
+pub fn write_output<T: Serialize + ?Sized>(val: &T) {
+    let ser: Vec<u8> = postcard::to_allocvec(&val).unwrap();
+    write_to_output(ser.as_slice())
+}
+
+/// Write a slice to the output tape
+pub fn write_to_output(b: &[u8]) {
+    TAPES.with_borrow_mut(|t| t.output.extend_from_slice(b));
+    append_to_file(OUTPUT_FILE, b);
+}
+
+/// Set the contents of the public input tape of the current thread to `val`
+///
+/// only available natively, for testing guest programs
+pub fn set_public_input<T: Serialize + ?Sized>(val: &T) {
+    let ser: Vec<u8> = postcard::to_allocvec(&val).unwrap();
+    TAPES.with_borrow_mut(|t| t.public_input = ser.into());
+}
+
+/// Set the contents of the private input tape of the current thread to `val`
+///
+/// only available natively, for testing guest programs
+pub fn set_private_input<T: Serialize + ?Sized>(val: &T) {
+    let ser: Vec<u8> = postcard::to_allocvec(&val).unwrap();
+    TAPES.with_borrow_mut(|t| t.private_input = ser.into());
+}
+
+/// Read an object off the output tape of the current thread
+///
+/// empties the output tape; only available natively, for testing guest programs
+pub fn read_output<T: DeserializeOwned>() -> Result<T, postcard::Error> {
+    let bytes = TAPES.with_borrow_mut(|t| std::mem::take(&mut t.output));
+    postcard::from_bytes::<T>(bytes.as_slice())
+}
+
+/// Bench cycles with input is function name
+///
+/// cycles are not counted natively, so this is a no-op.
+pub fn cycle_count_ecall(_: &str) {}
+
+// Evaluate `precompile` on `input`, whose output is a cache line, as in the VM.
+fn eval_precompile(precompile: impl Precompile, input: &[u8]) -> [u8; 32] {
+    let output = precompile
+        .eval(input)
+        .unwrap_or_else(|e| panic!("{} precompile failed: {e}", precompile.name()));
+    output.try_into().unwrap_or_else(|o: Vec<u8>| {
+        panic!(
+            "{} precompile returned {} bytes",
+            precompile.name(),
+            o.len()
+        )
+    })
+}
+
+/// Compute the Keccak-256 hash of `input` using the VM precompile
+pub fn keccak256(input: &[u8]) -> [u8; 32] {
+    eval_precompile(Keccak256, input)
+}
+
+/// Compute the SHA-256 hash of `input` using the VM precompile
+pub fn sha256(input: &[u8]) -> [u8; 32] {
+    eval_precompile(Sha256, input)
+}
+
+/// Compute the Poseidon hash of `input` using the VM precompile
+///
+/// the result is a little-endian encoded field element
+pub fn poseidon(input: &[u8]) -> [u8; 32] {
+    eval_precompile(Poseidon, input)
+}
+
+/// An empty type representing the VM terminal
+pub struct NexusLog;
+
+impl core::fmt::Write for NexusLog {
+    fn write_str(&mut self, s: &str) -> Result<(), core::fmt::Error> {
+        write_log(s);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tapes() {
+        set_public_input(&(1u32, 2u32));
+        set_private_input(&[3u8; 4]);
+
+        let (a, b): (u32, u32) = read_public_input().unwrap();
+        let c: [u8; 4] = read_private_input().unwrap();
+        assert_eq!((a, b, c), (1, 2, [3; 4]));
+
+        // inputs are exhausted once read
+        assert_eq!(read_from_public_input(), None);
+        assert!(read_private_input::<u8>().is_err());
+
+        write_output(&a);
+        write_output("ab");
+        assert_eq!(read_output::<(u32, String)>().unwrap(), (1, "ab".into()));
+        assert!(read_output::<u32>().is_err());
+
+        // tapes are per thread
+        std::thread::spawn(|| assert_eq!(read_from_public_input(), None))
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn precompiles() {
+        // test vectors for the empty input
+        assert_eq!(keccak256(b"")[..4], [0xc5, 0xd2, 0x46, 0x01],);
+        assert_eq!(sha256(b"")[..4], [0xe3, 0xb0, 0xc4, 0x42]);
+        assert_ne!(poseidon(b"abc"), poseidon(b"abd"));
+    }
+}
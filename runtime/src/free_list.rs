@@ -0,0 +1,171 @@
+// A size-class allocator which reuses freed memory.
+//
+// Every allocation is rounded up to a power-of-two size class of at
+// least `MIN_CLASS` bytes. Freed blocks are kept in a singly linked
+// free list per class, with the link stored in the first word of the
+// block, and are handed out again by later allocations of the same
+// class. When a list is empty, a new block is taken from the heap
+// with `sys_alloc_aligned`, which also checks that the heap does not
+// run into the stack.
+//
+// Blocks are aligned to their size, up to `MAX_ALIGN`, so that any
+// block of a class satisfies the alignment of any layout in that class.
+// Allocations requiring a larger alignment are rare, and are taken
+// directly from the heap and never reused.
+
+#[cfg(target_arch = "riscv32")]
+use crate::alloc::sys_alloc_aligned;
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::UnsafeCell;
+use core::ptr::null_mut;
+
+const MIN_CLASS: usize = 8;
+const MAX_ALIGN: usize = 0x1000;
+const NUM_CLASSES: usize = usize::BITS as usize;
+
+pub struct FreeList {
+    // Heads of the free lists, indexed by log2 of the class size.
+    free: UnsafeCell<[*mut u8; NUM_CLASSES]>,
+}
+
+// SAFETY: the VM is single threaded, so there is no concurrent access.
+unsafe impl Sync for FreeList {}
+
+impl FreeList {
+    pub const fn new() -> Self {
+        Self {
+            free: UnsafeCell::new([null_mut(); NUM_CLASSES]),
+        }
+    }
+}
+
+// The unit tests run on the host, where the heap is provided by the
+// system allocator. Blocks taken from it are never returned.
+#[cfg(not(target_arch = "riscv32"))]
+unsafe fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u8 {
+    std::alloc::alloc(Layout::from_size_align_unchecked(bytes.max(1), align))
+}
+
+// Size class index of `layout`, or `None` if it must not be reused.
+fn class(layout: &Layout) -> Option<usize> {
+    if layout.align() > MAX_ALIGN {
+        return None;
+    }
+    let size = layout.size().max(layout.align()).max(MIN_CLASS);
+    let size = size.checked_next_power_of_two()?;
+    Some(size.trailing_zeros() as usize)
+}
+
+unsafe impl GlobalAlloc for FreeList {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let Some(c) = class(&layout) else {
+            return sys_alloc_aligned(layout.size(), layout.align());
+        };
+
+        let free = &mut *self.free.get();
+        let head = free[c];
+        if !head.is_null() {
+            free[c] = *(head as *mut *mut u8);
+            return head;
+        }
+
+        let size = 1usize << c;
+        sys_alloc_aligned(size, size.min(MAX_ALIGN))
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if let Some(c) = class(&layout) {
+            let free = &mut *self.free.get();
+            *(ptr as *mut *mut u8) = free[c];
+            free[c] = ptr;
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let old = class(&layout);
+        if old.is_some() && old == class(&new_layout) {
+            return ptr;
+        }
+
+        let new_ptr = self.alloc(new_layout);
+        if !new_ptr.is_null() {
+            core::ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+            self.dealloc(ptr, layout);
+        }
+        new_ptr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(size: usize, align: usize) -> Layout {
+        Layout::from_size_align(size, align).unwrap()
+    }
+
+    #[test]
+    fn size_classes() {
+        assert_eq!(class(&layout(0, 1)), Some(3));
+        assert_eq!(class(&layout(8, 4)), Some(3));
+        assert_eq!(class(&layout(9, 1)), Some(4));
+        assert_eq!(class(&layout(3, 16)), Some(4));
+        assert_eq!(class(&layout(1, MAX_ALIGN)), Some(12));
+        assert_eq!(class(&layout(1, MAX_ALIGN * 2)), None);
+    }
+
+    #[test]
+    fn alloc_free() {
+        let heap = FreeList::new();
+        unsafe {
+            let a = heap.alloc(layout(12, 4));
+            let b = heap.alloc(layout(16, 8));
+            assert!(!a.is_null() && !b.is_null() && a != b);
+            assert_eq!(a as usize % 16, 0);
+
+            // freed blocks are reused by allocations of the same class,
+            // most recently freed first
+            heap.dealloc(a, layout(12, 4));
+            heap.dealloc(b, layout(16, 8));
+            assert_eq!(heap.alloc(layout(9, 1)), b);
+            assert_eq!(heap.alloc(layout(16, 16)), a);
+
+            // but not by allocations of another class
+            heap.dealloc(a, layout(16, 16));
+            let c = heap.alloc(layout(32, 4));
+            assert_ne!(c, a);
+            assert_eq!(heap.alloc(layout(16, 4)), a);
+
+            // over-aligned blocks are never reused
+            let d = heap.alloc(layout(8, MAX_ALIGN * 2));
+            assert_eq!(d as usize % (MAX_ALIGN * 2), 0);
+            heap.dealloc(d, layout(8, MAX_ALIGN * 2));
+            assert_ne!(heap.alloc(layout(8, MAX_ALIGN * 2)), d);
+        }
+    }
+
+    #[test]
+    fn realloc() {
+        let heap = FreeList::new();
+        unsafe {
+            // growing within a class keeps the block
+            let a = heap.alloc(layout(20, 4));
+            assert_eq!(heap.realloc(a, layout(20, 4), 32), a);
+            core::ptr::write_bytes(a, 7, 32);
+
+            // growing out of a class moves the contents to a new block,
+            // and frees the old one
+            let b = heap.realloc(a, layout(32, 4), 40);
+            assert_ne!(b, a);
+            assert_eq!(core::slice::from_raw_parts(b, 32), &[7; 32]);
+            assert_eq!(heap.alloc(layout(32, 4)), a);
+
+            // shrinking into a smaller class as well
+            let c = heap.realloc(b, layout(40, 4), 4);
+            assert_ne!(c, b);
+            assert_eq!(core::slice::from_raw_parts(c, 4), &[7; 4]);
+            assert_eq!(heap.alloc(layout(64, 4)), b);
+        }
+    }
+}
@@ -8,6 +8,8 @@ pub use runtime::*;
 
 #[cfg(target_arch = "riscv32")]
 mod alloc;
+#[cfg(any(test, all(target_arch = "riscv32", feature = "free-list")))]
+mod free_list;
 
 pub use nexus_rt_macros::{main, profile};
 
@@ -1,33 +1,50 @@
 // Nexus VM runtime environment
 // Note: adapted from riscv-rt, which was adapted from cortex-m.
+#[cfg(not(feature = "free-list"))]
 use crate::alloc::sys_alloc_aligned;
+#[cfg(not(feature = "free-list"))]
 use core::alloc::{GlobalAlloc, Layout};
+use core::fmt::Write;
 use core::panic::PanicInfo;
 
+// Exit code of a program which panicked, matching the exit code
+// used by Rust programs on other targets.
+const PANIC_EXIT_CODE: u32 = 101;
+
+// Writes to the panic message channel of the VM.
+struct PanicWriter;
+
+impl Write for PanicWriter {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        crate::write_panic(s);
+        Ok(())
+    }
+}
+
 #[inline(never)]
 #[panic_handler]
-fn panic(_info: &PanicInfo) -> ! {
-    extern "C" {
-        fn abort() -> !;
-    }
-    crate::write_log("PANIC\n");
-    unsafe {
-        abort();
-    }
+fn panic(info: &PanicInfo) -> ! {
+    let _ = write!(PanicWriter, "{info}");
+    crate::exit(PANIC_EXIT_CODE)
 }
 
 #[export_name = "error: nexus-rt appears more than once"]
 #[doc(hidden)]
 pub static __ONCE__: () = ();
 
+#[cfg(not(feature = "free-list"))]
 struct Heap;
 
+#[cfg(not(feature = "free-list"))]
 #[global_allocator]
 static HEAP: Heap = Heap;
 
 // This trivial allocate will always expand the heap, and never
-// deallocates. This should be fine for small programs.
+// deallocates. This should be fine for small programs; programs
+// which repeatedly allocate and free memory should enable the
+// `free-list` feature instead.
 
+#[cfg(not(feature = "free-list"))]
 unsafe impl GlobalAlloc for Heap {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
         sys_alloc_aligned(layout.size(), layout.align())
@@ -35,23 +52,9 @@ unsafe impl GlobalAlloc for Heap {
     unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
 }
 
-/// Stack size setup (_get_stack_size)
-///
-/// Because the stack will grow down from this point, and if the heap requests memory
-/// being used by the stack then the runtime will panic, this value also functions as
-/// the memory limit for the guest program execution more generally.
-#[doc(hidden)]
-#[link_section = ".init.rust"]
-#[export_name = "_get_stack_size"]
-pub unsafe extern "C" fn get_stack_size() -> i32 {
-    extern "Rust" {
-        // This symbol is generated by the macro parser, and returns:
-        //     `N`   if `#[nexus_rt::main(memset(N))]`
-        //    `-1`   otherwise
-        fn get_stack_size() -> i32;
-    }
-    get_stack_size()
-}
+#[cfg(feature = "free-list")]
+#[global_allocator]
+static HEAP: crate::free_list::FreeList = crate::free_list::FreeList::new();
 
 /// Rust entry point (_start_rust)
 #[doc(hidden)]
@@ -62,5 +65,6 @@ pub unsafe extern "C" fn start_rust(a0: u32, a1: u32, a2: u32) -> u32 {
         // This symbol will be provided by the user via `#[nexus_rt::main]`
         fn main(a0: u32, a1: u32, a2: u32) -> u32;
     }
-    main(a0, a1, a2)
+    main(a0, a1, a2);
+    crate::exit(0)
 }
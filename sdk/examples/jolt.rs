@@ -1,19 +1,32 @@
-use nexus_sdk::{compile::CompileOpts, jolt::Jolt, Local};
+use nexus_sdk::{compile::CompileOpts, jolt::Jolt, Local, Prover, Verifiable};
 
 const PACKAGE: &str = "example";
-const EXAMPLE: &str = "noecall";
+const EXAMPLE: &str = "jolt_io";
 
 fn main() {
     let opts = CompileOpts::new_with_custom_binary(PACKAGE, EXAMPLE);
 
     // defaults to local proving
     let prover: Jolt<Local> = Jolt::compile(&opts).expect("failed to load program");
+    let program = prover.program_id();
 
-    println!("Proving execution of vm...");
-    let proof = prover.prove().expect("failed to prove program");
+    println!("Preprocessing program...");
+    let pre = prover.preprocess();
+
+    // Jolt only supports public input
+    let input: u32 = 10;
+
+    print!("Proving execution of vm...");
+    let proof = prover
+        .prove_with_inputs::<u32, ()>(&pre, &input, &())
+        .expect("failed to prove program");
+    let output: u32 = proof.output::<u32>().expect("failed to deserialize output");
+    println!(" output is {}!", output);
 
     print!("Verifying execution...");
-    proof.verify().expect("failed to verify proof");
+    proof
+        .verify(&pre, &program, &input, &output)
+        .expect("failed to verify proof");
 
     println!("  Succeeded!");
 }
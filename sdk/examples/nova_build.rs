@@ -15,6 +15,7 @@ fn main() {
 
     println!("Compiling guest program...");
     let prover: Nova<Local> = Nova::compile(&opts).expect("failed to compile guest program");
+    let program = prover.program_id();
 
     println!("Proving execution of vm...");
     let proof = prover.prove(&pp).expect("failed to prove program");
@@ -22,7 +23,9 @@ fn main() {
     println!(">>>>> Logging\n{}<<<<<", proof.logs().join(""));
 
     print!("Verifying execution...");
-    proof.verify(&pp).expect("failed to verify proof");
+    proof
+        .verify(&pp, &program, &(), &())
+        .expect("failed to verify proof");
 
     println!("  Succeeded!");
 }
@@ -1,6 +1,6 @@
 use nexus_sdk::{
     nova::seq::{Generate, Nova, PP},
-    Local, Prover, Verifiable,
+    program_id, Local, Prover, Verifiable,
 };
 
 type Input = (u32, u32);
@@ -10,7 +10,7 @@ const EXAMPLE_NAME: &str = "input_output";
 
 const TARGET_PATH: &str = concat!(
     env!("CARGO_MANIFEST_DIR"),
-    "/../target/riscv32i-unknown-none-elf/release"
+    "/../target/riscv32im-unknown-none-elf/release"
 );
 
 fn main() {
@@ -19,7 +19,7 @@ fn main() {
         panic!(
             "{}{} was not found, make sure to compile the program \
              with `cd examples && cargo build --release --bin {}`",
-            "target/riscv32i-unknown-none-elf/release/", EXAMPLE_NAME, EXAMPLE_NAME,
+            "target/riscv32im-unknown-none-elf/release/", EXAMPLE_NAME, EXAMPLE_NAME,
         );
     }
 
@@ -36,17 +36,22 @@ fn main() {
         .prove_with_input::<Input>(&pp, &input)
         .expect("failed to prove program");
 
-    println!(
-        " output is {}!",
-        proof
-            .output::<Output>()
-            .expect("failed to deserialize output")
-    );
+    let output: Output = proof
+        .output::<Output>()
+        .expect("failed to deserialize output");
+    println!(" output is {}!", output);
 
     println!(">>>>> Logging\n{}<<<<<", proof.logs().join(""));
 
+    // the verifier computes the identifier of the program it expects a proof of
+    let program = program_id(&std::fs::read(&path).expect("failed to read program"))
+        .expect("failed to compute program identifier");
+
     print!("Verifying execution...");
-    proof.verify(&pp).expect("failed to verify proof");
+    // the private input is not committed to, but the output is
+    proof
+        .verify(&pp, &program, &(), &output)
+        .expect("failed to verify proof");
 
     println!("  Succeeded!");
 }
@@ -1,13 +1,13 @@
 use nexus_sdk::{
     nova::seq::{Generate, Nova, PP},
-    Local, Prover, Verifiable,
+    program_id, Local, Prover, Verifiable,
 };
 
 const EXAMPLE_NAME: &str = "example";
 
 const TARGET_PATH: &str = concat!(
     env!("CARGO_MANIFEST_DIR"),
-    "/../target/riscv32i-unknown-none-elf/release"
+    "/../target/riscv32im-unknown-none-elf/release"
 );
 
 fn main() {
@@ -16,7 +16,7 @@ fn main() {
         panic!(
             "{}{} was not found, make sure to compile the program \
              with `cd examples && cargo build --release --bin {}`",
-            "target/riscv32i-unknown-none-elf/release/", EXAMPLE_NAME, EXAMPLE_NAME,
+            "target/riscv32im-unknown-none-elf/release/", EXAMPLE_NAME, EXAMPLE_NAME,
         );
     }
 
@@ -31,8 +31,14 @@ fn main() {
 
     println!(">>>>> Logging\n{}<<<<<", proof.logs().join(""));
 
+    // the verifier computes the identifier of the program it expects a proof of
+    let program = program_id(&std::fs::read(&path).expect("failed to read program"))
+        .expect("failed to compute program identifier");
+
     print!("Verifying execution...");
-    proof.verify(&pp).expect("failed to verify proof");
+    proof
+        .verify(&pp, &program, &(), &())
+        .expect("failed to verify proof");
 
     println!("  Succeeded!");
 }
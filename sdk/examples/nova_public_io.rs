@@ -0,0 +1,59 @@
+use nexus_sdk::{
+    nova::seq::{Generate, Nova, PP},
+    program_id, Local, Prover, Verifiable,
+};
+
+type PublicInput = u32;
+type PrivateInput = (u32, u32);
+type Output = bool;
+
+const EXAMPLE_NAME: &str = "public_input";
+
+const TARGET_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../target/riscv32im-unknown-none-elf/release"
+);
+
+fn main() {
+    let path = std::path::Path::new(TARGET_PATH).join(EXAMPLE_NAME);
+    if path.try_exists().is_err() {
+        panic!(
+            "{}{} was not found, make sure to compile the program \
+             with `cd examples && cargo build --release --bin {}`",
+            "target/riscv32im-unknown-none-elf/release/", EXAMPLE_NAME, EXAMPLE_NAME,
+        );
+    }
+
+    println!("Setting up Nova public parameters...");
+    let pp: PP = PP::generate().expect("failed to generate parameters");
+
+    // defaults to local proving
+    let prover: Nova<Local> = Nova::new_from_file(&path).expect("failed to load program");
+
+    let public: PublicInput = 221;
+    let private: PrivateInput = (13, 17);
+
+    print!("Proving execution of vm...");
+    let proof = prover
+        .prove_with_inputs::<PublicInput, PrivateInput>(&pp, &public, &private)
+        .expect("failed to prove program");
+
+    let output: Output = proof
+        .output::<Output>()
+        .expect("failed to deserialize output");
+    println!(" output is {}!", output);
+
+    println!(">>>>> Logging\n{}<<<<<", proof.logs().join(""));
+
+    // the public input and output are committed to, the private input is not
+    // the verifier computes the identifier of the program it expects a proof of
+    let program = program_id(&std::fs::read(&path).expect("failed to read program"))
+        .expect("failed to compute program identifier");
+
+    print!("Verifying execution...");
+    proof
+        .verify(&pp, &program, &public, &output)
+        .expect("failed to verify proof");
+
+    println!("  Succeeded!");
+}
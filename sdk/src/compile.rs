@@ -36,7 +36,7 @@ pub struct CompileOpts {
     /// The binary produced by the build that should be loaded into the zkVM after successful compilation.
     pub binary: String,
     debug: bool,
-    //native: bool,
+    native: bool,
     unique: bool,
     pub(crate) memlimit: Option<usize>, // in mb
 }
@@ -48,7 +48,7 @@ impl CompileOpts {
             package: package.to_string(),
             binary: package.to_string(),
             debug: false,
-            //native: false,
+            native: false,
             unique: false,
             memlimit: None,
         }
@@ -60,7 +60,7 @@ impl CompileOpts {
             package: package.to_string(),
             binary: binary.to_string(),
             debug: false,
-            //native: false,
+            native: false,
             unique: false,
             memlimit: None,
         }
@@ -71,13 +71,13 @@ impl CompileOpts {
         self.debug = debug;
     }
 
-    // NOTE: SDK should be enhanced to support native building once feature parity is achieved for the runtime.
-    //   (see, https://github.com/nexus-xyz/nexus-zkvm/pull/195#discussion_r1646697743)
-    //
-    // /// Set dynamic compilation to build for the native (host machine) target, rather than for the zkVM.
-    // pub fn set_native_build(&mut self, native: bool) {
-    //     self.native = native;
-    // }
+    /// Set dynamic compilation to build for the native (host machine) target, rather than for the zkVM.
+    ///
+    /// Native builds can only be run with [`Native`](crate::native::Native), which always builds natively. They ignore the
+    /// memory limit.
+    pub fn set_native_build(&mut self, native: bool) {
+        self.native = native;
+    }
 
     /// Set dynamic compilation to run a unique build that neither overwrites prior builds nor will be overwritten by future builds. May be used to concurrently build different versions of the same binary.
     ///
@@ -133,27 +133,29 @@ impl CompileOpts {
     }
 
     pub(crate) fn build(&mut self, prover: &ForProver) -> Result<PathBuf, BuildError> {
-        let linker_path = self.set_linker(prover)?;
-
-        let rust_flags = [
-            "-C",
-            &format!("link-arg=-T{}", linker_path.display()),
-            "-C",
-            "panic=abort",
-        ];
-
-        // (see comment above on `set_native_build`)
-        //
-        // let target = if self.native {
-        //     "native"
-        // } else {
-        //     "riscv32i-unknown-none-elf"
-        // };
-        let target = "riscv32i-unknown-none-elf";
+        let (target, envs) = if self.native {
+            // native builds use the default linker, and unwind on panic like other host programs
+            (host_target()?, Vec::new())
+        } else {
+            let linker_path = self.set_linker(prover)?;
+
+            let rust_flags = [
+                "-C",
+                &format!("link-arg=-T{}", linker_path.display()),
+                "-C",
+                "panic=abort",
+            ];
+
+            // the runtime selects its input/output backend based on the prover
+            let envs = vec![
+                ("CARGO_ENCODED_RUSTFLAGS", rust_flags.join("\x1f")),
+                ("NEXUS_VM_PROVER", prover.to_string()),
+            ];
+            ("riscv32im-unknown-none-elf".to_string(), envs)
+        };
 
         let profile = if self.debug { "debug" } else { "release" };
 
-        let envs = vec![("CARGO_ENCODED_RUSTFLAGS", rust_flags.join("\x1f"))];
         let prog = self.binary.as_str();
 
         let mut dest = match std::env::var_os("OUT_DIR") {
@@ -178,7 +180,7 @@ impl CompileOpts {
             "--target-dir",
             &dest,
             "--target",
-            target,
+            &target,
             "--profile",
             profile,
         ]);
@@ -196,3 +198,16 @@ impl CompileOpts {
         Ok(elf_path)
     }
 }
+
+// The target triple of the host, as reported by `rustc`. The target is always passed explicitly, since guest programs
+// usually set the zkVM as their default target.
+fn host_target() -> Result<String, BuildError> {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_err| "rustc".into());
+    let res = Command::new(rustc).arg("-vV").output()?;
+
+    String::from_utf8_lossy(&res.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .map(str::to_string)
+        .ok_or(BuildError::CompilerError)
+}
@@ -1,23 +1,34 @@
 use crate::compile;
 use crate::traits::*;
+use crate::views::{CheckedView, UncheckedView};
 
-use std::fs;
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::Path;
 use thiserror::Error;
 
 use nexus_core::nvm::memory::MerkleTrie;
+use nexus_core::prover::jolt::pp::{load_pp, save_pp, PPError};
 use nexus_core::prover::jolt::types::{JoltCommitments, JoltPreprocessing, JoltProof};
 use nexus_core::prover::jolt::{
-    parse_elf, preprocess, prove, trace, verify, Error as ProofError, VM as JoltVM,
+    check_io, parse_elf, preprocess, prove, trace, verify, Error as ProofError, JoltDevice,
+    VM as JoltVM,
 };
 
-use crate::error::{BuildError, TapeError};
+use crate::error::{BuildError, PathError, TapeError};
+use nexus_core::config::vm::ProverImpl;
+use nexus_core::prover::envelope::{EnvelopeError, ProofEnvelope, ProofHeader};
 
 use std::marker::PhantomData;
 
+// Jolt proves the whole execution at once
+const K: usize = 0;
+
+const PROVER: ProverImpl = ProverImpl::Jolt;
+
 /// Errors that occur while proving using Jolt.
 #[derive(Debug, Error)]
 pub enum Error {
-    /// An error occurred during parameter generation, execution, proving, or proof verification for the zkVM.
+    /// An error occurred during execution, proving, or proof verification for the zkVM.
     #[error(transparent)]
     ProofError(#[from] ProofError),
 
@@ -29,56 +40,299 @@ pub enum Error {
     #[error(transparent)]
     IOError(#[from] std::io::Error),
 
+    /// An error occurred trying to parse a path for use with the filesystem.
+    #[error(transparent)]
+    PathError(#[from] PathError),
+
     /// An error occurred reading or writing to the zkVM input/output tapes.
     #[error(transparent)]
     TapeError(#[from] TapeError),
+
+    /// An error occurred encoding or decoding a saved proof.
+    #[error(transparent)]
+    EnvelopeError(#[from] EnvelopeError),
+
+    /// An error occurred saving or loading preprocessing.
+    #[error(transparent)]
+    PPError(#[from] PPError),
+
+    /// Jolt does not support private input.
+    #[error("private input is not supported by Jolt")]
+    PrivateInputUnsupported,
+
+    /// Jolt preprocessing depends on the program, and cannot be generated ahead of time.
+    #[error("Jolt preprocessing must be generated for a program, see `Jolt::preprocess`")]
+    PreprocessingUnsupported,
+
+    /// The preprocessing is of another program, with the contained identifier.
+    #[error("preprocessing is of another program, with id {0}")]
+    ProgramMismatch(ProgramId),
 }
 
 /// Prover for the Nexus zkVM using Jolt.
 ///
-/// An experimental implementation, which does not implement the [`Prover`] trait due to missing functionality.
+/// Unlike the folding-based provers, Jolt has no universal public parameters: a program is preprocessed with
+/// [`Jolt::preprocess`], and its [`Preprocessing`] is used in their place for proving and verifying.
+///
+/// Jolt does not support private input or precompiles, does not record logs or panic messages, and only proves whether the
+/// program exited successfully: any non-zero exit code is reported as [`ExitCode::PANIC`].
 pub struct Jolt<C: Compute = Local> {
     vm: JoltVM<MerkleTrie>,
+    program: ProgramId,
     _compute: PhantomData<C>,
 }
 
-/// A Jolt proof (and auxiliary information needed for verification).
+/// Preprocessing of a program, used to prove and verify its executions.
+///
+/// The Jolt verifier only accepts proofs of the preprocessed program, whose identifier is recorded alongside.
+pub struct Preprocessing {
+    pre: JoltPreprocessing,
+    program: ProgramId,
+}
+
+impl Preprocessing {
+    /// The identifier of the preprocessed program, see [`crate::program_id`].
+    pub fn program_id(&self) -> ProgramId {
+        self.program
+    }
+}
+
+/// A verifiable proof of a zkVM execution. Also contains a view capturing the output of the machine.
+///
+/// The proof contains a _checked_ view. Please review [`CheckedView`].
 pub struct Proof {
     proof: JoltProof,
-    pre: JoltPreprocessing,
     commits: JoltCommitments,
+    view: CheckedView,
+    header: ProofHeader,
+}
+
+impl<C: Compute> Jolt<C> {
+    /// Preprocess the program, producing the parameters used to prove and verify its executions.
+    pub fn preprocess(&self) -> Preprocessing {
+        Preprocessing {
+            pre: preprocess(&self.vm),
+            program: self.program,
+        }
+    }
+
+    /// The identifier of the program, which is checked when verifying proofs of its executions, see
+    /// [`crate::program_id`].
+    pub fn program_id(&self) -> ProgramId {
+        self.program
+    }
+
+    fn set_inputs<T, U>(&mut self, public: &T, private: &U) -> Result<(), Error>
+    where
+        T: Serialize + Sized,
+        U: Serialize + Sized,
+    {
+        if !postcard::to_stdvec(private)
+            .map_err(TapeError::from)?
+            .is_empty()
+        {
+            return Err(Error::PrivateInputUnsupported);
+        }
+
+        self.vm
+            .set_input(
+                postcard::to_stdvec(public)
+                    .map_err(TapeError::from)?
+                    .as_slice(),
+            )
+            .map_err(ProofError::from)?;
+        Ok(())
+    }
 }
 
-impl Jolt<Local> {
-    /// Construct a new proving instance through dynamic compilation (see [`compile`]).
-    pub fn compile(opts: &compile::CompileOpts) -> Result<Self, Error> {
+fn exit_code(io: &JoltDevice) -> ExitCode {
+    if io.panic {
+        ExitCode::PANIC
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+impl Prover for Jolt<Local> {
+    type Memory = MerkleTrie;
+    type Params = Preprocessing;
+    type View = UncheckedView;
+    type Proof = Proof;
+    type Error = Error;
+
+    fn new(elf_bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Jolt::<Local> {
+            vm: parse_elf::<Self::Memory>(elf_bytes).map_err(ProofError::from)?,
+            program: program_id(elf_bytes).map_err(ProofError::from)?,
+            _compute: PhantomData,
+        })
+    }
+
+    fn compile(opts: &compile::CompileOpts) -> Result<Self, Self::Error> {
         let mut iopts = opts.to_owned();
 
         let elf_path = iopts
             .build(&compile::ForProver::Jolt)
             .map_err(BuildError::from)?;
 
-        Ok(Jolt::<Local> {
-            vm: parse_elf::<MerkleTrie>(fs::read(elf_path)?.as_slice())
-                .map_err(ProofError::from)?,
-            _compute: PhantomData,
+        Self::new_from_file(&elf_path)
+    }
+
+    fn run_with_inputs<T, U>(mut self, public: &T, private: &U) -> Result<Self::View, Self::Error>
+    where
+        T: Serialize + Sized,
+        U: Serialize + Sized,
+    {
+        self.set_inputs(public, private)?;
+
+        let (_, io) = trace(self.vm).map_err(ProofError::from)?;
+
+        Ok(Self::View {
+            exit_code: exit_code(&io),
+            output: io.outputs,
+            logs: Vec::new(),
+            panic_info: None,
+        })
+    }
+
+    fn prove_with_inputs<T, U>(
+        mut self,
+        pp: &Self::Params,
+        public: &T,
+        private: &U,
+    ) -> Result<Self::Proof, Self::Error>
+    where
+        T: Serialize + Sized,
+        U: Serialize + Sized,
+    {
+        self.set_inputs(public, private)?;
+
+        let (tr, io) = trace(self.vm).map_err(ProofError::from)?;
+        let view = CheckedView {
+            exit_code: exit_code(&io),
+            output: io.outputs.clone(),
+            logs: Vec::new(),
+            panic_info: None,
+        };
+        let (proof, commits) = prove(tr, io, &pp.pre).map_err(ProofError::from)?;
+
+        Ok(Self::Proof {
+            proof,
+            commits,
+            view,
+            header: ProofHeader::new(PROVER, K, false, &())?,
         })
     }
+}
+
+impl Parameters for Preprocessing {
+    type Error = Error;
 
-    /// Prove the zkVM and return a verifiable proof.
-    pub fn prove(self) -> Result<Proof, Error> {
-        let pre = preprocess(&self.vm);
-        let tr = trace(self.vm).map_err(ProofError::from)?;
+    fn generate_for_testing() -> Result<Self, Self::Error> {
+        Err(Error::PreprocessingUnsupported)
+    }
+
+    fn load(path: &Path) -> Result<Self, Self::Error> {
+        if let Some(path_str) = path.to_str() {
+            let (pre, program) = load_pp(path_str)?;
+            return Ok(Preprocessing { pre, program });
+        }
+
+        Err(Self::Error::PathError(
+            crate::error::PathError::EncodingError,
+        ))
+    }
 
-        let (proof, commits) = prove(tr, &pre).map_err(ProofError::from)?;
+    fn save(pp: &Self, path: &Path) -> Result<(), Self::Error> {
+        if let Some(path_str) = path.to_str() {
+            return Ok(save_pp(&pp.pre, &pp.program, path_str)?);
+        }
 
-        Ok(Proof { proof, pre, commits })
+        Err(Self::Error::PathError(
+            crate::error::PathError::EncodingError,
+        ))
     }
 }
 
-impl Proof {
-    /// Verify the proof of an execution.
-    pub fn verify(self) -> Result<(), Error> {
-        Ok(verify(self.pre, self.proof, self.commits).map_err(ProofError::from)?)
+impl Verifiable for Proof {
+    type Params = Preprocessing;
+    type View = CheckedView;
+    type Error = Error;
+
+    fn output<U: DeserializeOwned>(&self) -> Result<U, Self::Error> {
+        Ok(Self::View::output::<U>(&self.view)?)
+    }
+
+    fn logs(&self) -> &Vec<String> {
+        Self::View::logs(&self.view)
+    }
+
+    fn exit_code(&self) -> ExitCode {
+        Self::View::exit_code(&self.view)
+    }
+
+    fn panic_info(&self) -> Option<&str> {
+        Self::View::panic_info(&self.view)
+    }
+
+    fn save(proof: &Self, path: &Path) -> Result<(), Self::Error> {
+        let view = Some((&proof.view).into());
+        ProofEnvelope::new(proof.header.clone(), view, &(&proof.proof, &proof.commits))?
+            .save(path)?;
+        Ok(())
+    }
+
+    fn load(path: &Path) -> Result<Self, Self::Error> {
+        let envelope = ProofEnvelope::load(path)?;
+        envelope.check(PROVER, K, false)?;
+
+        let (proof, commits) = envelope.proof()?;
+        Ok(Proof {
+            proof,
+            commits,
+            view: envelope
+                .view
+                .clone()
+                .ok_or(EnvelopeError::MissingView)?
+                .into(),
+            header: envelope.header,
+        })
+    }
+
+    fn verify_with_exit_code<T, U>(
+        &self,
+        pp: &Self::Params,
+        program: &ProgramId,
+        input: &T,
+        output: &U,
+        exit_code: ExitCode,
+    ) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+        U: Serialize + ?Sized,
+    {
+        // the preprocessing pins the program that the verifier accepts proofs of
+        if pp.program != *program {
+            return Err(Error::ProgramMismatch(pp.program));
+        }
+
+        check_io(
+            &self.proof,
+            postcard::to_stdvec(input)
+                .map_err(TapeError::from)?
+                .as_slice(),
+            postcard::to_stdvec(output)
+                .map_err(TapeError::from)?
+                .as_slice(),
+            !exit_code.is_success(),
+        )
+        .map_err(ProofError::from)?;
+
+        // the Jolt verifier consumes the proof, which is not `Clone`
+        let proof: (JoltProof, JoltCommitments) =
+            ProofEnvelope::new(self.header.clone(), None, &(&self.proof, &self.commits))?
+                .proof()?;
+
+        Ok(verify(pp.pre.clone(), proof.0, proof.1).map_err(ProofError::from)?)
     }
 }
@@ -3,10 +3,14 @@
 
 /// Interface into proving with [HyperNova](https://eprint.iacr.org/2023/573).
 pub mod hypernova;
-/// Experimental interface into proving with [Jolt](https://jolt.a16zcrypto.com/).
+/// Interface into proving with [Jolt](https://jolt.a16zcrypto.com/).
 pub mod jolt;
+/// Interface into running guest programs natively, as host binaries, without proving.
+pub mod native;
 /// Interface into proving with [Nova](https://eprint.iacr.org/2021/370).
 pub mod nova;
+/// Interface into proving with [SuperNova](https://eprint.iacr.org/2022/1758), using per-instruction-class circuits.
+pub mod supernova;
 
 mod traits;
 pub use traits::*;
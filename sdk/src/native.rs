@@ -0,0 +1,333 @@
+use crate::compile;
+use crate::traits::*;
+use crate::views::UncheckedView;
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Output};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::error::{BuildError, TapeError};
+
+// Tapes shared with the native backend of `nexus-rt`, which must agree on these names.
+const TAPES_DIR_ENV: &str = "NEXUS_TAPES_DIR";
+const PUBLIC_INPUT_FILE: &str = "public_input";
+const PRIVATE_INPUT_FILE: &str = "private_input";
+const OUTPUT_FILE: &str = "output";
+const EXIT_CODE_FILE: &str = "exit_code";
+
+// Exit status of host processes terminated by a panic.
+const PANIC_STATUS: i32 = 101;
+
+/// Errors that occur while running guest programs natively.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// An error occurred building the guest program dynamically.
+    #[error(transparent)]
+    BuildError(#[from] BuildError),
+
+    /// An error occurred reading or writing to the filesystem, or running the guest program.
+    #[error(transparent)]
+    IOError(#[from] std::io::Error),
+
+    /// An error occurred reading or writing to the input/output tapes.
+    #[error(transparent)]
+    TapeError(#[from] TapeError),
+
+    /// The guest program was terminated without exiting, e.g., by a signal.
+    #[error("guest program terminated without exiting ({0})")]
+    Terminated(ExitStatus),
+
+    /// The guest program recorded an exit code which could not be parsed.
+    #[error("invalid exit code: {0:?}")]
+    InvalidExitCode(String),
+
+    /// Native executions are not proven.
+    #[error("native executions cannot be proven, use one of the zkVM provers")]
+    ProvingUnsupported,
+}
+
+/// Runner for guest programs compiled natively, as host binaries.
+///
+/// The guest program reads its inputs from, and writes its output to, tapes backed by host buffers in the native backend
+/// of `nexus-rt`, with the same semantics as in the zkVM. This is useful for fast iteration on guest programs, which can
+/// also be unit tested with `cargo test` (see `nexus_rt::set_public_input`).
+///
+/// Each line written to the standard output is reported as a log. A panicking guest program exits with
+/// [`ExitCode::PANIC`], and its panic message is parsed from the standard error. Precompiles are evaluated on the host as
+/// in the zkVM, but executions cannot be proven: [`Params`] and [`Proof`] have no values.
+pub struct Native {
+    path: PathBuf,
+    // whether `path` was written by the runner, and is removed when it is dropped
+    temporary: bool,
+}
+
+/// Parameters of native executions, which are not proven. There are no values of this type.
+pub enum Params {}
+
+/// Proof of a native execution, which is not proven. There are no values of this type.
+pub enum Proof {}
+
+impl Prover for Native {
+    type Memory = ();
+    type Params = Params;
+    type View = UncheckedView;
+    type Proof = Proof;
+    type Error = Error;
+
+    /// Construct a new runner from the bytes of a host binary, which is written to a temporary file.
+    fn new(elf_bytes: &[u8]) -> Result<Self, Self::Error> {
+        let path = std::env::temp_dir().join(format!("nexus-native-{}", Uuid::new_v4()));
+        fs::write(&path, elf_bytes)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+        }
+
+        Ok(Native { path, temporary: true })
+    }
+
+    /// Construct a new runner for the host binary at `path`, which is left in place.
+    fn new_from_file<P: AsRef<Path>>(path: &P) -> Result<Self, Self::Error> {
+        Ok(Native {
+            path: path.as_ref().to_path_buf(),
+            temporary: false,
+        })
+    }
+
+    fn compile(opts: &compile::CompileOpts) -> Result<Self, Self::Error> {
+        let mut iopts = opts.to_owned();
+        iopts.set_native_build(true);
+
+        let path = iopts
+            .build(&compile::ForProver::Default)
+            .map_err(BuildError::from)?;
+
+        Self::new_from_file(&path)
+    }
+
+    fn run_with_inputs<T, U>(self, public: &T, private: &U) -> Result<Self::View, Self::Error>
+    where
+        T: Serialize + Sized,
+        U: Serialize + Sized,
+    {
+        let dir = std::env::temp_dir().join(format!("nexus-tapes-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir)?;
+
+        let res = run(&self.path, &dir, public, private);
+        let _ = fs::remove_dir_all(&dir);
+        res
+    }
+
+    fn prove_with_inputs<T, U>(
+        self,
+        _pp: &Self::Params,
+        _public: &T,
+        _private: &U,
+    ) -> Result<Self::Proof, Self::Error>
+    where
+        T: Serialize + Sized,
+        U: Serialize + Sized,
+    {
+        Err(Error::ProvingUnsupported)
+    }
+}
+
+impl Drop for Native {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn run<T, U>(path: &Path, dir: &Path, public: &T, private: &U) -> Result<UncheckedView, Error>
+where
+    T: Serialize + Sized,
+    U: Serialize + Sized,
+{
+    fs::write(
+        dir.join(PUBLIC_INPUT_FILE),
+        postcard::to_stdvec(public).map_err(TapeError::from)?,
+    )?;
+    fs::write(
+        dir.join(PRIVATE_INPUT_FILE),
+        postcard::to_stdvec(private).map_err(TapeError::from)?,
+    )?;
+
+    let Output { status, stdout, stderr } = Command::new(path)
+        .env(TAPES_DIR_ENV, dir)
+        .env("RUST_BACKTRACE", "0")
+        .output()?;
+
+    let output = match fs::read(dir.join(OUTPUT_FILE)) {
+        Ok(output) => output,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let logs = String::from_utf8(stdout)
+        .map_err(TapeError::from)?
+        .split_inclusive('\n')
+        .map(String::from)
+        .collect();
+
+    let exit_code = match fs::read_to_string(dir.join(EXIT_CODE_FILE)) {
+        Ok(code) => code
+            .trim()
+            .parse()
+            .map(ExitCode)
+            .map_err(|_| Error::InvalidExitCode(code))?,
+        // the guest program returned from `main`, or panicked
+        Err(e) if e.kind() == io::ErrorKind::NotFound => match status.code() {
+            Some(PANIC_STATUS) => ExitCode::PANIC,
+            Some(code) => ExitCode(code as u32),
+            None => return Err(Error::Terminated(status)),
+        },
+        Err(e) => return Err(e.into()),
+    };
+
+    let panic_info = if exit_code == ExitCode::PANIC {
+        panic_info(&String::from_utf8_lossy(&stderr))
+    } else {
+        None
+    };
+
+    Ok(UncheckedView { output, logs, exit_code, panic_info })
+}
+
+// Extract the panic location and message from the standard error of a guest program, in the format used by the zkVM
+// runtime, i.e., without the thread name or the note on backtraces.
+fn panic_info(stderr: &str) -> Option<String> {
+    let start = stderr.find("panicked at ")?;
+    let info = &stderr[start..];
+    let end = info.find("\nnote: ").unwrap_or(info.len());
+    Some(info[..end].trim_end().to_string())
+}
+
+impl Parameters for Params {
+    type Error = Error;
+
+    fn generate_for_testing() -> Result<Self, Self::Error> {
+        Err(Error::ProvingUnsupported)
+    }
+
+    fn load(_path: &Path) -> Result<Self, Self::Error> {
+        Err(Error::ProvingUnsupported)
+    }
+
+    fn save(pp: &Self, _path: &Path) -> Result<(), Self::Error> {
+        match *pp {}
+    }
+}
+
+impl Verifiable for Proof {
+    type Params = Params;
+    type View = UncheckedView;
+    type Error = Error;
+
+    fn output<U: DeserializeOwned>(&self) -> Result<U, Self::Error> {
+        match *self {}
+    }
+
+    fn logs(&self) -> &Vec<String> {
+        match *self {}
+    }
+
+    fn exit_code(&self) -> ExitCode {
+        match *self {}
+    }
+
+    fn panic_info(&self) -> Option<&str> {
+        match *self {}
+    }
+
+    fn save(proof: &Self, _path: &Path) -> Result<(), Self::Error> {
+        match *proof {}
+    }
+
+    fn load(_path: &Path) -> Result<Self, Self::Error> {
+        Err(Error::ProvingUnsupported)
+    }
+
+    fn verify_with_exit_code<T, U>(
+        &self,
+        _pp: &Self::Params,
+        _program: &ProgramId,
+        _input: &T,
+        _output: &U,
+        _exit_code: ExitCode,
+    ) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+        U: Serialize + ?Sized,
+    {
+        match *self {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_panic_info() {
+        let stderr = "\nthread 'main' panicked at src/main.rs:5:5:\nbad input\n\
+                      note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n";
+        assert_eq!(
+            panic_info(stderr).as_deref(),
+            Some("panicked at src/main.rs:5:5:\nbad input")
+        );
+        assert_eq!(panic_info("error: something else\n"), None);
+    }
+
+    fn compile_example(binary: &str) -> Native {
+        let opts = compile::CompileOpts::new_with_custom_binary("example", binary);
+        Native::compile(&opts).expect("failed to compile guest program")
+    }
+
+    #[test]
+    fn run_examples() {
+        let view = compile_example("input_output")
+            .run_with_input(&(3u32, 5u32))
+            .unwrap();
+        assert_eq!(view.exit_code(), ExitCode(0));
+        assert_eq!(view.output::<i32>().unwrap(), 15);
+        assert_eq!(
+            view.logs(),
+            &vec!["Read private input: (3, 5)\n".to_string()]
+        );
+        assert_eq!(view.panic_info(), None);
+
+        let view = compile_example("fail").run().unwrap();
+        assert_eq!(view.exit_code(), ExitCode::PANIC);
+        assert!(view.panic_info().unwrap().starts_with("panicked at "));
+
+        // precompiles are evaluated on the host
+        let view = compile_example("keccak_precompile").run().unwrap();
+        assert_eq!(
+            view.logs(),
+            &vec!["acaf3289d7b601cbd114fb36c4d29c85bbfd5e133f14cb355c3fd8d99367964f\n".to_string()]
+        );
+    }
+
+    #[test]
+    fn remove_temporary_binary() {
+        let native = Native::new(b"not an executable").unwrap();
+        let path = native.path.clone();
+        assert!(path.exists());
+        drop(native);
+        assert!(!path.exists());
+
+        // binaries loaded from a file are kept
+        fs::write(&path, b"not an executable").unwrap();
+        drop(Native::new_from_file(&path).unwrap());
+        assert!(path.exists());
+        fs::remove_file(&path).unwrap();
+    }
+}
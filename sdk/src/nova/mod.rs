@@ -1,2 +1,6 @@
 /// Sequential (non-parallelized, non-distributed) proving for [Nova](https://eprint.iacr.org/2021/370).
 pub mod seq;
+
+/// Sequential proving for [Nova](https://eprint.iacr.org/2021/370), checking memory accesses offline rather than with
+/// Merkle proofs.
+pub mod offline;
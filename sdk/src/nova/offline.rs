@@ -0,0 +1,269 @@
+use crate::compile;
+use crate::traits::*;
+use crate::views::{CheckedView, UncheckedView};
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::Path;
+
+use nexus_core::nvm::interactive::{eval, parse_elf};
+use nexus_core::nvm::memory::OfflineMemory;
+use nexus_core::nvm::{NexusVM, Statement};
+use nexus_core::prover::nova::pp::{gen_vm_pp, load_pp_for, save_pp};
+use nexus_core::prover::nova::{prove_seq_offline, verify_seq_offline, AuditedIVCProof};
+
+use crate::error::{BuildError, TapeError};
+use nexus_core::config::vm::{NovaImpl, ProverImpl};
+use nexus_core::prover::envelope::{EnvelopeError, ProofEnvelope, ProofHeader};
+use nexus_core::prover::nova::error::ProofError;
+use nexus_core::prover::params::serialize_digest;
+
+// re-exports
+pub use super::seq::{Error, Generate};
+/// Public parameters used to prove and verify zkVM executions using offline memory checking.
+pub use nexus_core::prover::nova::types::OfflineSeqPP as PP;
+
+use std::marker::PhantomData;
+
+// hard-coded number of vm instructions to pack per recursion step
+const K: usize = 64;
+
+const PROVER: ProverImpl = ProverImpl::Nova(NovaImpl::SequentialOffline);
+
+/// Prover for the Nexus zkVM using Nova, checking memory accesses offline rather than with Merkle proofs.
+pub struct NovaOffline<C: Compute = Local> {
+    vm: NexusVM<OfflineMemory>,
+    _compute: PhantomData<C>,
+}
+
+/// A verifiable proof of a zkVM execution. Also contains a view capturing the output of the machine.
+///
+/// The proof contains a _checked_ view. Please review [`CheckedView`].
+pub struct Proof {
+    proof: AuditedIVCProof,
+    view: CheckedView,
+    header: ProofHeader,
+}
+
+impl<C: Compute> NovaOffline<C> {
+    /// The identifier of the program, which is checked when verifying proofs of its executions, see
+    /// [`crate::program_id`].
+    pub fn program_id(&self) -> ProgramId {
+        ProgramId::initial(&self.vm)
+    }
+
+    fn set_inputs<T, U>(&mut self, public: &T, private: &U) -> Result<(), Error>
+    where
+        T: Serialize + Sized,
+        U: Serialize + Sized,
+    {
+        self.vm.syscalls.set_public_input(
+            postcard::to_stdvec(public)
+                .map_err(TapeError::from)?
+                .as_slice(),
+        );
+        self.vm.syscalls.set_input(
+            postcard::to_stdvec(private)
+                .map_err(TapeError::from)?
+                .as_slice(),
+        );
+        Ok(())
+    }
+}
+
+impl Prover for NovaOffline<Local> {
+    type Memory = OfflineMemory;
+    type Params = PP;
+    type View = UncheckedView;
+    type Proof = Proof;
+    type Error = Error;
+
+    fn new(elf_bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(NovaOffline::<Local> {
+            vm: parse_elf::<Self::Memory>(elf_bytes).map_err(ProofError::from)?,
+            _compute: PhantomData,
+        })
+    }
+
+    fn compile(opts: &compile::CompileOpts) -> Result<Self, Self::Error> {
+        let mut iopts = opts.to_owned();
+
+        // if the user has not set the memory limit, default to 4mb
+        if iopts.memlimit.is_none() {
+            iopts.set_memlimit(4);
+        }
+
+        let elf_path = iopts
+            .build(&compile::ForProver::Default)
+            .map_err(BuildError::from)?;
+
+        Self::new_from_file(&elf_path)
+    }
+
+    fn run_with_inputs<T, U>(mut self, public: &T, private: &U) -> Result<Self::View, Self::Error>
+    where
+        T: Serialize + Sized,
+        U: Serialize + Sized,
+    {
+        self.set_inputs(public, private)?;
+
+        eval(&mut self.vm, false, false).map_err(ProofError::from)?;
+
+        Ok(Self::View {
+            output: self.vm.syscalls.get_output(),
+            logs: self
+                .vm
+                .syscalls
+                .get_log_buffer()
+                .into_iter()
+                .map(String::from_utf8)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(TapeError::from)?,
+
+            exit_code: self.vm.syscalls.get_exit_code(),
+            panic_info: self.vm.syscalls.get_panic_info(),
+        })
+    }
+
+    fn prove_with_inputs<T, U>(
+        mut self,
+        pp: &Self::Params,
+        public: &T,
+        private: &U,
+    ) -> Result<Self::Proof, Self::Error>
+    where
+        T: Serialize + Sized,
+        U: Serialize + Sized,
+    {
+        self.set_inputs(public, private)?;
+
+        Ok(Self::Proof {
+            proof: prove_seq_offline(pp, &mut self.vm, K).map_err(ProofError::from)?,
+            view: CheckedView {
+                output: self.vm.syscalls.get_output(),
+                logs: self
+                    .vm
+                    .syscalls
+                    .get_log_buffer()
+                    .into_iter()
+                    .map(String::from_utf8)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(TapeError::from)?,
+
+                exit_code: self.vm.syscalls.get_exit_code(),
+                panic_info: self.vm.syscalls.get_panic_info(),
+            },
+            header: ProofHeader::new(PROVER, K, false, &pp.digest)?,
+        })
+    }
+}
+
+impl Parameters for PP {
+    type Error = Error;
+
+    fn generate_for_testing() -> Result<Self, Self::Error> {
+        Ok(gen_vm_pp(K, &()).map_err(ProofError::from)?)
+    }
+
+    fn load(path: &Path) -> Result<Self, Self::Error> {
+        if let Some(path_str) = path.to_str() {
+            return Ok(load_pp_for(path_str, PROVER, K).map_err(ProofError::from)?);
+        }
+
+        Err(Self::Error::PathError(
+            crate::error::PathError::EncodingError,
+        ))
+    }
+
+    fn save(pp: &Self, path: &Path) -> Result<(), Self::Error> {
+        if let Some(path_str) = path.to_str() {
+            return Ok(save_pp(pp, PROVER, K, path_str).map_err(ProofError::from)?);
+        }
+
+        Err(Self::Error::PathError(
+            crate::error::PathError::EncodingError,
+        ))
+    }
+}
+
+impl Generate for PP {
+    type Error = Error;
+
+    fn generate() -> Result<Self, Self::Error> {
+        Ok(gen_vm_pp(K, &()).map_err(ProofError::from)?)
+    }
+}
+
+impl Verifiable for Proof {
+    type Params = PP;
+    type View = CheckedView;
+    type Error = Error;
+
+    fn output<U: DeserializeOwned>(&self) -> Result<U, Self::Error> {
+        Ok(Self::View::output::<U>(&self.view)?)
+    }
+
+    fn logs(&self) -> &Vec<String> {
+        Self::View::logs(&self.view)
+    }
+
+    fn exit_code(&self) -> ExitCode {
+        Self::View::exit_code(&self.view)
+    }
+
+    fn panic_info(&self) -> Option<&str> {
+        Self::View::panic_info(&self.view)
+    }
+
+    fn save(proof: &Self, path: &Path) -> Result<(), Self::Error> {
+        let view = Some((&proof.view).into());
+        ProofEnvelope::new(proof.header.clone(), view, &proof.proof)?.save(path)?;
+        Ok(())
+    }
+
+    fn load(path: &Path) -> Result<Self, Self::Error> {
+        let envelope = ProofEnvelope::load(path)?;
+        envelope.check(PROVER, K, false)?;
+
+        Ok(Proof {
+            proof: envelope.proof()?,
+            view: envelope
+                .view
+                .clone()
+                .ok_or(EnvelopeError::MissingView)?
+                .into(),
+            header: envelope.header,
+        })
+    }
+
+    fn verify_with_exit_code<T, U>(
+        &self,
+        pp: &Self::Params,
+        program: &ProgramId,
+        input: &T,
+        output: &U,
+        exit_code: ExitCode,
+    ) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+        U: Serialize + ?Sized,
+    {
+        let digest = serialize_digest(&pp.digest).map_err(ProofError::from)?;
+        self.header
+            .check_params(&digest)
+            .map_err(ProofError::from)?;
+
+        let statement = Statement::new(
+            *program,
+            postcard::to_stdvec(input)
+                .map_err(TapeError::from)?
+                .as_slice(),
+            postcard::to_stdvec(output)
+                .map_err(TapeError::from)?
+                .as_slice(),
+            exit_code,
+        )
+        .map_err(ProofError::from)?;
+
+        Ok(verify_seq_offline(pp, &self.proof, &statement).map_err(ProofError::from)?)
+    }
+}
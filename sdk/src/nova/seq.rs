@@ -1,20 +1,23 @@
 use crate::compile;
 use crate::traits::*;
-use crate::views::UncheckedView;
+use crate::views::{CheckedView, UncheckedView};
 
 use serde::{de::DeserializeOwned, Serialize};
 use std::path::Path;
 use thiserror::Error;
 
-use nexus_core::nvm::interactive::{eval, parse_elf, trace};
+use nexus_core::nvm::interactive::{eval, parse_elf, TraceStream};
 use nexus_core::nvm::memory::MerkleTrie;
-use nexus_core::nvm::NexusVM;
-use nexus_core::prover::nova::pp::{gen_vm_pp, load_pp, save_pp};
-use nexus_core::prover::nova::prove_seq;
+use nexus_core::nvm::{NexusVM, Statement};
+use nexus_core::prover::nova::pp::{gen_vm_pp, load_pp_for, save_pp};
 use nexus_core::prover::nova::types::IVCProof;
+use nexus_core::prover::nova::{prove_seq_stream, verify_seq};
 
 use crate::error::{BuildError, PathError, TapeError};
+use nexus_core::config::vm::{NovaImpl, ProverImpl};
+use nexus_core::prover::envelope::{EnvelopeError, ProofEnvelope, ProofHeader};
 use nexus_core::prover::nova::error::ProofError;
+use nexus_core::prover::params::serialize_digest;
 
 // re-exports
 /// Public parameters used to prove and verify zkVM executions.
@@ -25,6 +28,8 @@ use std::marker::PhantomData;
 // hard-coded number of vm instructions to pack per recursion step
 const K: usize = 64;
 
+const PROVER: ProverImpl = ProverImpl::Nova(NovaImpl::Sequential);
+
 /// Errors that occur while proving using Nova.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -47,6 +52,10 @@ pub enum Error {
     /// An error occurred reading or writing to the zkVM input/output tapes.
     #[error(transparent)]
     TapeError(#[from] TapeError),
+
+    /// An error occurred encoding or decoding a saved proof.
+    #[error(transparent)]
+    EnvelopeError(#[from] EnvelopeError),
 }
 
 /// Prover for the Nexus zkVM using Nova.
@@ -57,10 +66,44 @@ pub struct Nova<C: Compute = Local> {
 
 /// A verifiable proof of a zkVM execution. Also contains a view capturing the output of the machine.
 ///
-/// **Warning**: The proof contains an _unchecked_ view. Please review [`UncheckedView`].
+/// The proof contains a _checked_ view. Please review [`CheckedView`].
 pub struct Proof {
     proof: IVCProof,
-    view: UncheckedView,
+    view: CheckedView,
+    header: ProofHeader,
+}
+
+impl Proof {
+    /// The identifier of the proven program, see [`crate::program_id`].
+    pub fn program_id(&self) -> ProgramId {
+        ProgramId::from_state(self.proof.z_0())
+    }
+}
+
+impl<C: Compute> Nova<C> {
+    /// The identifier of the program, which is checked when verifying proofs of its executions, see
+    /// [`crate::program_id`].
+    pub fn program_id(&self) -> ProgramId {
+        ProgramId::initial(&self.vm)
+    }
+
+    fn set_inputs<T, U>(&mut self, public: &T, private: &U) -> Result<(), Error>
+    where
+        T: Serialize + Sized,
+        U: Serialize + Sized,
+    {
+        self.vm.syscalls.set_public_input(
+            postcard::to_stdvec(public)
+                .map_err(TapeError::from)?
+                .as_slice(),
+        );
+        self.vm.syscalls.set_input(
+            postcard::to_stdvec(private)
+                .map_err(TapeError::from)?
+                .as_slice(),
+        );
+        Ok(())
+    }
 }
 
 impl Prover for Nova<Local> {
@@ -92,15 +135,12 @@ impl Prover for Nova<Local> {
         Self::new_from_file(&elf_path)
     }
 
-    fn run_with_input<T>(mut self, input: &T) -> Result<Self::View, Self::Error>
+    fn run_with_inputs<T, U>(mut self, public: &T, private: &U) -> Result<Self::View, Self::Error>
     where
         T: Serialize + Sized,
+        U: Serialize + Sized,
     {
-        self.vm.syscalls.set_input(
-            postcard::to_stdvec(input)
-                .map_err(TapeError::from)?
-                .as_slice(),
-        );
+        self.set_inputs(public, private)?;
 
         eval(&mut self.vm, false, false).map_err(ProofError::from)?;
 
@@ -114,28 +154,29 @@ impl Prover for Nova<Local> {
                 .map(String::from_utf8)
                 .collect::<Result<Vec<_>, _>>()
                 .map_err(TapeError::from)?,
+
+            exit_code: self.vm.syscalls.get_exit_code(),
+            panic_info: self.vm.syscalls.get_panic_info(),
         })
     }
 
-    fn prove_with_input<T>(
+    fn prove_with_inputs<T, U>(
         mut self,
         pp: &Self::Params,
-        input: &T,
+        public: &T,
+        private: &U,
     ) -> Result<Self::Proof, Self::Error>
     where
         T: Serialize + Sized,
+        U: Serialize + Sized,
     {
-        self.vm.syscalls.set_input(
-            postcard::to_stdvec(input)
-                .map_err(TapeError::from)?
-                .as_slice(),
-        );
+        self.set_inputs(public, private)?;
 
-        let tr = trace(&mut self.vm, K, false).map_err(ProofError::from)?;
+        let tr = TraceStream::new(&mut self.vm, K, false).map_err(ProofError::from)?;
 
         Ok(Self::Proof {
-            proof: prove_seq(pp, tr).map_err(ProofError::from)?,
-            view: Self::View {
+            proof: prove_seq_stream(pp, tr).map_err(ProofError::from)?,
+            view: CheckedView {
                 output: self.vm.syscalls.get_output(),
                 logs: self
                     .vm
@@ -145,7 +186,11 @@ impl Prover for Nova<Local> {
                     .map(String::from_utf8)
                     .collect::<Result<Vec<_>, _>>()
                     .map_err(TapeError::from)?,
+
+                exit_code: self.vm.syscalls.get_exit_code(),
+                panic_info: self.vm.syscalls.get_panic_info(),
             },
+            header: ProofHeader::new(PROVER, K, false, &pp.digest)?,
         })
     }
 }
@@ -159,7 +204,7 @@ impl Parameters for PP {
 
     fn load(path: &Path) -> Result<Self, Self::Error> {
         if let Some(path_str) = path.to_str() {
-            return Ok(load_pp(path_str).map_err(ProofError::from)?);
+            return Ok(load_pp_for(path_str, PROVER, K).map_err(ProofError::from)?);
         }
 
         Err(Self::Error::PathError(
@@ -169,7 +214,7 @@ impl Parameters for PP {
 
     fn save(pp: &Self, path: &Path) -> Result<(), Self::Error> {
         if let Some(path_str) = path.to_str() {
-            return Ok(save_pp(pp, path_str).map_err(ProofError::from)?);
+            return Ok(save_pp(pp, PROVER, K, path_str).map_err(ProofError::from)?);
         }
 
         Err(Self::Error::PathError(
@@ -198,7 +243,7 @@ impl Generate for PP {
 
 impl Verifiable for Proof {
     type Params = PP;
-    type View = UncheckedView;
+    type View = CheckedView;
     type Error = Error;
 
     fn output<U: DeserializeOwned>(&self) -> Result<U, Self::Error> {
@@ -209,7 +254,64 @@ impl Verifiable for Proof {
         Self::View::logs(&self.view)
     }
 
-    fn verify(&self, pp: &Self::Params) -> Result<(), Self::Error> {
-        Ok(self.proof.verify(pp).map_err(ProofError::from)?)
+    fn exit_code(&self) -> ExitCode {
+        Self::View::exit_code(&self.view)
+    }
+
+    fn panic_info(&self) -> Option<&str> {
+        Self::View::panic_info(&self.view)
+    }
+
+    fn save(proof: &Self, path: &Path) -> Result<(), Self::Error> {
+        let view = Some((&proof.view).into());
+        ProofEnvelope::new(proof.header.clone(), view, &proof.proof)?.save(path)?;
+        Ok(())
+    }
+
+    fn load(path: &Path) -> Result<Self, Self::Error> {
+        let envelope = ProofEnvelope::load(path)?;
+        envelope.check(PROVER, K, false)?;
+
+        Ok(Proof {
+            proof: envelope.proof()?,
+            view: envelope
+                .view
+                .clone()
+                .ok_or(EnvelopeError::MissingView)?
+                .into(),
+            header: envelope.header,
+        })
+    }
+
+    fn verify_with_exit_code<T, U>(
+        &self,
+        pp: &Self::Params,
+        program: &ProgramId,
+        input: &T,
+        output: &U,
+        exit_code: ExitCode,
+    ) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+        U: Serialize + ?Sized,
+    {
+        let digest = serialize_digest(&pp.digest).map_err(ProofError::from)?;
+        self.header
+            .check_params(&digest)
+            .map_err(ProofError::from)?;
+
+        let statement = Statement::new(
+            *program,
+            postcard::to_stdvec(input)
+                .map_err(TapeError::from)?
+                .as_slice(),
+            postcard::to_stdvec(output)
+                .map_err(TapeError::from)?
+                .as_slice(),
+            exit_code,
+        )
+        .map_err(ProofError::from)?;
+
+        Ok(verify_seq(pp, &self.proof, &statement).map_err(ProofError::from)?)
     }
 }
@@ -0,0 +1,2 @@
+/// Sequential (non-parallelized, non-distributed) proving for [SuperNova](https://eprint.iacr.org/2022/1758), using a separate step circuit per class of instructions.
+pub mod seq;
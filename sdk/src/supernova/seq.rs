@@ -0,0 +1,314 @@
+use crate::compile;
+use crate::traits::*;
+use crate::views::{CheckedView, UncheckedView};
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::Path;
+use thiserror::Error;
+
+use nexus_core::nvm::interactive::{eval, parse_elf, TraceStream};
+use nexus_core::nvm::memory::MerkleTrie;
+use nexus_core::nvm::{NexusVM, Statement};
+use nexus_core::prover::supernova::pp::{gen_vm_pp, load_pp, save_pp};
+use nexus_core::prover::supernova::types::NIVCProof;
+use nexus_core::prover::supernova::{program_id, prove_seq_stream, verify_seq, K};
+
+use crate::error::{BuildError, PathError, TapeError};
+use nexus_core::config::vm::ProverImpl;
+use nexus_core::prover::envelope::{EnvelopeError, ProofEnvelope, ProofHeader};
+use nexus_core::prover::params::serialize_digest;
+use nexus_core::prover::supernova::error::ProofError;
+
+// re-exports
+/// Public parameters used to prove and verify zkVM executions.
+pub use nexus_core::prover::supernova::types::PP;
+
+use std::marker::PhantomData;
+
+const PROVER: ProverImpl = ProverImpl::SuperNova;
+
+/// Errors that occur while proving using SuperNova.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// An error occurred during parameter generation, execution, proving, or proof verification for the zkVM.
+    #[error(transparent)]
+    ProofError(#[from] ProofError),
+
+    /// An error occurred building the guest program dynamically.
+    #[error(transparent)]
+    BuildError(#[from] BuildError),
+
+    /// An error occurred reading or writing to the filesystem.
+    #[error(transparent)]
+    IOError(#[from] std::io::Error),
+
+    /// An error occurred trying to parse a path for use with the filesystem.
+    #[error(transparent)]
+    PathError(#[from] PathError),
+
+    /// An error occurred reading or writing to the zkVM input/output tapes.
+    #[error(transparent)]
+    TapeError(#[from] TapeError),
+
+    /// An error occurred encoding or decoding a saved proof.
+    #[error(transparent)]
+    EnvelopeError(#[from] EnvelopeError),
+}
+
+/// Prover for the Nexus zkVM using SuperNova.
+pub struct SuperNova<C: Compute = Local> {
+    vm: NexusVM<MerkleTrie>,
+    _compute: PhantomData<C>,
+}
+
+/// A verifiable proof of a zkVM execution. Also contains a view capturing the output of the machine.
+///
+/// The proof contains a _checked_ view. Please review [`CheckedView`].
+pub struct Proof {
+    proof: NIVCProof,
+    view: CheckedView,
+    header: ProofHeader,
+}
+
+impl Proof {
+    /// The identifier of the proven program, see [`crate::program_id`].
+    pub fn program_id(&self) -> ProgramId {
+        program_id(&self.proof)
+    }
+}
+
+impl<C: Compute> SuperNova<C> {
+    /// The identifier of the program, which is checked when verifying proofs of its executions, see
+    /// [`crate::program_id`].
+    pub fn program_id(&self) -> ProgramId {
+        ProgramId::initial(&self.vm)
+    }
+
+    fn set_inputs<T, U>(&mut self, public: &T, private: &U) -> Result<(), Error>
+    where
+        T: Serialize + Sized,
+        U: Serialize + Sized,
+    {
+        self.vm.syscalls.set_public_input(
+            postcard::to_stdvec(public)
+                .map_err(TapeError::from)?
+                .as_slice(),
+        );
+        self.vm.syscalls.set_input(
+            postcard::to_stdvec(private)
+                .map_err(TapeError::from)?
+                .as_slice(),
+        );
+        Ok(())
+    }
+}
+
+impl Prover for SuperNova<Local> {
+    type Memory = MerkleTrie;
+    type Params = PP;
+    type View = UncheckedView;
+    type Proof = Proof;
+    type Error = Error;
+
+    fn new(elf_bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(SuperNova::<Local> {
+            vm: parse_elf::<Self::Memory>(elf_bytes).map_err(ProofError::from)?,
+            _compute: PhantomData,
+        })
+    }
+
+    fn compile(opts: &compile::CompileOpts) -> Result<Self, Self::Error> {
+        let mut iopts = opts.to_owned();
+
+        // if the user has not set the memory limit, default to 4mb
+        if iopts.memlimit.is_none() {
+            iopts.set_memlimit(4);
+        }
+
+        let elf_path = iopts
+            .build(&compile::ForProver::Default)
+            .map_err(BuildError::from)?;
+
+        Self::new_from_file(&elf_path)
+    }
+
+    fn run_with_inputs<T, U>(mut self, public: &T, private: &U) -> Result<Self::View, Self::Error>
+    where
+        T: Serialize + Sized,
+        U: Serialize + Sized,
+    {
+        self.set_inputs(public, private)?;
+
+        eval(&mut self.vm, false, false).map_err(ProofError::from)?;
+
+        Ok(Self::View {
+            output: self.vm.syscalls.get_output(),
+            logs: self
+                .vm
+                .syscalls
+                .get_log_buffer()
+                .into_iter()
+                .map(String::from_utf8)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(TapeError::from)?,
+
+            exit_code: self.vm.syscalls.get_exit_code(),
+            panic_info: self.vm.syscalls.get_panic_info(),
+        })
+    }
+
+    fn prove_with_inputs<T, U>(
+        mut self,
+        pp: &Self::Params,
+        public: &T,
+        private: &U,
+    ) -> Result<Self::Proof, Self::Error>
+    where
+        T: Serialize + Sized,
+        U: Serialize + Sized,
+    {
+        self.set_inputs(public, private)?;
+
+        let tr = TraceStream::new(&mut self.vm, K, false).map_err(ProofError::from)?;
+
+        Ok(Self::Proof {
+            proof: prove_seq_stream(pp, tr).map_err(ProofError::from)?,
+            view: CheckedView {
+                output: self.vm.syscalls.get_output(),
+                logs: self
+                    .vm
+                    .syscalls
+                    .get_log_buffer()
+                    .into_iter()
+                    .map(String::from_utf8)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(TapeError::from)?,
+
+                exit_code: self.vm.syscalls.get_exit_code(),
+                panic_info: self.vm.syscalls.get_panic_info(),
+            },
+            header: ProofHeader::new(PROVER, K, false, &pp.digest)?,
+        })
+    }
+}
+
+impl Parameters for PP {
+    type Error = Error;
+
+    fn generate_for_testing() -> Result<Self, Self::Error> {
+        Ok(gen_vm_pp().map_err(ProofError::from)?)
+    }
+
+    fn load(path: &Path) -> Result<Self, Self::Error> {
+        if let Some(path_str) = path.to_str() {
+            return Ok(load_pp(path_str).map_err(ProofError::from)?);
+        }
+
+        Err(Self::Error::PathError(
+            crate::error::PathError::EncodingError,
+        ))
+    }
+
+    fn save(pp: &Self, path: &Path) -> Result<(), Self::Error> {
+        if let Some(path_str) = path.to_str() {
+            return Ok(save_pp(pp, path_str).map_err(ProofError::from)?);
+        }
+
+        Err(Self::Error::PathError(
+            crate::error::PathError::EncodingError,
+        ))
+    }
+}
+
+/// Generate a deployment-ready parameter set used for proving and verifying.
+pub trait Generate {
+    type Error;
+
+    /// Generate parameters.
+    fn generate() -> Result<Self, Self::Error>
+    where
+        Self: Sized;
+}
+
+impl Generate for PP {
+    type Error = Error;
+
+    fn generate() -> Result<Self, Self::Error> {
+        Ok(gen_vm_pp().map_err(ProofError::from)?)
+    }
+}
+
+impl Verifiable for Proof {
+    type Params = PP;
+    type View = CheckedView;
+    type Error = Error;
+
+    fn output<U: DeserializeOwned>(&self) -> Result<U, Self::Error> {
+        Ok(Self::View::output::<U>(&self.view)?)
+    }
+
+    fn logs(&self) -> &Vec<String> {
+        Self::View::logs(&self.view)
+    }
+
+    fn exit_code(&self) -> ExitCode {
+        Self::View::exit_code(&self.view)
+    }
+
+    fn panic_info(&self) -> Option<&str> {
+        Self::View::panic_info(&self.view)
+    }
+
+    fn save(proof: &Self, path: &Path) -> Result<(), Self::Error> {
+        let view = Some((&proof.view).into());
+        ProofEnvelope::new(proof.header.clone(), view, &proof.proof)?.save(path)?;
+        Ok(())
+    }
+
+    fn load(path: &Path) -> Result<Self, Self::Error> {
+        let envelope = ProofEnvelope::load(path)?;
+        envelope.check(PROVER, K, false)?;
+
+        Ok(Proof {
+            proof: envelope.proof()?,
+            view: envelope
+                .view
+                .clone()
+                .ok_or(EnvelopeError::MissingView)?
+                .into(),
+            header: envelope.header,
+        })
+    }
+
+    fn verify_with_exit_code<T, U>(
+        &self,
+        pp: &Self::Params,
+        program: &ProgramId,
+        input: &T,
+        output: &U,
+        exit_code: ExitCode,
+    ) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+        U: Serialize + ?Sized,
+    {
+        let digest = serialize_digest(&pp.digest).map_err(ProofError::from)?;
+        self.header
+            .check_params(&digest)
+            .map_err(ProofError::from)?;
+
+        let statement = Statement::new(
+            *program,
+            postcard::to_stdvec(input)
+                .map_err(TapeError::from)?
+                .as_slice(),
+            postcard::to_stdvec(output)
+                .map_err(TapeError::from)?
+                .as_slice(),
+            exit_code,
+        )
+        .map_err(ProofError::from)?;
+
+        Ok(verify_seq(pp, &self.proof, &statement).map_err(ProofError::from)?)
+    }
+}
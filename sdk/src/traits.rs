@@ -5,6 +5,8 @@ use std::path::Path;
 use crate::compile::*;
 use crate::error::*;
 
+pub use nexus_core::nvm::{program_id, ExitCode, ProgramId};
+
 /// A compute resource.
 pub trait Compute {}
 
@@ -48,8 +50,31 @@ pub trait Prover {
         Self::run_with_input::<()>(self, &())
     }
 
-    /// Run the zkVM on input of type `T` and return a view of the execution output.
-    fn run_with_input<T: Serialize + Sized>(self, input: &T) -> Result<Self::View, Self::Error>;
+    /// Run the zkVM on private input of type `T` and return a view of the execution output.
+    fn run_with_input<T: Serialize + Sized>(self, input: &T) -> Result<Self::View, Self::Error>
+    where
+        Self: Sized,
+    {
+        Self::run_with_inputs::<(), T>(self, &(), input)
+    }
+
+    /// Run the zkVM on public input of type `T` and return a view of the execution output.
+    fn run_with_public_input<T: Serialize + Sized>(
+        self,
+        input: &T,
+    ) -> Result<Self::View, Self::Error>
+    where
+        Self: Sized,
+    {
+        Self::run_with_inputs::<T, ()>(self, input, &())
+    }
+
+    /// Run the zkVM on public input of type `T` and private input of type `U` and return a view of the execution output.
+    fn run_with_inputs<T: Serialize + Sized, U: Serialize + Sized>(
+        self,
+        public: &T,
+        private: &U,
+    ) -> Result<Self::View, Self::Error>;
 
     /// Run the zkVM and return a verifiable proof, along with a view of the execution output.
     fn prove(self, pp: &Self::Params) -> Result<Self::Proof, Self::Error>
@@ -59,11 +84,27 @@ pub trait Prover {
         Self::prove_with_input::<()>(self, pp, &())
     }
 
-    /// Run the zkVM on input of type `T` and return a verifiable proof, along with a view of the execution output.
+    /// Run the zkVM on private input of type `T` and return a verifiable proof, along with a view of the execution output.
     fn prove_with_input<T: Serialize + Sized>(
         self,
         pp: &Self::Params,
         input: &T,
+    ) -> Result<Self::Proof, Self::Error>
+    where
+        Self: Sized,
+    {
+        Self::prove_with_inputs::<(), T>(self, pp, &(), input)
+    }
+
+    /// Run the zkVM on public input of type `T` and private input of type `U` and return a verifiable proof, along with a
+    /// view of the execution output.
+    ///
+    /// The public input is committed to by the proof, and must be provided again to [`Verifiable::verify`].
+    fn prove_with_inputs<T: Serialize + Sized, U: Serialize + Sized>(
+        self,
+        pp: &Self::Params,
+        public: &T,
+        private: &U,
     ) -> Result<Self::Proof, Self::Error>;
 }
 
@@ -95,6 +136,12 @@ pub trait Viewable {
 
     /// Get the logging output of the zkVM.
     fn logs(&self) -> &Vec<String>;
+
+    /// Get the exit code of the zkVM execution.
+    fn exit_code(&self) -> ExitCode;
+
+    /// Get the panic location and message, if the guest program panicked.
+    fn panic_info(&self) -> Option<&str>;
 }
 
 /// A verifiable proof of a zkVM execution. Also contains a view capturing the output of the machine.
@@ -109,6 +156,44 @@ pub trait Verifiable {
     /// Get the logging output of the zkVM.
     fn logs(&self) -> &Vec<String>;
 
-    /// Verify the proof of an execution.
-    fn verify(&self, pp: &Self::Params) -> Result<(), Self::Error>;
+    /// Get the exit code of the zkVM execution.
+    fn exit_code(&self) -> ExitCode;
+
+    /// Get the panic location and message, if the guest program panicked.
+    fn panic_info(&self) -> Option<&str>;
+
+    /// Save the proof and its view to a file, using the envelope format shared with the CLI and the network
+    /// (see [`ProofEnvelope`](nexus_core::prover::envelope::ProofEnvelope)).
+    fn save(proof: &Self, path: &Path) -> Result<(), Self::Error>;
+
+    /// Load a proof and its view from a file written by [`Verifiable::save`].
+    fn load(path: &Path) -> Result<Self, Self::Error>
+    where
+        Self: Sized;
+
+    /// Verify the proof of an execution, checking that it is of the program with identifier `program` (see
+    /// [`program_id`]), that the execution read `input` of type `T` from the public input tape, wrote `output` of type `U`
+    /// to the output tape, and exited successfully.
+    fn verify<T: Serialize + ?Sized, U: Serialize + ?Sized>(
+        &self,
+        pp: &Self::Params,
+        program: &ProgramId,
+        input: &T,
+        output: &U,
+    ) -> Result<(), Self::Error> {
+        self.verify_with_exit_code(pp, program, input, output, ExitCode::SUCCESS)
+    }
+
+    /// Verify the proof of an execution, checking that it is of the program with identifier `program` (see
+    /// [`program_id`]), that the execution read `input` of type `T` from the public input tape, wrote `output` of type `U`
+    /// to the output tape, and exited with `exit_code`. The proof commits to the exit code, so this can be used to verify
+    /// that a guest program panicked (with `ExitCode::PANIC`).
+    fn verify_with_exit_code<T: Serialize + ?Sized, U: Serialize + ?Sized>(
+        &self,
+        pp: &Self::Params,
+        program: &ProgramId,
+        input: &T,
+        output: &U,
+        exit_code: ExitCode,
+    ) -> Result<(), Self::Error>;
 }
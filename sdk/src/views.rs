@@ -1,16 +1,19 @@
 use crate::error::TapeError;
-use crate::traits::Viewable;
+use crate::traits::{ExitCode, Viewable};
 use serde::de::DeserializeOwned;
 
+use nexus_core::prover::envelope::ProofView;
+
 /// A view capturing the unchecked output of a zkVM execution.
 ///
-/// By _unchecked_, it is meant that although the zkVM proves that the guest program correctly wrote to the output tape, there is no cryptographic
-/// guarantee that the return of `output()` as accessed by the host program contains the same values that were written.
-///
-/// Support for checked views is under active development.
+/// By _unchecked_, it is meant that there is no cryptographic guarantee that the return of `output()` as accessed by the host
+/// program contains the same values that were written by the guest program. Views returned by running the zkVM without proving
+/// are unchecked. For proven executions, see [`CheckedView`].
 pub struct UncheckedView {
     pub(crate) output: Vec<u8>,
     pub(crate) logs: Vec<String>,
+    pub(crate) exit_code: ExitCode,
+    pub(crate) panic_info: Option<String>,
 }
 
 impl Viewable for UncheckedView {
@@ -21,4 +24,65 @@ impl Viewable for UncheckedView {
     fn logs(&self) -> &Vec<String> {
         &self.logs
     }
+
+    fn exit_code(&self) -> ExitCode {
+        self.exit_code
+    }
+
+    fn panic_info(&self) -> Option<&str> {
+        self.panic_info.as_deref()
+    }
+}
+
+/// A view capturing the checked output of a zkVM execution.
+///
+/// By _checked_, it is meant that the proof commits to the public input read and the output written by the guest program,
+/// through running hashes carried in the state of the step circuit, as well as to its exit code. Verifying the proof with
+/// [`Verifiable::verify`](crate::Verifiable::verify) checks these commitments against the claimed input and output, so that
+/// a verified output is the one the guest program wrote. The panic message is not committed to.
+pub struct CheckedView {
+    pub(crate) output: Vec<u8>,
+    pub(crate) logs: Vec<String>,
+    pub(crate) exit_code: ExitCode,
+    pub(crate) panic_info: Option<String>,
+}
+
+impl Viewable for CheckedView {
+    fn output<U: DeserializeOwned>(&self) -> Result<U, TapeError> {
+        Ok(postcard::from_bytes::<U>(self.output.as_slice())?)
+    }
+
+    fn logs(&self) -> &Vec<String> {
+        &self.logs
+    }
+
+    fn exit_code(&self) -> ExitCode {
+        self.exit_code
+    }
+
+    fn panic_info(&self) -> Option<&str> {
+        self.panic_info.as_deref()
+    }
+}
+
+impl From<&CheckedView> for ProofView {
+    fn from(view: &CheckedView) -> Self {
+        ProofView {
+            output: view.output.clone(),
+            logs: view.logs.clone(),
+            exit_code: view.exit_code,
+            panic_info: view.panic_info.clone(),
+        }
+    }
+}
+
+impl From<ProofView> for CheckedView {
+    fn from(view: ProofView) -> Self {
+        CheckedView {
+            output: view.output,
+            logs: view.logs,
+            exit_code: view.exit_code,
+            panic_info: view.panic_info,
+        }
+    }
 }
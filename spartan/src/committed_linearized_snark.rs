@@ -0,0 +1,450 @@
+#![allow(dead_code)]
+/// A variant of the committed relaxed SNARK for linearized R1CS instances, as produced by HyperNova folding.
+///
+/// A linearized instance consists of a commitment to the witness W, the public input (u, x), a point rx, and
+/// claimed evaluations (v_A, v_B, v_C). It is satisfied by W if ~Mz~(rx) = v_M for M in {A, B, C}, where
+/// z = (W, u, x). Since the first sum-check of Spartan has been already carried out by the folding scheme,
+/// only the second one is left to prove.
+use core::cmp::max;
+
+use ark_ec::CurveGroup;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{One, Zero};
+use merlin::Transcript;
+
+use crate::{
+  dense_mlpoly::{DensePolynomial, EqPolynomial},
+  errors::ProofVerifyError,
+  math::Math,
+  polycommitments::{PCSKeys, PolyCommitmentScheme},
+  r1csinstance::{R1CSCommitmentGens, R1CSEvalProof},
+  sparse_mlpoly::{SparsePolyEntry, SparsePolynomial},
+  sumcheck::SumcheckInstanceProof,
+  timer::Timer,
+  transcript::{AppendToTranscript, ProofTranscript},
+  ComputationCommitment, ComputationDecommitment, InputsAssignment, Instance, VarsAssignment,
+};
+
+/// `LinearizedSNARKKey` holds public parameters for producing and verifying proofs with the linearized SNARK
+#[derive(CanonicalDeserialize, CanonicalSerialize)]
+pub struct LinearizedSNARKKey<G: CurveGroup, PC: PolyCommitmentScheme<G>> {
+  pub keys: PCSKeys<G, PC>,
+  pub gens_r1cs_eval: R1CSCommitmentGens<G, PC>,
+}
+
+impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> LinearizedSNARKKey<G, PC> {
+  /// Constructs a new `LinearizedSNARKKey` given the size of the R1CS statement
+  /// `num_nz_entries` specifies the maximum number of non-zero entries in any of the three R1CS matrices
+  pub fn new(
+    SRS: &PC::SRS,
+    num_cons: usize,
+    num_vars: usize,
+    num_inputs: usize,
+    num_nz_entries: usize,
+  ) -> Self {
+    let num_vars_padded = Self::get_num_vars_padded(num_vars, num_inputs);
+    let keys = PC::trim(SRS, num_vars_padded.log_2());
+    let gens_r1cs_eval =
+      R1CSCommitmentGens::new(SRS, num_cons, num_vars_padded, num_inputs, num_nz_entries);
+    LinearizedSNARKKey {
+      keys,
+      gens_r1cs_eval,
+    }
+  }
+  fn get_num_vars_padded(num_vars: usize, num_inputs: usize) -> usize {
+    max(num_vars, num_inputs + 1).next_power_of_two()
+  }
+  pub fn get_min_num_vars(
+    num_cons: usize,
+    num_vars: usize,
+    num_inputs: usize,
+    num_nz_entries: usize,
+  ) -> usize {
+    let num_vars_padded = Self::get_num_vars_padded(num_vars, num_inputs);
+    let min_num_vars_eval =
+      R1CSCommitmentGens::<G, PC>::get_min_num_vars(num_cons, num_vars_padded, num_nz_entries);
+    max(num_vars_padded.log_2(), min_num_vars_eval)
+  }
+}
+
+/// A linearized R1CS instance, that is, a claim that ~Mz~(rx) = v_M for M in {A, B, C}, where z = (W, u, x)
+/// and W is the committed witness.
+pub struct LinearizedR1CSInstance<G: CurveGroup, PC: PolyCommitmentScheme<G>> {
+  pub input: InputsAssignment<G::ScalarField>,
+  pub u: G::ScalarField,
+  pub comm_W: PC::Commitment,
+  pub rx: Vec<G::ScalarField>,
+  pub evals: (G::ScalarField, G::ScalarField, G::ScalarField),
+}
+
+impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> LinearizedR1CSInstance<G, PC> {
+  fn append_to_transcript(&self, transcript: &mut Transcript) {
+    let (v_A, v_B, v_C) = &self.evals;
+    <Transcript as ProofTranscript<G>>::append_scalars(
+      transcript,
+      b"input",
+      &self.input.assignment,
+    );
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"u", &self.u);
+    self.comm_W.append_to_transcript(b"comm_W", transcript);
+    <Transcript as ProofTranscript<G>>::append_scalars(transcript, b"rx", &self.rx);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"v_A", v_A);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"v_B", v_B);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"v_C", v_C);
+  }
+}
+
+/// `SNARK` holds a proof produced by the linearized Spartan SNARK
+#[derive(CanonicalSerialize, CanonicalDeserialize, Debug)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct SNARK<G: CurveGroup, PC: PolyCommitmentScheme<G>> {
+  /// Sumcheck proof for the polynomial F(y) = ~Z~(y) * ~ABC~(y), where ABC(y) = \sum_t ~M~(t,y) eq(rx,t)
+  /// for M a random linear combination of A, B, and C.
+  sc_proof: SumcheckInstanceProof<G::ScalarField>,
+  /// The claimed evaluation ~W~(ry)
+  eval_vars_at_ry: G::ScalarField,
+  /// A polynomial evaluation proof of the claimed evaluation ~W~(ry) with respect to the commitment comm_W.
+  proof_eval_vars_at_ry: PC::PolyCommitmentProof,
+  inst_evals: (G::ScalarField, G::ScalarField, G::ScalarField),
+  r1cs_eval_proof: R1CSEvalProof<G, PC>,
+}
+
+impl<G: CurveGroup, PC: PolyCommitmentScheme<G>> SNARK<G, PC> {
+  fn protocol_name() -> &'static [u8] {
+    b"Spartan linearized SNARK proof"
+  }
+
+  /// A public computation to create a commitment to an R1CS instance
+  pub fn encode(
+    inst: &Instance<G::ScalarField>,
+    gens: &LinearizedSNARKKey<G, PC>,
+  ) -> (
+    ComputationCommitment<G, PC>,
+    ComputationDecommitment<G::ScalarField>,
+  ) {
+    let timer_encode = Timer::new("SNARK::encode");
+    let (comm, decomm) = inst.inst.commit(&gens.gens_r1cs_eval);
+    timer_encode.stop();
+    (
+      ComputationCommitment { comm },
+      ComputationDecommitment { decomm },
+    )
+  }
+
+  /// A method to produce a SNARK proof of the satisfiability of a linearized R1CS instance
+  pub fn prove(
+    inst: &Instance<G::ScalarField>,
+    instance: &LinearizedR1CSInstance<G, PC>,
+    vars: VarsAssignment<G::ScalarField>,
+    comm: &ComputationCommitment<G, PC>,
+    decomm: &ComputationDecommitment<G::ScalarField>,
+    key: &LinearizedSNARKKey<G, PC>,
+    transcript: &mut Transcript,
+  ) -> Self {
+    let timer_prove = Timer::new("SNARK::prove");
+
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      SNARK::<G, PC>::protocol_name(),
+    );
+    comm.comm.append_to_transcript(b"comm", transcript);
+    instance.append_to_transcript(transcript);
+
+    let LinearizedR1CSInstance {
+      input,
+      u,
+      comm_W,
+      rx,
+      evals: (v_A, v_B, v_C),
+    } = instance;
+    let input = input.assignment.as_slice();
+
+    // we might need to pad variables
+    let vars = {
+      let num_padded_vars = inst.inst.get_num_vars();
+      let num_vars = vars.assignment.len();
+      if num_padded_vars > num_vars {
+        vars.pad(num_padded_vars).assignment
+      } else {
+        vars.assignment
+      }
+    };
+    // we currently require the number of |inputs| + 1 to be at most number of vars
+    assert!(input.len() < vars.len());
+
+    let poly_vars = DensePolynomial::<G::ScalarField>::new(vars.clone());
+
+    // append input to variables to create a single vector z
+    let z = {
+      let num_inputs = input.len();
+      let num_vars = vars.len();
+      let mut z = vars;
+      z.extend(vec![*u]);
+      z.extend(input);
+      z.extend(&vec![G::ScalarField::zero(); num_vars - num_inputs - 1]);
+      z
+    };
+
+    // combine the three claims into a single claim
+    let gamma =
+      <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_gamma");
+    let gamma_sq = gamma * gamma;
+    let claim = *v_A + gamma * v_B + gamma_sq * v_C;
+
+    let timer_sc_proof = Timer::new("prove_sc");
+    let evals_ABC = {
+      let evals_rx = EqPolynomial::new(rx.clone()).evals();
+      let (evals_A, evals_B, evals_C) =
+        inst
+          .inst
+          .compute_eval_table_sparse(inst.inst.get_num_cons(), z.len(), &evals_rx);
+
+      assert_eq!(evals_A.len(), evals_B.len());
+      assert_eq!(evals_A.len(), evals_C.len());
+      (0..evals_A.len())
+        .map(|i| evals_A[i] + gamma * evals_B[i] + gamma_sq * evals_C[i])
+        .collect::<Vec<G::ScalarField>>()
+    };
+
+    let num_rounds_y = z.len().log_2();
+    let comb_func = |poly_A_comp: &G::ScalarField,
+                     poly_B_comp: &G::ScalarField|
+     -> G::ScalarField { *poly_A_comp * *poly_B_comp };
+    let (sc_proof, ry, _claims) = SumcheckInstanceProof::prove_quad::<_, G>(
+      &claim,
+      num_rounds_y,
+      &mut DensePolynomial::new(z),
+      &mut DensePolynomial::new(evals_ABC),
+      comb_func,
+      transcript,
+    );
+    timer_sc_proof.stop();
+
+    let timer_polyeval = Timer::new("polyeval");
+    let eval_vars_at_ry = poly_vars.evaluate::<G>(&ry[1..]);
+    let proof_eval_vars_at_ry = PC::prove(
+      Some(comm_W),
+      &poly_vars,
+      &ry[1..],
+      &eval_vars_at_ry,
+      &key.keys.ck,
+      transcript,
+    );
+    timer_polyeval.stop();
+
+    // We send evaluations of A, B, C at r = (rx, ry) as claims
+    // to enable the verifier complete the sum-check
+    let timer_eval = Timer::new("eval_sparse_polys");
+    let inst_evals = {
+      let (Ar, Br, Cr) = inst.inst.evaluate(rx, &ry);
+      <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Ar);
+      <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Br);
+      <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", &Cr);
+      (Ar, Br, Cr)
+    };
+    timer_eval.stop();
+
+    let r1cs_eval_proof = {
+      let proof = R1CSEvalProof::prove(
+        &decomm.decomm,
+        rx,
+        &ry,
+        &inst_evals,
+        &key.gens_r1cs_eval,
+        transcript,
+      );
+
+      let mut proof_encoded = vec![];
+      proof.serialize_compressed(&mut proof_encoded).unwrap();
+
+      Timer::print(&format!("len_r1cs_eval_proof {:?}", proof_encoded.len()));
+      proof
+    };
+
+    timer_prove.stop();
+    SNARK {
+      sc_proof,
+      eval_vars_at_ry,
+      proof_eval_vars_at_ry,
+      inst_evals,
+      r1cs_eval_proof,
+    }
+  }
+
+  /// A method to verify the SNARK proof of the satisfiability of a linearized R1CS instance
+  pub fn verify(
+    &self,
+    comm: &ComputationCommitment<G, PC>,
+    instance: &LinearizedR1CSInstance<G, PC>,
+    transcript: &mut Transcript,
+    key: &LinearizedSNARKKey<G, PC>,
+  ) -> Result<(), ProofVerifyError> {
+    let timer_verify = Timer::new("SNARK::verify");
+    <Transcript as ProofTranscript<G>>::append_protocol_name(
+      transcript,
+      SNARK::<G, PC>::protocol_name(),
+    );
+    comm.comm.append_to_transcript(b"comm", transcript);
+    instance.append_to_transcript(transcript);
+
+    let LinearizedR1CSInstance {
+      input,
+      u,
+      comm_W,
+      rx,
+      evals: (v_A, v_B, v_C),
+    } = instance;
+    let input = input.assignment.as_slice();
+
+    let (num_vars, num_cons) = (comm.comm.get_num_vars(), comm.comm.get_num_cons());
+    if input.len() != comm.comm.get_num_inputs() || rx.len() != num_cons.log_2() {
+      return Err(ProofVerifyError::InternalError);
+    }
+
+    let gamma =
+      <Transcript as ProofTranscript<G>>::challenge_scalar(transcript, b"challenge_gamma");
+    let gamma_sq = gamma * gamma;
+    let claim = *v_A + gamma * v_B + gamma_sq * v_C;
+
+    let timer_sat_proof = Timer::new("verify_sat_proof");
+    let num_rounds_y = (2 * num_vars).log_2();
+    let (claim_post, ry) = self
+      .sc_proof
+      .verify::<G>(claim, num_rounds_y, 2, transcript)?;
+
+    // verify W(ry) proof against the commitment `comm_W`
+    PC::verify(
+      comm_W,
+      &self.proof_eval_vars_at_ry,
+      &key.keys.vk,
+      transcript,
+      &ry[1..],
+      &self.eval_vars_at_ry,
+    )
+    .map_err(|_| ProofVerifyError::InternalError)?;
+
+    let poly_input_eval = {
+      // constant term
+      let mut input_as_sparse_poly_entries = vec![SparsePolyEntry::new(0, *u)];
+      //remaining inputs
+      input_as_sparse_poly_entries.extend(
+        (0..input.len())
+          .map(|i| SparsePolyEntry::new(i + 1, input[i]))
+          .collect::<Vec<SparsePolyEntry<G::ScalarField>>>(),
+      );
+      SparsePolynomial::new(num_vars.log_2(), input_as_sparse_poly_entries).evaluate(&ry[1..])
+    };
+
+    let eval_Z_at_ry =
+      (G::ScalarField::one() - ry[0]) * self.eval_vars_at_ry + ry[0] * poly_input_eval;
+
+    let (Ar, Br, Cr) = &self.inst_evals;
+    if claim_post != eval_Z_at_ry * (*Ar + gamma * Br + gamma_sq * Cr) {
+      return Err(ProofVerifyError::InternalError);
+    }
+    timer_sat_proof.stop();
+
+    let timer_eval_proof = Timer::new("verify_eval_proof");
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Ar);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Br);
+    <Transcript as ProofTranscript<G>>::append_scalar(transcript, b"Ar_claim", Cr);
+    self.r1cs_eval_proof.verify(
+      &comm.comm,
+      rx,
+      &ry,
+      &self.inst_evals,
+      &key.gens_r1cs_eval,
+      transcript,
+    )?;
+    timer_eval_proof.stop();
+    timer_verify.stop();
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::polycommitments::zeromorph::Zeromorph;
+
+  use ark_bls12_381::{Bls12_381, G1Projective};
+  use ark_std::{test_rng, UniformRand};
+
+  #[test]
+  pub fn check_linearized_snark() {
+    check_linearized_snark_helper::<G1Projective, Zeromorph<Bls12_381>>()
+  }
+
+  pub fn check_linearized_snark_helper<G: CurveGroup, PC: PolyCommitmentScheme<G>>() {
+    let num_vars = 256;
+    let num_cons = num_vars;
+    let num_inputs = 10;
+
+    let (inst, vars, inputs) =
+      Instance::<G::ScalarField>::produce_synthetic_r1cs(num_cons, num_vars, num_inputs);
+    let (num_cons, num_vars, num_inputs) = (
+      inst.inst.get_num_cons(),
+      inst.inst.get_num_vars(),
+      inst.inst.get_num_inputs(),
+    );
+
+    let min_num_vars =
+      LinearizedSNARKKey::<G, PC>::get_min_num_vars(num_cons, num_vars, num_inputs, num_cons);
+    let SRS = PC::setup(min_num_vars, b"LinearizedSNARK_SRS", &mut test_rng()).unwrap();
+    let key = LinearizedSNARKKey::<G, PC>::new(&SRS, num_cons, num_vars, num_inputs, num_cons);
+
+    // linearize the instance at a random point
+    let mut rng = test_rng();
+    let rx: Vec<G::ScalarField> = (0..num_cons.log_2())
+      .map(|_| G::ScalarField::rand(&mut rng))
+      .collect();
+    let u = G::ScalarField::one();
+    let mut z = vars.assignment.clone();
+    z.push(u);
+    z.extend(inputs.assignment.clone());
+    let (poly_A, poly_B, poly_C) =
+      inst
+        .inst
+        .multiply_vec(num_cons, num_vars + num_inputs + 1, z.as_slice());
+    let evals = (
+      poly_A.evaluate::<G>(&rx),
+      poly_B.evaluate::<G>(&rx),
+      poly_C.evaluate::<G>(&rx),
+    );
+
+    let comm_W = PC::commit(&DensePolynomial::new(vars.assignment.clone()), &key.keys.ck);
+    let instance = LinearizedR1CSInstance::<G, PC> {
+      input: inputs,
+      u,
+      comm_W,
+      rx,
+      evals,
+    };
+
+    let (comm, decomm) = SNARK::<G, PC>::encode(&inst, &key);
+
+    let mut prover_transcript = Transcript::new(b"example");
+    let proof = SNARK::<G, PC>::prove(
+      &inst,
+      &instance,
+      vars,
+      &comm,
+      &decomm,
+      &key,
+      &mut prover_transcript,
+    );
+
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm, &instance, &mut verifier_transcript, &key)
+      .is_ok());
+
+    // a wrong claimed evaluation must be rejected
+    let mut instance = instance;
+    instance.evals.0 += G::ScalarField::one();
+    let mut verifier_transcript = Transcript::new(b"example");
+    assert!(proof
+      .verify(&comm, &instance, &mut verifier_transcript, &key)
+      .is_err());
+  }
+}
@@ -9,6 +9,7 @@ extern crate rand;
 extern crate sha3;
 
 mod commitments;
+pub mod committed_linearized_snark;
 pub mod committed_relaxed_snark;
 mod crr1cs;
 pub mod crr1csproof;
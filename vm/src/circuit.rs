@@ -6,5 +6,5 @@ mod step;
 mod test;
 
 pub use r1cs::F;
-pub use riscv::ARITY;
-pub use step::build_constraints;
+pub use riscv::{OpcodeClass, ARITY};
+pub use step::{build_class_constraints, build_constraints};
@@ -1,20 +1,36 @@
 //! Generic RISC-V circuits for the Nexus VM (nexus-vm)
 
-use ark_ff::{BigInt, PrimeField};
+use ark_ff::{BigInt, Field, PrimeField};
 
 use crate::{
-    memory::MemoryProof,
+    memory::{cacheline::CACHE_BITS, layout::MemoryLayout, MemoryProof},
+    precompiles::{FIRST_PRECOMPILE, KECCAK256},
     rv32::{parse::*, *},
+    syscalls::SyscallCode,
     trace::*,
 };
 
 use super::r1cs::*;
 
 /// The arity of the NexusVM step circuit
-pub const ARITY: usize = 34;
+pub const ARITY: usize = 42;
 
 // Note: circuit generation code depends on this ordering
-// (inputs: pc,x0..31 and then outputs: PC,x'0..31)
+// (inputs: pc,x0..31,layout,in,out,exit,root and then outputs: PC,x'0..31,LAYOUT,IN,OUT,EXIT,ROOT)
+
+// Names of the memory layout variables, in the order of `layout_values`.
+// The corresponding outputs have upper-case names.
+const LAYOUT: [&str; 5] = ["exec_start", "exec_end", "ro_start", "ro_end", "top"];
+
+fn layout_values(layout: &MemoryLayout) -> [u64; 5] {
+    [
+        layout.exec_start,
+        layout.exec_end,
+        layout.ro_start,
+        layout.ro_end,
+        layout.top,
+    ]
+}
 
 #[allow(clippy::field_reassign_with_default)]
 #[allow(clippy::needless_range_loop)]
@@ -27,6 +43,12 @@ fn init_cs(w: &Witness<impl MemoryProof>) -> R1CS {
     for i in 0..32 {
         cs.set_var(&format!("x{i}"), w.regs.x[i]);
     }
+    for (name, val) in LAYOUT.iter().zip(layout_values(&w.layout)) {
+        cs.set_field_var(name, F::from(val));
+    }
+    cs.set_field_var("in", w.io.input);
+    cs.set_field_var("out", w.io.output);
+    cs.set_var("exit", w.io.exit_code.0);
     cs.set_field_var("root", w.pc_proof.commit());
 
     // outputs
@@ -34,6 +56,12 @@ fn init_cs(w: &Witness<impl MemoryProof>) -> R1CS {
     for i in 0..32 {
         cs.set_var(&format!("x'{i}"), w.regs.x[i]);
     }
+    for (name, val) in LAYOUT.iter().zip(layout_values(&w.layout)) {
+        cs.set_field_var(&name.to_uppercase(), F::from(val));
+    }
+    cs.set_field_var("IN", w.IO.input);
+    cs.set_field_var("OUT", w.IO.output);
+    cs.set_var("EXIT", w.IO.exit_code.0);
     cs.set_field_var("ROOT", w.write_proof.commit());
 
     // memory contents
@@ -447,6 +475,28 @@ fn parse_J(cs: &mut R1CS, J: u32) {
     });
     cs.mul("f3=5b", "f3=5", "inst_30");
 
+    // used to separate RV32M from RV32I ALU instructions
+    let f7m = cs.set_bit("f7=1", *cs.get_var("f7") == ONE);
+    let alui = cs.new_local_var("opcode=51,i");
+    let alum = cs.new_local_var("opcode=51,m");
+
+    cs.w[alui] = cs.get_var("opcode=51") * &(ONE - cs.w[f7m]);
+    cs.w[alum] = cs.get_var("opcode=51") * &cs.w[f7m];
+
+    // f7=1 * (f7 - 1) = 0
+    cs.constraint(|cs, a, b, _c| {
+        a[f7m] = ONE;
+        b[0] = MINUS;
+        b[cs.var("f7")] = ONE;
+    });
+    cs.constraint(|cs, a, b, c| {
+        a[0] = ONE;
+        a[f7m] = MINUS;
+        b[cs.var("opcode=51")] = ONE;
+        c[alui] = ONE;
+    });
+    cs.mul("opcode=51,m", "opcode=51", "f7=1");
+
     cs.set_eq("J=1", "opcode=55"); // lui
     cs.set_eq("J=2", "opcode=23"); // auipc
     cs.set_eq("J=3", "opcode=111"); // jal
@@ -484,28 +534,37 @@ fn parse_J(cs: &mut R1CS, J: u32) {
     cs.mul("J=27", "opcode=19", "f3=6"); // ori
     cs.mul("J=28", "opcode=19", "f3=7"); // andi
 
-    cs.mul("J=29", "opcode=51", "f3=0a"); // add
+    cs.mul("J=29", "opcode=51,i", "f3=0a"); // add
     cs.nand("J=29", "f7"); // f7 == 0 if add
-    cs.mul("J=30", "opcode=51", "f3=0b"); // sub
+    cs.mul("J=30", "opcode=51,i", "f3=0b"); // sub
     cs.nand("J=30", "f7-32"); // f7 == 32 if sub
-    cs.mul("J=31", "opcode=51", "f3=1"); // sll
+    cs.mul("J=31", "opcode=51,i", "f3=1"); // sll
     cs.nand("J=31", "f7"); // f7 == 0 if sll
-    cs.mul("J=32", "opcode=51", "f3=2"); // slt
+    cs.mul("J=32", "opcode=51,i", "f3=2"); // slt
     cs.nand("J=32", "f7"); // f7 == 0 if slt
-    cs.mul("J=33", "opcode=51", "f3=3"); // sltu
+    cs.mul("J=33", "opcode=51,i", "f3=3"); // sltu
     cs.nand("J=33", "f7"); // f7 == 0 if sltu
-    cs.mul("J=34", "opcode=51", "f3=4"); // xor
+    cs.mul("J=34", "opcode=51,i", "f3=4"); // xor
     cs.nand("J=34", "f7"); // f7 == 0 if xor
-    cs.mul("J=35", "opcode=51", "f3=5a"); // srl
+    cs.mul("J=35", "opcode=51,i", "f3=5a"); // srl
     cs.nand("J=35", "f7"); // f7 == 0 if srl
-    cs.mul("J=36", "opcode=51", "f3=5b"); // sra
+    cs.mul("J=36", "opcode=51,i", "f3=5b"); // sra
     cs.nand("J=36", "f7-32"); // f7 == 32 if sra
-    cs.mul("J=37", "opcode=51", "f3=6"); // or
+    cs.mul("J=37", "opcode=51,i", "f3=6"); // or
     cs.nand("J=37", "f7"); // f7 == 0 if or
-    cs.mul("J=38", "opcode=51", "f3=7"); // and
+    cs.mul("J=38", "opcode=51,i", "f3=7"); // and
     cs.nand("J=38", "f7"); // f7 == 0 if and
 
-    cs.set_eq("J=39", "opcode=15"); // fence
+    cs.mul("J=39", "opcode=51,m", "f3=0"); // mul
+    cs.mul("J=40", "opcode=51,m", "f3=1"); // mulh
+    cs.mul("J=41", "opcode=51,m", "f3=2"); // mulhsu
+    cs.mul("J=42", "opcode=51,m", "f3=3"); // mulhu
+    cs.mul("J=43", "opcode=51,m", "f3=4"); // div
+    cs.mul("J=44", "opcode=51,m", "f3=5"); // divu
+    cs.mul("J=45", "opcode=51,m", "f3=6"); // rem
+    cs.mul("J=46", "opcode=51,m", "f3=7"); // remu
+
+    cs.set_eq("J=47", "opcode=15"); // fence
 
     let ecall = cs.new_local_var("ecall");
     cs.w[ecall] = (ONE - cs.get_var("inst_12")) * (ONE - cs.get_var("inst_20"));
@@ -517,9 +576,9 @@ fn parse_J(cs: &mut R1CS, J: u32) {
         b[cs.var("inst_20")] = MINUS;
         c[ecall] = ONE;
     });
-    cs.mul("J=40", "opcode=115", "ecall"); // ecall
-    cs.mul("J=41", "opcode=115", "inst_20"); // ebreak
-    cs.mul("J=42", "opcode=115", "inst_12"); // unimp
+    cs.mul("J=48", "opcode=115", "ecall"); // ecall
+    cs.mul("J=49", "opcode=115", "inst_20"); // ebreak
+    cs.mul("J=50", "opcode=115", "inst_12"); // unimp
 
     // One of J=? variables hold
     cs.constraint(|cs, a, b, c| {
@@ -533,7 +592,90 @@ fn parse_J(cs: &mut R1CS, J: u32) {
     cs.seal();
 }
 
+/// Classes of instructions which are proven by separate step circuits.
+///
+/// Each class only contains the sub-circuits needed by its own
+/// instructions, so that cheap instructions do not pay for the
+/// constraints of expensive ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OpcodeClass {
+    /// LUI, AUIPC and all RV32IM arithmetic instructions.
+    Alu,
+    /// JAL, JALR and conditional branches.
+    Branch,
+    /// Loads and stores.
+    LoadStore,
+    /// FENCE, system calls, EBREAK and UNIMP.
+    System,
+    /// Calls of the Keccak-256 precompile, see [`provable`](crate::precompiles::provable).
+    Keccak256,
+}
+
+impl OpcodeClass {
+    /// All classes, ordered by their index.
+    pub const ALL: [Self; 5] = [
+        Self::Alu,
+        Self::Branch,
+        Self::LoadStore,
+        Self::System,
+        Self::Keccak256,
+    ];
+
+    /// Index of the class, which is also the index of its circuit.
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    /// Returns the class of the instruction with index `j`, see [`RV32::index_j`],
+    /// if it is not a precompile call.
+    pub const fn of(j: u32) -> Self {
+        match j {
+            1 | 2 | 19..=46 => Self::Alu,
+            3..=10 => Self::Branch,
+            11..=18 => Self::LoadStore,
+            _ => Self::System,
+        }
+    }
+
+    /// Returns the class of the instruction of `w`.
+    pub fn of_witness(w: &Witness<impl MemoryProof>) -> Self {
+        if w.J == (ECALL { rd: 0 }).index_j() && w.regs.x[18] == KECCAK256 {
+            Self::Keccak256
+        } else {
+            Self::of(w.J)
+        }
+    }
+
+    /// Returns the instruction indices contained in this class.
+    pub fn index_js(self) -> impl Iterator<Item = u32> {
+        let ecall = (ECALL { rd: 0 }).index_j();
+        (1..=RV32::MAX_J).filter(move |j| match self {
+            Self::Keccak256 => *j == ecall,
+            _ => Self::of(*j) == self,
+        })
+    }
+}
+
+/// Build the step circuit for the instruction in `vm`, covering every instruction kind.
 pub fn step(vm: &Witness<impl MemoryProof>, witness_only: bool) -> R1CS {
+    step_for(vm, None, witness_only)
+}
+
+/// Build the step circuit for `class`, which is only satisfied if the
+/// instruction in `vm` belongs to it.
+pub fn step_class(vm: &Witness<impl MemoryProof>, class: OpcodeClass, witness_only: bool) -> R1CS {
+    step_for(vm, Some(class), witness_only)
+}
+
+fn step_for(
+    vm: &Witness<impl MemoryProof>,
+    class: Option<OpcodeClass>,
+    witness_only: bool,
+) -> R1CS {
     let mut cs = init_cs(vm);
     cs.witness_only = witness_only;
 
@@ -563,24 +705,89 @@ pub fn step(vm: &Witness<impl MemoryProof>, witness_only: bool) -> R1CS {
     add_cir(&mut cs, "pc+4", "pc", "four", vm.regs.pc, 4);
     add_cir(&mut cs, "pc+I", "pc", "I", vm.regs.pc, vm.I);
 
-    // process alu first so we get definitions for common values
-    alu(&mut cs, vm);
+    layout(&mut cs);
+    fetch(&mut cs);
+
+    match class {
+        None => {
+            // process alu first so we get definitions for common values
+            alu(&mut cs, vm);
 
-    lui(&mut cs, vm);
-    auipc(&mut cs, vm);
-    jal(&mut cs, vm);
-    jalr(&mut cs, vm);
+            lui(&mut cs, vm);
+            auipc(&mut cs, vm);
+            jal(&mut cs, vm);
+            jalr(&mut cs, vm);
 
-    br(&mut cs);
+            br(&mut cs);
 
-    load(&mut cs, vm);
-    store(&mut cs, vm);
-    ecall(&mut cs, vm);
+            load(&mut cs, vm);
+            store(&mut cs, vm);
+            memory_access(&mut cs);
+            memory_lines(&mut cs, true);
+            ecall(&mut cs, vm);
+            io(&mut cs, vm);
 
-    misc(&mut cs);
+            misc(&mut cs);
+        }
+        Some(OpcodeClass::Alu) => {
+            alu(&mut cs, vm);
+
+            lui(&mut cs, vm);
+            auipc(&mut cs, vm);
+            memory_lines(&mut cs, false);
+            io(&mut cs, vm);
+        }
+        Some(OpcodeClass::Branch) => {
+            // the common values normally defined by alu
+            sub_cir(&mut cs, "X-Y", "X", "Y", vm.X, vm.Y);
+            add_cir(&mut cs, "X+I", "X", "I", vm.X, vm.I);
+
+            jal(&mut cs, vm);
+            jalr(&mut cs, vm);
+
+            br(&mut cs);
+            memory_lines(&mut cs, false);
+            io(&mut cs, vm);
+        }
+        Some(OpcodeClass::LoadStore) => {
+            add_cir(&mut cs, "X+I", "X", "I", vm.X, vm.I);
+
+            load(&mut cs, vm);
+            store(&mut cs, vm);
+            memory_access(&mut cs);
+            memory_lines(&mut cs, true);
+            io(&mut cs, vm);
+        }
+        Some(OpcodeClass::System) => {
+            ecall(&mut cs, vm);
+            memory_lines(&mut cs, false);
+            io(&mut cs, vm);
+
+            misc(&mut cs);
+        }
+        Some(OpcodeClass::Keccak256) => {
+            keccak256(&mut cs, vm);
+            io(&mut cs, vm);
+        }
+    }
+
+    let js: Vec<u32> = match class {
+        None => (1..=RV32::MAX_J).collect(),
+        Some(class) => {
+            // the instruction belongs to this class
+            cs.constraint(|cs, a, b, c| {
+                for j in class.index_js() {
+                    a[cs.var(&format!("J={j}"))] = ONE;
+                }
+                b[0] = ONE;
+                c[0] = ONE;
+            });
+            class.index_js().collect()
+        }
+    };
 
     // constrain Z and PC according to instruction index J
-    for j in 1..=RV32::MAX_J {
+    for &j in &js {
         #[rustfmt::skip]
         cs.set_var(
             &format!("JZ{j}"),
@@ -598,7 +805,7 @@ pub fn step(vm: &Witness<impl MemoryProof>, witness_only: bool) -> R1CS {
 
     // Z = Z[J]
     cs.constraint(|cs, a, b, c| {
-        for j in 1..=RV32::MAX_J {
+        for &j in &js {
             a[cs.var(&format!("JZ{j}"))] = ONE;
         }
         b[0] = ONE;
@@ -607,7 +814,7 @@ pub fn step(vm: &Witness<impl MemoryProof>, witness_only: bool) -> R1CS {
 
     // PC = PC[J]
     cs.constraint(|cs, a, b, c| {
-        for j in 1..=RV32::MAX_J {
+        for &j in &js {
             a[cs.var(&format!("JPC{j}"))] = ONE;
         }
         b[0] = ONE;
@@ -700,8 +907,11 @@ fn alu(cs: &mut R1CS, vm: &Witness<impl MemoryProof>) {
 
     bitops(cs, vm);
 
+    mul(cs, vm);
+    div(cs, vm);
+
     let start = (ALUI { aop: ADD, rd: 0, rs1: 0, imm: 0 }).index_j();
-    let end = (ALU { aop: AND, rd: 0, rs1: 0, rs2: 0 }).index_j();
+    let end = (ALU { aop: REMU, rd: 0, rs1: 0, rs2: 0 }).index_j();
     for j in start..=end {
         cs.set_eq(&format!("PC{j}"), "pc+4");
     }
@@ -1215,6 +1425,148 @@ fn store_byte(cs: &mut R1CS) {
     });
 }
 
+// memory protection
+//
+// The memory layout (see `memory::layout`) is part of the state, and is
+// constant. Its bounds are at most 2^32, and so, as the addresses, can be
+// compared using the 34-bit decomposition of their difference (offset by
+// 2^33): the top bit is set if and only if the difference is not negative.
+
+// name = (x + dx <= y), for x, y <= 2^32 + 4 and dx <= 32
+fn le_cir(cs: &mut R1CS, name: &str, x_name: &str, dx: u32, y_name: &str) {
+    let val = |cs: &R1CS, name: &str| cs.get_var(name).into_bigint().0[0];
+    let d = val(&*cs, y_name) + (1 << 33) - val(&*cs, x_name) - dx as u64;
+
+    let js: Vec<usize> = (0..33)
+        .map(|i| cs.set_bit(&format!("{name}_{i}"), (d >> i) & 1 == 1))
+        .collect();
+    let j = cs.set_bit(name, (d >> 33) & 1 == 1);
+
+    // y - x - dx + 2^33 = SUM 2^i d_i
+    cs.constraint(|cs, a, b, c| {
+        for (i, &bj) in js.iter().enumerate() {
+            a[bj] = F::from(1u64 << i);
+        }
+        a[j] = F::from(1u64 << 33);
+        b[0] = ONE;
+        c[0] = F::from((1u64 << 33) - dx as u64);
+        c[cs.var(y_name)] = ONE;
+        c[cs.var(x_name)] = MINUS;
+    });
+}
+
+fn layout(cs: &mut R1CS) {
+    for name in LAYOUT {
+        cs.constraint(|cs, a, b, c| {
+            a[cs.var(name)] = ONE;
+            b[0] = ONE;
+            c[cs.var(&name.to_uppercase())] = ONE;
+        });
+    }
+}
+
+// Set `line` to the address of the cache line containing `addr`,
+// whose bits have been computed.
+fn line_address(cs: &mut R1CS, line: &str, addr: &str) {
+    let lj = cs.new_var(line);
+    cs.w[lj] = (CACHE_BITS..32).fold(ZERO, |s, i| {
+        s + F::from(1u64 << i) * cs.get_var(&format!("{addr}_{i}"))
+    });
+    cs.constraint(|cs, a, b, c| {
+        for i in CACHE_BITS..32 {
+            a[cs.var(&format!("{addr}_{i}"))] = F::from(1u64 << i);
+        }
+        b[0] = ONE;
+        c[lj] = ONE;
+    });
+}
+
+// The addresses of the cache lines accessed by the memory proofs of a
+// step: the instruction fetch reads the line of pc, and loads and stores
+// the line of X+I. Steps which do not load or store re-read the line of
+// pc instead, see `Memory::skip`.
+fn memory_lines(cs: &mut R1CS, ldst: bool) {
+    line_address(cs, "pc_line", "pc");
+    if !ldst {
+        cs.set_eq("mem_line", "pc_line");
+        return;
+    }
+    line_address(cs, "X+I_line", "X+I");
+
+    let load = format!("opcode={OPC_LOAD}");
+    let store = format!("opcode={OPC_STORE}");
+    let j = cs.new_var("ldst");
+    cs.w[j] = *cs.get_var(&load) + cs.get_var(&store);
+    cs.add("ldst", &load, &store);
+    choose(cs, "mem_line", "ldst", "X+I_line", "pc_line");
+}
+
+// the instruction is fetched from the executable range
+fn fetch(cs: &mut R1CS) {
+    le_cir(cs, "exec_start<=pc", "exec_start", 0, "pc");
+    le_cir(cs, "pc+4<=exec_end", "pc", 4, "exec_end");
+    cs.eqi("exec_start<=pc", ONE);
+    cs.eqi("pc+4<=exec_end", ONE);
+}
+
+// The bytes [X+I, X+I+size) accessed by loads and stores are below the
+// top of memory, and are outside of the read-only range for stores.
+fn memory_access(cs: &mut R1CS) {
+    let loads = [(LB, 1), (LBU, 1), (LH, 2), (LHU, 2), (LW, 4)]
+        .map(|(lop, size)| ((LOAD { lop, rd: 0, rs1: 0, imm: 0 }).index_j(), size));
+    let stores = [(SB, 1), (SH, 2), (SW, 4)]
+        .map(|(sop, size)| ((STORE { sop, rs1: 0, rs2: 0, imm: 0 }).index_j(), size));
+    let all: Vec<(u32, u32)> = loads.iter().chain(&stores).copied().collect();
+
+    // size of the access, zero for other instructions
+    let sj = cs.new_var("size");
+    cs.w[sj] = all.iter().fold(ZERO, |s, &(J, size)| {
+        s + F::from(size) * cs.get_var(&format!("J={J}"))
+    });
+    cs.constraint(|cs, a, b, c| {
+        for &(J, size) in &all {
+            a[cs.var(&format!("J={J}"))] = F::from(size);
+        }
+        b[0] = ONE;
+        c[sj] = ONE;
+    });
+
+    let ej = cs.new_var("X+I+size");
+    cs.w[ej] = *cs.get_var("X+I") + cs.w[sj];
+    cs.add("X+I+size", "X+I", "size");
+
+    le_cir(cs, "X+I+size<=top", "X+I+size", 0, "top");
+    cs.constraint(|cs, a, b, _c| {
+        for &(J, _) in &all {
+            a[cs.var(&format!("J={J}"))] = ONE;
+        }
+        b[0] = ONE;
+        b[cs.var("X+I+size<=top")] = MINUS;
+    });
+
+    le_cir(cs, "X+I+size<=ro_start", "X+I+size", 0, "ro_start");
+    le_cir(cs, "ro_end<=X+I", "ro_end", 0, "X+I");
+
+    // in_ro = (1 - below) (1 - above)
+    let below = cs.var("X+I+size<=ro_start");
+    let above = cs.var("ro_end<=X+I");
+    let rj = cs.new_var("in_ro");
+    cs.w[rj] = (ONE - cs.w[below]) * (ONE - cs.w[above]);
+    cs.constraint(|_cs, a, b, c| {
+        a[0] = ONE;
+        a[below] = MINUS;
+        b[0] = ONE;
+        b[above] = MINUS;
+        c[rj] = ONE;
+    });
+    cs.constraint(|cs, a, b, _c| {
+        for &(J, _) in &stores {
+            a[cs.var(&format!("J={J}"))] = ONE;
+        }
+        b[rj] = ONE;
+    });
+}
+
 // shift operations
 //
 // There are two basic approaches to shift, the most obvious is
@@ -1419,10 +1771,356 @@ fn bitops(cs: &mut R1CS, vm: &Witness<impl MemoryProof>) {
     bitop(cs, &format!("Z{J}"), "Y", vm.X ^ vm.Y, F::from(-2));
 }
 
+// multiply operations
+//
+// X and Y are 32-bit values, so the full unsigned product X*Y
+// fits in 64 bits and can be computed directly in the field.
+// The high words of the signed products are derived from the
+// unsigned product by correcting for the sign bits:
+//   MULH   = hi(X*Y) - X_31 Y - Y_31 X  (mod 2^32)
+//   MULHSU = hi(X*Y) - X_31 Y           (mod 2^32)
+
+fn mul(cs: &mut R1CS, vm: &Witness<impl MemoryProof>) {
+    let O = F::from(0x100000000u64);
+    let X = vm.X;
+    let Y = vm.Y;
+
+    let XY = (X as u64) * (Y as u64);
+    let lo = XY as u32;
+    let hi = (XY >> 32) as u32;
+
+    // X*Y = lo + hi 2^32
+    let xyj = cs.set_field_var("X*Y", F::from(XY));
+    cs.mul("X*Y", "X", "Y");
+    cs.to_bits("X*Y_lo", lo);
+    cs.to_bits("X*Y_hi", hi);
+    cs.constraint(|cs, a, b, c| {
+        a[cs.var("X*Y_lo")] = ONE;
+        a[cs.var("X*Y_hi")] = O;
+        b[0] = ONE;
+        c[xyj] = ONE;
+    });
+
+    let J = (ALU { aop: MUL, rd: 0, rs1: 0, rs2: 0 }).index_j();
+    cs.set_eq(&format!("Z{J}"), "X*Y_lo");
+
+    let J = (ALU { aop: MULHU, rd: 0, rs1: 0, rs2: 0 }).index_j();
+    cs.set_eq(&format!("Z{J}"), "X*Y_hi");
+
+    // sign corrections
+    let xs = bit(X, 31);
+    let ys = bit(Y, 31);
+    let xsj = cs.set_var("X_31*Y", if xs { Y } else { 0 });
+    cs.mul("X_31*Y", "X_31", "Y");
+    let ysj = cs.set_var("Y_31*X", if ys { X } else { 0 });
+    cs.mul("Y_31*X", "Y_31", "X");
+
+    // hi - X_31 Y - Y_31 X + 2^32 k = MULH, where 0 <= k < 3
+    let J = (ALU { aop: MULH, rd: 0, rs1: 0, rs2: 0 }).index_j();
+    let z = ((X as i32 as i64 * Y as i32 as i64) >> 32) as u32;
+    let v = hi as i64 - xs as i64 * Y as i64 - ys as i64 * X as i64;
+    let k = (z as i64 - v) >> 32;
+    cs.to_bits(&format!("Z{J}"), z);
+    let k0 = cs.set_bit(&format!("Z{J}_k0"), (k & 1) != 0);
+    let k1 = cs.set_bit(&format!("Z{J}_k1"), (k & 2) != 0);
+    cs.constraint(|cs, a, b, c| {
+        a[cs.var("X*Y_hi")] = ONE;
+        a[xsj] = MINUS;
+        a[ysj] = MINUS;
+        a[k0] = O;
+        a[k1] = O * TWO;
+        b[0] = ONE;
+        c[cs.var(&format!("Z{J}"))] = ONE;
+    });
+
+    // hi - X_31 Y + 2^32 k = MULHSU, where 0 <= k < 2
+    let J = (ALU { aop: MULHSU, rd: 0, rs1: 0, rs2: 0 }).index_j();
+    let z = ((X as i32 as i64 * Y as i64) >> 32) as u32;
+    let v = hi as i64 - xs as i64 * Y as i64;
+    let k = (z as i64 - v) >> 32;
+    cs.to_bits(&format!("Z{J}"), z);
+    let k0 = cs.set_bit(&format!("Z{J}_k0"), k != 0);
+    cs.constraint(|cs, a, b, c| {
+        a[cs.var("X*Y_hi")] = ONE;
+        a[xsj] = MINUS;
+        a[k0] = O;
+        b[0] = ONE;
+        c[cs.var(&format!("Z{J}"))] = ONE;
+    });
+}
+
+// x = 0 flag for a 32-bit value, using the inverse of x as advice
+fn is_zero(cs: &mut R1CS, name: &str) {
+    let xj = cs.var(name);
+    let x = cs.w[xj];
+    let zj = cs.set_bit(&format!("{name}=0"), x == ZERO);
+    let inv = x.inverse().unwrap_or(ZERO);
+    let ij = cs.set_field_var(&format!("{name}^-1"), inv);
+
+    // x x^-1 = 1 - (x=0)
+    cs.constraint(|_cs, a, b, c| {
+        a[xj] = ONE;
+        b[ij] = ONE;
+        c[0] = ONE;
+        c[zj] = MINUS;
+    });
+
+    // x (x=0) = 0
+    cs.constraint(|_cs, a, b, _c| {
+        a[xj] = ONE;
+        b[zj] = ONE;
+    });
+}
+
+// output = sign ? -input : input  (mod 2^32)
+fn negate(cs: &mut R1CS, output: &str, input: &str, sign: &str, x: u32, s: bool) {
+    let O = F::from(0x100000000u64);
+
+    cs.to_bits(output, if s { x.wrapping_neg() } else { x });
+
+    let sx = format!("{sign}*{input}");
+    let sxj = cs.set_var(&sx, if s { x } else { 0 });
+    cs.mul(&sx, sign, input);
+
+    // carry out of 2^32 - x, only zero if x = 0
+    let cj = cs.set_bit(&format!("{output}_c"), s && x != 0);
+
+    // output + 2 sign input = input + 2^32 c
+    cs.constraint(|cs, a, b, c| {
+        a[cs.var(output)] = ONE;
+        a[sxj] = TWO;
+        b[0] = ONE;
+        c[cs.var(input)] = ONE;
+        c[cj] = O;
+    });
+}
+
+// unsigned division, using the quotient Q and remainder R as advice:
+//   X = Q Y + R, R < Y
+// Division by zero does not trap, rather Q = 2^32 - 1 and R = X.
+
+fn divu_cir(cs: &mut R1CS, x_name: &str, y_name: &str, zero_name: &str, x: u32, y: u32) {
+    let (q, r) = if y == 0 {
+        (u32::MAX, x)
+    } else {
+        (x / y, x % y)
+    };
+
+    let q_name = format!("{x_name}/{y_name}");
+    let r_name = format!("{x_name}%{y_name}");
+    cs.to_bits(&q_name, q);
+    cs.to_bits(&r_name, r);
+
+    // X = Q Y + R
+    let qy = format!("{q_name}*{y_name}");
+    cs.set_field_var(&qy, F::from(q as u64 * y as u64));
+    cs.mul(&qy, &q_name, y_name);
+    cs.add(x_name, &qy, &r_name);
+
+    // (1 - Y=0) (1 - R<Y) = 0
+    sub_cir(cs, &format!("{r_name}-{y_name}"), &r_name, y_name, r, y);
+    cs.constraint(|cs, a, b, _c| {
+        a[0] = ONE;
+        a[cs.var(zero_name)] = MINUS;
+        b[0] = ONE;
+        b[cs.var(&format!("{r_name}<{y_name}"))] = MINUS;
+    });
+
+    // Y=0 (Q - (2^32 - 1)) = 0
+    cs.constraint(|cs, a, b, _c| {
+        a[cs.var(zero_name)] = ONE;
+        b[0] = ZERO - F::from(u32::MAX);
+        b[cs.var(&q_name)] = ONE;
+    });
+}
+
+// division operations
+//
+// Signed division is reduced to unsigned division of the absolute
+// values |X| and |Y|. The quotient is negated if the signs of X and Y
+// differ (and Y is not zero), and the remainder takes the sign of X.
+// This also covers the signed overflow case, -2^31 / -1 = -2^31.
+
+fn div(cs: &mut R1CS, vm: &Witness<impl MemoryProof>) {
+    let X = vm.X;
+    let Y = vm.Y;
+
+    is_zero(cs, "Y");
+
+    divu_cir(cs, "X", "Y", "Y=0", X, Y);
+
+    let J = (ALU { aop: DIVU, rd: 0, rs1: 0, rs2: 0 }).index_j();
+    cs.set_eq(&format!("Z{J}"), "X/Y");
+
+    let J = (ALU { aop: REMU, rd: 0, rs1: 0, rs2: 0 }).index_j();
+    cs.set_eq(&format!("Z{J}"), "X%Y");
+
+    let xs = bit(X, 31);
+    let ys = bit(Y, 31);
+    let aX = (X as i32).unsigned_abs();
+    let aY = (Y as i32).unsigned_abs();
+
+    negate(cs, "|X|", "X", "X_31", X, xs);
+    negate(cs, "|Y|", "Y", "Y_31", Y, ys);
+    divu_cir(cs, "|X|", "|Y|", "Y=0", aX, aY);
+
+    // sign of quotient: (X_31 xor Y_31) (1 - Y=0)
+    let qs = xs != ys && Y != 0;
+    let j = cs.set_var("X_31*Y_31", (xs && ys) as u32);
+    cs.mul("X_31*Y_31", "X_31", "Y_31");
+    let qsj = cs.set_bit("|X|/|Y|_sb", qs);
+    cs.constraint(|cs, a, b, c| {
+        a[cs.var("X_31")] = ONE;
+        a[cs.var("Y_31")] = ONE;
+        a[j] = F::from(-2);
+        b[0] = ONE;
+        b[cs.var("Y=0")] = MINUS;
+        c[qsj] = ONE;
+    });
+
+    let (q, r) = if aY == 0 {
+        (u32::MAX, aX)
+    } else {
+        (aX / aY, aX % aY)
+    };
+
+    let J = (ALU { aop: DIV, rd: 0, rs1: 0, rs2: 0 }).index_j();
+    negate(cs, &format!("Z{J}"), "|X|/|Y|", "|X|/|Y|_sb", q, qs);
+
+    let J = (ALU { aop: REM, rd: 0, rs1: 0, rs2: 0 }).index_j();
+    negate(cs, &format!("Z{J}"), "|X|%|Y|", "X_31", r, xs);
+}
+
 fn ecall(cs: &mut R1CS, vm: &Witness<impl MemoryProof>) {
     let J = (ECALL { rd: 0 }).index_j();
     cs.set_var(&format!("Z{J}"), vm.Z);
     cs.set_eq(&format!("PC{J}"), "pc+4");
+
+    // precompiles are called in their own class (see `keccak256`), so
+    // x18 is below FIRST_PRECOMPILE, which is a power of two
+    cs.to_bits("x18", vm.regs.x[18]);
+    cs.constraint(|cs, a, b, _c| {
+        a[cs.var(&format!("J={J}"))] = ONE;
+        for i in FIRST_PRECOMPILE.trailing_zeros()..32 {
+            b[cs.var(&format!("x18_{i}"))] = ONE;
+        }
+    });
+}
+
+// Calls of the Keccak-256 precompile, see `precompiles::provable`. The
+// input is at the start of the cache line at x13, which is read, and
+// then replaced by the output, with the memory proofs of a store. The
+// hash itself is computed by the precompile gadget, see step module.
+fn keccak256(cs: &mut R1CS, vm: &Witness<impl MemoryProof>) {
+    let size = 1 << CACHE_BITS;
+
+    let J = (ECALL { rd: 0 }).index_j();
+    cs.set_var(&format!("Z{J}"), vm.Z);
+    cs.eqi(&format!("Z{J}"), F::from(size));
+    cs.set_eq(&format!("PC{J}"), "pc+4");
+
+    // x18 holds the syscall code, and x11 the address of the input
+    cs.eqi("x18", F::from(KECCAK256));
+    cs.constraint(|cs, a, b, c| {
+        a[cs.var("x11")] = ONE;
+        b[0] = ONE;
+        c[cs.var("x13")] = ONE;
+    });
+
+    // the line is aligned, and accessed in place of the line of X+I
+    cs.to_bits("x13", vm.regs.x[13]);
+    for i in 0..CACHE_BITS {
+        cs.eqi(&format!("x13_{i}"), ZERO);
+    }
+    line_address(cs, "pc_line", "pc");
+    line_address(cs, "mem_line", "x13");
+
+    // the line is below the top of memory, and outside of the
+    // read-only range, as for stores (see `memory_access`)
+    le_cir(cs, "x13+size<=top", "x13", size, "top");
+    cs.eqi("x13+size<=top", ONE);
+    le_cir(cs, "x13+size<=ro_start", "x13", size, "ro_start");
+    le_cir(cs, "ro_end<=x13", "ro_end", 0, "x13");
+    cs.constraint(|cs, a, b, _c| {
+        a[0] = ONE;
+        a[cs.var("x13+size<=ro_start")] = MINUS;
+        b[0] = ONE;
+        b[cs.var("ro_end<=x13")] = MINUS;
+    });
+}
+
+// Compute the flags and values used to update the input and output
+// hashes (see step module). A write to the output absorbs the low
+// byte of x11, and a read from the public input absorbs Z, unless
+// it is u32::MAX (the input has been exhausted).
+fn io(cs: &mut R1CS, vm: &Witness<impl MemoryProof>) {
+    let J = (ECALL { rd: 0 }).index_j();
+
+    // x18 holds the syscall code
+    let out = SyscallCode::WriteToOutput as u32;
+    let inp = SyscallCode::ReadFromPublicInput as u32;
+    let exit = SyscallCode::Exit as u32;
+    for code in [out, inp, exit] {
+        let name = format!("x18-{code}");
+        let j = cs.new_var(&name);
+        cs.w[j] = *cs.get_var("x18") - F::from(code);
+        cs.addi(&name, "x18", ZERO - F::from(code));
+        is_zero(cs, &name);
+    }
+
+    // output
+    cs.to_bits("x11", vm.regs.x[11]);
+    let bj = cs.set_var("x11&0xff", vm.regs.x[11] & 0xff);
+    cs.constraint(|cs, a, b, c| {
+        for i in 0..8 {
+            a[cs.var(&format!("x11_{i}"))] = F::from(1u64 << i);
+        }
+        b[0] = ONE;
+        c[bj] = ONE;
+    });
+
+    let j = cs.new_var("io_out");
+    cs.w[j] = cs.get_var(&format!("J={J}")) * cs.get_var(&format!("x18-{out}=0"));
+    cs.mul("io_out", &format!("J={J}"), &format!("x18-{out}=0"));
+
+    // input
+    let j = cs.new_var("Z-MAX");
+    cs.w[j] = *cs.get_var("Z") - F::from(u32::MAX);
+    cs.addi("Z-MAX", "Z", ZERO - F::from(u32::MAX));
+    is_zero(cs, "Z-MAX");
+
+    let rj = cs.new_var("io_read");
+    cs.w[rj] = cs.get_var(&format!("J={J}")) * cs.get_var(&format!("x18-{inp}=0"));
+    cs.mul("io_read", &format!("J={J}"), &format!("x18-{inp}=0"));
+
+    let j = cs.new_var("io_in");
+    cs.w[j] = cs.w[rj] * (ONE - cs.get_var("Z-MAX=0"));
+    cs.constraint(|cs, a, b, c| {
+        a[rj] = ONE;
+        b[0] = ONE;
+        b[cs.var("Z-MAX=0")] = MINUS;
+        c[j] = ONE;
+    });
+
+    // exit code: EXIT = exit + io_exit * (x11 - exit)
+    let ej = cs.new_var("io_exit");
+    cs.w[ej] = cs.get_var(&format!("J={J}")) * cs.get_var(&format!("x18-{exit}=0"));
+    cs.mul("io_exit", &format!("J={J}"), &format!("x18-{exit}=0"));
+
+    let dj = cs.new_var("exit_d");
+    cs.w[dj] = cs.w[ej] * (*cs.get_var("x11") - cs.get_var("exit"));
+    cs.constraint(|cs, a, b, c| {
+        a[ej] = ONE;
+        b[cs.var("x11")] = ONE;
+        b[cs.var("exit")] = MINUS;
+        c[dj] = ONE;
+    });
+    cs.constraint(|cs, a, b, c| {
+        a[cs.var("exit")] = ONE;
+        a[dj] = ONE;
+        b[0] = ONE;
+        c[cs.var("EXIT")] = ONE;
+    });
 }
 
 fn misc(cs: &mut R1CS) {
@@ -1702,6 +2400,105 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_mul() {
+        let mut vm = Witness::<Path>::default();
+        for x in [0u32, 1, 7, 0x7fffffff, 0x80000000, 0xfffffff9, 0xffffffff] {
+            for y in [0u32, 1, 13, 0x7fffffff, 0x80000000, 0xfffffff9, 0xffffffff] {
+                vm.X = x;
+                vm.Y = y;
+                let mut cs = R1CS::default();
+                cs.to_bits("X", x);
+                cs.to_bits("Y", y);
+
+                mul(&mut cs, &vm);
+
+                assert!(cs.is_sat());
+
+                let (sx, sy) = (x as i32 as i64, y as i32 as i64);
+                assert!(cs.get_var("Z39") == &F::from(x.wrapping_mul(y)));
+                assert!(cs.get_var("Z40") == &F::from(((sx * sy) >> 32) as u32));
+                assert!(cs.get_var("Z41") == &F::from(((sx * y as i64) >> 32) as u32));
+                assert!(cs.get_var("Z42") == &F::from(((x as u64 * y as u64) >> 32) as u32));
+            }
+        }
+    }
+
+    #[test]
+    fn test_div() {
+        let mut vm = Witness::<Path>::default();
+        for x in [0u32, 1, 7, 0x7fffffff, 0x80000000, 0xfffffff9, 0xffffffff] {
+            for y in [0u32, 1, 2, 0x7fffffff, 0x80000000, 0xfffffffe, 0xffffffff] {
+                vm.X = x;
+                vm.Y = y;
+                let mut cs = R1CS::default();
+                cs.to_bits("X", x);
+                cs.to_bits("Y", y);
+
+                div(&mut cs, &vm);
+
+                assert!(cs.is_sat());
+
+                let (div, rem) = match y {
+                    0 => (u32::MAX, x),
+                    _ => (
+                        (x as i32).wrapping_div(y as i32) as u32,
+                        (x as i32).wrapping_rem(y as i32) as u32,
+                    ),
+                };
+                let (divu, remu) = match y {
+                    0 => (u32::MAX, x),
+                    _ => (x / y, x % y),
+                };
+                assert!(cs.get_var("Z43") == &F::from(div));
+                assert!(cs.get_var("Z44") == &F::from(divu));
+                assert!(cs.get_var("Z45") == &F::from(rem));
+                assert!(cs.get_var("Z46") == &F::from(remu));
+            }
+        }
+    }
+
+    #[test]
+    fn test_io() {
+        let J = (ECALL { rd: 0 }).index_j();
+        let mut vm = Witness::<Path>::default();
+        #[rustfmt::skip]
+        let tests: [(u32, u32, u32, u32, u32, u32, u32); 8] = [
+            // (J, x18, x11, Z, io_out, io_in, EXIT)
+            (J,     3, 0x12b, 0,        1, 0, 5),
+            (J,     4, 0,     7,        0, 1, 5),
+            (J,     4, 0,     u32::MAX, 0, 0, 5),
+            (J,     2, 0,     7,        0, 0, 5),
+            (J,     8, 101,   0,        0, 0, 101),
+            (J - 1, 3, 0,     0,        0, 0, 5),
+            (J - 1, 4, 0,     7,        0, 0, 5),
+            (J - 1, 8, 101,   0,        0, 0, 5),
+        ];
+        for (j, s2, a1, z, io_out, io_in, exit) in tests {
+            vm.regs.x[11] = a1;
+            vm.regs.x[18] = s2;
+            let mut cs = R1CS::default();
+            cs.set_var("x11", a1);
+            cs.set_var("x18", s2);
+            cs.set_var("Z", z);
+            cs.set_var("exit", 5);
+            cs.set_var("EXIT", exit);
+            cs.set_bit(&format!("J={J}"), j == J);
+
+            io(&mut cs, &vm);
+
+            assert!(cs.is_sat());
+            assert_eq!(cs.get_var("io_out"), &F::from(io_out));
+            assert_eq!(cs.get_var("io_in"), &F::from(io_in));
+            assert_eq!(cs.get_var("x11&0xff"), &F::from(a1 & 0xff));
+
+            // the exit code cannot be changed by other instructions
+            let e = cs.var("EXIT");
+            cs.w[e] = F::from(exit + 1);
+            assert!(!cs.is_sat());
+        }
+    }
+
     #[test]
     fn test_memory_pc() {
         let values = [1, 2, 3, 4, 5, 6, 7, 8];
@@ -1,8 +1,13 @@
 //! Integration with ArkWorks R1CS circuits.
 
+use ark_crypto_primitives::crh::TwoToOneCRHSchemeGadget;
 use ark_r1cs_std::{
     alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
     fields::fp::{AllocatedFp, FpVar},
+    uint8::UInt8,
+    ToBitsGadget,
 };
 use ark_relations::{
     lc,
@@ -11,21 +16,28 @@ use ark_relations::{
 
 use crate::{
     error::Result,
-    memory::MemoryProof,
+    memory::{
+        path::{poseidon_config, ParamsVar, TwoToOneHashG},
+        MemoryProof,
+    },
+    precompiles::Keccak256,
     trace::{Block, Trace, Witness},
 };
 
 use super::{
     r1cs::{R1CS, V, ZERO},
-    riscv::{step, ARITY},
+    riscv::{step, step_class, OpcodeClass, ARITY},
     F,
 };
 
 type CS = ConstraintSystemRef<F>;
 
+// The addresses of the accessed cache lines are computed by the
+// memory_lines function in the riscv module.
 fn add_memory_proofs<P: MemoryProof>(
     cs: CS,
     w: &Witness<P>,
+    rcs: &R1CS,
     vars: &[FpVar<F>],
 ) -> Result<(), SynthesisError> {
     let params = P::params(cs.clone())?;
@@ -35,22 +47,70 @@ fn add_memory_proofs<P: MemoryProof>(
     let root_out = &vars[ARITY * 2];
     let mem = ARITY * 2 + 1;
 
-    w.pc_proof
-        .circuit(cs.clone(), &params, root_in, &vars[mem..])?;
-    w.read_proof
-        .circuit(cs.clone(), &params, root_in, &vars[mem + 2..])?;
-    w.write_proof
-        .circuit(cs.clone(), &params, root_out, &vars[mem + 4..])?;
+    P::step_circuit(
+        cs,
+        &params,
+        root_in,
+        root_out,
+        (&vars[rcs.var("pc_line")], &vars[rcs.var("mem_line")]),
+        (&w.pc_proof, &vars[mem..]),
+        (&w.read_proof, &vars[mem + 2..]),
+        (&w.write_proof, &vars[mem + 4..]),
+    )
+}
+
+// Update the running hashes of the input and output tapes.
+// The flags and absorbed values are computed by the io function
+// in the riscv module.
+fn add_io_hashes(cs: CS, rcs: &R1CS, vars: &[FpVar<F>]) -> Result<(), SynthesisError> {
+    let params = ParamsVar::new_constant(cs.clone(), poseidon_config())?;
+
+    // TODO: fixme (constants) - see init_cs in riscv module
+    let hashes = [(ARITY - 3, "io_in", "Z"), (ARITY - 2, "io_out", "x11&0xff")];
+
+    for (i, flag, value) in hashes {
+        let h_in = &vars[i];
+        let h_out = &vars[ARITY + i];
+        let flag = &vars[rcs.var(flag)];
+
+        let h = TwoToOneHashG::compress(&params, h_in, &vars[rcs.var(value)])?;
+        let h_sel = h_in + flag * (h - h_in);
+        h_out.enforce_equal(&h_sel)?;
+    }
 
     Ok(())
 }
 
+// Compute the hash of a call of the Keccak-256 precompile, of the first
+// x12 bytes of the cache line read, and check that it is the cache line
+// written. The address of the line is checked by the keccak256 function
+// in the riscv module.
+fn add_keccak256(rcs: &R1CS, vars: &[FpVar<F>]) -> Result<(), SynthesisError> {
+    let mut input = Vec::new();
+    for half in ["read_mem_lo", "read_mem_hi"] {
+        let bits = vars[rcs.var(half)].to_bits_le()?;
+        input.extend(bits[..128].chunks(8).map(UInt8::from_bits_le));
+    }
+
+    let output = Keccak256.bounded_circuit(&input, &vars[rcs.var("x12")])?;
+
+    let bits = output.to_bits_le()?;
+    for (half, bits) in ["write_mem_lo", "write_mem_hi"]
+        .iter()
+        .zip(bits.chunks(128))
+    {
+        vars[rcs.var(half)].enforce_equal(&Boolean::le_bits_to_fp_var(bits)?)?;
+    }
+    Ok(())
+}
+
 fn build_constraints_partial(
     cs: CS,
     witness_only: bool,
     z: &[FpVar<F>],
     w: &Witness<impl MemoryProof>,
     rcs: R1CS,
+    class: Option<OpcodeClass>,
 ) -> Result<Vec<FpVar<F>>, SynthesisError> {
     let mut vars: Vec<FpVar<F>> = Vec::new();
     let mut output: Vec<FpVar<F>> = Vec::new();
@@ -69,7 +129,11 @@ fn build_constraints_partial(
         }
     }
 
-    add_memory_proofs(cs.clone(), w, &vars)?;
+    add_memory_proofs(cs.clone(), w, &rcs, &vars)?;
+    add_io_hashes(cs.clone(), &rcs, &vars)?;
+    if class == Some(OpcodeClass::Keccak256) {
+        add_keccak256(&rcs, &vars)?;
+    }
 
     if witness_only {
         return Ok(output);
@@ -100,6 +164,28 @@ pub fn build_constraints<P: MemoryProof>(
     index: usize,
     z: &[FpVar<F>],
     tr: &Trace<P>,
+) -> Result<Vec<FpVar<F>>, SynthesisError> {
+    build_block_constraints(cs, index, z, tr, None)
+}
+
+/// Build the constraints of block `index` using the circuit of `class`, which
+/// is only satisfied if every instruction in the block belongs to it.
+pub fn build_class_constraints<P: MemoryProof>(
+    cs: CS,
+    class: OpcodeClass,
+    index: usize,
+    z: &[FpVar<F>],
+    tr: &Trace<P>,
+) -> Result<Vec<FpVar<F>>, SynthesisError> {
+    build_block_constraints(cs, index, z, tr, Some(class))
+}
+
+fn build_block_constraints<P: MemoryProof>(
+    cs: CS,
+    index: usize,
+    z: &[FpVar<F>],
+    tr: &Trace<P>,
+    class: Option<OpcodeClass>,
 ) -> Result<Vec<FpVar<F>>, SynthesisError> {
     let witness_only = !cs.should_construct_matrices();
 
@@ -111,8 +197,11 @@ pub fn build_constraints<P: MemoryProof>(
     let mut v = Vec::new();
 
     for w in b {
-        let rcs = step(&w, witness_only);
-        v = build_constraints_partial(cs.clone(), witness_only, z, &w, rcs)?;
+        let rcs = match class {
+            None => step(&w, witness_only),
+            Some(class) => step_class(&w, class, witness_only),
+        };
+        v = build_constraints_partial(cs.clone(), witness_only, z, &w, rcs, class)?;
         z = &v;
     }
 
@@ -1,15 +1,30 @@
 use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
 use ark_relations::r1cs::ConstraintSystem;
 
+use elf::abi::{PF_R, PF_W};
+
 use crate::{
     error::Result,
-    eval::NexusVM,
-    machines::loop_vm,
-    memory::{trie::MerkleTrie, Memory},
-    trace::trace,
+    eval::{eval_inst, NexusVM},
+    loader::{test_elf::TestElf, Segment},
+    machines::{lookup_test_machine, loop_vm, nop_vm},
+    memory::{
+        cacheline::CacheLine,
+        layout::MemoryLayout,
+        offline::{self, OfflineMemory},
+        trie::MerkleTrie,
+        Memory, MemoryProof,
+    },
+    parse_elf,
+    trace::{trace, Trace},
 };
 
-use super::{r1cs::R1CS, riscv::step, step::build_constraints, F};
+use super::{
+    r1cs::R1CS,
+    riscv::{step, step_class, OpcodeClass},
+    step::{build_class_constraints, build_constraints},
+    F,
+};
 
 // generate R1CS matrices
 fn vm_circuit(k: usize) -> Result<R1CS> {
@@ -45,7 +60,11 @@ fn nvm_step() {
 
 fn ark_check(mut vm: NexusVM<impl Memory>, k: usize) -> Result<()> {
     let tr = trace(&mut vm, k, false)?;
+    ark_check_trace(&tr);
+    Ok(())
+}
 
+fn ark_check_trace(tr: &Trace<impl MemoryProof>) {
     for i in 0..tr.blocks.len() {
         let cs = ConstraintSystem::<F>::new_ref();
         let inp = tr
@@ -55,10 +74,9 @@ fn ark_check(mut vm: NexusVM<impl Memory>, k: usize) -> Result<()> {
             .map(|f| FpVar::new_input(cs.clone(), || Ok(f)).unwrap())
             .collect::<Vec<_>>();
 
-        build_constraints(cs.clone(), i, &inp, &tr).unwrap();
+        build_constraints(cs.clone(), i, &inp, tr).unwrap();
         assert!(cs.is_satisfied().unwrap());
     }
-    Ok(())
 }
 
 fn ark_check_steps(k: usize) {
@@ -74,3 +92,153 @@ fn ark_step() {
         ark_check_steps(k);
     }
 }
+
+#[test]
+fn ark_step_offline() {
+    let vm = nop_vm::<OfflineMemory>(2);
+    ark_check(vm, 1).unwrap();
+
+    let vm = loop_vm::<OfflineMemory>(2);
+    ark_check(vm, 3).unwrap();
+
+    // with challenges derived from the execution
+    let mut vm = loop_vm::<OfflineMemory>(2);
+    let (tr, _) = offline::trace(&mut vm, 3, false).unwrap();
+    ark_check_trace(&tr);
+}
+
+// check that the offline memory accesses of each step are made to the
+// cache lines of the instruction, and of the loaded or stored address
+#[test]
+fn offline_memory_lines() {
+    let mut vm = lookup_test_machine::<OfflineMemory>("ldst").unwrap();
+    let (tr, _) = offline::trace(&mut vm, 1, false).unwrap();
+    ark_check_trace(&tr);
+
+    for b in &tr.blocks {
+        for w in b {
+            let mut rcs = step(&w, false);
+            assert_eq!(*rcs.get_var("pc_line"), F::from(w.pc_proof.addr));
+            assert_eq!(*rcs.get_var("mem_line"), F::from(w.read_proof.addr));
+
+            let j = rcs.var("mem_line");
+            rcs.w[j] += F::from(32u64);
+            assert!(!rcs.is_sat());
+        }
+    }
+}
+
+// check that each step is only satisfied by the circuit of its own class
+fn class_check_steps(mut vm: NexusVM<impl Memory>) -> Result<()> {
+    let tr = trace(&mut vm, 1, false)?;
+    for b in &tr.blocks {
+        for w in b {
+            let class = OpcodeClass::of_witness(&w);
+            for c in OpcodeClass::ALL {
+                assert_eq!(step_class(&w, c, false).is_sat(), c == class);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn class_step() {
+    let vm = loop_vm::<OfflineMemory>(3);
+    class_check_steps(vm).unwrap();
+
+    let vm = lookup_test_machine::<OfflineMemory>("keccak").unwrap();
+    class_check_steps(vm).unwrap();
+}
+
+// check block `i` of a trace with `k = 1`, using the circuit of its class
+fn ark_class_check(tr: &Trace<impl MemoryProof>, i: usize) -> bool {
+    let w = tr.blocks[i].into_iter().next().unwrap();
+    let cs = ConstraintSystem::<F>::new_ref();
+    let inp = tr
+        .input(i)
+        .unwrap()
+        .iter()
+        .map(|f| FpVar::new_input(cs.clone(), || Ok(f)).unwrap())
+        .collect::<Vec<_>>();
+
+    build_class_constraints(cs.clone(), OpcodeClass::of_witness(&w), i, &inp, tr).unwrap();
+    cs.is_satisfied().unwrap()
+}
+
+#[test]
+fn ark_class_step() {
+    let mut vm = loop_vm::<OfflineMemory>(2);
+    let tr = trace(&mut vm, 1, false).unwrap();
+    for i in 0..tr.blocks.len() {
+        assert!(ark_class_check(&tr, i));
+    }
+}
+
+// check that calls of the Keccak-256 precompile are only satisfied by the
+// circuit of their class, and only if they write the hash of their input
+#[test]
+fn keccak_step() {
+    let mut vm = lookup_test_machine::<MerkleTrie>("keccak").unwrap();
+    let mut tr = trace(&mut vm, 1, false).unwrap();
+    for i in 0..tr.blocks.len() {
+        assert!(ark_class_check(&tr, i));
+    }
+
+    let call = 6;
+    let w = tr.blocks[call].iter().next().unwrap();
+    assert_eq!(OpcodeClass::of_witness(&w), OpcodeClass::Keccak256);
+    assert!(!step(&w, false).is_sat());
+
+    // write another line in place of the hash, with a valid memory proof
+    let mut vm = lookup_test_machine::<MerkleTrie>("keccak").unwrap();
+    for _ in 0..call {
+        eval_inst(&mut vm).unwrap();
+    }
+    let proof = vm
+        .mem
+        .update(0x100, |cl| {
+            *cl = CacheLine::from([1u8; 32]);
+            Ok(())
+        })
+        .unwrap();
+    tr.blocks[call].steps[0].write_proof = Some(proof);
+
+    let w = tr.blocks[call].iter().next().unwrap();
+    assert!(step_class(&w, OpcodeClass::Keccak256, false).is_sat());
+    assert!(!ark_class_check(&tr, call));
+}
+
+// check that steps violating the memory layout are not satisfied
+#[test]
+fn memory_layout() {
+    // sw x0, 0x100(x0); lw x1, 0x100(x0); unimp
+    let mut elf = TestElf::new(&[0x10002023, 0x10002083, 0xc0001073]);
+    elf.segments.push(Segment {
+        vaddr: 0x100,
+        memsz: 4,
+        data: Vec::new(),
+        flags: PF_R | PF_W,
+    });
+    let mut vm = parse_elf::<OfflineMemory>(&elf.build()).unwrap();
+    let tr = trace(&mut vm, 1, false).unwrap();
+
+    let check = |i: usize, f: fn(&mut MemoryLayout)| {
+        let mut w = tr.blocks[i].iter().next().unwrap();
+        f(&mut w.layout);
+        let sat = step(&w, false).is_sat();
+        let class = OpcodeClass::of_witness(&w);
+        assert_eq!(step_class(&w, class, false).is_sat(), sat);
+        sat
+    };
+
+    for i in 0..3 {
+        assert!(check(i, |_| ()));
+        assert!(!check(i, |l| l.exec_start = 12));
+    }
+    assert!(!check(0, |l| l.ro_end = 0x104));
+    assert!(check(1, |l| l.ro_end = 0x104));
+    assert!(!check(0, |l| l.top = 0x102));
+    assert!(!check(1, |l| l.top = 0x102));
+    assert!(check(2, |l| l.top = 0x102));
+}
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::memory::layout::Access;
+
 /// Errors related to VM initialization and execution
 #[derive(Debug, Error)]
 pub enum NexusVMError {
@@ -19,6 +21,18 @@ pub enum NexusVMError {
     #[error("unknown ecall {1} at pc:{0:x}")]
     UnknownECall(u32, u32),
 
+    /// Syscall number is reserved or already in use
+    #[error("invalid precompile syscall number {0}")]
+    InvalidPrecompile(u32),
+
+    /// The precompile call is not constrained by the step circuits
+    #[error("precompile syscall {1} at pc:{0:x} cannot be proven")]
+    UnprovablePrecompile(u32, u32),
+
+    /// The output of a precompile does not fill a cache line
+    #[error("precompile syscall {1} at pc:{0:x} returned {2} bytes")]
+    InvalidPrecompileOutput(u32, u32, usize),
+
     /// An I/O error occurred
     #[error(transparent)]
     IOError(#[from] std::io::Error),
@@ -35,6 +49,10 @@ pub enum NexusVMError {
     #[error("ELF format not supported: {0}")]
     ELFFormat(&'static str),
 
+    /// Memory access not permitted by the memory layout of the program
+    #[error("invalid {0} access to memory {2:x} at pc:{1:x}")]
+    MemoryFault(Access, u32, u32),
+
     /// Invalid memory alignment
     #[error("misaligned memory access {0:x}")]
     Misaligned(u32),
@@ -47,6 +65,18 @@ pub enum NexusVMError {
     #[error("reached maximum number of executed instructions: {0}")]
     MaxTraceLengthExceeded(usize),
 
+    /// An error occurred while (de)serializing a snapshot
+    #[error(transparent)]
+    SerializationError(#[from] ark_serialize::SerializationError),
+
+    /// Snapshot was written with an unsupported format version
+    #[error("unsupported snapshot version {0}")]
+    SnapshotVersion(u32),
+
+    /// A trace can only be resumed at a block boundary
+    #[error("cannot resume trace after {0} instructions with k = {1}")]
+    MisalignedTrace(usize, usize),
+
     /// Benchmark labels are invalid.
     #[error("Labels are invalid.")]
     InvalidProfileLabel,
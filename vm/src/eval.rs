@@ -2,7 +2,12 @@
 
 use crate::{
     error::*,
-    memory::Memory,
+    memory::{
+        cacheline::{CacheLine, CACHE_BITS},
+        layout::{Access, MemoryLayout},
+        Memory,
+    },
+    profiler::Profiler,
     rv32::{parse::*, *},
     syscalls::{SyscallCode, Syscalls},
 };
@@ -46,6 +51,10 @@ pub struct NexusVM<M: Memory> {
     pub cycle_count: u64,
     /// The cycles tracker label: (func_name, (cycle_count, counter))
     pub cycle_tracker: HashMap<String, (u64, u32)>,
+    /// Optional profiler, recording every executed instruction.
+    pub profiler: Option<Profiler>,
+    /// Memory protection regions of the program.
+    pub layout: MemoryLayout,
 }
 
 /// ISA defined registers
@@ -191,6 +200,27 @@ fn alu_op(aop: AOP, x: u32, y: u32) -> u32 {
         AND => x & y,
         OR => x | y,
         XOR => x ^ y,
+        MUL => x.wrapping_mul(y),
+        MULH => (((x as i32 as i64) * (y as i32 as i64)) >> 32) as u32,
+        MULHSU => (((x as i32 as i64) * (y as i64)) >> 32) as u32,
+        MULHU => (((x as u64) * (y as u64)) >> 32) as u32,
+        // division by zero and overflow do not trap (pg. 45)
+        DIV => match y {
+            0 => u32::MAX,
+            _ => (x as i32).wrapping_div(y as i32) as u32,
+        },
+        DIVU => match y {
+            0 => u32::MAX,
+            _ => x / y,
+        },
+        REM => match y {
+            0 => x,
+            _ => (x as i32).wrapping_rem(y as i32) as u32,
+        },
+        REMU => match y {
+            0 => x,
+            _ => x % y,
+        },
     }
 }
 
@@ -236,6 +266,38 @@ fn end_profile(vm: &mut NexusVM<impl Memory>, fn_name: String) -> Result<()> {
     Ok(())
 }
 
+// Evaluate the precompile called by the current instruction on the a2
+// bytes at a1, and write its output to the cache line at a3, returning
+// the length of the output. The line is read and written as by a store.
+fn eval_precompile(vm: &mut NexusVM<impl Memory>) -> Result<u32> {
+    let pc = vm.regs.pc;
+    let [code, src, len, dst] = [18, 11, 12, 13].map(|i| vm.regs.x[i]);
+    let p = vm
+        .syscalls
+        .precompiles()
+        .get(code)
+        .ok_or(NexusVMError::UnknownECall(pc, code))?;
+
+    if dst % (1 << CACHE_BITS) != 0 {
+        return Err(NexusVMError::Misaligned(dst));
+    }
+    vm.layout.check(Access::Read, pc, src, len)?;
+    vm.layout.check(Access::Write, pc, dst, 1 << CACHE_BITS)?;
+
+    let output = p.eval(&vm.mem.load_n(src, len)?)?;
+    let line: [u8; 32] = output
+        .try_into()
+        .map_err(|o: Vec<u8>| NexusVMError::InvalidPrecompileOutput(pc, code, o.len()))?;
+
+    let (_, proof) = vm.mem.query(dst);
+    vm.read_proof = Some(proof);
+    vm.write_proof = Some(vm.mem.update(dst, |cl| {
+        *cl = CacheLine::from(line);
+        Ok(())
+    })?);
+    Ok(line.len() as u32)
+}
+
 /// evaluate next instruction
 pub fn eval_inst(vm: &mut NexusVM<impl Memory>) -> Result<()> {
     if vm
@@ -245,6 +307,8 @@ pub fn eval_inst(vm: &mut NexusVM<impl Memory>) -> Result<()> {
         return Err(NexusVMError::MaxTraceLengthExceeded(vm.trace_len));
     }
 
+    vm.layout
+        .check(Access::Execute, vm.regs.pc, vm.regs.pc, 4)?;
     let (word, proof) = vm.mem.read_inst(vm.regs.pc)?;
     vm.inst = parse_inst(vm.regs.pc, &word.to_le_bytes())?;
 
@@ -292,6 +356,13 @@ pub fn eval_inst(vm: &mut NexusVM<impl Memory>) -> Result<()> {
             RD = rd;
 
             let addr = add32(X, imm);
+            let size = match lop {
+                LB | LBU => 1,
+                LH | LHU => 2,
+                LW => 4,
+            };
+            vm.layout.check(Access::Read, vm.regs.pc, addr, size)?;
+
             let (val, proof) = vm.mem.load(lop, addr)?;
             vm.read_proof = Some(proof);
             vm.Z = val;
@@ -301,11 +372,12 @@ pub fn eval_inst(vm: &mut NexusVM<impl Memory>) -> Result<()> {
             let Y = vm.get_reg(rs2);
 
             let addr = add32(X, imm);
-            let lop = match sop {
-                SB => LB,
-                SH => LH,
-                SW => LW,
+            let (lop, size) = match sop {
+                SB => (LB, 1),
+                SH => (LH, 2),
+                SW => (LW, 4),
             };
+            vm.layout.check(Access::Write, vm.regs.pc, addr, size)?;
 
             let (_, proof) = vm.mem.load(lop, addr)?;
             vm.read_proof = Some(proof);
@@ -326,7 +398,11 @@ pub fn eval_inst(vm: &mut NexusVM<impl Memory>) -> Result<()> {
         EBREAK { .. } => {}
         ECALL { rd } => {
             RD = rd;
-            vm.Z = vm.syscalls.syscall(vm.regs.pc, vm.regs.x, &vm.mem)?;
+            if vm.syscalls.precompiles().get(vm.regs.x[18]).is_some() {
+                vm.Z = eval_precompile(vm)?;
+            } else {
+                vm.Z = vm.syscalls.syscall(vm.regs.pc, vm.regs.x, &vm.mem)?;
+            }
             // Profile cycles
             if vm.regs.x[18] == SyscallCode::ProfileCycles as u32 {
                 handle_profile_cycles(vm)?;
@@ -337,6 +413,15 @@ pub fn eval_inst(vm: &mut NexusVM<impl Memory>) -> Result<()> {
         }
     }
 
+    // some memory controllers prove a load and a store for every step
+    if vm.read_proof.is_none() {
+        vm.read_proof = vm.mem.skip(&vm.pc_proof);
+    }
+    if vm.write_proof.is_none() {
+        let prev = vm.read_proof.as_ref().unwrap_or(&vm.pc_proof);
+        vm.write_proof = vm.mem.skip(prev);
+    }
+
     // Counts cycles per instruction kind.
     // In RISC-V:
     // - Memory instructions are 3 cycles
@@ -353,6 +438,10 @@ pub fn eval_inst(vm: &mut NexusVM<impl Memory>) -> Result<()> {
         _ => 0,
     };
 
+    if let Some(p) = vm.profiler.as_mut() {
+        p.record(&vm.inst, cycles);
+    }
+
     if PC == 0 {
         PC = add32(vm.inst.pc, vm.inst.len);
     }
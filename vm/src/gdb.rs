@@ -0,0 +1,574 @@
+//! A GDB remote serial protocol stub for the Nexus VM
+//!
+//! The stub allows a debugger such as `riscv32-elf-gdb` (or lldb) to
+//! attach to a guest program running in a `NexusVM`. The stub listens
+//! on a local TCP port, and drives the VM one instruction at a time
+//! using `eval_inst`. It supports reading and writing registers and
+//! memory, single-stepping, continuing, software and hardware
+//! breakpoints, and interrupting a running program.
+//!
+//! A typical session looks like:
+//!
+//! ```text
+//! $ cargo nexus run --gdb
+//! $ riscv32-elf-gdb target/riscv32im-unknown-none-elf/debug/guest
+//! (gdb) target remote :1234
+//! ```
+//!
+//! The guest reaching an UNIMP instruction is reported to the debugger
+//! as a normal program exit, and errors raised by the VM (for instance,
+//! an invalid instruction) are reported as SIGILL.
+
+use std::collections::HashSet;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use crate::error::Result;
+use crate::eval::{eval_inst, NexusVM};
+use crate::memory::Memory;
+use crate::rv32::{LOP, RV32::UNIMP, SOP};
+
+/// Default TCP port used by the stub.
+pub const DEFAULT_PORT: u16 = 1234;
+
+const LOG_TARGET: &str = "nexus-vm::gdb";
+
+// maximum size of a packet, advertised to the debugger
+const PACKET_SIZE: usize = 0x4000;
+
+// signal numbers used in stop replies
+const SIGINT: u8 = 2;
+const SIGILL: u8 = 4;
+const SIGTRAP: u8 = 5;
+
+// number of instructions executed between checks for an interrupt
+const POLL_INTERVAL: usize = 1024;
+
+const TARGET_XML: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE target SYSTEM "gdb-target.dtd">
+<target version="1.0">
+<architecture>riscv:rv32</architecture>
+<feature name="org.gnu.gdb.riscv.cpu">
+<reg name="zero" bitsize="32" type="int" regnum="0"/>
+<reg name="ra" bitsize="32" type="code_ptr"/>
+<reg name="sp" bitsize="32" type="data_ptr"/>
+<reg name="gp" bitsize="32" type="data_ptr"/>
+<reg name="tp" bitsize="32" type="data_ptr"/>
+<reg name="t0" bitsize="32" type="int"/>
+<reg name="t1" bitsize="32" type="int"/>
+<reg name="t2" bitsize="32" type="int"/>
+<reg name="fp" bitsize="32" type="data_ptr"/>
+<reg name="s1" bitsize="32" type="int"/>
+<reg name="a0" bitsize="32" type="int"/>
+<reg name="a1" bitsize="32" type="int"/>
+<reg name="a2" bitsize="32" type="int"/>
+<reg name="a3" bitsize="32" type="int"/>
+<reg name="a4" bitsize="32" type="int"/>
+<reg name="a5" bitsize="32" type="int"/>
+<reg name="a6" bitsize="32" type="int"/>
+<reg name="a7" bitsize="32" type="int"/>
+<reg name="s2" bitsize="32" type="int"/>
+<reg name="s3" bitsize="32" type="int"/>
+<reg name="s4" bitsize="32" type="int"/>
+<reg name="s5" bitsize="32" type="int"/>
+<reg name="s6" bitsize="32" type="int"/>
+<reg name="s7" bitsize="32" type="int"/>
+<reg name="s8" bitsize="32" type="int"/>
+<reg name="s9" bitsize="32" type="int"/>
+<reg name="s10" bitsize="32" type="int"/>
+<reg name="s11" bitsize="32" type="int"/>
+<reg name="t3" bitsize="32" type="int"/>
+<reg name="t4" bitsize="32" type="int"/>
+<reg name="t5" bitsize="32" type="int"/>
+<reg name="t6" bitsize="32" type="int"/>
+<reg name="pc" bitsize="32" type="code_ptr"/>
+</feature>
+</target>
+"#;
+
+/// Wait for a debugger to connect on `localhost:port`, and serve
+/// requests for `vm` until the debugger detaches or kills the program.
+pub fn listen<M: Memory>(vm: &mut NexusVM<M>, port: u16) -> Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    tracing::info!(
+        target: LOG_TARGET,
+        addr = %listener.local_addr()?,
+        "Waiting for debugger",
+    );
+    let (stream, addr) = listener.accept()?;
+    tracing::info!(
+        target: LOG_TARGET,
+        %addr,
+        "Debugger connected",
+    );
+    GdbStub::new(vm, stream)?.run()
+}
+
+/// Why the VM stopped executing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stop {
+    Signal(u8),
+    Exited,
+}
+
+/// A remote serial protocol session for a single VM.
+pub struct GdbStub<'a, M: Memory> {
+    vm: &'a mut NexusVM<M>,
+    stream: TcpStream,
+    breakpoints: HashSet<u32>,
+    exited: bool,
+}
+
+impl<'a, M: Memory> GdbStub<'a, M> {
+    /// Create a session for `vm` over a connected `stream`.
+    pub fn new(vm: &'a mut NexusVM<M>, stream: TcpStream) -> Result<Self> {
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            vm,
+            stream,
+            breakpoints: HashSet::new(),
+            exited: false,
+        })
+    }
+
+    /// Serve requests until the debugger detaches, kills the program,
+    /// or closes the connection.
+    pub fn run(&mut self) -> Result<()> {
+        while let Some(packet) = self.read_packet()? {
+            let reply = match packet.as_bytes().first() {
+                Some(b'D') => {
+                    self.write_packet("OK")?;
+                    return Ok(());
+                }
+                Some(b'k') => return Ok(()),
+                _ => self.handle(&packet),
+            };
+            self.write_packet(&reply)?;
+        }
+        Ok(())
+    }
+
+    // Compute the reply to a single packet. Unsupported packets
+    // get an empty reply, as required by the protocol.
+    fn handle(&mut self, packet: &str) -> String {
+        if packet.is_empty() {
+            return String::new();
+        }
+        let (cmd, args) = packet.split_at(1);
+        match cmd {
+            "?" => self.stop_reply(Stop::Signal(SIGTRAP)),
+            "g" => self.read_registers(),
+            "G" => self.write_registers(args),
+            "p" => self.read_register(args),
+            "P" => self.write_register(args),
+            "m" => self.read_memory(args),
+            "M" => self.write_memory(args),
+            "s" => {
+                let stop = self.step();
+                self.stop_reply(stop)
+            }
+            "c" => {
+                let stop = self.resume();
+                self.stop_reply(stop)
+            }
+            "Z" => self.breakpoint(args, true),
+            "z" => self.breakpoint(args, false),
+            "H" | "T" => "OK".into(),
+            "q" => self.query(args),
+            _ => String::new(),
+        }
+    }
+
+    fn query(&self, args: &str) -> String {
+        if args.starts_with("Supported") {
+            format!("PacketSize={PACKET_SIZE:x};qXfer:features:read+")
+        } else if args == "Attached" {
+            "1".into()
+        } else if args == "C" {
+            "QC1".into()
+        } else if args == "fThreadInfo" {
+            "m1".into()
+        } else if args == "sThreadInfo" {
+            "l".into()
+        } else if let Some(range) = args.strip_prefix("Xfer:features:read:target.xml:") {
+            match parse_pair(range, ',') {
+                Some((offset, len)) => xfer(TARGET_XML, offset as usize, len as usize),
+                None => "E01".into(),
+            }
+        } else {
+            String::new()
+        }
+    }
+
+    fn stop_reply(&self, stop: Stop) -> String {
+        match stop {
+            Stop::Signal(sig) => format!("S{sig:02x}"),
+            Stop::Exited => "W00".into(),
+        }
+    }
+
+    // Execute a single instruction.
+    fn step(&mut self) -> Stop {
+        if self.exited {
+            return Stop::Exited;
+        }
+        if let Err(e) = eval_inst(self.vm) {
+            tracing::warn!(target: LOG_TARGET, "{e}");
+            return Stop::Signal(SIGILL);
+        }
+        if self.vm.inst.inst == UNIMP {
+            self.exited = true;
+            return Stop::Exited;
+        }
+        Stop::Signal(SIGTRAP)
+    }
+
+    // Execute instructions until a breakpoint is hit, the program
+    // exits, or the debugger sends an interrupt.
+    fn resume(&mut self) -> Stop {
+        let mut count = 0;
+        loop {
+            let stop = self.step();
+            if stop != Stop::Signal(SIGTRAP) || self.breakpoints.contains(&self.vm.regs.pc) {
+                return stop;
+            }
+            count += 1;
+            if count % POLL_INTERVAL == 0 && self.interrupted() {
+                return Stop::Signal(SIGINT);
+            }
+        }
+    }
+
+    // Check, without blocking, whether the debugger sent an interrupt.
+    fn interrupted(&mut self) -> bool {
+        let mut buf = [0u8; 1];
+        if self.stream.set_nonblocking(true).is_err() {
+            return false;
+        }
+        let res = self.stream.read(&mut buf);
+        let _ = self.stream.set_nonblocking(false);
+        matches!(res, Ok(1) if buf[0] == 0x03)
+    }
+
+    fn read_registers(&self) -> String {
+        (0..33).map(|r| self.register(r).unwrap()).collect()
+    }
+
+    fn write_registers(&mut self, args: &str) -> String {
+        for (r, chunk) in args.as_bytes().chunks(8).enumerate().take(33) {
+            match std::str::from_utf8(chunk).ok().and_then(parse_le) {
+                Some(val) => self.set_register(r, val),
+                None => return "E01".into(),
+            }
+        }
+        "OK".into()
+    }
+
+    fn read_register(&self, args: &str) -> String {
+        match usize::from_str_radix(args, 16)
+            .ok()
+            .and_then(|r| self.register(r))
+        {
+            Some(s) => s,
+            None => "E01".into(),
+        }
+    }
+
+    fn write_register(&mut self, args: &str) -> String {
+        let Some((r, val)) = args.split_once('=') else {
+            return "E01".into();
+        };
+        match (usize::from_str_radix(r, 16), parse_le(val)) {
+            (Ok(r), Some(val)) if r <= 32 => {
+                self.set_register(r, val);
+                "OK".into()
+            }
+            _ => "E01".into(),
+        }
+    }
+
+    // Registers are numbered as in the target description:
+    // x0-x31 followed by pc.
+    fn register(&self, r: usize) -> Option<String> {
+        let val = match r {
+            0..=31 => self.vm.get_reg(r as u32),
+            32 => self.vm.regs.pc,
+            _ => return None,
+        };
+        Some(hex(&val.to_le_bytes()))
+    }
+
+    fn set_register(&mut self, r: usize, val: u32) {
+        if r == 32 {
+            self.vm.regs.pc = val;
+        } else {
+            self.vm.set_reg(r as u32, val);
+        }
+    }
+
+    // Read at most as many bytes as fit in a reply, with each byte
+    // encoded as two hexadecimal digits.
+    fn read_memory(&self, args: &str) -> String {
+        let Some((addr, len)) = parse_pair(args, ',') else {
+            return "E01".into();
+        };
+        let len = len.min(PACKET_SIZE as u32 / 2);
+        let bytes: Result<Vec<u8>> = (0..len)
+            .map(|i| {
+                let (b, _) = self.vm.mem.load(LOP::LBU, addr.wrapping_add(i))?;
+                Ok(b as u8)
+            })
+            .collect();
+        match bytes {
+            Ok(bytes) => hex(&bytes),
+            Err(_) => "E14".into(),
+        }
+    }
+
+    fn write_memory(&mut self, args: &str) -> String {
+        let Some((range, data)) = args.split_once(':') else {
+            return "E01".into();
+        };
+        let (Some((addr, len)), Some(bytes)) = (parse_pair(range, ','), unhex(data)) else {
+            return "E01".into();
+        };
+        if bytes.len() != len as usize {
+            return "E01".into();
+        }
+        for (i, b) in bytes.iter().enumerate() {
+            let addr = addr.wrapping_add(i as u32);
+            if self.vm.mem.store(SOP::SB, addr, *b as u32).is_err() {
+                return "E14".into();
+            }
+        }
+        "OK".into()
+    }
+
+    // Insert or remove a software (type 0) or hardware (type 1) breakpoint.
+    // Both are implemented by checking the pc after each instruction.
+    fn breakpoint(&mut self, args: &str, insert: bool) -> String {
+        let mut parts = args.split(',');
+        let (Some(kind), Some(addr)) = (parts.next(), parts.next()) else {
+            return "E01".into();
+        };
+        if kind != "0" && kind != "1" {
+            return String::new();
+        }
+        let Ok(addr) = u32::from_str_radix(addr, 16) else {
+            return "E01".into();
+        };
+        if insert {
+            self.breakpoints.insert(addr);
+        } else {
+            self.breakpoints.remove(&addr);
+        }
+        "OK".into()
+    }
+
+    // Read the next packet, acknowledging it. Returns `None` if the
+    // connection was closed.
+    fn read_packet(&mut self) -> Result<Option<String>> {
+        loop {
+            // skip acknowledgements and stray interrupts
+            loop {
+                match self.read_byte()? {
+                    None => return Ok(None),
+                    Some(b'$') => break,
+                    Some(_) => (),
+                }
+            }
+
+            let mut data = Vec::new();
+            loop {
+                match self.read_byte()? {
+                    None => return Ok(None),
+                    Some(b'#') => break,
+                    Some(b) => data.push(b),
+                }
+            }
+
+            let mut cs = [0u8; 2];
+            self.stream.read_exact(&mut cs)?;
+            let expected = std::str::from_utf8(&cs)
+                .ok()
+                .and_then(|s| u8::from_str_radix(s, 16).ok());
+
+            if expected == Some(checksum(&data)) {
+                self.stream.write_all(b"+")?;
+                return Ok(Some(String::from_utf8_lossy(&data).into_owned()));
+            }
+            self.stream.write_all(b"-")?;
+        }
+    }
+
+    fn read_byte(&mut self) -> Result<Option<u8>> {
+        let mut buf = [0u8; 1];
+        loop {
+            match self.stream.read(&mut buf) {
+                Ok(0) => return Ok(None),
+                Ok(_) => return Ok(Some(buf[0])),
+                Err(e) if e.kind() == ErrorKind::Interrupted => (),
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    // Send a packet, retransmitting until it is acknowledged.
+    fn write_packet(&mut self, data: &str) -> Result<()> {
+        let packet = format!("${data}#{:02x}", checksum(data.as_bytes()));
+        loop {
+            self.stream.write_all(packet.as_bytes())?;
+            match self.read_byte()? {
+                Some(b'-') => continue,
+                _ => return Ok(()),
+            }
+        }
+    }
+}
+
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |acc, b| acc.wrapping_add(*b))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn unhex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+// parse a little-endian 32-bit register value
+fn parse_le(s: &str) -> Option<u32> {
+    let bytes: [u8; 4] = unhex(s)?.try_into().ok()?;
+    Some(u32::from_le_bytes(bytes))
+}
+
+// parse two hexadecimal numbers separated by `sep`
+fn parse_pair(s: &str, sep: char) -> Option<(u32, u32)> {
+    let (a, b) = s.split_once(sep)?;
+    Some((
+        u32::from_str_radix(a, 16).ok()?,
+        u32::from_str_radix(b, 16).ok()?,
+    ))
+}
+
+// reply to a qXfer read of `offset` and `len` bytes of `doc`
+fn xfer(doc: &str, offset: usize, len: usize) -> String {
+    let rest = doc.get(offset.min(doc.len())..).unwrap_or("");
+    if rest.len() <= len {
+        format!("l{rest}")
+    } else {
+        format!("m{}", &rest[..len])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::machines::nop_vm;
+    use crate::memory::paged::Paged;
+    use std::thread;
+
+    // A minimal client, sending packets and returning the replies.
+    struct Client(TcpStream);
+
+    impl Client {
+        fn send(&mut self, data: &str) -> String {
+            let packet = format!("${data}#{:02x}", checksum(data.as_bytes()));
+            self.0.write_all(packet.as_bytes()).unwrap();
+
+            let mut buf = [0u8; 1];
+            self.0.read_exact(&mut buf).unwrap();
+            assert_eq!(buf[0], b'+');
+            if data == "k" {
+                return String::new();
+            }
+
+            let mut reply = Vec::new();
+            self.0.read_exact(&mut buf).unwrap();
+            assert_eq!(buf[0], b'$');
+            loop {
+                self.0.read_exact(&mut buf).unwrap();
+                if buf[0] == b'#' {
+                    break;
+                }
+                reply.push(buf[0]);
+            }
+            let mut cs = [0u8; 2];
+            self.0.read_exact(&mut cs).unwrap();
+            assert_eq!(
+                u8::from_str_radix(std::str::from_utf8(&cs).unwrap(), 16).unwrap(),
+                checksum(&reply)
+            );
+            self.0.write_all(b"+").unwrap();
+            String::from_utf8(reply).unwrap()
+        }
+    }
+
+    #[test]
+    fn gdb_session() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let client = thread::spawn(move || {
+            let mut c = Client(TcpStream::connect(addr).unwrap());
+            assert!(c
+                .send("qSupported:swbreak+")
+                .contains("qXfer:features:read+"));
+            assert!(c
+                .send("qXfer:features:read:target.xml:0,40")
+                .starts_with("m<?xml"));
+            assert_eq!(c.send("?"), "S05");
+            assert_eq!(c.send("p20"), "00000000");
+
+            // step over the first nop
+            assert_eq!(c.send("s"), "S05");
+            assert_eq!(c.send("p20"), "04000000");
+
+            // continue to a breakpoint
+            assert_eq!(c.send("Z0,c,4"), "OK");
+            assert_eq!(c.send("c"), "S05");
+            assert_eq!(c.send("p20"), "0c000000");
+            assert_eq!(c.send("z0,c,4"), "OK");
+
+            // registers and memory
+            assert_eq!(c.send("P5=78563412"), "OK");
+            assert_eq!(c.send("p5"), "78563412");
+            assert_eq!(&c.send("g")[40..48], "78563412");
+            assert_eq!(c.send("m0,4"), "13000000");
+            assert_eq!(c.send("M1000,2:abcd"), "OK");
+            assert_eq!(c.send("m1000,3"), "abcd00");
+            assert_eq!(c.send("mffff0000,ffffffff").len(), PACKET_SIZE);
+
+            // run to completion
+            assert_eq!(c.send("c"), "W00");
+            assert_eq!(c.send("D"), "OK");
+        });
+
+        let mut vm = nop_vm::<Paged>(5);
+        let (stream, _) = listener.accept().unwrap();
+        GdbStub::new(&mut vm, stream).unwrap().run().unwrap();
+        client.join().unwrap();
+
+        assert_eq!(vm.regs.pc, 5 * 4);
+        assert_eq!(vm.regs.x[5], 0x12345678);
+    }
+
+    #[test]
+    fn hex_encoding() {
+        assert_eq!(hex(&[0x01, 0xab]), "01ab");
+        assert_eq!(unhex("01ab"), Some(vec![0x01, 0xab]));
+        assert_eq!(unhex("1ab"), None);
+        assert_eq!(parse_le("78563412"), Some(0x12345678));
+        assert_eq!(parse_pair("1000,2", ','), Some((0x1000, 2)));
+        assert_eq!(xfer("abcdef", 2, 2), "mcd");
+        assert_eq!(xfer("abcdef", 4, 10), "lef");
+    }
+}
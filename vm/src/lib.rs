@@ -5,9 +5,14 @@
 
 pub mod error;
 pub mod eval;
+pub mod gdb;
+pub mod loader;
 pub mod machines;
 pub mod rv32;
 
+pub mod precompiles;
+pub mod profiler;
+pub mod snapshot;
 pub mod syscalls;
 pub mod trace;
 
@@ -17,9 +22,10 @@ pub mod memory;
 pub mod circuit;
 
 use clap::Args;
-use elf::{abi::PT_LOAD, endian::LittleEndian, ElfBytes};
-use std::fs::read;
-use std::path::PathBuf;
+use elf::{endian::LittleEndian, ElfBytes};
+use std::fs::{read, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 pub use error::*;
@@ -50,21 +56,13 @@ pub fn parse_elf_bytes(bytes: &[u8]) -> Result<ElfBytes<LittleEndian>> {
 
 #[doc(hidden)]
 pub fn init_vm<M: Memory>(elf: &ElfBytes<LittleEndian>, data: &[u8]) -> Result<NexusVM<M>> {
-    let e_entry = elf.ehdr.e_entry as u32;
+    let program = loader::Program::from_elf(elf, data)?;
 
-    let load_phdrs = elf
-        .segments()
-        .unwrap()
-        .iter()
-        .filter(|phdr| phdr.p_type == PT_LOAD);
-
-    let mut vm = NexusVM::new(e_entry);
-    for p in load_phdrs {
-        let s = p.p_offset as usize;
-        let e = (p.p_offset + p.p_filesz) as usize;
-        let bytes = &data[s..e];
-        vm.init_memory(p.p_vaddr as u32, bytes)?;
+    let mut vm = NexusVM::new(program.entry);
+    for s in &program.segments {
+        vm.init_memory(s.vaddr, &s.data)?;
     }
+    vm.layout = layout::MemoryLayout::from_program(&program);
     Ok(vm)
 }
 
@@ -73,6 +71,16 @@ pub fn parse_elf<M: Memory>(bytes: &[u8]) -> Result<NexusVM<M>> {
     init_vm(&file, bytes)
 }
 
+/// Compute the canonical identifier of the program contained in `elf`, which
+/// commits to its entry point and the contents of its loaded segments.
+///
+/// A proof of an execution of the program starts from the state committed to
+/// by this identifier, see [`trace::ProgramId::from_state`].
+pub fn program_id(elf: &[u8]) -> Result<ProgramId> {
+    let vm = parse_elf::<trie::MerkleTrie>(elf)?;
+    Ok(ProgramId::initial(&vm))
+}
+
 /// A structure describing a VM to load.
 /// This structure can be used with clap.
 #[derive(Default, Debug, Args)]
@@ -184,15 +192,60 @@ pub fn eval(vm: &mut NexusVM<impl Memory>, show: bool, verbose: bool) -> Result<
             assert_eq!(*cnt, 0);
         }
     }
+
+    if show {
+        report_exit(vm);
+    }
     Ok(())
 }
 
+// Report the panic message and exit code of a program which did not
+// exit successfully.
+fn report_exit(vm: &NexusVM<impl Memory>) {
+    if let Some(info) = vm.syscalls.get_panic_info() {
+        println!("{info}");
+    }
+    let code = vm.syscalls.get_exit_code();
+    if !code.is_success() {
+        println!("Program exited with code {code}");
+    }
+}
+
 /// Load and run an ELF file
 pub fn run_vm<M: Memory>(vm: &VMOpts, show: bool, verbose: bool) -> Result<()> {
     let mut vm: NexusVM<M> = load_vm(vm)?;
     eval(&mut vm, show, verbose)
 }
 
+/// Load and run an ELF file, profiling every executed instruction
+/// using the ELF symbol table. The profile is written to `output`
+/// with the extensions `.folded` (folded stacks, for flamegraphs)
+/// and `.pb` (pprof).
+pub fn profile_vm<M: Memory>(path: &PathBuf, show: bool, output: &Path) -> Result<()> {
+    let file_data = read(path)?;
+    let elf = parse_elf_bytes(&file_data)?;
+    let mut vm: NexusVM<M> = init_vm(&elf, &file_data)?;
+    vm.profiler = Some(profiler::Profiler::from_elf(&elf)?);
+
+    eval(&mut vm, show, false)?;
+
+    let profiler = vm.profiler.as_ref().unwrap();
+    println!("Profile by function (inclusive):");
+    for (name, c) in profiler.functions().iter().take(10) {
+        println!(
+            "  {:>10} cycles {:>10} instructions  {}",
+            c.cycles, c.instructions, name
+        );
+    }
+
+    let folded = output.with_extension("folded");
+    profiler.write_folded(BufWriter::new(File::create(&folded)?))?;
+    let pprof = output.with_extension("pb");
+    profiler.write_pprof(BufWriter::new(File::create(&pprof)?))?;
+    println!("Wrote {} and {}", folded.display(), pprof.display());
+    Ok(())
+}
+
 /// Load and run an ELF file, then return the execution trace
 pub fn trace_vm<M: Memory>(
     opts: &VMOpts,
@@ -213,6 +266,10 @@ pub fn trace_vm<M: Memory>(
     let start = Instant::now();
     let trace = trace::<M>(&mut vm, opts.k, pow)?;
 
+    if show {
+        report_exit(&vm);
+    }
+
     if verbose {
         println!(
             "Executed {} instructions in {:?}. {} bytes used by trace.",
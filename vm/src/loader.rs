@@ -0,0 +1,452 @@
+//! Validating loader for guest ELF files
+//!
+//! Guest programs are statically linked RV32I executables. The loader
+//! checks the ELF header, and that the loadable segments are contained
+//! in the file and in the 32-bit address space, without overlapping.
+//! Segments may occupy more memory than they contain in the file
+//! (e.g. `.bss`); the remainder is zero, like the rest of the memory.
+//! Finally, the permissions of the segments must be preserved by the
+//! memory layout of the program, see [`MemoryLayout`].
+
+use std::collections::HashMap;
+
+use elf::{
+    abi::{EI_DATA, ELFDATA2LSB, EM_RISCV, ET_EXEC, PF_R, PF_W, PF_X, PT_LOAD},
+    endian::LittleEndian,
+    file::Class,
+    ElfBytes,
+};
+
+use crate::error::{NexusVMError::ELFFormat, Result};
+use crate::memory::layout::{Access, MemoryLayout};
+
+/// ELF header flag of programs using compressed instructions.
+pub const EF_RISCV_RVC: u32 = 0x1;
+
+/// A loadable segment of a program.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// Address of the segment.
+    pub vaddr: u32,
+    /// Size of the segment in memory, which is at least the size of `data`.
+    pub memsz: u32,
+    /// Contents of the beginning of the segment, the remainder is zero.
+    pub data: Vec<u8>,
+    /// Segment permissions (`PF_R`, `PF_W` and `PF_X`).
+    pub flags: u32,
+}
+
+impl Segment {
+    /// Address following the end of the segment.
+    pub fn end(&self) -> u32 {
+        self.vaddr + self.memsz
+    }
+
+    /// Whether `addr` is contained in the segment.
+    pub fn contains(&self, addr: u32) -> bool {
+        self.vaddr <= addr && addr < self.end()
+    }
+
+    pub fn is_readable(&self) -> bool {
+        self.flags & PF_R != 0
+    }
+
+    pub fn is_writable(&self) -> bool {
+        self.flags & PF_W != 0
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.flags & PF_X != 0
+    }
+}
+
+/// A program loaded from an ELF file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Program {
+    /// Entry point.
+    pub entry: u32,
+    /// Non-empty loadable segments, sorted by address.
+    pub segments: Vec<Segment>,
+    /// Addresses of the defined symbols, by name.
+    pub symbols: HashMap<String, u32>,
+}
+
+impl Program {
+    /// Load and validate the program contained in the ELF file `elf`,
+    /// whose contents are `data`.
+    pub fn from_elf(elf: &ElfBytes<LittleEndian>, data: &[u8]) -> Result<Self> {
+        let ehdr = &elf.ehdr;
+        if ehdr.class != Class::ELF32 {
+            return Err(ELFFormat("not a 32-bit ELF file"));
+        }
+        if data.get(EI_DATA) != Some(&ELFDATA2LSB) {
+            return Err(ELFFormat("not a little-endian ELF file"));
+        }
+        if ehdr.e_machine != EM_RISCV {
+            return Err(ELFFormat("not a RISC-V ELF file"));
+        }
+        if ehdr.e_type != ET_EXEC {
+            return Err(ELFFormat("not an executable ELF file"));
+        }
+        if ehdr.e_flags & EF_RISCV_RVC != 0 {
+            return Err(ELFFormat("compressed instructions are not supported"));
+        }
+
+        let Some(phdrs) = elf.segments() else {
+            return Err(ELFFormat("missing program headers"));
+        };
+
+        let mut segments = Vec::new();
+        for p in phdrs
+            .iter()
+            .filter(|p| p.p_type == PT_LOAD && p.p_memsz > 0)
+        {
+            if p.p_filesz > p.p_memsz {
+                return Err(ELFFormat("segment is larger in the file than in memory"));
+            }
+            if !matches!(p.p_vaddr.checked_add(p.p_memsz), Some(end) if end <= u32::MAX.into()) {
+                return Err(ELFFormat("segment exceeds the address space"));
+            }
+            let bytes = usize::try_from(p.p_offset)
+                .ok()
+                .zip(usize::try_from(p.p_filesz).ok())
+                .and_then(|(s, n)| data.get(s..s.checked_add(n)?))
+                .ok_or(ELFFormat("segment exceeds the file"))?;
+
+            segments.push(Segment {
+                vaddr: p.p_vaddr as u32,
+                memsz: p.p_memsz as u32,
+                data: bytes.to_vec(),
+                flags: p.p_flags,
+            });
+        }
+
+        segments.sort_by_key(|s| s.vaddr);
+        if segments.windows(2).any(|w| w[0].end() > w[1].vaddr) {
+            return Err(ELFFormat("overlapping segments"));
+        }
+
+        let entry = u32::try_from(ehdr.e_entry).map_err(|_| ELFFormat("invalid entry point"))?;
+        if !segments
+            .iter()
+            .any(|s| s.contains(entry) && s.is_executable())
+        {
+            return Err(ELFFormat("entry point is not in an executable segment"));
+        }
+
+        let mut symbols = HashMap::new();
+        if let Some((symtab, strtab)) = elf.symbol_table()? {
+            for sym in symtab.iter() {
+                if sym.st_name == 0 || sym.is_undefined() {
+                    continue;
+                }
+                let name = strtab.get(sym.st_name as usize)?;
+                symbols.insert(name.to_string(), sym.st_value as u32);
+            }
+        }
+
+        let program = Program { entry, segments, symbols };
+
+        // the memory layout must preserve the permissions of the segments
+        if program
+            .segments
+            .iter()
+            .any(|s| s.is_writable() && s.is_executable())
+        {
+            return Err(ELFFormat("segment is both writable and executable"));
+        }
+        let layout = MemoryLayout::from_program(&program);
+        if program
+            .segments
+            .iter()
+            .any(|s| s.is_writable() && !layout.allows(Access::Write, s.vaddr, s.memsz))
+        {
+            return Err(ELFFormat("writable segment between read-only segments"));
+        }
+
+        Ok(program)
+    }
+
+    /// Whether `addr` is contained in a segment which is not writable.
+    pub fn is_read_only(&self, addr: u32) -> bool {
+        self.segments
+            .iter()
+            .any(|s| s.contains(addr) && !s.is_writable())
+    }
+}
+
+/// Builder of ELF files for testing, shared with the tests of dependent crates.
+#[doc(hidden)]
+pub mod test_elf {
+    use super::*;
+
+    pub const EHSIZE: usize = 52;
+    pub const PHENTSIZE: usize = 32;
+    const SHENTSIZE: usize = 40;
+
+    /// A statically linked RV32 executable, which can be modified before
+    /// being built into an ELF file.
+    pub struct TestElf {
+        pub ident: [u8; 3],
+        pub machine: u16,
+        pub flags: u32,
+        pub entry: u32,
+        pub segments: Vec<Segment>,
+        pub symbols: Vec<(&'static str, u32)>,
+    }
+
+    impl TestElf {
+        /// An executable containing `code` in a read-only segment at address 0.
+        pub fn new(code: &[u32]) -> Self {
+            TestElf {
+                ident: [1, 1, 1], // ELFCLASS32, ELFDATA2LSB, EV_CURRENT
+                machine: EM_RISCV,
+                flags: 0,
+                entry: 0,
+                segments: vec![Segment {
+                    vaddr: 0,
+                    memsz: code.len() as u32 * 4,
+                    data: code.iter().flat_map(|w| w.to_le_bytes()).collect(),
+                    flags: PF_R | PF_X,
+                }],
+                symbols: Vec::new(),
+            }
+        }
+
+        pub fn build(&self) -> Vec<u8> {
+            fn put(bytes: &mut Vec<u8>, words: &[u32]) {
+                for w in words {
+                    bytes.extend(w.to_le_bytes());
+                }
+            }
+            let phoff = EHSIZE;
+            let mut offset = phoff + PHENTSIZE * self.segments.len();
+            let mut phdrs = Vec::new();
+            for s in &self.segments {
+                let (filesz, memsz) = (s.data.len() as u32, s.memsz);
+                put(
+                    &mut phdrs,
+                    &[
+                        PT_LOAD,
+                        offset as u32,
+                        s.vaddr,
+                        s.vaddr,
+                        filesz,
+                        memsz,
+                        s.flags,
+                        4,
+                    ],
+                );
+                offset += s.data.len();
+            }
+
+            let mut strtab = vec![0];
+            let mut symtab = vec![0; 16];
+            for (name, value) in &self.symbols {
+                put(&mut symtab, &[strtab.len() as u32, *value, 0]);
+                symtab.extend([0x12, 0]); // STB_GLOBAL, STT_FUNC
+                symtab.extend(1u16.to_le_bytes());
+                strtab.extend(name.bytes());
+                strtab.push(0);
+            }
+            let (symoff, stroff) = (offset, offset + symtab.len());
+            let shoff = stroff + strtab.len();
+            let shnum = if self.symbols.is_empty() { 0 } else { 3 };
+
+            let mut bytes = vec![0x7f, b'E', b'L', b'F'];
+            bytes.extend(self.ident);
+            bytes.resize(16, 0);
+            for half in [ET_EXEC, self.machine] {
+                bytes.extend(half.to_le_bytes());
+            }
+            let shoff_field = if shnum == 0 { 0 } else { shoff as u32 };
+            put(
+                &mut bytes,
+                &[1, self.entry, phoff as u32, shoff_field, self.flags],
+            );
+            for half in [EHSIZE, PHENTSIZE, self.segments.len(), SHENTSIZE, shnum, 0] {
+                bytes.extend((half as u16).to_le_bytes());
+            }
+            bytes.extend(phdrs);
+            for s in &self.segments {
+                bytes.extend(&s.data);
+            }
+            if shnum != 0 {
+                bytes.extend(&symtab);
+                bytes.extend(&strtab);
+                put(&mut bytes, &[0; 10]);
+                // SHT_SYMTAB, linked to the string table
+                let (symsz, strsz) = (symtab.len() as u32, strtab.len() as u32);
+                put(&mut bytes, &[0, 2, 0, 0, symoff as u32, symsz, 2, 1, 4, 16]);
+                // SHT_STRTAB
+                put(&mut bytes, &[0, 3, 0, 0, stroff as u32, strsz, 0, 0, 1, 0]);
+            }
+            bytes
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eval, init_vm,
+        memory::{trie::MerkleTrie, Memory},
+        parse_elf_bytes, NexusVMError,
+    };
+
+    use super::test_elf::{TestElf, EHSIZE, PHENTSIZE};
+
+    // offset of field `field` of program header `i`
+    fn phdr_field(i: usize, field: usize) -> usize {
+        EHSIZE + PHENTSIZE * i + 4 * field
+    }
+
+    fn set_u32(bytes: &mut [u8], offset: usize, val: u32) {
+        bytes[offset..offset + 4].copy_from_slice(&val.to_le_bytes());
+    }
+
+    fn load(bytes: &[u8]) -> Result<Program> {
+        Program::from_elf(&parse_elf_bytes(bytes)?, bytes)
+    }
+
+    fn assert_format_error(bytes: &[u8], msg: &str) {
+        match load(bytes) {
+            Err(ELFFormat(m)) => assert_eq!(m, msg),
+            r => panic!("expected \"{msg}\", got {r:?}"),
+        }
+    }
+
+    const NOPS: [u32; 3] = [0x13, 0x13, 0xc0001073]; // nop, nop, unimp
+
+    #[test]
+    fn load_program() {
+        let mut elf = TestElf::new(&NOPS);
+        elf.segments.push(Segment {
+            vaddr: 0x100,
+            memsz: 0x40,
+            data: vec![1, 2, 3, 4],
+            flags: PF_R | PF_W,
+        });
+        elf.symbols = vec![("_start", 0), ("DATA", 0x100)];
+
+        let program = load(&elf.build()).unwrap();
+        assert_eq!(program.entry, 0);
+        assert_eq!(program.segments, elf.segments);
+        assert_eq!(program.symbols.len(), 2);
+        assert_eq!(program.symbols["DATA"], 0x100);
+        assert!(program.is_read_only(8));
+        assert!(!program.is_read_only(0x104));
+        assert!(!program.is_read_only(0x200));
+
+        // memory beyond the contents of a segment in the file is zero
+        let bytes = elf.build();
+        let vm = init_vm::<MerkleTrie>(&parse_elf_bytes(&bytes).unwrap(), &bytes).unwrap();
+        assert_eq!(vm.mem.load_n(0x100, 8).unwrap(), [1, 2, 3, 4, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn invalid_header() {
+        let mut elf = TestElf::new(&NOPS);
+        elf.ident[0] = 2;
+        assert!(load(&elf.build()).is_err());
+
+        let mut elf = TestElf::new(&NOPS);
+        elf.ident[1] = 2;
+        assert!(load(&elf.build()).is_err());
+
+        let mut elf = TestElf::new(&NOPS);
+        elf.machine = 0x3e; // EM_X86_64
+        assert_format_error(&elf.build(), "not a RISC-V ELF file");
+
+        let mut elf = TestElf::new(&NOPS);
+        elf.flags = EF_RISCV_RVC;
+        assert_format_error(&elf.build(), "compressed instructions are not supported");
+
+        let mut elf = TestElf::new(&NOPS);
+        elf.entry = 0x100;
+        assert_format_error(&elf.build(), "entry point is not in an executable segment");
+
+        let bytes = TestElf::new(&NOPS).build();
+        assert!(load(&bytes[..EHSIZE + 8]).is_err());
+        assert!(load(&bytes[..20]).is_err());
+    }
+
+    #[test]
+    fn invalid_segments() {
+        let mut bytes = TestElf::new(&NOPS).build();
+        set_u32(&mut bytes, phdr_field(0, 5), 4); // p_memsz < p_filesz
+        assert_format_error(&bytes, "segment is larger in the file than in memory");
+
+        let mut bytes = TestElf::new(&NOPS).build();
+        set_u32(&mut bytes, phdr_field(0, 1), 0x1000); // p_offset
+        assert_format_error(&bytes, "segment exceeds the file");
+
+        let mut bytes = TestElf::new(&NOPS).build();
+        set_u32(&mut bytes, phdr_field(0, 1), u32::MAX); // p_offset
+        assert_format_error(&bytes, "segment exceeds the file");
+
+        let mut bytes = TestElf::new(&NOPS).build();
+        set_u32(&mut bytes, phdr_field(0, 2), u32::MAX - 4); // p_vaddr
+        assert_format_error(&bytes, "segment exceeds the address space");
+
+        let mut elf = TestElf::new(&NOPS);
+        elf.segments.push(Segment {
+            vaddr: 8,
+            memsz: 8,
+            data: Vec::new(),
+            flags: PF_R | PF_W,
+        });
+        assert_format_error(&elf.build(), "overlapping segments");
+
+        let mut elf = TestElf::new(&NOPS);
+        elf.segments[0].flags |= PF_W;
+        assert_format_error(&elf.build(), "segment is both writable and executable");
+
+        let mut elf = TestElf::new(&NOPS);
+        for (vaddr, flags) in [(0x100, PF_R | PF_W), (0x200, PF_R)] {
+            elf.segments
+                .push(Segment { vaddr, memsz: 4, data: Vec::new(), flags });
+        }
+        assert_format_error(&elf.build(), "writable segment between read-only segments");
+    }
+
+    // Run `code` with a writable segment at 0x100, returning the fault.
+    pub(crate) fn run_fault(code: &[u32]) -> Option<(Access, u32, u32)> {
+        let mut elf = TestElf::new(code);
+        elf.segments.push(Segment {
+            vaddr: 0x100,
+            memsz: 4,
+            data: Vec::new(),
+            flags: PF_R | PF_W,
+        });
+        let bytes = elf.build();
+
+        let mut vm = init_vm::<MerkleTrie>(&parse_elf_bytes(&bytes).unwrap(), &bytes).unwrap();
+        match eval(&mut vm, false, false) {
+            Err(NexusVMError::MemoryFault(access, pc, addr)) => Some((access, pc, addr)),
+            r => {
+                r.unwrap();
+                None
+            }
+        }
+    }
+
+    #[test]
+    fn memory_faults() {
+        // sw x0, 0x100(x0); unimp
+        assert_eq!(run_fault(&[0x10002023, 0xc0001073]), None);
+
+        // sw x0, 0x100(x0); sw x0, 0(x0); unimp
+        let fault = run_fault(&[0x10002023, 0x00002023, 0xc0001073]);
+        assert_eq!(fault, Some((Access::Write, 4, 0)));
+
+        // li x1, 0x100; jalr x0, 0(x1)
+        let fault = run_fault(&[0x10000093, 0x00008067]);
+        assert_eq!(fault, Some((Access::Execute, 0x100, 0x100)));
+
+        // lui x1, 0x80000; lw x2, 0(x1); unimp
+        let fault = run_fault(&[0x800000b7, 0x0000a103, 0xc0001073]);
+        assert_eq!(fault, Some((Access::Read, 4, 0x8000_0000)));
+    }
+}
@@ -3,7 +3,11 @@
 #![allow(clippy::field_reassign_with_default)]
 #![allow(clippy::identity_op)]
 
-use super::{memory::Memory, rv32::SOP};
+use super::{
+    memory::Memory,
+    precompiles::{Keccak256, Precompile},
+    rv32::SOP,
+};
 use crate::{NexusVM, Regs};
 
 type TestMachine<'a> = (&'a str, fn() -> Vec<u32>, fn() -> Regs, fn() -> Vec<u8>);
@@ -20,7 +24,11 @@ pub const MACHINES: &[TestMachine] = &[
     ("ldst", ldst_code, ldst_result, Vec::new),
     ("shift", shift_code, shift_result, Vec::new),
     ("sub", sub_code, sub_result, Vec::new),
+    ("mul", mul_code, mul_result, Vec::new),
+    ("div", div_code, div_result, Vec::new),
     ("priv", priv_code, priv_result, priv_input),
+    ("output", output_code, output_result, Vec::new),
+    ("keccak", keccak_code, keccak_result, Vec::new),
 ];
 
 /// Lookup and initialize a test VM by name
@@ -387,6 +395,90 @@ fn sub_result() -> Regs {
     regs
 }
 
+// Test the multiply instructions (RV32M).
+fn mul_code() -> Vec<u32> {
+    vec![
+        0xfaaab0b7, //  lui     x1,0xfaaab
+        0xaaa08093, //  addi    x1,x1,-1366 # faaaaaaa
+        0xff900113, //  addi    x2,x0,-7
+        0x00d00193, //  addi    x3,x0,13
+        0x02308233, //  mul     x4,x1,x3
+        0x022092b3, //  mulh    x5,x1,x2
+        0x0220a333, //  mulhsu  x6,x1,x2
+        0x0220b3b3, //  mulhu   x7,x1,x2
+        0x02310433, //  mul     x8,x2,x3
+        0x023114b3, //  mulh    x9,x2,x3
+        0x0221a533, //  mulhsu  x10,x3,x2
+        0x0221b5b3, //  mulhu   x11,x3,x2
+        0xc0001073, //  unimp
+    ]
+}
+
+// Expected result of running the mul VM.
+fn mul_result() -> Regs {
+    let mut regs = Regs::default();
+    regs.pc = 12 * 4;
+    regs.x[1] = 0xfaaaaaaa;
+    regs.x[2] = -7i32 as u32;
+    regs.x[3] = 13;
+
+    regs.x[4] = 0xbaaaaaa2;
+    regs.x[5] = 0;
+    regs.x[6] = 0xfaaaaaaa;
+    regs.x[7] = 0xfaaaaaa3;
+    regs.x[8] = -91i32 as u32;
+    regs.x[9] = 0xffffffff;
+    regs.x[10] = 0xc;
+    regs.x[11] = 0xc;
+    regs
+}
+
+// Test the divide and remainder instructions (RV32M), including
+// the division by zero and signed overflow cases.
+fn div_code() -> Vec<u32> {
+    vec![
+        0xff900093, //  addi    x1,x0,-7
+        0x00200113, //  addi    x2,x0,2
+        0x800001b7, //  lui     x3,0x80000
+        0xfff00213, //  addi    x4,x0,-1
+        0x0220c2b3, //  div     x5,x1,x2
+        0x0220d333, //  divu    x6,x1,x2
+        0x0220e3b3, //  rem     x7,x1,x2
+        0x0220f433, //  remu    x8,x1,x2
+        0x0241c4b3, //  div     x9,x3,x4
+        0x0241e533, //  rem     x10,x3,x4
+        0x0200c5b3, //  div     x11,x1,x0
+        0x0200d633, //  divu    x12,x1,x0
+        0x0200e6b3, //  rem     x13,x1,x0
+        0x0200f733, //  remu    x14,x1,x0
+        0xc0001073, //  unimp
+    ]
+}
+
+// Expected result of running the div VM.
+fn div_result() -> Regs {
+    let mut regs = Regs::default();
+    regs.pc = 14 * 4;
+    regs.x[1] = -7i32 as u32;
+    regs.x[2] = 2;
+    regs.x[3] = 0x80000000;
+    regs.x[4] = -1i32 as u32;
+
+    regs.x[5] = -3i32 as u32;
+    regs.x[6] = (-7i32 as u32) / 2;
+    regs.x[7] = -1i32 as u32;
+    regs.x[8] = 1;
+
+    regs.x[9] = 0x80000000;
+    regs.x[10] = 0;
+
+    regs.x[11] = 0xffffffff;
+    regs.x[12] = 0xffffffff;
+    regs.x[13] = -7i32 as u32;
+    regs.x[14] = -7i32 as u32;
+    regs
+}
+
 // Test reading private input
 fn priv_code() -> Vec<u32> {
     vec![
@@ -413,6 +505,57 @@ fn priv_result() -> Regs {
     regs
 }
 
+// Test writing to the output tape
+fn output_code() -> Vec<u32> {
+    vec![
+        0b_0000_0000_0011_0000_0000_1001_0001_0011, // addi x18, x0, 3
+        0b_0000_0010_1010_0000_0000_0101_1001_0011, // addi x11, x0, 42
+        0b_0000_0000_0000_0000_0000_0000_0111_0011, // ecall x0
+        0b_0001_0010_1011_0000_0000_0101_1001_0011, // addi x11, x0, 0x12b
+        0b_0000_0000_0000_0000_0000_0000_0111_0011, // ecall x0
+        0xc0001073,                                 //  unimp
+    ]
+}
+
+// Expected result of running the output VM.
+fn output_result() -> Regs {
+    let mut regs = Regs::default();
+    regs.pc = 5 * 4;
+    regs.x[18] = 3;
+    regs.x[11] = 0x12b;
+    regs
+}
+
+// Test calling the Keccak-256 precompile, hashing a cache line in place
+fn keccak_code() -> Vec<u32> {
+    vec![
+        0xfff00293, //  addi    x5,x0,-1
+        0x10502023, //  sw      x5,0x100(x0)
+        0x10000913, //  addi    x18,x0,0x100
+        0x10000593, //  addi    x11,x0,0x100
+        0x00400613, //  addi    x12,x0,4
+        0x10000693, //  addi    x13,x0,0x100
+        0x00000573, //  ecall   x10
+        0x10002303, //  lw      x6,0x100(x0)
+        0xc0001073, //  unimp
+    ]
+}
+
+// Expected result of running the keccak VM.
+fn keccak_result() -> Regs {
+    let hash = Keccak256.eval(&[0xff; 4]).unwrap();
+    let mut regs = Regs::default();
+    regs.pc = 8 * 4;
+    regs.x[5] = -1i32 as u32;
+    regs.x[6] = u32::from_le_bytes(hash[..4].try_into().unwrap());
+    regs.x[10] = 32;
+    regs.x[11] = 0x100;
+    regs.x[12] = 4;
+    regs.x[13] = 0x100;
+    regs.x[18] = 0x100;
+    regs
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1,6 +1,8 @@
 //! Virtual Machine Memory
 
 pub mod cacheline;
+pub mod layout;
+pub mod offline;
 pub mod paged;
 pub mod path;
 pub mod trie;
@@ -33,6 +35,29 @@ pub trait MemoryProof: Default + Clone + CanonicalSerialize + CanonicalDeseriali
         data: &[FpVar<F>],
     ) -> Result<(), SynthesisError>;
 
+    /// Generate in-circuit verification of the memory proofs of one
+    /// step: `pc` for the instruction fetch, `read` for the load and
+    /// `write` for the store, each with the variables holding its
+    /// `CacheLine` data. `lines` holds the addresses of the cache lines
+    /// fetched, and loaded and stored. By default, `pc` and `read` are
+    /// checked against the commitment `root_in` before the step, and
+    /// `write` against the commitment `root_out` after the step, and the
+    /// addresses are not used.
+    fn step_circuit(
+        cs: ConstraintSystemRef<F>,
+        params: &Self::Params,
+        root_in: &FpVar<F>,
+        root_out: &FpVar<F>,
+        _lines: (&FpVar<F>, &FpVar<F>),
+        pc: (&Self, &[FpVar<F>]),
+        read: (&Self, &[FpVar<F>]),
+        write: (&Self, &[FpVar<F>]),
+    ) -> Result<(), SynthesisError> {
+        pc.0.circuit(cs.clone(), params, root_in, pc.1)?;
+        read.0.circuit(cs.clone(), params, root_in, read.1)?;
+        write.0.circuit(cs, params, root_out, write.1)
+    }
+
     /// Return the memory commitment related to this proof.
     fn commit(&self) -> F;
 
@@ -58,6 +83,17 @@ pub trait Memory: Default {
     where
         F: Fn(&mut CacheLine) -> Result<()>;
 
+    /// Return the non-zero cachelines of this memory, each with an
+    /// address contained in the cacheline.
+    fn lines(&self) -> Vec<(u32, CacheLine)>;
+
+    /// Return the proof for a load or store which a step did not make,
+    /// following the access proven by `prev`. By default, no proof is
+    /// generated, and the proof of the previous access is reused.
+    fn skip(&self, _prev: &Self::Proof) -> Option<Self::Proof> {
+        None
+    }
+
     /// read instruction at address
     fn read_inst(&self, addr: u32) -> Result<(u32, Self::Proof)> {
         let (cl, path) = self.query(addr);
@@ -91,7 +127,7 @@ pub trait Memory: Default {
 
 #[cfg(test)]
 mod test {
-    use super::{paged::Paged, trie::MerkleTrie, *};
+    use super::{offline::OfflineMemory, paged::Paged, trie::MerkleTrie, *};
     use crate::rv32::{LOP::*, SOP::*};
 
     #[test]
@@ -104,7 +140,12 @@ mod test {
         test_mem(Paged::default());
     }
 
-    fn test_mem(mut mem: impl Memory) {
+    #[test]
+    fn test_mem_offline() {
+        test_mem(OfflineMemory::default());
+    }
+
+    fn test_mem<M: Memory>(mut mem: M) {
         // read before write
         assert_eq!(mem.load(LW, 0x1000).unwrap().0, 0);
 
@@ -144,5 +185,24 @@ mod test {
 
         mem.store(SH, 0x1300, 0x8321).unwrap();
         assert_eq!(mem.load(LH, 0x1300).unwrap().0, 0xffff8321);
+
+        // copy memory through its populated lines
+        let lines = mem.lines();
+        assert_eq!(lines.len(), 5);
+        let mut copy = M::default();
+        for (addr, line) in lines {
+            copy.update(addr, |cl| {
+                *cl = line;
+                Ok(())
+            })
+            .unwrap();
+        }
+        for addr in [0x1000, 0x1100, 0x1104, 0x1200, 0x1300, 0x11000] {
+            assert_eq!(
+                copy.load(LW, addr).unwrap().0,
+                mem.load(LW, addr).unwrap().0
+            );
+        }
+        assert_eq!(copy.lines().len(), 5);
     }
 }
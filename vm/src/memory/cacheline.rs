@@ -4,6 +4,10 @@ use std::fmt::{Debug, Error, Formatter};
 
 use ark_bn254::Fr as F;
 use ark_ff::PrimeField;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
 
 use crate::error::*;
 use crate::rv32::*;
@@ -67,6 +71,37 @@ impl PartialEq for CacheLine {
     }
 }
 
+// serialized as its bytes
+impl CanonicalSerialize for CacheLine {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> std::result::Result<(), SerializationError> {
+        unsafe { self.bytes.serialize_with_mode(writer, compress) }
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        unsafe { self.bytes.serialized_size(compress) }
+    }
+}
+
+impl Valid for CacheLine {
+    fn check(&self) -> std::result::Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for CacheLine {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> std::result::Result<Self, SerializationError> {
+        <[u8; 32]>::deserialize_with_mode(reader, compress, validate).map(CacheLine::from)
+    }
+}
+
 impl CacheLine {
     pub const ZERO: CacheLine = CacheLine { words: [0; 8] };
 
@@ -193,6 +228,18 @@ mod test {
         assert_ne!(a, b);
     }
 
+    #[test]
+    fn cache_serialize() {
+        let a = CacheLine::from([7u32; 8]);
+        let mut buf = Vec::new();
+        a.serialize_compressed(&mut buf).unwrap();
+        assert_eq!(buf.len(), 32);
+        assert_eq!(
+            CacheLine::deserialize_compressed(buf.as_slice()).unwrap(),
+            a
+        );
+    }
+
     #[test]
     fn cache_endian_lsb() {
         // Two ways of setting least significant byte, assuming little endian
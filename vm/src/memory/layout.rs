@@ -0,0 +1,202 @@
+//! Memory protection for guest programs
+//!
+//! A `MemoryLayout` determines the memory accesses a program may perform.
+//! Instructions may only be fetched from the executable range, which is
+//! the smallest range containing the executable segments of the program.
+//! Memory may only be written outside of the read-only range, which is the
+//! smallest range containing the segments which are not writable (and so,
+//! in particular, the executable range). Finally, all accesses must be below
+//! the top of memory, given by the symbol [`MEMORY_TOP_SYMBOL`] if the program
+//! defines it: the stack and the heap occupy the memory between the end of the
+//! program and the top of memory.
+//!
+//! The layout is part of the state of the step circuit, which enforces the
+//! same policy as the VM (see `circuit::riscv`).
+
+use std::fmt;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{NexusVMError::MemoryFault, Result};
+use crate::loader::{Program, Segment};
+
+/// Top of memory of programs which do not declare one.
+pub const MEMORY_TOP: u64 = 0x8000_0000;
+
+/// Symbol defined by the linker scripts of `nexus-rt` as the top of memory,
+/// or by `#[nexus_rt::main(memlimit = ...)]`. The stack starts below it.
+pub const MEMORY_TOP_SYMBOL: &str = "__memory_top";
+
+/// Kinds of memory accesses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+    Execute,
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Access::Read => "read",
+            Access::Write => "write",
+            Access::Execute => "execute",
+        })
+    }
+}
+
+/// Memory protection regions of a program. Ranges are half-open, and
+/// bounds are at most 2^32.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+)]
+pub struct MemoryLayout {
+    /// Start of the executable range.
+    pub exec_start: u64,
+    /// End of the executable range.
+    pub exec_end: u64,
+    /// Start of the read-only range.
+    pub ro_start: u64,
+    /// End of the read-only range.
+    pub ro_end: u64,
+    /// Top of memory.
+    pub top: u64,
+}
+
+/// The default layout permits every access, it is used for machines which
+/// are not loaded from an ELF file.
+impl Default for MemoryLayout {
+    fn default() -> Self {
+        Self {
+            exec_start: 0,
+            exec_end: 1 << 32,
+            ro_start: 0,
+            ro_end: 0,
+            top: 1 << 32,
+        }
+    }
+}
+
+impl MemoryLayout {
+    /// The layout of `program`, see the module documentation.
+    pub fn from_program(program: &Program) -> Self {
+        // segments are sorted, and do not overlap
+        let hull = |pred: fn(&Segment) -> bool| {
+            let segs: Vec<&Segment> = program.segments.iter().filter(|s| pred(s)).collect();
+            match (segs.first(), segs.last()) {
+                (Some(first), Some(last)) => (first.vaddr as u64, last.end() as u64),
+                _ => (0, 0),
+            }
+        };
+        let (exec_start, exec_end) = hull(|s| s.is_executable());
+        let (ro_start, ro_end) = hull(|s| !s.is_writable());
+
+        let program_end = program.segments.last().map_or(0, |s| s.end() as u64);
+        let top = program
+            .symbols
+            .get(MEMORY_TOP_SYMBOL)
+            .map_or(MEMORY_TOP, |&top| top as u64)
+            .max(program_end);
+
+        Self {
+            exec_start,
+            exec_end,
+            ro_start,
+            ro_end,
+            top,
+        }
+    }
+
+    /// Whether the `size` bytes at `addr` may be accessed by `access`.
+    pub fn allows(&self, access: Access, addr: u32, size: u32) -> bool {
+        let (start, end) = (addr as u64, addr as u64 + size as u64);
+        end <= self.top
+            && match access {
+                Access::Read => true,
+                Access::Write => end <= self.ro_start || self.ro_end <= start,
+                Access::Execute => self.exec_start <= start && end <= self.exec_end,
+            }
+    }
+
+    /// Check that the instruction at `pc` may access the `size` bytes at `addr`.
+    pub fn check(&self, access: Access, pc: u32, addr: u32, size: u32) -> Result<()> {
+        if self.allows(access, addr, size) {
+            Ok(())
+        } else {
+            Err(MemoryFault(access, pc, addr))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use elf::abi::{PF_R, PF_W, PF_X};
+
+    #[test]
+    fn program_layout() {
+        let seg = |vaddr, memsz, flags| Segment { vaddr, memsz, data: Vec::new(), flags };
+        let mut program = Program {
+            entry: 0x1000,
+            segments: vec![
+                seg(0x1000, 0x100, PF_R | PF_X),
+                seg(0x1100, 0x20, PF_R),
+                seg(0x2000, 0x40, PF_R | PF_W),
+            ],
+            ..Program::default()
+        };
+        let layout = MemoryLayout::from_program(&program);
+        assert_eq!(
+            layout,
+            MemoryLayout {
+                exec_start: 0x1000,
+                exec_end: 0x1100,
+                ro_start: 0x1000,
+                ro_end: 0x1120,
+                top: MEMORY_TOP,
+            }
+        );
+
+        assert!(layout.allows(Access::Execute, 0x10fc, 4));
+        assert!(!layout.allows(Access::Execute, 0x10fe, 4));
+        assert!(!layout.allows(Access::Execute, 0x1100, 4));
+        assert!(!layout.allows(Access::Execute, 0x2000, 4));
+
+        assert!(layout.allows(Access::Read, 0x1000, 4));
+        assert!(layout.allows(Access::Read, 0x7fff_fffc, 4));
+        assert!(!layout.allows(Access::Read, 0x7fff_fffe, 4));
+
+        assert!(layout.allows(Access::Write, 0xffc, 4));
+        assert!(!layout.allows(Access::Write, 0xffe, 4));
+        assert!(!layout.allows(Access::Write, 0x1110, 1));
+        assert!(layout.allows(Access::Write, 0x1120, 1));
+        assert!(layout.allows(Access::Write, 0x2000, 4));
+        assert!(!layout.allows(Access::Write, u32::MAX, 1));
+
+        // the declared top of memory is enforced, even below the default
+        program.symbols.insert(MEMORY_TOP_SYMBOL.into(), 0x40_0000);
+        let layout = MemoryLayout::from_program(&program);
+        assert_eq!(layout.top, 0x40_0000);
+        assert!(layout.allows(Access::Write, 0x3f_fffc, 4));
+        assert!(!layout.allows(Access::Write, 0x40_0000, 4));
+
+        program
+            .symbols
+            .insert(MEMORY_TOP_SYMBOL.into(), 0x8040_0000);
+        assert_eq!(MemoryLayout::from_program(&program).top, 0x8040_0000);
+
+        let all = MemoryLayout::default();
+        assert!(all.allows(Access::Write, u32::MAX - 3, 4));
+        assert!(all.allows(Access::Execute, u32::MAX - 3, 4));
+        assert!(!all.allows(Access::Read, u32::MAX - 2, 4));
+    }
+}
@@ -0,0 +1,744 @@
+//! A memory controller using offline memory checking.
+//!
+//! Rather than proving each access against a Merkle root, every access
+//! is recorded in a running fingerprint of the multisets of tuples read
+//! from and written to memory (see Blum et al., "Checking the Correctness
+//! of Memories"). An access to the cache line at address `a` holding `v`,
+//! last accessed at time `t'`, removes `(a, v, t')` from memory and puts
+//! back `(a, v', t)`, where `v'` is the new contents (equal to `v` for
+//! loads) and `t > t'` is the current time. Each tuple is mapped to the
+//! field element `gamma - (a + alpha*v_lo + alpha^2*v_hi + alpha^3*t)`,
+//! and the fingerprint is the product of the elements of tuples written,
+//! divided by the product of the elements of tuples read.
+//!
+//! Every access is also hashed into a running commitment to the trace of
+//! memory accesses. The challenges `alpha` and `gamma` are derived by
+//! Fiat-Shamir from the commitment to the complete trace, together with
+//! the final contents of memory, so that they are only known once the
+//! accesses are fixed. As the fingerprint depends on the challenges, an
+//! execution is run twice, see [`trace`]: once to commit to its accesses,
+//! and once to fingerprint them.
+//!
+//! The step circuit state holds a commitment to the fingerprint, the
+//! clock counting the accesses made so far, the trace commitment and the
+//! challenges. Each step makes exactly three accesses: an instruction
+//! fetch, a load and a store. Steps which do not load or store instead
+//! re-read the previously accessed line (see `Memory::skip`).
+//!
+//! The fingerprint only shows that memory behaved correctly once it is
+//! audited at the end of the execution: a proof of an execution using
+//! offline memory checking must be checked with [`verify`], given the
+//! initial contents of memory and an [`Audit`] of its final state.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+use ark_crypto_primitives::crh::{CRHSchemeGadget, TwoToOneCRHSchemeGadget};
+use ark_ff::{AdditiveGroup, Field};
+use ark_r1cs_std::{alloc::AllocVar, boolean::Boolean, fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use super::cacheline::*;
+use super::path::{
+    compress, hash_leaf, poseidon_config, LeafHashG, Params, ParamsVar, TwoToOneHashG,
+};
+use super::{Memory, MemoryProof};
+use crate::circuit::F;
+use crate::error::Result;
+use crate::eval::NexusVM;
+use crate::rv32::LOP;
+use crate::trace::{Trace, TraceStream};
+
+type CS = ConstraintSystemRef<F>;
+
+// width of the range check on the time elapsed between accesses
+const TIME_BITS: usize = 64;
+
+fn params() -> &'static Params {
+    static PARAMS: OnceLock<Params> = OnceLock::new();
+    PARAMS.get_or_init(poseidon_config)
+}
+
+/// The challenges `(alpha, gamma)` used to fingerprint tuples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Challenges {
+    /// Challenge combining the components of a tuple.
+    pub alpha: F,
+    /// Challenge shifting the combined tuple.
+    pub gamma: F,
+}
+
+impl Challenges {
+    /// Derive the challenges from the commitment `trace` to the memory
+    /// accesses of an execution, and the final contents and time of last
+    /// access of each cache line.
+    pub fn derive(trace: F, lines: &[(u32, [F; 2], u64)]) -> Self {
+        let params = params();
+        let c = lines.iter().fold(trace, |c, (addr, data, time)| {
+            let leaf = hash_leaf(params, &[F::from(*addr), data[0], data[1], F::from(*time)]);
+            compress(params, &c, &leaf.unwrap()).unwrap()
+        });
+        Self {
+            alpha: compress(params, &c, &F::from(1u64)).unwrap(),
+            gamma: compress(params, &c, &F::from(2u64)).unwrap(),
+        }
+    }
+
+    fn var(&self, cs: CS) -> Result<[FpVar<F>; 2], SynthesisError> {
+        Ok([
+            FpVar::new_witness(cs.clone(), || Ok(self.alpha))?,
+            FpVar::new_witness(cs, || Ok(self.gamma))?,
+        ])
+    }
+}
+
+/// The challenges used before an execution is committed to, see
+/// [`OfflineMemory::start`].
+impl Default for Challenges {
+    fn default() -> Self {
+        static CHALLENGES: OnceLock<Challenges> = OnceLock::new();
+        *CHALLENGES.get_or_init(|| Challenges::derive(F::ZERO, &[]))
+    }
+}
+
+// fingerprint of the tuple (addr, data, time)
+fn term(ch: &Challenges, addr: u32, data: &[F; 2], time: u64) -> F {
+    let Challenges { alpha, gamma } = *ch;
+    let enc = F::from(addr) + alpha * (data[0] + alpha * (data[1] + alpha * F::from(time)));
+    gamma - enc
+}
+
+// in-circuit version of `term`
+fn term_var(ch: &[FpVar<F>], addr: &FpVar<F>, data: &[FpVar<F>], time: &FpVar<F>) -> FpVar<F> {
+    let (alpha, gamma) = (&ch[0], &ch[1]);
+    let enc = addr + (&data[0] + (&data[1] + time * alpha) * alpha) * alpha;
+    gamma - enc
+}
+
+// update the trace commitment with an access to the line at `addr`,
+// changing `old` written at `t_prev` to `data` written at `t`
+fn trace_hash(trace: F, addr: u32, (old, data): (&[F; 2], &[F; 2]), (t_prev, t): (u64, u64)) -> F {
+    let params = params();
+    let leaf = [
+        F::from(addr),
+        old[0],
+        old[1],
+        F::from(t_prev),
+        data[0],
+        data[1],
+        F::from(t),
+    ];
+    compress(params, &trace, &hash_leaf(params, &leaf).unwrap()).unwrap()
+}
+
+// commitment to the trace made by accesses to an empty memory
+// which initialize it with `lines`
+fn initial_trace(lines: &[(u32, [F; 2])]) -> F {
+    lines.iter().fold(F::ZERO, |trace, (addr, data)| {
+        trace_hash(trace, *addr, (&CacheLine::ZERO.scalars(), data), (0, 0))
+    })
+}
+
+// the non-zero lines of `initial`, by address of the cache line
+fn initial_lines(initial: &[(u32, CacheLine)]) -> Vec<(u32, [F; 2])> {
+    let lines: BTreeMap<u32, [F; 2]> = initial
+        .iter()
+        .filter(|(_, cl)| *cl != CacheLine::ZERO)
+        .map(|(addr, cl)| (line_addr(*addr), cl.scalars()))
+        .collect();
+    lines.into_iter().collect()
+}
+
+/// Return the state commitment for the given fingerprint, clock, trace
+/// commitment and challenges.
+pub fn commitment(fingerprint: F, clock: u64, trace: F, ch: &Challenges) -> F {
+    let state = [fingerprint, F::from(clock), trace, ch.alpha, ch.gamma];
+    hash_leaf(params(), &state).unwrap()
+}
+
+// in-circuit version of `commitment`
+fn commitment_var(
+    params: &ParamsVar,
+    fingerprint: &FpVar<F>,
+    clock: &FpVar<F>,
+    trace: &FpVar<F>,
+    ch: &[FpVar<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+    let state = [
+        fingerprint.clone(),
+        clock.clone(),
+        trace.clone(),
+        ch[0].clone(),
+        ch[1].clone(),
+    ];
+    LeafHashG::evaluate(params, &state)
+}
+
+/// Return the fingerprint expected after an execution starting from
+/// memory holding `initial`, which leaves each accessed cache line with
+/// the given address, contents and time of last access.
+pub fn audit(ch: &Challenges, initial: &[(u32, CacheLine)], lines: &[(u32, [F; 2], u64)]) -> F {
+    let initial: HashMap<u32, [F; 2]> = initial_lines(initial).into_iter().collect();
+    let zero = CacheLine::ZERO.scalars();
+    lines.iter().fold(F::ONE, |f, (addr, data, time)| {
+        let old = initial.get(addr).unwrap_or(&zero);
+        f * term(ch, *addr, data, *time) * term(ch, *addr, old, 0).inverse().unwrap_or(F::ZERO)
+    })
+}
+
+/// The final state of an `OfflineMemory`, opening the commitment to the
+/// memory state after an execution.
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Audit {
+    /// Final fingerprint.
+    pub fingerprint: F,
+    /// Number of accesses made.
+    pub clock: u64,
+    /// Commitment to the trace of accesses.
+    pub trace: F,
+    /// Address, contents and time of last access of each cache line,
+    /// by increasing address.
+    pub lines: Vec<(u32, [F; 2], u64)>,
+}
+
+/// Check that the memory accesses of an execution are consistent, given
+/// the contents `initial` of memory before the execution, and the state
+/// commitments `start` and `end` before and after it, such as the memory
+/// entries of the first and last step circuit states: `audit` must open
+/// `end`, with challenges derived from the trace commitment and lines of
+/// `audit`, and its fingerprint must match the final contents of memory.
+pub fn verify(initial: &[(u32, CacheLine)], start: F, end: F, audit: &Audit) -> bool {
+    // each line is audited once
+    if !audit.lines.windows(2).all(|w| w[0].0 < w[1].0) {
+        return false;
+    }
+    let ch = Challenges::derive(audit.trace, &audit.lines);
+    let initial_trace = initial_trace(&initial_lines(initial));
+
+    start == commitment(F::ONE, 0, initial_trace, &ch)
+        && end == commitment(audit.fingerprint, audit.clock, audit.trace, &ch)
+        && audit.fingerprint == self::audit(&ch, initial, &audit.lines)
+}
+
+/// Generate a program trace of `vm`, as `trace::trace`, with challenges
+/// derived from the memory accesses of the execution. The contents of
+/// memory when called are the initial memory of the execution. Returns
+/// the trace, together with the audit of the final memory state needed
+/// by [`verify`].
+///
+/// The accesses are first committed to by running the execution on a
+/// copy of `vm`, restored from a `Snapshot`.
+///
+/// [`Snapshot`]: crate::snapshot::Snapshot
+pub fn trace(
+    vm: &mut NexusVM<OfflineMemory>,
+    k: usize,
+    pow: bool,
+) -> Result<(Trace<OfflineProof>, Audit)> {
+    let mut copy = NexusVM::<OfflineMemory>::restore(&vm.snapshot())?;
+    copy.mem.start(Challenges::default());
+    for block in TraceStream::new(&mut copy, k, pow)? {
+        block?;
+    }
+    let audit = copy.mem.audit();
+    let challenges = Challenges::derive(audit.trace, &audit.lines);
+
+    vm.mem.start(challenges);
+    let trace = crate::trace::trace(vm, k, pow)?;
+    Ok((trace, vm.mem.audit()))
+}
+
+/// A record of a single memory access, used to update the fingerprint.
+#[derive(Debug, Default, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct OfflineProof {
+    /// Address of the cache line.
+    pub addr: u32,
+    /// Contents of the cache line before the access.
+    pub old: [F; 2],
+    /// Contents of the cache line after the access.
+    pub data: [F; 2],
+    /// Time of the previous access to the cache line, or zero.
+    pub t_prev: u64,
+    /// Time of this access.
+    pub t: u64,
+    /// Fingerprint before the access.
+    pub before: F,
+    /// Fingerprint after the access.
+    pub after: F,
+    /// Trace commitment before the access.
+    pub trace_before: F,
+    /// Trace commitment after the access.
+    pub trace_after: F,
+    /// Challenges used to fingerprint the access.
+    pub challenges: Challenges,
+    /// True if `commit` returns the commitment after the access, as for
+    /// stores, rather than before it.
+    pub commit_after: bool,
+}
+
+impl OfflineProof {
+    // check the access in-circuit, given the fingerprint and trace
+    // commitment, challenges, address, previous and new contents, and
+    // previous and current times of the access, and return the
+    // resulting fingerprint and trace commitment.
+    #[allow(clippy::too_many_arguments)]
+    fn access_circuit(
+        &self,
+        cs: CS,
+        params: &ParamsVar,
+        (fingerprint, trace): (&FpVar<F>, &FpVar<F>),
+        ch: &[FpVar<F>],
+        addr: &FpVar<F>,
+        (old, data): (&[FpVar<F>], &[FpVar<F>]),
+        (t_prev, t): (&FpVar<F>, &FpVar<F>),
+    ) -> Result<(FpVar<F>, FpVar<F>), SynthesisError> {
+        let after = FpVar::new_witness(cs, || Ok(self.after))?;
+        let removed = term_var(ch, addr, old, t_prev);
+        let added = term_var(ch, addr, data, t);
+        let product = fingerprint * added;
+        after.mul_equals(&removed, &product)?;
+
+        let leaf = [
+            addr.clone(),
+            old[0].clone(),
+            old[1].clone(),
+            t_prev.clone(),
+            data[0].clone(),
+            data[1].clone(),
+            t.clone(),
+        ];
+        let leaf = LeafHashG::evaluate(params, &leaf)?;
+        let trace = TwoToOneHashG::compress(params, trace, &leaf)?;
+        Ok((after, trace))
+    }
+
+    // check in-circuit that the previous access happened before `t`
+    fn time_circuit(&self, cs: CS, t: &FpVar<F>) -> Result<FpVar<F>, SynthesisError> {
+        let t_prev = FpVar::new_witness(cs.clone(), || Ok(F::from(self.t_prev)))?;
+        let elapsed = self.t.wrapping_sub(self.t_prev).wrapping_sub(1);
+        let mut sum = FpVar::zero();
+        for i in 0..TIME_BITS {
+            let b = Boolean::new_witness(cs.clone(), || Ok((elapsed >> i) & 1 == 1))?;
+            sum += FpVar::from(b) * F::from(1u64 << i);
+        }
+        sum.enforce_equal(&(t - &t_prev - F::ONE))?;
+        Ok(t_prev)
+    }
+}
+
+impl MemoryProof for OfflineProof {
+    type Params = ParamsVar;
+
+    fn params(cs: CS) -> Result<Self::Params, SynthesisError> {
+        ParamsVar::new_constant(cs.clone(), params())
+    }
+
+    fn circuit(
+        &self,
+        cs: CS,
+        params: &Self::Params,
+        root: &FpVar<F>,
+        data: &[FpVar<F>],
+    ) -> Result<(), SynthesisError> {
+        let before = FpVar::new_witness(cs.clone(), || Ok(self.before))?;
+        let trace = FpVar::new_witness(cs.clone(), || Ok(self.trace_before))?;
+        let ch = self.challenges.var(cs.clone())?;
+        let clock = FpVar::new_witness(cs.clone(), || Ok(F::from(self.t.saturating_sub(1))))?;
+        let t = &clock + F::ONE;
+
+        let addr = FpVar::new_witness(cs.clone(), || Ok(F::from(self.addr)))?;
+        let old = Vec::<FpVar<F>>::new_witness(cs.clone(), || Ok(self.old.to_vec()))?;
+        let t_prev = self.time_circuit(cs.clone(), &t)?;
+        let (after, trace_after) = self.access_circuit(
+            cs,
+            params,
+            (&before, &trace),
+            &ch,
+            &addr,
+            (&old, data),
+            (&t_prev, &t),
+        )?;
+
+        let commit = if self.commit_after {
+            commitment_var(params, &after, &t, &trace_after, &ch)?
+        } else {
+            commitment_var(params, &before, &clock, &trace, &ch)?
+        };
+        commit.enforce_equal(root)
+    }
+
+    fn step_circuit(
+        cs: CS,
+        params: &Self::Params,
+        root_in: &FpVar<F>,
+        root_out: &FpVar<F>,
+        (pc_line, mem_line): (&FpVar<F>, &FpVar<F>),
+        pc: (&Self, &[FpVar<F>]),
+        read: (&Self, &[FpVar<F>]),
+        write: (&Self, &[FpVar<F>]),
+    ) -> Result<(), SynthesisError> {
+        let f0 = FpVar::new_witness(cs.clone(), || Ok(pc.0.before))?;
+        let h0 = FpVar::new_witness(cs.clone(), || Ok(pc.0.trace_before))?;
+        let ch = pc.0.challenges.var(cs.clone())?;
+        let clock = FpVar::new_witness(cs.clone(), || Ok(F::from(pc.0.t.saturating_sub(1))))?;
+        commitment_var(params, &f0, &clock, &h0, &ch)?.enforce_equal(root_in)?;
+
+        let t1 = &clock + F::ONE;
+        let t2 = &clock + F::from(2u64);
+        let t3 = &clock + F::from(3u64);
+
+        // instruction fetch
+        let (p, data) = pc;
+        let t_prev = p.time_circuit(cs.clone(), &t1)?;
+        let (f1, h1) = p.access_circuit(
+            cs.clone(),
+            params,
+            (&f0, &h0),
+            &ch,
+            pc_line,
+            (data, data),
+            (&t_prev, &t1),
+        )?;
+
+        // load, or re-read of the instruction
+        let (p, data) = read;
+        let t_prev = p.time_circuit(cs.clone(), &t2)?;
+        let (f2, h2) = p.access_circuit(
+            cs.clone(),
+            params,
+            (&f1, &h1),
+            &ch,
+            mem_line,
+            (data, data),
+            (&t_prev, &t2),
+        )?;
+
+        // store to the loaded line, or re-read of it
+        let (p, new) = write;
+        let (f3, h3) = p.access_circuit(
+            cs,
+            params,
+            (&f2, &h2),
+            &ch,
+            mem_line,
+            (data, new),
+            (&t2, &t3),
+        )?;
+
+        commitment_var(params, &f3, &t3, &h3, &ch)?.enforce_equal(root_out)
+    }
+
+    fn commit(&self) -> F {
+        let ch = &self.challenges;
+        if self.commit_after {
+            commitment(self.after, self.t, self.trace_after, ch)
+        } else {
+            commitment(self.before, self.t.saturating_sub(1), self.trace_before, ch)
+        }
+    }
+
+    fn data(&self) -> [F; 2] {
+        self.data
+    }
+}
+
+// fingerprint state, updated by every access including queries
+struct State {
+    fingerprint: F,
+    clock: u64,
+    trace: F,
+    challenges: Challenges,
+    times: HashMap<u32, u64>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            fingerprint: F::ONE,
+            clock: 0,
+            trace: F::ZERO,
+            challenges: Challenges::default(),
+            times: HashMap::new(),
+        }
+    }
+}
+
+/// A memory using offline memory checking.
+///
+/// Note, the fingerprint is not saved in a `Snapshot`: a restored
+/// memory starts again from an empty fingerprint.
+///
+/// [`Snapshot`]: crate::snapshot::Snapshot
+#[derive(Default)]
+pub struct OfflineMemory {
+    lines: BTreeMap<u32, CacheLine>,
+    state: RefCell<State>,
+}
+
+// address of the cache line containing `addr`
+fn line_addr(addr: u32) -> u32 {
+    addr & !((1 << CACHE_BITS) - 1)
+}
+
+impl OfflineMemory {
+    fn line(&self, addr: u32) -> &CacheLine {
+        self.lines.get(&line_addr(addr)).unwrap_or(&CacheLine::ZERO)
+    }
+
+    // record an access to the line at `addr`, changing `old` to `new`
+    fn access(&self, addr: u32, old: &CacheLine, new: &CacheLine) -> OfflineProof {
+        let addr = line_addr(addr);
+        let mut st = self.state.borrow_mut();
+        let t_prev = st.times.get(&addr).copied().unwrap_or(0);
+        let t = st.clock + 1;
+        let (old, data) = (old.scalars(), new.scalars());
+
+        let before = st.fingerprint;
+        // a zero term has negligible probability, and leaves the
+        // fingerprint unusable rather than panicking
+        let inv = term(&st.challenges, addr, &old, t_prev)
+            .inverse()
+            .unwrap_or(F::ZERO);
+        let after = before * term(&st.challenges, addr, &data, t) * inv;
+        let trace_before = st.trace;
+        let trace_after = trace_hash(trace_before, addr, (&old, &data), (t_prev, t));
+
+        st.fingerprint = after;
+        st.clock = t;
+        st.trace = trace_after;
+        st.times.insert(addr, t);
+        OfflineProof {
+            addr,
+            old,
+            data,
+            t_prev,
+            t,
+            before,
+            after,
+            trace_before,
+            trace_after,
+            challenges: st.challenges,
+            commit_after: false,
+        }
+    }
+
+    /// Start checking accesses to memory with the given challenges. The
+    /// current contents of memory become its initial contents, and the
+    /// accesses recorded so far, such as those loading a program, are
+    /// discarded.
+    pub fn start(&mut self, challenges: Challenges) {
+        let initial = initial_lines(&self.lines());
+        *self.state.get_mut() = State {
+            trace: initial_trace(&initial),
+            challenges,
+            times: initial.iter().map(|(addr, _)| (*addr, 0)).collect(),
+            ..State::default()
+        };
+    }
+
+    /// Return the current fingerprint.
+    pub fn fingerprint(&self) -> F {
+        self.state.borrow().fingerprint
+    }
+
+    /// Return the number of accesses recorded so far.
+    pub fn clock(&self) -> u64 {
+        self.state.borrow().clock
+    }
+
+    /// Return the commitment to the current state, as carried in the
+    /// step circuit state.
+    pub fn commit(&self) -> F {
+        let st = self.state.borrow();
+        commitment(st.fingerprint, st.clock, st.trace, &st.challenges)
+    }
+
+    /// Return the address, contents and time of last access of each
+    /// accessed or initial cache line, as needed by `audit`.
+    pub fn accessed_lines(&self) -> Vec<(u32, [F; 2], u64)> {
+        let st = self.state.borrow();
+        let mut v: Vec<_> = st
+            .times
+            .iter()
+            .map(|(addr, t)| (*addr, self.line(*addr).scalars(), *t))
+            .collect();
+        v.sort_by_key(|l| l.0);
+        v
+    }
+
+    /// Return the audit of the current state, see [`verify`].
+    pub fn audit(&self) -> Audit {
+        let st = self.state.borrow();
+        Audit {
+            fingerprint: st.fingerprint,
+            clock: st.clock,
+            trace: st.trace,
+            lines: self.accessed_lines(),
+        }
+    }
+}
+
+impl Memory for OfflineMemory {
+    type Proof = OfflineProof;
+
+    fn query(&self, addr: u32) -> (&CacheLine, Self::Proof) {
+        let cl = self.line(addr);
+        (cl, self.access(addr, cl, cl))
+    }
+
+    fn update<F>(&mut self, addr: u32, f: F) -> Result<Self::Proof>
+    where
+        F: Fn(&mut CacheLine) -> Result<()>,
+    {
+        let cl = self.lines.entry(line_addr(addr)).or_default();
+        let old = *cl;
+        f(cl)?;
+        let new = *cl;
+        Ok(self.access(addr, &old, &new))
+    }
+
+    fn skip(&self, prev: &Self::Proof) -> Option<Self::Proof> {
+        let (_, mut proof) = self.query(prev.addr);
+        proof.commit_after = true;
+        Some(proof)
+    }
+
+    fn lines(&self) -> Vec<(u32, CacheLine)> {
+        self.lines
+            .iter()
+            .filter(|(_, cl)| **cl != CacheLine::ZERO)
+            .map(|(addr, cl)| (*addr, *cl))
+            .collect()
+    }
+
+    // reads made by system calls are not part of the trace, and so
+    // must not be recorded
+    fn load_n(&self, address: u32, len: u32) -> Result<Vec<u8>> {
+        (address..address + len)
+            .map(|addr| self.line(addr).load(LOP::LBU, addr).map(|b| b as u8))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::circuit::ARITY;
+    use crate::machines::loop_vm;
+    use crate::rv32::SOP;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    #[test]
+    fn offline_audit() {
+        let mut mem = OfflineMemory::default();
+        mem.store(SOP::SW, 0x1000, 1).unwrap();
+        mem.store(SOP::SW, 0x2000, 2).unwrap();
+        assert_eq!(mem.load(LOP::LW, 0x1000).unwrap().0, 1);
+        assert_eq!(mem.load(LOP::LW, 0x3000).unwrap().0, 0);
+        assert_eq!(mem.clock(), 4);
+        let ch = Challenges::default();
+        assert_eq!(audit(&ch, &[], &mem.accessed_lines()), mem.fingerprint());
+
+        // a system call read is not recorded
+        assert_eq!(mem.load_n(0x2000, 1).unwrap(), vec![2]);
+        assert_eq!(mem.clock(), 4);
+
+        // the audit fails if the final contents are misreported
+        let mut lines = mem.accessed_lines();
+        lines[0].1[0] += F::ONE;
+        assert_ne!(audit(&ch, &[], &lines), mem.fingerprint());
+
+        // once started, the current contents are the initial memory
+        let initial = mem.lines();
+        mem.start(ch);
+        mem.store(SOP::SW, 0x1004, 3).unwrap();
+        assert_eq!(mem.clock(), 1);
+        assert_eq!(mem.accessed_lines().len(), 2);
+        assert_eq!(
+            audit(&ch, &initial, &mem.accessed_lines()),
+            mem.fingerprint()
+        );
+        assert_ne!(audit(&ch, &[], &mem.accessed_lines()), mem.fingerprint());
+    }
+
+    #[test]
+    fn offline_verify() {
+        let mut vm = loop_vm::<OfflineMemory>(2);
+        let initial = vm.mem.lines();
+        let (tr, audit) = trace(&mut vm, 1, false).unwrap();
+        let start = tr.input(0).unwrap()[ARITY - 1];
+        let end = vm.mem.commit();
+        let last = tr.blocks.last().unwrap().steps.last().unwrap();
+        assert_eq!(last.write_proof.as_ref().unwrap().commit(), end);
+        assert!(verify(&initial, start, end, &audit));
+
+        // the challenges are derived from the execution
+        let ch = Challenges::derive(audit.trace, &audit.lines);
+        assert_ne!(ch, Challenges::default());
+        assert_eq!(last.write_proof.as_ref().unwrap().challenges, ch);
+
+        // the final contents of memory, or the initial ones, cannot be misreported
+        let mut bad = audit.clone();
+        bad.lines[0].1[0] += F::ONE;
+        assert!(!verify(&initial, start, end, &bad));
+        let mut bad = audit.clone();
+        bad.lines.pop();
+        assert!(!verify(&initial, start, end, &bad));
+        assert!(!verify(&initial[1..], start, end, &audit));
+
+        // nor can the accesses be fingerprinted with other challenges
+        let mut vm = loop_vm::<OfflineMemory>(2);
+        vm.mem.start(Challenges::default());
+        let tr = crate::trace::trace(&mut vm, 1, false).unwrap();
+        let start = tr.input(0).unwrap()[ARITY - 1];
+        assert!(!verify(&initial, start, vm.mem.commit(), &vm.mem.audit()));
+    }
+
+    #[test]
+    fn offline_proofs_chain() {
+        let mut mem = OfflineMemory::default();
+        let p1 = mem.store(SOP::SW, 0x1000, 1).unwrap();
+        let (_, p2) = mem.query(0x1004);
+        let p3 = mem.skip(&p2).unwrap();
+        assert_eq!(p2.before, p1.after);
+        assert_eq!(p3.before, p2.after);
+        assert_eq!(p3.trace_before, p2.trace_after);
+        assert_eq!((p2.t_prev, p2.t, p3.t_prev, p3.t), (1, 2, 2, 3));
+        assert_eq!(p3.commit(), mem.commit());
+        let ch = Challenges::default();
+        assert_eq!(p2.commit(), commitment(p1.after, 1, p1.trace_after, &ch));
+    }
+
+    // check a single access against the commitment before it
+    fn check_access(p: &OfflineProof, tamper: bool) -> bool {
+        let cs = ConstraintSystem::<F>::new_ref();
+        let params = OfflineProof::params(cs.clone()).unwrap();
+        let root = FpVar::new_input(cs.clone(), || Ok(p.commit())).unwrap();
+        let mut data = p.data;
+        if tamper {
+            data[0] += F::ONE;
+        }
+        let data = Vec::<FpVar<F>>::new_input(cs.clone(), || Ok(data.to_vec())).unwrap();
+        p.circuit(cs.clone(), &params, &root, &data).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    #[test]
+    fn offline_circuit() {
+        let mut mem = OfflineMemory::default();
+        let p = mem.store(SOP::SW, 0x1000, 1).unwrap();
+        let (_, mut q) = mem.query(0x1000);
+        assert!(check_access(&q, false));
+        assert!(!check_access(&q, true));
+
+        let mut p = p;
+        p.commit_after = true;
+        assert!(check_access(&p, false));
+
+        // accesses must move forward in time
+        q.t_prev = q.t;
+        assert!(!check_access(&q, false));
+    }
+}
@@ -82,4 +82,16 @@ impl Memory for Paged {
         f(&mut arr[offset])?;
         Ok(UncheckedMemory { data: arr[offset].scalars() })
     }
+
+    fn lines(&self) -> Vec<(u32, CacheLine)> {
+        let mut v = Vec::new();
+        for (page, arr) in &self.tree {
+            for (offset, cl) in arr.iter().enumerate() {
+                if *cl != CacheLine::ZERO {
+                    v.push(((page << 12) | ((offset as u32) << 5), *cl));
+                }
+            }
+        }
+        v
+    }
 }
@@ -245,6 +245,38 @@ impl MerkleTrie {
     }
 }
 
+impl MerkleTrie {
+    /// Return the non-zero `CacheLine`s held in the tree, with an
+    /// address for each.
+    pub fn lines(&self) -> Vec<(u32, CacheLine)> {
+        let mut v = Vec::new();
+        Self::lines_inner(&self.root, 0, 0, &mut v);
+        v
+    }
+
+    // The child taken at level `l` corresponds to bit `31 - l` of the
+    // address, see `query_inner`.
+    fn lines_inner(
+        node: &Option<Box<Node>>,
+        level: usize,
+        addr: u32,
+        v: &mut Vec<(u32, CacheLine)>,
+    ) {
+        let Some(n) = node else { return };
+        if level == CACHE_LOG {
+            let cl = n.data.leaf();
+            if *cl != CacheLine::ZERO {
+                v.push((addr, *cl));
+            }
+            return;
+        }
+        let level = level + 1;
+        let bit = 1 << (31 - level);
+        Self::lines_inner(n.data.left(), level, addr, v);
+        Self::lines_inner(n.data.right(), level, addr | bit, v);
+    }
+}
+
 impl Default for MerkleTrie {
     fn default() -> Self {
         let params = poseidon_config();
@@ -266,6 +298,10 @@ impl Memory for MerkleTrie {
     {
         self.update(addr, f)
     }
+
+    fn lines(&self) -> Vec<(u32, CacheLine)> {
+        self.lines()
+    }
 }
 
 #[cfg(test)]
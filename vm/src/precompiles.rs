@@ -0,0 +1,154 @@
+//! Precompiles
+//!
+//! A precompile is a function that a guest program can invoke with a
+//! single system call, rather than by executing it instruction by
+//! instruction. Each precompile provides a native implementation, used
+//! by the VM, and an R1CS gadget computing the same function.
+//!
+//! Precompiles are registered with the `Syscalls` of a VM under a
+//! syscall number, starting at `FIRST_PRECOMPILE`. A call takes the
+//! address and length of the input in a1 and a2, and the address of a
+//! cache line in a3, to which the output is written. It returns the
+//! length of the output.
+//!
+//! Note: the step circuits only invoke the Keccak-256 gadget, on inputs
+//! of at most one cache line which are hashed in place (see `provable`).
+//! Those calls are proven by the SuperNova prover, using the circuit of
+//! `OpcodeClass::Keccak256`. Other precompile calls are only available
+//! when running a program: tracing an execution which makes one fails
+//! with `UnprovablePrecompile`.
+
+pub mod keccak;
+pub mod poseidon;
+pub mod sha256;
+
+use std::collections::BTreeMap;
+
+use ark_r1cs_std::uint8::UInt8;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use crate::circuit::F;
+use crate::error::{NexusVMError::InvalidPrecompile, Result};
+use crate::memory::cacheline::CACHE_BITS;
+
+pub use keccak::Keccak256;
+pub use poseidon::Poseidon;
+pub use sha256::Sha256;
+
+/// The first syscall number available to precompiles.
+pub const FIRST_PRECOMPILE: u32 = 0x100;
+
+/// Syscall number of the Keccak-256 precompile.
+pub const KECCAK256: u32 = FIRST_PRECOMPILE;
+/// Syscall number of the SHA-256 precompile.
+pub const SHA256: u32 = FIRST_PRECOMPILE + 1;
+/// Syscall number of the Poseidon precompile.
+pub const POSEIDON: u32 = FIRST_PRECOMPILE + 2;
+
+/// Returns true if the call of precompile `code` on the `len` bytes at
+/// `src`, writing its output to `dst`, can be proven: the precompile is
+/// Keccak-256, and its input is at the start of the cache line of its
+/// output, which it then replaces.
+pub fn provable(code: u32, src: u32, len: u32, dst: u32) -> bool {
+    code == KECCAK256 && src == dst && len as usize <= 1 << CACHE_BITS
+}
+
+/// A function which can be called from a guest program by syscall.
+pub trait Precompile: Send + Sync {
+    /// Name of this precompile, used for display.
+    fn name(&self) -> &'static str;
+
+    /// Evaluate the precompile natively. The output must be 32 bytes
+    /// long, so that it can be written to a single cache line.
+    fn eval(&self, input: &[u8]) -> Result<Vec<u8>>;
+
+    /// Generate the constraints computing the output of the precompile on `input`.
+    fn circuit(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        input: &[UInt8<F>],
+    ) -> Result<Vec<UInt8<F>>, SynthesisError>;
+}
+
+/// A registry of precompiles, indexed by syscall number.
+pub struct Precompiles {
+    map: BTreeMap<u32, Box<dyn Precompile>>,
+}
+
+impl Default for Precompiles {
+    /// Create a registry holding the standard precompiles.
+    fn default() -> Self {
+        let mut p = Self::new();
+        p.map.insert(KECCAK256, Box::new(Keccak256));
+        p.map.insert(SHA256, Box::new(Sha256));
+        p.map.insert(POSEIDON, Box::new(Poseidon));
+        p
+    }
+}
+
+impl Precompiles {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self { map: BTreeMap::new() }
+    }
+
+    /// Register a precompile under syscall number `code`. The number must
+    /// not be reserved for system calls, or already in use.
+    pub fn register(&mut self, code: u32, p: impl Precompile + 'static) -> Result<()> {
+        if code < FIRST_PRECOMPILE || self.map.contains_key(&code) {
+            return Err(InvalidPrecompile(code));
+        }
+        self.map.insert(code, Box::new(p));
+        Ok(())
+    }
+
+    /// Return the precompile registered under syscall number `code`.
+    pub fn get(&self, code: u32) -> Option<&dyn Precompile> {
+        self.map.get(&code).map(|p| p.as_ref())
+    }
+
+    /// Iterate over the registered precompiles, in order of syscall number.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &dyn Precompile)> {
+        self.map.iter().map(|(c, p)| (*c, p.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_r1cs_std::{alloc::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    // check that the gadget agrees with the native implementation
+    fn check_precompile(p: &dyn Precompile) {
+        // lengths around the SHA-256 padding and Keccak-256 rate boundaries
+        for len in [0, 1, 55, 56, 64, 136, 137] {
+            let input: Vec<u8> = (0..len).map(|i| (i * 7 + 3) as u8).collect();
+            let expected = p.eval(&input).unwrap();
+
+            let cs = ConstraintSystem::<F>::new_ref();
+            let input = UInt8::new_witness_vec(cs.clone(), &input).unwrap();
+            let output = p.circuit(cs.clone(), &input).unwrap();
+
+            assert!(cs.is_satisfied().unwrap(), "{} len={len}", p.name());
+            assert_eq!(output.value().unwrap(), expected, "{} len={len}", p.name());
+        }
+    }
+
+    #[test]
+    fn test_precompile_circuits() {
+        for (_, p) in Precompiles::default().iter() {
+            check_precompile(p);
+        }
+    }
+
+    #[test]
+    fn test_register() {
+        let mut p = Precompiles::default();
+        assert!(p.register(KECCAK256, Keccak256).is_err());
+        assert!(p.register(5, Keccak256).is_err());
+        assert!(p.register(FIRST_PRECOMPILE + 100, Keccak256).is_ok());
+        assert_eq!(p.get(FIRST_PRECOMPILE + 100).unwrap().name(), "keccak256");
+        assert!(p.get(FIRST_PRECOMPILE + 101).is_none());
+    }
+}
@@ -0,0 +1,251 @@
+//! Keccak-256 precompile
+
+use ark_r1cs_std::{
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    uint8::UInt8,
+    ToBitsGadget,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use sha3::{Digest, Keccak256 as Hasher};
+
+use super::Precompile;
+use crate::circuit::F;
+use crate::error::Result;
+
+/// Keccak-256, as used by Ethereum.
+pub struct Keccak256;
+
+// rate in bytes
+const RATE: usize = 136;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+// rotation offsets, indexed by x + 5y
+#[rustfmt::skip]
+const ROTATIONS: [usize; 25] = [
+     0,  1, 62, 28, 27,
+    36, 44,  6, 55, 20,
+     3, 10, 43, 25, 39,
+    41, 45, 15, 21,  8,
+    18,  2, 61, 56, 14,
+];
+
+// A 64-bit lane, as little-endian bits.
+type Lane = Vec<Boolean<F>>;
+
+fn xor(a: &Lane, b: &Lane) -> Result<Lane, SynthesisError> {
+    a.iter().zip(b).map(|(x, y)| x.xor(y)).collect()
+}
+
+fn rotl(a: &Lane, n: usize) -> Lane {
+    (0..64).map(|i| a[(i + 64 - n) % 64].clone()).collect()
+}
+
+fn constant(x: u64) -> Lane {
+    (0..64)
+        .map(|i| Boolean::constant((x >> i) & 1 == 1))
+        .collect()
+}
+
+fn keccak_f(mut a: Vec<Lane>) -> Result<Vec<Lane>, SynthesisError> {
+    for rc in ROUND_CONSTANTS {
+        // theta
+        let mut c = Vec::with_capacity(5);
+        for x in 0..5 {
+            let mut l = a[x].clone();
+            for y in 1..5 {
+                l = xor(&l, &a[x + 5 * y])?;
+            }
+            c.push(l);
+        }
+        for x in 0..5 {
+            let d = xor(&c[(x + 4) % 5], &rotl(&c[(x + 1) % 5], 1))?;
+            for y in 0..5 {
+                a[x + 5 * y] = xor(&a[x + 5 * y], &d)?;
+            }
+        }
+
+        // rho and pi
+        let mut b = vec![Lane::new(); 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = rotl(&a[x + 5 * y], ROTATIONS[x + 5 * y]);
+            }
+        }
+
+        // chi
+        for x in 0..5 {
+            for y in 0..5 {
+                let l1 = &b[(x + 1) % 5 + 5 * y];
+                let l2 = &b[(x + 2) % 5 + 5 * y];
+                a[x + 5 * y] = b[x + 5 * y]
+                    .iter()
+                    .zip(l1.iter().zip(l2))
+                    .map(|(u, (v, w))| u.xor(&v.not().and(w)?))
+                    .collect::<Result<_, _>>()?;
+            }
+        }
+
+        // iota
+        a[0] = xor(&a[0], &constant(rc))?;
+    }
+    Ok(a)
+}
+
+// Absorb the padded message `msg`, and squeeze the first 32 bytes.
+fn sponge(msg: &[UInt8<F>]) -> Result<Vec<UInt8<F>>, SynthesisError> {
+    let mut state = vec![constant(0); 25];
+    for block in msg.chunks(RATE) {
+        for (i, lane) in block.chunks(8).enumerate() {
+            let bits = lane.to_bits_le()?;
+            state[i] = xor(&state[i], &bits)?;
+        }
+        state = keccak_f(state)?;
+    }
+
+    Ok(state[..4]
+        .iter()
+        .flat_map(|l| l.chunks(8).map(UInt8::from_bits_le))
+        .collect())
+}
+
+impl Keccak256 {
+    /// Generate the constraints computing the hash of the first `len`
+    /// bytes of `input`, where `len` is a variable which is at most
+    /// `input.len()`. The input must fit in a single block.
+    pub fn bounded_circuit(
+        &self,
+        input: &[UInt8<F>],
+        len: &FpVar<F>,
+    ) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        assert!(input.len() < RATE);
+
+        // len = i, for exactly one i in 0..=input.len()
+        let eq = (0..=input.len())
+            .map(|i| len.is_eq(&FpVar::constant(F::from(i as u64))))
+            .collect::<Result<Vec<_>, _>>()?;
+        eq.iter()
+            .fold(FpVar::zero(), |s, b| s + FpVar::from(b.clone()))
+            .enforce_equal(&FpVar::one())?;
+
+        // pad10*1 with the original Keccak domain byte, which replaces
+        // the bytes of the input from position len
+        let mut msg = vec![UInt8::constant(0); RATE];
+        let mut past = Boolean::FALSE;
+        for (i, eq) in eq.iter().enumerate() {
+            past = past.or(eq)?;
+            let mut bits = match input.get(i) {
+                Some(b) => b
+                    .to_bits_le()?
+                    .iter()
+                    .map(|x| x.and(&past.not()))
+                    .collect::<Result<Vec<_>, _>>()?,
+                None => vec![Boolean::FALSE; 8],
+            };
+            bits[0] = bits[0].or(eq)?;
+            msg[i] = UInt8::from_bits_le(&bits);
+        }
+        msg[RATE - 1] = UInt8::constant(0x80);
+
+        sponge(&msg)
+    }
+}
+
+impl Precompile for Keccak256 {
+    fn name(&self) -> &'static str {
+        "keccak256"
+    }
+
+    fn eval(&self, input: &[u8]) -> Result<Vec<u8>> {
+        Ok(Hasher::digest(input).to_vec())
+    }
+
+    fn circuit(
+        &self,
+        _cs: ConstraintSystemRef<F>,
+        input: &[UInt8<F>],
+    ) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        // pad10*1 with the original Keccak domain byte
+        let mut msg = input.to_vec();
+        msg.push(UInt8::constant(0x01));
+        while msg.len() % RATE != 0 {
+            msg.push(UInt8::constant(0));
+        }
+        let n = msg.len();
+        msg[n - 1] = UInt8::constant(0x80).xor(&msg[n - 1])?;
+
+        sponge(&msg)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_r1cs_std::{alloc::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    #[test]
+    fn test_bounded_circuit() {
+        let input: Vec<u8> = (0..32).map(|i| i * 5 + 1).collect();
+        for len in [0, 1, 7, 31, 32] {
+            let cs = ConstraintSystem::<F>::new_ref();
+            let bytes = UInt8::new_witness_vec(cs.clone(), &input).unwrap();
+            let n = FpVar::new_witness(cs.clone(), || Ok(F::from(len as u64))).unwrap();
+            let output = Keccak256.bounded_circuit(&bytes, &n).unwrap();
+
+            assert!(cs.is_satisfied().unwrap(), "len={len}");
+            assert_eq!(
+                output.value().unwrap(),
+                Keccak256.eval(&input[..len]).unwrap(),
+                "len={len}"
+            );
+        }
+
+        // the length is at most the size of the input
+        let cs = ConstraintSystem::<F>::new_ref();
+        let bytes = UInt8::new_witness_vec(cs.clone(), &input).unwrap();
+        let n = FpVar::new_witness(cs.clone(), || Ok(F::from(33u64))).unwrap();
+        Keccak256.bounded_circuit(&bytes, &n).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_keccak256() {
+        let h = Keccak256.eval(b"").unwrap();
+        assert_eq!(
+            h,
+            [
+                0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7,
+                0x03, 0xc0, 0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04,
+                0x5d, 0x85, 0xa4, 0x70
+            ]
+        );
+    }
+}
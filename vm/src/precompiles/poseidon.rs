@@ -0,0 +1,51 @@
+//! Poseidon precompile
+//!
+//! The input is split into chunks of 31 bytes, each of which is read
+//! as a little-endian field element. These are hashed with the same
+//! Poseidon configuration used for memory commitments, and the output
+//! is the 32-byte little-endian encoding of the digest.
+
+use ark_crypto_primitives::crh::CRHSchemeGadget;
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{alloc::AllocVar, boolean::Boolean, uint8::UInt8, ToBitsGadget, ToBytesGadget};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use super::Precompile;
+use crate::circuit::F;
+use crate::error::Result;
+use crate::memory::path::{hash_leaf, poseidon_config, LeafHashG, ParamsVar};
+
+/// Poseidon over the scalar field of BN254.
+pub struct Poseidon;
+
+// bytes per field element
+const CHUNK: usize = 31;
+
+impl Precompile for Poseidon {
+    fn name(&self) -> &'static str {
+        "poseidon"
+    }
+
+    fn eval(&self, input: &[u8]) -> Result<Vec<u8>> {
+        let elems: Vec<F> = input
+            .chunks(CHUNK)
+            .map(F::from_le_bytes_mod_order)
+            .collect();
+        let digest = hash_leaf(&poseidon_config(), &elems)?;
+        Ok(digest.into_bigint().to_bytes_le())
+    }
+
+    fn circuit(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        input: &[UInt8<F>],
+    ) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        let params = ParamsVar::new_constant(cs, poseidon_config())?;
+        let elems = input
+            .chunks(CHUNK)
+            .map(|c| Boolean::le_bits_to_fp_var(&c.to_bits_le()?))
+            .collect::<Result<Vec<_>, _>>()?;
+        let digest = LeafHashG::evaluate(&params, &elems)?;
+        digest.to_bytes()
+    }
+}
@@ -0,0 +1,51 @@
+//! SHA-256 precompile
+
+use ark_crypto_primitives::crh::{
+    sha256::{constraints::Sha256Gadget, Sha256 as Hasher},
+    CRHScheme,
+};
+use ark_r1cs_std::uint8::UInt8;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use super::Precompile;
+use crate::circuit::F;
+use crate::error::{NexusVMError::HashError, Result};
+
+/// SHA-256.
+pub struct Sha256;
+
+impl Precompile for Sha256 {
+    fn name(&self) -> &'static str {
+        "sha256"
+    }
+
+    fn eval(&self, input: &[u8]) -> Result<Vec<u8>> {
+        Hasher::evaluate(&(), input).map_err(|e| HashError(e.to_string()))
+    }
+
+    fn circuit(
+        &self,
+        _cs: ConstraintSystemRef<F>,
+        input: &[UInt8<F>],
+    ) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        Ok(Sha256Gadget::digest(input)?.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sha256() {
+        let h = Sha256.eval(b"abc").unwrap();
+        assert_eq!(
+            h,
+            [
+                0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae,
+                0x22, 0x23, 0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61,
+                0xf2, 0x00, 0x15, 0xad
+            ]
+        );
+    }
+}
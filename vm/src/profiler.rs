@@ -0,0 +1,305 @@
+//! A symbolized execution profiler
+//!
+//! When enabled on a `NexusVM`, the profiler attributes each executed
+//! instruction, and its cycle count, to the function containing it,
+//! using the symbol table of the guest ELF file. Call stacks are
+//! reconstructed from the calling convention: a JAL or JALR writing the
+//! return address to `ra` (or `t0`) is a call, and a JALR jumping to
+//! `ra` (or `t0`) without linking is a return.
+//!
+//! Profiles can be written in the folded-stack format used by
+//! flamegraph tools, or in the pprof protobuf format.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use elf::{abi::STT_FUNC, endian::LittleEndian, ElfBytes};
+
+use crate::error::{NexusVMError::ELFFormat, Result};
+use crate::rv32::{Inst, RV32::*};
+
+const UNKNOWN: &str = "[unknown]";
+
+/// A function symbol from the guest ELF file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    /// Address of the first instruction.
+    pub addr: u32,
+    /// Size of the function, in bytes.
+    pub size: u32,
+    /// Demangled name.
+    pub name: String,
+}
+
+/// Counters for a single call stack.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Counts {
+    /// Number of instructions executed.
+    pub instructions: u64,
+    /// Number of cycles, as counted by `NexusVM::cycle_count`.
+    pub cycles: u64,
+}
+
+/// Execution profile of a guest program.
+#[derive(Debug, Default)]
+pub struct Profiler {
+    // function symbols, sorted by address
+    symbols: Vec<Symbol>,
+    // indices of the functions on the call stack, excluding the current one
+    stack: Vec<usize>,
+    // counters indexed by call stack, outermost function first
+    counts: HashMap<Vec<usize>, Counts>,
+}
+
+impl Profiler {
+    /// Create a profiler using the given function symbols.
+    pub fn new(mut symbols: Vec<Symbol>) -> Self {
+        symbols.sort_by_key(|s| s.addr);
+        Self { symbols, ..Self::default() }
+    }
+
+    /// Create a profiler using the function symbols of an ELF file.
+    pub fn from_elf(elf: &ElfBytes<LittleEndian>) -> Result<Self> {
+        let Some((symtab, strtab)) = elf.symbol_table()? else {
+            return Err(ELFFormat("missing symbol table"));
+        };
+        let mut symbols = Vec::new();
+        for sym in symtab.iter() {
+            if sym.st_symtype() != STT_FUNC || (sym.st_value == 0 && sym.st_size == 0) {
+                continue;
+            }
+            let name = strtab.get(sym.st_name as usize)?;
+            symbols.push(Symbol {
+                addr: sym.st_value as u32,
+                size: sym.st_size as u32,
+                name: format!("{:#}", rustc_demangle::demangle(name)),
+            });
+        }
+        Ok(Self::new(symbols))
+    }
+
+    // index of the function containing `pc`, or `symbols.len()` if unknown
+    fn lookup(&self, pc: u32) -> usize {
+        let i = self.symbols.partition_point(|s| s.addr <= pc);
+        match i.checked_sub(1) {
+            Some(i) if pc - self.symbols[i].addr < self.symbols[i].size.max(1) => i,
+            _ => self.symbols.len(),
+        }
+    }
+
+    fn name(&self, i: usize) -> &str {
+        self.symbols.get(i).map_or(UNKNOWN, |s| s.name.as_str())
+    }
+
+    /// Record the execution of `inst`, which took `cycles` cycles.
+    pub fn record(&mut self, inst: &Inst, cycles: u64) {
+        let f = self.lookup(inst.pc);
+
+        let mut stack = self.stack.clone();
+        stack.push(f);
+        let c = self.counts.entry(stack).or_default();
+        c.instructions += 1;
+        c.cycles += cycles;
+
+        let is_link = |r: u32| r == 1 || r == 5;
+        match inst.inst {
+            JAL { rd, .. } | JALR { rd, .. } if is_link(rd) => self.stack.push(f),
+            JALR { rd: 0, rs1, .. } if is_link(rs1) => {
+                self.stack.pop();
+            }
+            _ => (),
+        }
+    }
+
+    /// Return the counters for each call stack, as lists of function
+    /// names with the outermost function first.
+    pub fn stacks(&self) -> Vec<(Vec<&str>, Counts)> {
+        let mut v: Vec<_> = self
+            .counts
+            .iter()
+            .map(|(s, c)| (s.iter().map(|i| self.name(*i)).collect::<Vec<_>>(), *c))
+            .collect();
+        v.sort_by(|a, b| a.0.cmp(&b.0));
+        v
+    }
+
+    /// Return the total counters for each function, including time
+    /// spent in the functions it calls, sorted by decreasing cycles.
+    pub fn functions(&self) -> Vec<(&str, Counts)> {
+        let mut totals: HashMap<usize, Counts> = HashMap::new();
+        for (stack, c) in &self.counts {
+            let mut seen = stack.clone();
+            seen.sort();
+            seen.dedup();
+            for f in seen {
+                let t = totals.entry(f).or_default();
+                t.instructions += c.instructions;
+                t.cycles += c.cycles;
+            }
+        }
+        let mut v: Vec<_> = totals.into_iter().map(|(f, c)| (self.name(f), c)).collect();
+        v.sort_by(|a, b| b.1.cycles.cmp(&a.1.cycles).then(a.0.cmp(b.0)));
+        v
+    }
+
+    /// Write the profile in folded-stack format, one line per call
+    /// stack, weighted by cycles.
+    pub fn write_folded(&self, mut w: impl Write) -> Result<()> {
+        for (stack, c) in self.stacks() {
+            writeln!(w, "{} {}", stack.join(";"), c.cycles)?;
+        }
+        Ok(())
+    }
+
+    /// Write the profile in the (uncompressed) pprof protobuf format,
+    /// with samples for both instructions and cycles.
+    pub fn write_pprof(&self, mut w: impl Write) -> Result<()> {
+        let mut strings = vec![String::new()];
+        let mut string = |s: &str| -> u64 {
+            match strings.iter().position(|x| x == s) {
+                Some(i) => i as u64,
+                None => {
+                    strings.push(s.to_string());
+                    (strings.len() - 1) as u64
+                }
+            }
+        };
+
+        let mut profile = Vec::new();
+        for (ty, unit) in [("instructions", "count"), ("cycles", "count")] {
+            let mut vt = Vec::new();
+            pb_varint_field(&mut vt, 1, string(ty));
+            pb_varint_field(&mut vt, 2, string(unit));
+            pb_bytes_field(&mut profile, 1, &vt);
+        }
+
+        // one function and one location per symbol, with id = index + 1
+        for (stack, c) in &self.counts {
+            let mut sample = Vec::new();
+            let ids: Vec<u64> = stack.iter().rev().map(|i| *i as u64 + 1).collect();
+            pb_packed_field(&mut sample, 1, &ids);
+            pb_packed_field(&mut sample, 2, &[c.instructions, c.cycles]);
+            pb_bytes_field(&mut profile, 2, &sample);
+        }
+        for i in 0..=self.symbols.len() {
+            let id = i as u64 + 1;
+            let mut line = Vec::new();
+            pb_varint_field(&mut line, 1, id);
+
+            let mut loc = Vec::new();
+            pb_varint_field(&mut loc, 1, id);
+            pb_varint_field(
+                &mut loc,
+                3,
+                self.symbols.get(i).map_or(0, |s| s.addr as u64),
+            );
+            pb_bytes_field(&mut loc, 4, &line);
+            pb_bytes_field(&mut profile, 4, &loc);
+
+            let mut func = Vec::new();
+            pb_varint_field(&mut func, 1, id);
+            pb_varint_field(&mut func, 2, string(self.name(i)));
+            pb_bytes_field(&mut profile, 5, &func);
+        }
+        for s in &strings {
+            pb_bytes_field(&mut profile, 6, s.as_bytes());
+        }
+
+        w.write_all(&profile)?;
+        Ok(())
+    }
+}
+
+// Minimal protobuf encoding, sufficient for the pprof format.
+
+fn pb_varint(buf: &mut Vec<u8>, mut x: u64) {
+    while x >= 0x80 {
+        buf.push(x as u8 | 0x80);
+        x >>= 7;
+    }
+    buf.push(x as u8);
+}
+
+fn pb_varint_field(buf: &mut Vec<u8>, field: u64, x: u64) {
+    pb_varint(buf, field << 3);
+    pb_varint(buf, x);
+}
+
+fn pb_bytes_field(buf: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    pb_varint(buf, field << 3 | 2);
+    pb_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn pb_packed_field(buf: &mut Vec<u8>, field: u64, xs: &[u64]) {
+    let mut packed = Vec::new();
+    for x in xs {
+        pb_varint(&mut packed, *x);
+    }
+    pb_bytes_field(buf, field, &packed);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{eval::NexusVM, memory::paged::Paged};
+
+    fn profile() -> Profiler {
+        let code: [u32; 5] = [
+            0x0080006f, // jal ra, 8
+            0xc0001073, // unimp
+            0x00000013, // nop
+            0x00000013, // nop
+            0x00008067, // ret
+        ];
+        let mut vm = NexusVM::<Paged>::new(0);
+        let bytes: Vec<u8> = code.iter().flat_map(|w| w.to_le_bytes()).collect();
+        vm.init_memory(0, &bytes).unwrap();
+        vm.profiler = Some(Profiler::new(vec![
+            Symbol { addr: 8, size: 12, name: "f".into() },
+            Symbol { addr: 0, size: 8, name: "main".into() },
+        ]));
+        crate::eval(&mut vm, false, false).unwrap();
+        vm.profiler.unwrap()
+    }
+
+    #[test]
+    fn profile_stacks() {
+        let p = profile();
+        let stacks = p.stacks();
+        assert_eq!(stacks.len(), 2);
+        assert_eq!(stacks[0].0, vec!["main"]);
+        assert_eq!(stacks[0].1, Counts { instructions: 2, cycles: 1 });
+        assert_eq!(stacks[1].0, vec!["main", "f"]);
+        assert_eq!(stacks[1].1, Counts { instructions: 3, cycles: 3 });
+
+        let fs = p.functions();
+        assert_eq!(fs[0], ("main", Counts { instructions: 5, cycles: 4 }));
+        assert_eq!(fs[1], ("f", Counts { instructions: 3, cycles: 3 }));
+
+        let mut folded = Vec::new();
+        p.write_folded(&mut folded).unwrap();
+        assert_eq!(String::from_utf8(folded).unwrap(), "main 1\nmain;f 3\n");
+    }
+
+    #[test]
+    fn profile_lookup() {
+        let p = profile();
+        assert_eq!(p.lookup(0), 0);
+        assert_eq!(p.lookup(4), 0);
+        assert_eq!(p.lookup(8), 1);
+        assert_eq!(p.lookup(0x14), 2);
+        assert_eq!(p.name(2), UNKNOWN);
+    }
+
+    #[test]
+    fn protobuf_encoding() {
+        let mut buf = Vec::new();
+        pb_varint(&mut buf, 300);
+        assert_eq!(buf, [0xac, 0x02]);
+
+        let mut buf = Vec::new();
+        pb_bytes_field(&mut buf, 6, b"ab");
+        assert_eq!(buf, [0x32, 2, b'a', b'b']);
+    }
+}
@@ -48,12 +48,29 @@ pub enum AOP {
     OR,
     AND,
     XOR,
+    // RV32M extension
+    MUL,
+    MULH,
+    MULHSU,
+    MULHU,
+    DIV,
+    DIVU,
+    REM,
+    REMU,
 }
 pub use AOP::*;
 
+impl AOP {
+    /// true if the operation belongs to the RV32M extension
+    pub const fn is_rv32m(&self) -> bool {
+        matches!(self, MUL | MULH | MULHSU | MULHU | DIV | DIVU | REM | REMU)
+    }
+}
+
 #[derive(Eq, Hash, PartialEq)]
 pub enum InstructionSet {
     RV32i,
+    RV32m,
     RV32Nexus,
 }
 
@@ -184,10 +201,11 @@ pub struct Inst {
 
 impl RV32 {
     /// maximum J value
-    pub const MAX_J: u32 = 42;
+    pub const MAX_J: u32 = 50;
 
     pub const fn instruction_set(&self) -> InstructionSet {
         match self {
+            ALU { aop, .. } if aop.is_rv32m() => InstructionSet::RV32m,
             LUI { .. }
             | AUIPC { .. }
             | JAL { .. }
@@ -244,6 +262,11 @@ impl RV32 {
             ALUI { aop: SRA, .. } => 26,
             ALUI { aop: OR, .. } => 27,
             ALUI { aop: AND, .. } => 28,
+            // note: RV32M has no immediate forms, these do not exist
+            ALUI {
+                aop: MUL | MULH | MULHSU | MULHU | DIV | DIVU | REM | REMU,
+                ..
+            } => Self::MAX_J,
 
             ALU { aop: ADD, .. } => 29,
             ALU { aop: SUB, .. } => 30,
@@ -256,10 +279,19 @@ impl RV32 {
             ALU { aop: OR, .. } => 37,
             ALU { aop: AND, .. } => 38,
 
-            FENCE => 39,
-            ECALL { .. } => 40,
-            EBREAK { .. } => 41,
-            UNIMP => 42,
+            ALU { aop: MUL, .. } => 39,
+            ALU { aop: MULH, .. } => 40,
+            ALU { aop: MULHSU, .. } => 41,
+            ALU { aop: MULHU, .. } => 42,
+            ALU { aop: DIV, .. } => 43,
+            ALU { aop: DIVU, .. } => 44,
+            ALU { aop: REM, .. } => 45,
+            ALU { aop: REMU, .. } => 46,
+
+            FENCE => 47,
+            ECALL { .. } => 48,
+            EBREAK { .. } => 49,
+            UNIMP => 50,
         }
     }
 }
@@ -272,4 +304,18 @@ mod test {
     fn check_j() {
         assert!(RV32::UNIMP.index_j() == RV32::MAX_J);
     }
+
+    #[test]
+    fn check_j_rv32m() {
+        let first = (ALU { aop: AND, rd: 0, rs1: 0, rs2: 0 }).index_j();
+        for (i, aop) in [MUL, MULH, MULHSU, MULHU, DIV, DIVU, REM, REMU]
+            .into_iter()
+            .enumerate()
+        {
+            let inst = ALU { aop, rd: 0, rs1: 0, rs2: 0 };
+            assert_eq!(inst.index_j(), first + 1 + i as u32);
+            assert!(inst.instruction_set() == InstructionSet::RV32m);
+        }
+        assert!(FENCE.index_j() < RV32::MAX_J);
+    }
 }
@@ -131,6 +131,15 @@ fn aop(word: u32) -> Option<AOP> {
         (0b101, 0b0100000) => SRA,
         (0b110, 0b0000000) => OR,
         (0b111, 0b0000000) => AND,
+        // RV32M extension pg. 43-45
+        (0b000, 0b0000001) => MUL,
+        (0b001, 0b0000001) => MULH,
+        (0b010, 0b0000001) => MULHSU,
+        (0b011, 0b0000001) => MULHU,
+        (0b100, 0b0000001) => DIV,
+        (0b101, 0b0000001) => DIVU,
+        (0b110, 0b0000001) => REM,
+        (0b111, 0b0000001) => REMU,
         _ => return None,
     };
     Some(res)
@@ -505,6 +514,29 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_rv32m() {
+        let ops = [MUL, MULH, MULHSU, MULHU, DIV, DIVU, REM, REMU];
+
+        for rd in [0, 1, 31] {
+            for rs1 in [0, 1, 31] {
+                for f3 in 0..8u32 {
+                    let word = 0x03f00033 | (rs1 << 15) | (f3 << 12) | (rd << 7);
+                    let inst = ALU { aop: ops[f3 as usize], rd, rs1, rs2: 31 };
+                    assert_eq!(parse_u32(word), Some(inst));
+                }
+            }
+        }
+
+        // no immediate forms
+        assert_eq!(parse_u32(0x03f01013), None);
+        // mul x1, x2, x3
+        assert_eq!(
+            parse_u32(0x023100b3),
+            Some(ALU { aop: MUL, rd: 1, rs1: 2, rs2: 3 })
+        );
+    }
+
     #[test]
     fn test_nexus() {
         assert_eq!(parse_u32(0x00000573), Some(ECALL { rd: 10 }));
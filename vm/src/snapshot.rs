@@ -0,0 +1,207 @@
+//! Snapshots of the Nexus VM state
+//!
+//! A `Snapshot` captures the state of a `NexusVM` between two
+//! instructions: the register file, the contents of memory, the
+//! input and output tapes, the cycle counters, and the memory
+//! layout. A VM restored from a snapshot continues execution
+//! exactly where the original left off; in particular, `trace::trace`
+//! will resume the program trace at the corresponding block. This allows long programs to be checkpointed,
+//! and traces to be produced in segments on different machines.
+//!
+//! Snapshots are stored on disk prefixed with a format version number.
+//! Loading a snapshot with a different version is an error.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::error::{NexusVMError::SnapshotVersion, Result};
+use crate::eval::{NexusVM, Regs};
+use crate::memory::{cacheline::CacheLine, layout::MemoryLayout, Memory};
+use crate::syscalls::Tapes;
+
+/// Current version of the snapshot format.
+pub const SNAPSHOT_VERSION: u32 = 4;
+
+/// A serializable copy of the state of a `NexusVM`.
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Snapshot {
+    /// ISA registers.
+    pub regs: Regs,
+    /// Non-zero memory contents, as (address, cacheline) pairs.
+    pub memory: Vec<(u32, [u32; 8])>,
+    /// Input and output tapes.
+    pub tapes: Tapes,
+    /// Number of executed instructions.
+    pub trace_len: usize,
+    /// Maximum number of instructions the VM is allowed to execute.
+    pub max_trace_len: Option<usize>,
+    /// The cycle count for execution trace.
+    pub cycle_count: u64,
+    /// The cycles tracker, as (func_name, cycle_count, counter).
+    pub cycle_tracker: Vec<(String, u64, u32)>,
+    /// Memory protection regions.
+    pub layout: MemoryLayout,
+}
+
+impl Snapshot {
+    /// Write this snapshot, prefixed by the format version.
+    pub fn write(&self, mut w: impl Write) -> Result<()> {
+        SNAPSHOT_VERSION.serialize_compressed(&mut w)?;
+        self.serialize_compressed(&mut w)?;
+        Ok(())
+    }
+
+    /// Read a snapshot written by `write`.
+    pub fn read(mut r: impl Read) -> Result<Self> {
+        let version = u32::deserialize_compressed(&mut r)?;
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotVersion(version));
+        }
+        Ok(Self::deserialize_compressed(&mut r)?)
+    }
+
+    /// Save this snapshot to the file at `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write(&mut w)?;
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Load a snapshot from the file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<M: Memory> NexusVM<M> {
+    /// Capture the current state of this VM.
+    pub fn snapshot(&self) -> Snapshot {
+        let mut cycle_tracker: Vec<_> = self
+            .cycle_tracker
+            .iter()
+            .map(|(name, (clk, cnt))| (name.clone(), *clk, *cnt))
+            .collect();
+        cycle_tracker.sort();
+
+        Snapshot {
+            regs: self.regs.clone(),
+            memory: self
+                .mem
+                .lines()
+                .into_iter()
+                .map(|(addr, cl)| (addr, unsafe { cl.words }))
+                .collect(),
+            tapes: self.syscalls.tapes(),
+            trace_len: self.trace_len,
+            max_trace_len: self.max_trace_len,
+            cycle_count: self.cycle_count,
+            cycle_tracker,
+            layout: self.layout,
+        }
+    }
+
+    /// Create a VM from a snapshot. The VM is created with the
+    /// default syscall configuration: output is not sent to stdout,
+    /// and only the standard precompiles are registered.
+    pub fn restore(snapshot: &Snapshot) -> Result<Self> {
+        let mut vm = Self::new(snapshot.regs.pc);
+        vm.regs = snapshot.regs.clone();
+
+        for (addr, words) in &snapshot.memory {
+            let line = CacheLine::from(*words);
+            vm.mem.update(*addr, |cl| {
+                *cl = line;
+                Ok(())
+            })?;
+        }
+
+        vm.syscalls.set_tapes(snapshot.tapes.clone());
+        vm.trace_len = snapshot.trace_len;
+        vm.max_trace_len = snapshot.max_trace_len;
+        vm.cycle_count = snapshot.cycle_count;
+        vm.cycle_tracker = snapshot
+            .cycle_tracker
+            .iter()
+            .map(|(name, clk, cnt)| (name.clone(), (*clk, *cnt)))
+            .collect::<HashMap<_, _>>();
+        vm.layout = snapshot.layout;
+        Ok(vm)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        machines::lookup_test_machine,
+        memory::{paged::Paged, trie::MerkleTrie},
+        trace::{trace, trace_segment},
+        NexusVMError,
+    };
+
+    fn round_trip(s: &Snapshot) -> Snapshot {
+        let mut bytes = Vec::new();
+        s.write(&mut bytes).unwrap();
+        Snapshot::read(bytes.as_slice()).unwrap()
+    }
+
+    // tracing in two segments, through a snapshot, gives the same
+    // blocks as tracing in one go
+    fn resume_trace<M: Memory>(k: usize, n: usize) {
+        let mut vm = lookup_test_machine::<M>("output").unwrap();
+        let full = trace(&mut vm, k, false).unwrap();
+
+        let mut vm = lookup_test_machine::<M>("output").unwrap();
+        let first = trace_segment(&mut vm, k, n).unwrap();
+        let snapshot = round_trip(&vm.snapshot());
+        assert_eq!(snapshot, vm.snapshot());
+
+        let mut vm = NexusVM::<M>::restore(&snapshot).unwrap();
+        let second = trace(&mut vm, k, false).unwrap();
+
+        assert_eq!(first.start, 0);
+        assert_eq!(second.start, n);
+        assert_eq!(full.blocks.len(), n + second.blocks.len());
+        for (i, b) in first.blocks.iter().chain(&second.blocks).enumerate() {
+            assert_eq!(b.regs, full.blocks[i].regs);
+            assert_eq!(b.io, full.blocks[i].io);
+            assert_eq!(second.input(i), full.input(i).filter(|_| i >= n));
+        }
+        assert_eq!(vm.syscalls.get_output(), vec![42, 43]);
+    }
+
+    #[test]
+    fn snapshot_resume_trace() {
+        resume_trace::<Paged>(1, 3);
+        resume_trace::<MerkleTrie>(1, 3);
+        resume_trace::<MerkleTrie>(2, 2);
+    }
+
+    #[test]
+    fn snapshot_misaligned() {
+        let mut vm = lookup_test_machine::<Paged>("output").unwrap();
+        trace_segment(&mut vm, 1, 3).unwrap();
+        let mut vm = NexusVM::<Paged>::restore(&vm.snapshot()).unwrap();
+        assert!(matches!(
+            trace(&mut vm, 2, false),
+            Err(NexusVMError::MisalignedTrace(3, 2))
+        ));
+    }
+
+    #[test]
+    fn snapshot_version() {
+        let vm = lookup_test_machine::<Paged>("output").unwrap();
+        let mut bytes = Vec::new();
+        vm.snapshot().write(&mut bytes).unwrap();
+        bytes[0] = 0xff;
+        assert!(matches!(
+            Snapshot::read(bytes.as_slice()),
+            Err(NexusVMError::SnapshotVersion(_))
+        ));
+    }
+}
@@ -1,11 +1,16 @@
 //! Implementation of system calls
 
 use std::collections::VecDeque;
+use std::fmt;
 use std::io::Write;
 
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::{Deserialize, Serialize};
+
 use crate::{
     error::{NexusVMError::UnknownECall, Result},
     memory::Memory,
+    precompiles::{Precompile, Precompiles},
 };
 
 /// Holds information related to syscall implementation.
@@ -14,15 +19,85 @@ pub struct Syscalls {
     to_stdout: bool,
     log_buffer: Vec<Vec<u8>>,
     input: VecDeque<u8>,
+    public_input: VecDeque<u8>,
+    public_input_read: Vec<u8>,
     output: Vec<u8>,
     label: Vec<Vec<u8>>,
+    precompiles: Precompiles,
+    panic_info: Vec<u8>,
+    exit_code: ExitCode,
+}
+
+/// The exit code of a guest program, set with the `Exit` syscall.
+///
+/// Programs which return from `main` exit with `ExitCode::SUCCESS`,
+/// and programs which panic exit with `ExitCode::PANIC`. The exit code
+/// is part of the step circuit state, and so is committed to by proofs.
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+)]
+pub struct ExitCode(pub u32);
+
+impl ExitCode {
+    /// Exit code of a successful run.
+    pub const SUCCESS: Self = Self(0);
+    /// Exit code of a run which ended in a panic.
+    pub const PANIC: Self = Self(101);
+
+    /// Returns true if this is `ExitCode::SUCCESS`.
+    pub fn is_success(self) -> bool {
+        self == Self::SUCCESS
+    }
+}
+
+impl From<u32> for ExitCode {
+    fn from(code: u32) -> Self {
+        Self(code)
+    }
+}
+
+impl fmt::Display for ExitCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// The contents of the input and output tapes, used to save and
+/// restore the syscall state of a VM.
+#[derive(Default, Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Tapes {
+    /// Unread private input.
+    pub input: Vec<u8>,
+    /// Public input read so far.
+    pub public_input_read: Vec<u8>,
+    /// Unread public input.
+    pub public_input: Vec<u8>,
+    /// Output written so far.
+    pub output: Vec<u8>,
+    /// Panic message written so far.
+    pub panic_info: Vec<u8>,
+    /// Exit code set so far.
+    pub exit_code: ExitCode,
 }
 
 pub enum SyscallCode {
     WriteLog = 1,
     ReadFromPrivateInput = 2,
     WriteToOutput = 3,
+    ReadFromPublicInput = 4,
     ProfileCycles = 5,
+    WritePanic = 7,
+    Exit = 8,
 }
 
 impl SyscallCode {
@@ -31,7 +106,10 @@ impl SyscallCode {
             1 => Ok(SyscallCode::WriteLog),
             2 => Ok(SyscallCode::ReadFromPrivateInput),
             3 => Ok(SyscallCode::WriteToOutput),
+            4 => Ok(SyscallCode::ReadFromPublicInput),
             5 => Ok(SyscallCode::ProfileCycles),
+            7 => Ok(SyscallCode::WritePanic),
+            8 => Ok(SyscallCode::Exit),
             _ => Err(UnknownECall(pc, syscode)),
         }
     }
@@ -49,6 +127,16 @@ impl Syscalls {
         self.input = slice.to_owned().into();
     }
 
+    pub fn set_public_input(&mut self, slice: &[u8]) {
+        self.public_input = slice.to_owned().into();
+        self.public_input_read.clear();
+    }
+
+    /// Returns the part of the public input read so far.
+    pub fn get_public_input_read(&self) -> &[u8] {
+        &self.public_input_read
+    }
+
     pub fn get_output(&mut self) -> Vec<u8> {
         self.output.clone()
     }
@@ -60,6 +148,55 @@ impl Syscalls {
     pub fn get_label(&mut self) -> Option<Vec<u8>> {
         self.label.pop()
     }
+
+    /// Returns the exit code set by the program, or `ExitCode::SUCCESS`
+    /// if the program has not called `Exit`.
+    pub fn get_exit_code(&self) -> ExitCode {
+        self.exit_code
+    }
+
+    /// Returns the panic location and message written by the program,
+    /// if it panicked.
+    pub fn get_panic_info(&self) -> Option<String> {
+        if self.panic_info.is_empty() {
+            None
+        } else {
+            Some(String::from_utf8_lossy(&self.panic_info).into_owned())
+        }
+    }
+
+    /// Returns the current contents of the input and output tapes.
+    pub fn tapes(&self) -> Tapes {
+        Tapes {
+            input: self.input.iter().copied().collect(),
+            public_input_read: self.public_input_read.clone(),
+            public_input: self.public_input.iter().copied().collect(),
+            output: self.output.clone(),
+            panic_info: self.panic_info.clone(),
+            exit_code: self.exit_code,
+        }
+    }
+
+    /// Replaces the contents of the input and output tapes.
+    pub fn set_tapes(&mut self, tapes: Tapes) {
+        self.input = tapes.input.into();
+        self.public_input_read = tapes.public_input_read;
+        self.public_input = tapes.public_input.into();
+        self.output = tapes.output;
+        self.panic_info = tapes.panic_info;
+        self.exit_code = tapes.exit_code;
+    }
+
+    /// Register a precompile under syscall number `code`, see [`Precompiles::register`].
+    pub fn register_precompile(&mut self, code: u32, p: impl Precompile + 'static) -> Result<()> {
+        self.precompiles.register(code, p)
+    }
+
+    /// Returns the registered precompiles.
+    pub fn precompiles(&self) -> &Precompiles {
+        &self.precompiles
+    }
+
     /// Read `len` bytes from memory starting at `source` to log.
     /// If `to_stdout` is true, writes the log to standard output; otherwise, stores it in `log_buffer`.
     ///
@@ -86,6 +223,30 @@ impl Syscalls {
         Ok(0)
     }
 
+    /// Reads `len` bytes from memory starting at `source`, and appends
+    /// them to the panic message. Unlike the log, the panic message is
+    /// always kept, so that it can be reported after the run.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<u32>` indicating success or any encountered errors.
+    fn write_panic(&mut self, source: u32, len: u32, memory: &impl Memory) -> Result<u32> {
+        let buf = memory.load_n(source, len)?;
+        self.panic_info.extend_from_slice(&buf);
+        Ok(0)
+    }
+
+    /// Sets the exit code of the program. The program is expected to
+    /// halt immediately afterwards.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<u32>` indicating success or any encountered errors.
+    fn exit(&mut self, code: u32) -> Result<u32> {
+        self.exit_code = ExitCode(code);
+        Ok(0)
+    }
+
     /// Reads a value from the private input buffer.
     /// If the buffer is empty, returns `u32::MAX`.
     ///
@@ -96,19 +257,39 @@ impl Syscalls {
         Ok(self.input.pop_front().map_or(u32::MAX, |b| b as u32))
     }
 
-    /// Writes `len` bytes from memory starting at `source` to the output.
+    /// Reads a value from the public input buffer.
+    /// If the buffer is empty, returns `u32::MAX`.
+    ///
+    /// Each byte read is absorbed into the running hash of the public
+    /// input carried by the step circuit state.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<u32>` containing the read value or indicating success or any encountered error.
+    fn read_from_public_input(&mut self) -> Result<u32> {
+        match self.public_input.pop_front() {
+            Some(b) => {
+                self.public_input_read.push(b);
+                Ok(b as u32)
+            }
+            None => Ok(u32::MAX),
+        }
+    }
+
+    /// Writes the low byte of `value` to the output.
+    ///
+    /// Output is written one byte per call so that the step circuit can
+    /// absorb each byte into the running hash of the output tape.
     ///
     /// # Arguments
     ///
-    /// * `source` - Starting address in memory to read the output from.
-    /// * `len` - Number of bytes to read from memory.
-    /// * `memory` - A reference to the memory implementation.
+    /// * `value` - Register holding the byte to write.
     ///
     /// # Returns
     ///
     /// A `Result<u32>` indicating success or any encountered errors.
-    fn write_to_output(&mut self, source: u32, len: u32, memory: &impl Memory) -> Result<u32> {
-        self.output.extend(memory.load_n(source, len)?);
+    fn write_to_output(&mut self, value: u32) -> Result<u32> {
+        self.output.push(value as u8);
         Ok(0)
     }
 
@@ -133,6 +314,9 @@ impl Syscalls {
 
     /// Handles the syscall based on the given program counter, registers, and memory.
     ///
+    /// Calls of precompiles, which write their output to memory, are
+    /// handled by the VM rather than here.
+    ///
     /// # Arguments
     ///
     /// * `pc` - Program counter.
@@ -143,15 +327,19 @@ impl Syscalls {
     ///
     /// A `Result<u32>` indicating success or any encountered errors.
     pub fn syscall(&mut self, pc: u32, regs: [u32; 32], memory: &impl Memory) -> Result<u32> {
-        let code = SyscallCode::try_from(pc, regs[18])?; // s2 = x18  syscall number
         let rs1 = regs[11]; // a1 = x11
         let rs2 = regs[12]; // a2 = x12
 
+        let code = SyscallCode::try_from(pc, regs[18])?; // s2 = x18  syscall number
+
         match code {
             SyscallCode::WriteLog => self.writelog(rs1, rs2, memory),
             SyscallCode::ReadFromPrivateInput => self.read_from_private_input(),
-            SyscallCode::WriteToOutput => self.write_to_output(rs1, rs2, memory),
+            SyscallCode::WriteToOutput => self.write_to_output(rs1),
+            SyscallCode::ReadFromPublicInput => self.read_from_public_input(),
             SyscallCode::ProfileCycles => self.profile_cycles(rs1, rs2, memory),
+            SyscallCode::WritePanic => self.write_panic(rs1, rs2, memory),
+            SyscallCode::Exit => self.exit(rs1),
         }
     }
 }
@@ -13,14 +13,30 @@
 //! step contained in the block. The witnesses can be reconstructed
 //! by iterating over the steps in the block.
 
-use crate::circuit::F;
-use crate::error::Result;
+use crate::circuit::{ARITY, F};
+use crate::error::{
+    NexusVMError::{MisalignedTrace, UnprovablePrecompile},
+    Result,
+};
 use crate::eval::{eval_inst, NexusVM, Regs};
-use crate::memory::{Memory, MemoryProof};
-use crate::rv32::{parse::*, RV32::UNIMP};
+use crate::memory::{
+    cacheline::CacheLine,
+    layout::MemoryLayout,
+    path::{compress, poseidon_config, Digest, Params},
+    trie::MerkleTrie,
+    Memory, MemoryProof,
+};
+use crate::precompiles::{provable, FIRST_PRECOMPILE};
+use crate::rv32::{
+    parse::*,
+    RV32::{ECALL, UNIMP},
+};
+use crate::syscalls::{ExitCode, SyscallCode};
 
+use ark_ff::{BigInteger, PrimeField};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use serde::{Deserialize, Serialize};
+use sha3::{Digest as _, Keccak256};
 
 /// Represents a program trace.
 #[derive(Default, Clone, Serialize, Deserialize, CanonicalSerialize, CanonicalDeserialize)]
@@ -38,6 +54,10 @@ pub struct Trace<P: MemoryProof> {
 pub struct Block<P: MemoryProof> {
     /// Starting register file for this block.
     pub regs: Regs,
+    /// Memory layout of the program.
+    pub layout: MemoryLayout,
+    /// Starting input and output hashes for this block.
+    pub io: IOHashes,
     /// Sequence of `k` steps contained in this block.
     pub steps: Vec<Step<P>>,
 }
@@ -62,11 +82,281 @@ pub struct Step<P: MemoryProof> {
     pub write_proof: Option<P>,
 }
 
+/// Running hashes of the public input and output tapes, together
+/// with the exit code of the program.
+///
+/// Starting from zero, each byte `b` read from the public input tape
+/// (resp. written to the output tape) updates the corresponding hash
+/// `h` to `compress(h, b)`. The exit code starts as `ExitCode::SUCCESS`,
+/// and is updated by each `Exit` syscall.
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+)]
+pub struct IOHashes {
+    /// Hash of the bytes read from the public input tape.
+    #[serde(with = "crate::ark_serde")]
+    pub input: Digest,
+    /// Hash of the bytes written to the output tape.
+    #[serde(with = "crate::ark_serde")]
+    pub output: Digest,
+    /// Exit code of the program.
+    pub exit_code: ExitCode,
+}
+
+// position of the input hash in the circuit state (see `Trace::input`),
+// which is followed by the output hash and the exit code
+const IO_INDEX: usize = 38;
+
+impl IOHashes {
+    /// Compute the hashes committing to the given public input and output tapes.
+    pub fn from_tapes(input: &[u8], output: &[u8]) -> Result<Self> {
+        let params = poseidon_config();
+        let mut io = IOHashes::default();
+        for b in input {
+            io.input = compress(&params, &io.input, &F::from(*b))?;
+        }
+        for b in output {
+            io.output = compress(&params, &io.output, &F::from(*b))?;
+        }
+        Ok(io)
+    }
+
+    /// Replace the exit code, see `from_tapes`.
+    pub fn with_exit_code(self, exit_code: ExitCode) -> Self {
+        Self { exit_code, ..self }
+    }
+
+    /// Return the hashes contained in a step circuit state vector.
+    /// Returns `None` if the state is too short, or does not contain
+    /// a valid exit code.
+    pub fn from_state(z: &[F]) -> Option<Self> {
+        let code = z.get(IO_INDEX + 2)?.into_bigint().0;
+        if code[1..].iter().any(|l| *l != 0) {
+            return None;
+        }
+        Some(IOHashes {
+            input: *z.get(IO_INDEX)?,
+            output: *z.get(IO_INDEX + 1)?,
+            exit_code: ExitCode(u32::try_from(code[0]).ok()?),
+        })
+    }
+
+    // Update the hashes after executing instruction `J`, given
+    // the initial values of s2 (x18) and a1 (x11), and the result `Z`.
+    fn update(&mut self, params: &Params, J: u32, s2: u32, a1: u32, Z: u32) -> Result<()> {
+        if J != (ECALL { rd: 0 }).index_j() {
+            return Ok(());
+        }
+        if s2 == SyscallCode::WriteToOutput as u32 {
+            self.output = compress(params, &self.output, &F::from(a1 & 0xff))?;
+        } else if s2 == SyscallCode::ReadFromPublicInput as u32 && Z != u32::MAX {
+            self.input = compress(params, &self.input, &F::from(Z))?;
+        } else if s2 == SyscallCode::Exit as u32 {
+            self.exit_code = ExitCode(a1);
+        }
+        Ok(())
+    }
+}
+
+/// Canonical identifier of a program, committing to its entry point, its
+/// memory layout and the contents of its loaded segments. See [`crate::program_id`].
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+)]
+pub struct ProgramId(pub [u8; 32]);
+
+impl ProgramId {
+    /// Compute the identifier of the program executed by `vm`, which must
+    /// not have executed any instruction yet. The identifier commits to the
+    /// Merkle root of the contents of memory, which is computed without
+    /// accessing the memory of `vm`.
+    pub fn initial(vm: &NexusVM<impl Memory>) -> Self {
+        Self::from_state(&state(
+            &vm.regs,
+            &vm.layout,
+            &IOHashes::default(),
+            memory_root(&vm.mem.lines()),
+        ))
+    }
+
+    /// Compute the identifier of the program whose execution starts from
+    /// the step circuit state `z_0`.
+    pub fn from_state(z_0: &[F]) -> Self {
+        let mut hasher = Keccak256::new();
+        for x in z_0 {
+            hasher.update(x.into_bigint().to_bytes_le());
+        }
+        Self(hasher.finalize().into())
+    }
+
+    /// Check that the step circuit state `z_0`, which may be followed by
+    /// other variables, is the initial state of this program.
+    pub fn check(&self, z_0: &[F]) -> std::result::Result<(), StatementMismatch> {
+        let z_0 = &z_0[..z_0.len().min(ARITY)];
+        let program = Self::from_state(z_0);
+        if program != *self {
+            return Err(StatementMismatch::Program(program));
+        }
+        // implied by the program identifier, but checked anyway: executions
+        // start with empty tapes
+        if IOHashes::from_state(z_0) != Some(IOHashes::default()) {
+            return Err(StatementMismatch::IO);
+        }
+        Ok(())
+    }
+
+    /// Check, as [`ProgramId::check`], an execution whose memory is not
+    /// committed to by its Merkle root, such as with offline memory
+    /// checking. The program is identified from `initial`, the contents of
+    /// memory before the execution, in place of the memory commitment of `z_0`.
+    pub fn check_with_memory(
+        &self,
+        z_0: &[F],
+        initial: &[(u32, CacheLine)],
+    ) -> std::result::Result<(), StatementMismatch> {
+        let mut z_0 = z_0[..z_0.len().min(ARITY)].to_vec();
+        if let Some(mem) = z_0.get_mut(ARITY - 1) {
+            *mem = memory_root(initial);
+        }
+        self.check(&z_0)
+    }
+}
+
+impl std::fmt::Display for ProgramId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for b in self.0 {
+            write!(f, "{b:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Statement proven about an execution: the identifier of the executed
+/// program, and the hashes of the public input it read and of the output
+/// it wrote, together with its exit code.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Statement {
+    /// Identifier of the program, which also commits to its initial state.
+    pub program: ProgramId,
+    /// Hashes of the tapes, and exit code, at the end of the execution.
+    pub io: IOHashes,
+}
+
+/// The ways in which a proven execution may differ from a [`Statement`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatementMismatch {
+    /// The execution is of another program, with the contained identifier.
+    Program(ProgramId),
+    /// The execution read other public input, or wrote other output.
+    IO,
+    /// The execution exited with the contained code.
+    ExitCode(ExitCode),
+}
+
+impl Statement {
+    /// The statement that `program` read `input` from the public input tape,
+    /// wrote `output` to the output tape, and exited with `exit_code`.
+    pub fn new(
+        program: ProgramId,
+        input: &[u8],
+        output: &[u8],
+        exit_code: ExitCode,
+    ) -> Result<Self> {
+        let io = IOHashes::from_tapes(input, output)?.with_exit_code(exit_code);
+        Ok(Self { program, io })
+    }
+
+    /// Check the statement against an execution proven from the step circuit
+    /// state `z_0` to `z_i`. States may be followed by other variables, which
+    /// are ignored.
+    pub fn check(&self, z_0: &[F], z_i: &[F]) -> std::result::Result<(), StatementMismatch> {
+        self.program.check(z_0)?;
+        self.check_io(z_i)
+    }
+
+    /// Check the statement as [`Statement::check`], against an execution
+    /// whose memory is not committed to by its Merkle root, see
+    /// [`ProgramId::check_with_memory`].
+    pub fn check_with_memory(
+        &self,
+        z_0: &[F],
+        z_i: &[F],
+        initial: &[(u32, CacheLine)],
+    ) -> std::result::Result<(), StatementMismatch> {
+        self.program.check_with_memory(z_0, initial)?;
+        self.check_io(z_i)
+    }
+
+    fn check_io(&self, z_i: &[F]) -> std::result::Result<(), StatementMismatch> {
+        let Some(io) = IOHashes::from_state(z_i) else {
+            return Err(StatementMismatch::IO);
+        };
+        if io.with_exit_code(self.io.exit_code) != self.io {
+            return Err(StatementMismatch::IO);
+        }
+        if io.exit_code != self.io.exit_code {
+            return Err(StatementMismatch::ExitCode(io.exit_code));
+        }
+        Ok(())
+    }
+}
+
+// The Merkle root of a memory holding `lines`.
+fn memory_root(lines: &[(u32, CacheLine)]) -> Digest {
+    let mut mem = MerkleTrie::default();
+    for (addr, cl) in lines {
+        // replacing a whole cache line cannot fail
+        let _ = mem.update(*addr, |line| {
+            *line = *cl;
+            Ok(())
+        });
+    }
+    mem.root()
+}
+
+// The step circuit state: the registers, the memory layout, the input and
+// output hashes and exit code, and the commitment to the memory.
+fn state(regs: &Regs, layout: &MemoryLayout, io: &IOHashes, mem: F) -> Vec<F> {
+    let mut v = Vec::new();
+    v.push(F::from(regs.pc));
+    for x in regs.x {
+        v.push(F::from(x));
+    }
+    v.push(F::from(layout.exec_start));
+    v.push(F::from(layout.exec_end));
+    v.push(F::from(layout.ro_start));
+    v.push(F::from(layout.ro_end));
+    v.push(F::from(layout.top));
+    v.push(io.input);
+    v.push(io.output);
+    v.push(F::from(io.exit_code.0));
+    v.push(mem);
+    v
+}
+
 impl<P: MemoryProof> Trace<P> {
     /// Split a trace into subtraces with `n` blocks each. Note, the
     /// final subtrace may contain fewer than `n` blocks.
     pub fn split_by(&self, n: usize) -> impl Iterator<Item = Self> + '_ {
-        let mut index = 0;
+        let mut index = self.start;
         self.blocks.chunks(n).map(move |bs| {
             let start = index;
             index += n;
@@ -95,13 +385,28 @@ impl<P: MemoryProof> Trace<P> {
     /// This vector is compatible with the step circuit.
     pub fn input(&self, n: usize) -> Option<Vec<F>> {
         let b = self.block(n)?;
-        let mut v = Vec::new();
-        v.push(F::from(b.regs.pc));
-        for x in b.regs.x {
-            v.push(F::from(x));
+        Some(state(
+            &b.regs,
+            &b.layout,
+            &b.io,
+            b.steps[0].pc_proof.commit(),
+        ))
+    }
+
+    /// Check that no step of this trace calls a precompile. Precompile
+    /// calls are only constrained by the circuit of their own class (see
+    /// `OpcodeClass`), and so cannot be proven by the step circuit of
+    /// every instruction.
+    pub fn check_no_precompiles(&self) -> Result<()> {
+        let ecall = (ECALL { rd: 0 }).index_j();
+        for b in &self.blocks {
+            for w in b {
+                if w.J == ecall && w.regs.x[18] >= FIRST_PRECOMPILE {
+                    return Err(UnprovablePrecompile(w.regs.pc, w.regs.x[18]));
+                }
+            }
         }
-        v.push(b.steps[0].pc_proof.commit());
-        Some(v)
+        Ok(())
     }
 
     /// Estimate the size, in bytes, of this trace.
@@ -114,10 +419,27 @@ impl<P: MemoryProof> Trace<P> {
     }
 }
 
-// Generate a `Step` by evaluating the next instruction of `vm`.
-fn step<M: Memory>(vm: &mut NexusVM<M>) -> Result<Step<M::Proof>> {
+// Generate a `Step` by evaluating the next instruction of `vm`,
+// updating the running input and output hashes.
+//
+// Only the precompile calls which the step circuits constrain are
+// traced, see `precompiles::provable`: executions making other calls
+// are rejected.
+fn step<M: Memory>(
+    vm: &mut NexusVM<M>,
+    params: &Params,
+    io: &mut IOHashes,
+) -> Result<Step<M::Proof>> {
     let pc = vm.regs.pc;
+    let [s2, a1, a2, a3] = [18, 11, 12, 13].map(|i| vm.regs.x[i]);
     eval_inst(vm)?;
+    if vm.inst.inst.index_j() == (ECALL { rd: 0 }).index_j()
+        && s2 >= FIRST_PRECOMPILE
+        && !provable(s2, a1, a2, a3)
+    {
+        return Err(UnprovablePrecompile(pc, s2));
+    }
+    io.update(params, vm.inst.inst.index_j(), s2, a1, vm.Z)?;
     let step = Step {
         inst: vm.inst.word,
         Z: vm.Z,
@@ -134,46 +456,165 @@ fn step<M: Memory>(vm: &mut NexusVM<M>) -> Result<Step<M::Proof>> {
 }
 
 // Generate a `Block` by evaluating `k` steps of `vm`.
-fn k_step<M: Memory>(vm: &mut NexusVM<M>, k: usize) -> Result<Block<M::Proof>> {
-    let mut block = Block { regs: vm.regs.clone(), steps: Vec::new() };
+fn k_step<M: Memory>(
+    vm: &mut NexusVM<M>,
+    k: usize,
+    params: &Params,
+    io: &mut IOHashes,
+) -> Result<Block<M::Proof>> {
+    let mut block = Block {
+        regs: vm.regs.clone(),
+        layout: vm.layout,
+        io: *io,
+        steps: Vec::new(),
+    };
 
     for _ in 0..k {
-        block.steps.push(step(vm)?);
+        block.steps.push(step(vm, params, io)?);
     }
 
     Ok(block)
 }
 
-/// Generate a program trace by evaluating `vm`, using `k` steps
-/// per block. If `pow` is true, the number of blocks will be
-/// rounded up to the nearest power of two by inserting UNIMP
-/// instructions.
-pub fn trace<M: Memory>(vm: &mut NexusVM<M>, k: usize, pow: bool) -> Result<Trace<M::Proof>> {
-    let mut trace = Trace { k, start: 0, blocks: Vec::new() };
+/// A program trace, generated one block at a time.
+///
+/// A `TraceStream` evaluates its VM lazily, yielding each block as a
+/// single-block subtrace (see `Trace::get`). Consumers which process
+/// blocks in order, such as the sequential prover, only need to hold
+/// the blocks they are currently working on, rather than the complete
+/// trace. The blocks produced are the same as those of `trace`.
+pub struct TraceStream<'a, M: Memory> {
+    vm: &'a mut NexusVM<M>,
+    k: usize,
+    pow: bool,
+    params: Params,
+    io: IOHashes,
+    index: usize,
+    done: bool,
+}
+
+impl<'a, M: Memory> TraceStream<'a, M> {
+    /// Start streaming the trace of `vm`, using `k` steps per block.
+    /// If `pow` is true, the number of blocks will be rounded up as
+    /// described for `trace`.
+    ///
+    /// If `vm` has already executed instructions, for instance because
+    /// it was restored from a `Snapshot`, the trace is resumed from the
+    /// current state: the number of executed instructions must be a
+    /// multiple of `k`, and the first block has index `vm.trace_len / k`.
+    ///
+    /// [`Snapshot`]: crate::snapshot::Snapshot
+    pub fn new(vm: &'a mut NexusVM<M>, k: usize, pow: bool) -> Result<Self> {
+        if vm.trace_len % k != 0 {
+            return Err(MisalignedTrace(vm.trace_len, k));
+        }
+        let io = IOHashes::from_tapes(
+            vm.syscalls.get_public_input_read(),
+            &vm.syscalls.get_output(),
+        )?
+        .with_exit_code(vm.syscalls.get_exit_code());
+        let index = vm.trace_len / k;
+        Ok(Self {
+            vm,
+            k,
+            pow,
+            params: poseidon_config(),
+            io,
+            index,
+            done: false,
+        })
+    }
 
-    loop {
-        let block = k_step(vm, k)?;
-        trace.blocks.push(block);
+    /// Steps per block.
+    pub fn k(&self) -> usize {
+        self.k
+    }
 
-        if vm.inst.inst == UNIMP {
-            if pow {
-                let count = trace.blocks.len();
-                if count.next_power_of_two() == count + 1 {
-                    break;
-                }
-            } else {
-                break;
+    /// Index of the next block to be generated.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<'a, M: Memory> Iterator for TraceStream<'a, M> {
+    type Item = Result<Trace<M::Proof>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let block = match k_step(self.vm, self.k, &self.params, &mut self.io) {
+            Ok(block) => block,
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+        };
+        let trace = Trace {
+            k: self.k,
+            start: self.index,
+            blocks: vec![block],
+        };
+        self.index += 1;
+
+        if self.vm.inst.inst == UNIMP {
+            let count = self.index;
+            if !self.pow || count.next_power_of_two() == count + 1 {
+                self.done = true;
             }
         }
+        Some(Ok(trace))
+    }
+}
+
+// Collect the blocks of a stream into a single (sub)trace.
+fn collect<P: MemoryProof>(
+    k: usize,
+    start: usize,
+    stream: impl Iterator<Item = Result<Trace<P>>>,
+) -> Result<Trace<P>> {
+    let mut trace = Trace { k, start, blocks: Vec::new() };
+    for t in stream {
+        trace.blocks.extend(t?.blocks);
     }
     Ok(trace)
 }
 
+/// Generate a program trace by evaluating `vm`, using `k` steps
+/// per block. If `pow` is true, the number of blocks will be
+/// rounded up to the nearest power of two by inserting UNIMP
+/// instructions.
+///
+/// If `vm` has already executed instructions, the trace is resumed
+/// from the current state, see `TraceStream::new`.
+pub fn trace<M: Memory>(vm: &mut NexusVM<M>, k: usize, pow: bool) -> Result<Trace<M::Proof>> {
+    let stream = TraceStream::new(vm, k, pow)?;
+    collect(k, stream.index(), stream)
+}
+
+/// Generate a segment of a program trace by evaluating at most `n`
+/// blocks of `k` steps. Tracing stops early if the program halts.
+/// As with `trace`, the segment starts from the current state of `vm`,
+/// and a snapshot taken afterwards can be used to trace the next segment.
+pub fn trace_segment<M: Memory>(
+    vm: &mut NexusVM<M>,
+    k: usize,
+    n: usize,
+) -> Result<Trace<M::Proof>> {
+    let stream = TraceStream::new(vm, k, false)?;
+    collect(k, stream.index(), stream.take(n))
+}
+
 /// Witness for a single VM step.
 #[derive(Default, Debug)]
 pub struct Witness<P: MemoryProof> {
     /// Initial register file.
     pub regs: Regs,
+    /// Memory layout of the program.
+    pub layout: MemoryLayout,
+    /// Initial input and output hashes.
+    pub io: IOHashes,
     /// Instruction being executed.
     pub inst: u32,
     /// RISC-V instruction components.
@@ -191,6 +632,8 @@ pub struct Witness<P: MemoryProof> {
     pub Z: u32,
     /// Program counter.
     pub PC: u32,
+    /// Resulting input and output hashes.
+    pub IO: IOHashes,
     /// Proof for reading instruction at pc.
     pub pc_proof: P,
     /// Proof for load instructions.
@@ -216,13 +659,21 @@ impl<'a, P: MemoryProof> IntoIterator for &'a Block<P> {
 
 pub struct BlockIter<'a, P: MemoryProof> {
     regs: Regs,
+    io: IOHashes,
+    params: Params,
     block: &'a Block<P>,
     index: usize,
 }
 
 impl<P: MemoryProof> BlockIter<'_, P> {
     fn new(b: &Block<P>) -> BlockIter<'_, P> {
-        BlockIter { regs: b.regs.clone(), block: b, index: 0 }
+        BlockIter {
+            regs: b.regs.clone(),
+            io: b.io,
+            params: poseidon_config(),
+            block: b,
+            index: 0,
+        }
     }
 }
 
@@ -238,6 +689,7 @@ impl<P: MemoryProof> Iterator for BlockIter<'_, P> {
         let inst = parse_u32(s.inst).unwrap();
         let mut w = parse_alt(&self.regs, s.inst);
         w.regs = self.regs.clone();
+        w.layout = self.block.layout;
         w.inst = s.inst;
         w.J = inst.index_j();
         w.X = w.regs.x[w.rs1 as usize];
@@ -252,6 +704,12 @@ impl<P: MemoryProof> Iterator for BlockIter<'_, P> {
         w.read_proof = s.read_proof.as_ref().unwrap_or(&w.pc_proof).clone();
         w.write_proof = s.write_proof.as_ref().unwrap_or(&w.read_proof).clone();
 
+        w.io = self.io;
+        self.io
+            .update(&self.params, w.J, w.regs.x[18], w.regs.x[11], w.Z)
+            .unwrap();
+        w.IO = self.io;
+
         self.regs.pc = w.PC;
         if w.rd > 0 {
             self.regs.x[w.rd as usize] = w.Z;
@@ -322,9 +780,10 @@ mod test {
     use super::*;
     use crate::{
         eval,
-        machines::{loop_vm, nop_vm},
+        machines::{lookup_test_machine, loop_vm, nop_vm},
+        memory::offline::OfflineMemory,
         memory::paged::Paged,
-        memory::trie::MerkleTrie,
+        precompiles::{KECCAK256, SHA256},
         NexusVMError,
     };
 
@@ -346,6 +805,216 @@ mod test {
         trace_test_machine(loop_vm::<MerkleTrie>(5));
     }
 
+    #[test]
+    fn trace_stream() {
+        for pow in [false, true] {
+            let tr = trace(&mut loop_vm::<MerkleTrie>(5), 2, pow).unwrap();
+
+            let mut vm = loop_vm::<MerkleTrie>(5);
+            let stream = TraceStream::new(&mut vm, 2, pow).unwrap();
+            let mut count = 0;
+            for (i, t) in stream.enumerate() {
+                let t = t.unwrap();
+                assert_eq!(t.start, i);
+                assert_eq!(t.blocks.len(), 1);
+                assert_eq!(t.input(i), tr.input(i));
+                count += 1;
+            }
+            assert_eq!(count, tr.blocks.len());
+        }
+    }
+
+    // check that the hashes carried by blocks and witnesses agree
+    // with the hashes of the final tapes
+    fn check_io_hashes(tr: &Trace<impl MemoryProof>, expected: IOHashes) {
+        let mut io = IOHashes::default();
+        for b in &tr.blocks {
+            assert_eq!(b.io, io);
+            for w in b.iter() {
+                assert_eq!(w.io, io);
+                io = w.IO;
+            }
+        }
+        assert_eq!(io, expected);
+    }
+
+    #[test]
+    fn trace_output_hash() {
+        let mut vm = lookup_test_machine::<MerkleTrie>("output").unwrap();
+        let tr = trace(&mut vm, 2, false).unwrap();
+
+        let output = vm.syscalls.get_output();
+        assert_eq!(output, vec![42, 43]);
+        check_io_hashes(&tr, IOHashes::from_tapes(&[], &output).unwrap());
+    }
+
+    #[test]
+    fn trace_public_input_hash() {
+        let code: [u32; 4] = [
+            0x00400913, // addi x18, x0, 4
+            0x000002f3, // ecall x5
+            0x00000373, // ecall x6
+            0xc0001073, // unimp
+        ];
+        let mut vm = NexusVM::<MerkleTrie>::new(0);
+        let bytes: Vec<u8> = code.iter().flat_map(|w| w.to_le_bytes()).collect();
+        vm.init_memory(0, &bytes).unwrap();
+        vm.syscalls.set_public_input(&[7]);
+
+        let tr = trace(&mut vm, 1, false).unwrap();
+        assert_eq!(vm.regs.x[5], 7);
+        assert_eq!(vm.regs.x[6], u32::MAX);
+        check_io_hashes(&tr, IOHashes::from_tapes(&[7], &[]).unwrap());
+    }
+
+    #[test]
+    fn trace_exit_code() {
+        let code: [u32; 4] = [
+            0x00800913, // addi x18, x0, 8
+            0x06500593, // addi x11, x0, 101
+            0x00000073, // ecall
+            0xc0001073, // unimp
+        ];
+        let mut vm = NexusVM::<MerkleTrie>::new(0);
+        let bytes: Vec<u8> = code.iter().flat_map(|w| w.to_le_bytes()).collect();
+        vm.init_memory(0, &bytes).unwrap();
+
+        let tr = trace(&mut vm, 1, false).unwrap();
+        assert_eq!(vm.syscalls.get_exit_code(), ExitCode::PANIC);
+        let expected = IOHashes::from_tapes(&[], &[]).unwrap();
+        check_io_hashes(&tr, expected.with_exit_code(ExitCode::PANIC));
+
+        let n = tr.blocks.len() - 1;
+        let z = tr.input(n).unwrap();
+        assert_eq!(IOHashes::from_state(&z), Some(tr.blocks[n].io));
+    }
+
+    #[test]
+    fn trace_precompile() {
+        // addi x18, x0, code; addi x11, x0, src; addi x12, x0, 4;
+        // addi x13, x0, 0x100; ecall; unimp
+        let call = |code: u32, src: u32| {
+            let code = [
+                (code << 20) | 0x913,
+                (src << 20) | 0x593,
+                0x00400613,
+                0x10000693,
+                0x00000073,
+                0xc0001073,
+            ];
+            let bytes: Vec<u8> = code.iter().flat_map(|w| w.to_le_bytes()).collect();
+            let mut vm = NexusVM::<MerkleTrie>::new(0);
+            vm.init_memory(0, &bytes).unwrap();
+            vm
+        };
+
+        // Keccak-256 calls hashing the line of their output are traced,
+        // but are not proven by the step circuit of every instruction
+        let tr = trace(&mut call(KECCAK256, 0x100), 1, false).unwrap();
+        assert!(matches!(
+            tr.check_no_precompiles(),
+            Err(NexusVMError::UnprovablePrecompile(16, KECCAK256))
+        ));
+        let tr = trace(&mut nop_vm::<MerkleTrie>(2), 1, false).unwrap();
+        assert!(tr.check_no_precompiles().is_ok());
+
+        // other calls can be evaluated, but not proven
+        for (code, src) in [(KECCAK256, 0x40), (SHA256, 0x100)] {
+            eval(&mut call(code, src), false, false).unwrap();
+            assert!(matches!(
+                trace(&mut call(code, src), 1, false),
+                Err(NexusVMError::UnprovablePrecompile(16, s2)) if s2 == code
+            ));
+        }
+    }
+
+    #[test]
+    fn program_id_from_state() {
+        let id = ProgramId::initial(&nop_vm::<MerkleTrie>(3));
+        let tr = trace(&mut nop_vm::<MerkleTrie>(3), 1, false).unwrap();
+        assert_eq!(ProgramId::from_state(&tr.input(0).unwrap()), id);
+        assert_ne!(ProgramId::from_state(&tr.input(1).unwrap()), id);
+        assert_ne!(ProgramId::initial(&nop_vm::<MerkleTrie>(4)), id);
+
+        // the identifier does not depend on the memory used, which it
+        // does not access
+        let vm = nop_vm::<OfflineMemory>(3);
+        let clock = vm.mem.clock();
+        assert_eq!(ProgramId::initial(&vm), id);
+        assert_eq!(vm.mem.clock(), clock);
+    }
+
+    #[test]
+    fn statement() {
+        let mut vm = lookup_test_machine::<MerkleTrie>("output").unwrap();
+        let program = ProgramId::initial(&vm);
+        // with one instruction per block, the last block only halts
+        let tr = trace(&mut vm, 1, false).unwrap();
+        let (z_0, z_i) = (tr.input(0).unwrap(), tr.input(tr.blocks.len() - 1).unwrap());
+
+        let st = Statement::new(program, &[], &[42, 43], ExitCode::SUCCESS).unwrap();
+        assert_eq!(st.check(&z_0, &z_i), Ok(()));
+
+        let st = Statement::new(program, &[], &[42], ExitCode::SUCCESS).unwrap();
+        assert_eq!(st.check(&z_0, &z_i), Err(StatementMismatch::IO));
+
+        let st = Statement::new(program, &[], &[42, 43], ExitCode::PANIC).unwrap();
+        assert_eq!(
+            st.check(&z_0, &z_i),
+            Err(StatementMismatch::ExitCode(ExitCode::SUCCESS))
+        );
+
+        // an execution starting from another state is of another program
+        let st = Statement::new(program, &[], &[42, 43], ExitCode::SUCCESS).unwrap();
+        let z_1 = tr.input(1).unwrap();
+        assert_eq!(
+            st.check(&z_1, &z_i),
+            Err(StatementMismatch::Program(ProgramId::from_state(&z_1)))
+        );
+
+        // the memory layout is part of the initial state, so that a prover
+        // cannot lift the memory protection of the program
+        let mut vm = lookup_test_machine::<MerkleTrie>("output").unwrap();
+        vm.layout.top += 4;
+        let mut z_0 = z_0.clone();
+        z_0[IO_INDEX - 1] += F::from(4u64);
+        assert_ne!(ProgramId::initial(&vm), program);
+        assert_eq!(ProgramId::initial(&vm), ProgramId::from_state(&z_0));
+        assert!(matches!(
+            st.check(&z_0, &z_i),
+            Err(StatementMismatch::Program(_))
+        ));
+    }
+
+    #[test]
+    fn statement_with_memory() {
+        let mut vm = lookup_test_machine::<OfflineMemory>("output").unwrap();
+        let program = ProgramId::initial(&vm);
+        let initial = vm.mem.lines();
+        let (tr, _) = crate::memory::offline::trace(&mut vm, 1, false).unwrap();
+        let (z_0, z_i) = (tr.input(0).unwrap(), tr.input(tr.blocks.len() - 1).unwrap());
+
+        // the initial state commits to the memory accesses, and not to the
+        // contents of memory
+        let st = Statement::new(program, &[], &[42, 43], ExitCode::SUCCESS).unwrap();
+        assert!(matches!(
+            st.check(&z_0, &z_i),
+            Err(StatementMismatch::Program(_))
+        ));
+        assert_eq!(st.check_with_memory(&z_0, &z_i, &initial), Ok(()));
+        assert_eq!(program.check_with_memory(&z_0, &initial), Ok(()));
+
+        assert!(matches!(
+            st.check_with_memory(&z_0, &z_i, &initial[1..]),
+            Err(StatementMismatch::Program(_))
+        ));
+        let st = Statement::new(program, &[], &[42], ExitCode::SUCCESS).unwrap();
+        assert_eq!(
+            st.check_with_memory(&z_0, &z_i, &initial),
+            Err(StatementMismatch::IO)
+        );
+    }
+
     #[test]
     fn run_with_trace_limit() {
         let mut vm = nop_vm::<MerkleTrie>(10);